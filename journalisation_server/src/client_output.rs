@@ -0,0 +1,127 @@
+use std::time::Duration;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+// Résultat de la mise en file d'un message pour le client
+#[derive(Debug, PartialEq)]
+pub enum Delivery {
+    Sent,
+    Dropped { first: bool },    // File pleine ; `first` au début d'une série de pertes
+    Closed,                     // L'écrivain s'est arrêté (connexion perdue)
+}
+
+// Sortie d'un client : une tâche dédiée écrit les messages en file, le
+// gestionnaire du client n'est jamais bloqué par un client qui lit lentement
+pub struct ClientOutput {
+    tx: mpsc::Sender<String>,
+    writer: JoinHandle<()>,
+    dropping: bool,
+}
+
+impl ClientOutput {
+    pub fn spawn<W>(mut write_half: W, capacity: usize) -> Self
+    where
+        W: AsyncWrite + Unpin + Send + 'static,
+    {
+        let (tx, mut rx) = mpsc::channel::<String>(capacity);
+        let writer = tokio::spawn(async move {
+            while let Some(message) = rx.recv().await {
+                if write_half.write_all(message.as_bytes()).await.is_err() {
+                    break;
+                }
+            }
+            let _ = write_half.shutdown().await;
+        });
+
+        ClientOutput { tx, writer, dropping: false }
+    }
+
+    // Mettre un message en file sans attendre
+    pub fn send(&mut self, message: impl Into<String>) -> Delivery {
+        match self.tx.try_send(message.into()) {
+            Ok(()) => {
+                self.dropping = false;
+                Delivery::Sent
+            }
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                let first = !self.dropping;
+                self.dropping = true;
+                Delivery::Dropped { first }
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => Delivery::Closed,
+        }
+    }
+
+    // Laisser l'écrivain vider la file, au plus `timeout`, puis fermer la connexion
+    pub async fn close(self, timeout: Duration) {
+        let ClientOutput { tx, mut writer, .. } = self;
+        drop(tx);
+        if tokio::time::timeout(timeout, &mut writer).await.is_err() {
+            writer.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, DuplexStream};
+
+    // Lire exactement `len` octets envoyés au client
+    async fn read_exact(reader: &mut DuplexStream, len: usize) -> String {
+        let mut buffer = vec![0; len];
+        reader.read_exact(&mut buffer).await.unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[tokio::test]
+    async fn full_queue_drops_messages_and_flags_the_first_of_a_series() {
+        // Tampon d'un octet : l'écrivain reste bloqué tant que le client ne lit pas
+        let (write_half, mut reader) = tokio::io::duplex(1);
+        let mut output = ClientOutput::spawn(write_half, 1);
+
+        assert_eq!(output.send("un\n"), Delivery::Sent);
+        // Premier octet lu : l'écrivain a sorti "un" de la file et attend la suite
+        assert_eq!(read_exact(&mut reader, 1).await, "u");
+        assert_eq!(output.send("deux\n"), Delivery::Sent);
+        assert_eq!(output.send("trois\n"), Delivery::Dropped { first: true });
+        assert_eq!(output.send("quatre\n"), Delivery::Dropped { first: false });
+
+        // Le client rattrape son retard : un envoi réussi termine la série de pertes
+        assert_eq!(read_exact(&mut reader, "n\ndeux\n".len()).await, "n\ndeux\n");
+        assert_eq!(output.send("cinq\n"), Delivery::Sent);
+        assert_eq!(read_exact(&mut reader, 1).await, "c");
+        assert_eq!(output.send("six\n"), Delivery::Sent);
+        assert_eq!(output.send("sept\n"), Delivery::Dropped { first: true });
+    }
+
+    #[tokio::test]
+    async fn lost_connection_reports_closed() {
+        let (write_half, reader) = tokio::io::duplex(64);
+        let mut output = ClientOutput::spawn(write_half, 4);
+        drop(reader);
+
+        // L'écrivain s'arrête à sa première écriture ratée, la file est alors fermée
+        let closed = tokio::time::timeout(Duration::from_secs(2), async {
+            while output.send("message\n") != Delivery::Closed {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }).await;
+        assert!(closed.is_ok());
+    }
+
+    #[tokio::test]
+    async fn close_flushes_queued_messages() {
+        let (write_half, mut reader) = tokio::io::duplex(64);
+        let mut output = ClientOutput::spawn(write_half, 4);
+        for message in ["a\n", "b\n", "c\n"] {
+            assert_eq!(output.send(message), Delivery::Sent);
+        }
+
+        output.close(Duration::from_secs(1)).await;
+        let mut received = String::new();
+        reader.read_to_string(&mut received).await.unwrap();
+        assert_eq!(received, "a\nb\nc\n");
+    }
+}
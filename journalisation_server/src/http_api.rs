@@ -0,0 +1,147 @@
+use std::sync::Arc;
+use axum::extract::{Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::{DateTime, Local, NaiveDateTime};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::net::TcpListener;
+
+use crate::LoggingServer;
+
+// Nombre de lignes renvoyées par défaut par GET /logs
+const DEFAULT_LOGS_LIMIT: usize = 100;
+
+// Configuration de l'API HTTP d'administration
+pub struct HttpApiConfig {
+    pub port: u16,
+    pub token: Option<String>,
+}
+
+#[derive(Clone)]
+struct ApiState {
+    server: Arc<LoggingServer>,
+    token: Option<Arc<str>>,
+}
+
+#[derive(Deserialize)]
+struct LogsQuery {
+    since: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct BroadcastRequest {
+    message: String,
+}
+
+// Démarrer l'API HTTP ; elle s'arrête avec le serveur (Ctrl+C)
+pub async fn serve(server: Arc<LoggingServer>, config: HttpApiConfig) -> tokio::io::Result<()> {
+    let addr = format!("127.0.0.1:{}", config.port);
+    let listener = TcpListener::bind(&addr).await?;
+
+    let state = ApiState {
+        server: server.clone(),
+        token: config.token.map(Arc::from),
+    };
+    let app = Router::new()
+        .route("/logs", get(get_logs))
+        .route("/clients", get(get_clients))
+        .route("/broadcast", post(post_broadcast))
+        .layer(middleware::from_fn_with_state(state.clone(), require_token))
+        .with_state(state);
+
+    server.log_info(&format!("🌐 API HTTP en écoute sur http://{}", addr)).await?;
+
+    let mut shutdown_rx = server.shutdown_tx.subscribe();
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async move {
+            let _ = shutdown_rx.changed().await;
+        })
+        .await
+}
+
+// Authentification `Authorization: Bearer <token>` si --http-token est défini
+async fn require_token(State(state): State<ApiState>, request: Request, next: Next) -> Response {
+    if let Some(token) = &state.token {
+        let provided = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+
+        if provided != Some(token.as_ref()) {
+            return (StatusCode::UNAUTHORIZED, Json(json!({ "error": "token invalide" }))).into_response();
+        }
+    }
+
+    next.run(request).await
+}
+
+// GET /logs?since=<horodatage>&limit=<n> : les `limit` lignes les plus récentes du log courant
+async fn get_logs(State(state): State<ApiState>, Query(query): Query<LogsQuery>) -> Response {
+    let since = match query.since.as_deref().map(parse_timestamp) {
+        Some(None) => {
+            let error = json!({ "error": "paramètre 'since' invalide (ex: 2024-01-31T12:00:00)" });
+            return (StatusCode::BAD_REQUEST, Json(error)).into_response();
+        }
+        Some(Some(since)) => Some(since),
+        None => None,
+    };
+
+    let limit = query.limit.unwrap_or(DEFAULT_LOGS_LIMIT);
+    let lines = state.server.recent_log_lines(limit, |line| match since {
+        Some(since) => line_timestamp(line).is_some_and(|timestamp| timestamp >= since),
+        None => true,
+    }).await;
+
+    match lines {
+        Ok(lines) => Json(lines).into_response(),
+        Err(e) => {
+            let error = json!({ "error": format!("lecture du log impossible: {}", e) });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response()
+        }
+    }
+}
+
+// GET /clients : clients actuellement connectés
+async fn get_clients(State(state): State<ApiState>) -> Response {
+    let clients = state.server.active_clients.lock().await;
+    Json(clients.clone()).into_response()
+}
+
+// POST /broadcast {"message": "..."} : même effet que BROADCAST sur la console
+async fn post_broadcast(State(state): State<ApiState>, Json(request): Json<BroadcastRequest>) -> Response {
+    let message = request.message.trim();
+    if message.is_empty() {
+        return (StatusCode::BAD_REQUEST, Json(json!({ "error": "message vide" }))).into_response();
+    }
+
+    let recipients = state.server.broadcast(message).await;
+    Json(json!({ "recipients": recipients })).into_response()
+}
+
+// Horodatage accepté : RFC 3339 ou heure locale `AAAA-MM-JJ[T ]HH:MM:SS`
+fn parse_timestamp(value: &str) -> Option<NaiveDateTime> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Some(timestamp.with_timezone(&Local).naive_local());
+    }
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S"))
+        .ok()
+}
+
+// Horodatage d'une ligne de log, au format texte ou JSON
+fn line_timestamp(line: &str) -> Option<NaiveDateTime> {
+    if line.starts_with('{') {
+        let entry: serde_json::Value = serde_json::from_str(line).ok()?;
+        let timestamp = DateTime::parse_from_rfc3339(entry.get("timestamp")?.as_str()?).ok()?;
+        Some(timestamp.with_timezone(&Local).naive_local())
+    } else {
+        let timestamp = line.get(1..24)?;
+        NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S%.3f").ok()
+    }
+}
@@ -0,0 +1,141 @@
+use std::net::SocketAddr;
+use chrono::{DateTime, Local};
+use clap::ValueEnum;
+use serde::Serialize;
+
+// Niveau de sévérité : les entrées sous le niveau configuré sont ignorées
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, ValueEnum)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum LogLevel {
+    Debug = 0,
+    Info = 1,
+    Warn = 2,
+    Error = 3,
+}
+
+// Origine d'une entrée de log
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum LogSource {
+    Server,
+    Client,
+}
+
+// Entrée de log avant mise en forme
+#[derive(Debug, Serialize)]
+pub struct LogEntry<'a> {
+    #[serde(serialize_with = "serialize_timestamp")]
+    pub timestamp: DateTime<Local>,
+    pub level: LogLevel,
+    pub source: LogSource,
+    pub client_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nickname: Option<&'a str>,
+    pub address: Option<SocketAddr>,
+    pub message: &'a str,
+}
+
+fn serialize_timestamp<S: serde::Serializer>(
+    timestamp: &DateTime<Local>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&timestamp.format("%Y-%m-%dT%H:%M:%S%.3f%:z"))
+}
+
+// Mise en forme d'une entrée en une ligne terminée par '\n'
+pub trait LogFormatter: Send + Sync {
+    fn format(&self, entry: &LogEntry) -> String;
+}
+
+// Format lisible historique : [horodatage] [SOURCE] message
+pub struct TextFormatter;
+
+impl LogFormatter for TextFormatter {
+    fn format(&self, entry: &LogEntry) -> String {
+        let timestamp = entry.timestamp.format("%Y-%m-%d %H:%M:%S%.3f");
+        match (entry.source, entry.nickname.or(entry.client_id), entry.address) {
+            (LogSource::Client, Some(name), Some(address)) => {
+                format!("[{}] [CLIENT:{}] [{}] {}\n", timestamp, name, address, entry.message)
+            }
+            _ => format!("[{}] [SERVER] {}\n", timestamp, entry.message),
+        }
+    }
+}
+
+// Une entrée JSON par ligne (JSONL), exploitable avec jq
+pub struct JsonFormatter;
+
+impl LogFormatter for JsonFormatter {
+    fn format(&self, entry: &LogEntry) -> String {
+        match serde_json::to_string(entry) {
+            Ok(json) => format!("{}\n", json),
+            Err(e) => format!("{{\"level\":\"ERROR\",\"message\":\"sérialisation impossible: {}\"}}\n", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn entry<'a>(source: LogSource, nickname: Option<&'a str>, message: &'a str) -> LogEntry<'a> {
+        LogEntry {
+            timestamp: Local.with_ymd_and_hms(2024, 1, 31, 12, 30, 45).unwrap(),
+            level: LogLevel::Warn,
+            source,
+            client_id: Some("a1b2c3d4"),
+            nickname,
+            address: Some("127.0.0.1:4242".parse().unwrap()),
+            message,
+        }
+    }
+
+    #[test]
+    fn text_client_line_uses_the_nickname_then_the_id() {
+        let line = TextFormatter.format(&entry(LogSource::Client, None, "bonjour"));
+        assert_eq!(line, "[2024-01-31 12:30:45.000] [CLIENT:a1b2c3d4] [127.0.0.1:4242] bonjour\n");
+
+        let line = TextFormatter.format(&entry(LogSource::Client, Some("alice"), "bonjour"));
+        assert_eq!(line, "[2024-01-31 12:30:45.000] [CLIENT:alice] [127.0.0.1:4242] bonjour\n");
+    }
+
+    #[test]
+    fn text_server_line_has_no_client_fields() {
+        let mut server_entry = entry(LogSource::Server, None, "🎯 Serveur en écoute");
+        server_entry.client_id = None;
+        server_entry.address = None;
+        assert_eq!(TextFormatter.format(&server_entry), "[2024-01-31 12:30:45.000] [SERVER] 🎯 Serveur en écoute\n");
+    }
+
+    #[test]
+    fn json_line_parses_with_all_fields() {
+        let line = JsonFormatter.format(&entry(LogSource::Client, Some("alice"), "message \"cité\"\nsur deux lignes"));
+        assert!(line.ends_with('\n'));
+        assert_eq!(line.matches('\n').count(), 1);
+
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert!(value["timestamp"].as_str().unwrap().starts_with("2024-01-31T12:30:45.000"));
+        assert_eq!(value["level"], "WARN");
+        assert_eq!(value["source"], "CLIENT");
+        assert_eq!(value["client_id"], "a1b2c3d4");
+        assert_eq!(value["nickname"], "alice");
+        assert_eq!(value["address"], "127.0.0.1:4242");
+        assert_eq!(value["message"], "message \"cité\"\nsur deux lignes");
+    }
+
+    #[test]
+    fn json_line_omits_a_missing_nickname() {
+        let line = JsonFormatter.format(&entry(LogSource::Client, None, "bonjour"));
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert!(value.get("nickname").is_none());
+        assert_eq!(value["client_id"], "a1b2c3d4");
+    }
+
+    #[test]
+    fn levels_are_ordered_by_severity() {
+        assert!(LogLevel::Debug < LogLevel::Info);
+        assert!(LogLevel::Info < LogLevel::Warn);
+        assert!(LogLevel::Warn < LogLevel::Error);
+    }
+}
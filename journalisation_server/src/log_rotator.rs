@@ -0,0 +1,321 @@
+use tokio::fs::{self, File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::task::JoinHandle;
+use std::collections::BTreeMap;
+use std::io::Result as IoResult;
+use std::path::{Path, PathBuf};
+use chrono::Local;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+// Fichier de log avec rotation par taille
+// Quand le fichier dépasse `max_size` octets, il est renommé en
+// `<nom>.<horodatage>` et un nouveau fichier vide prend sa place.
+// Seuls les `max_rotated` fichiers archivés les plus récents sont conservés.
+// Les archives sont compressées en `.gz` en arrière-plan (désactivable).
+pub struct LogRotator {
+    path: PathBuf,
+    file: File,
+    current_size: u64,
+    max_size: u64,
+    max_rotated: usize,
+    compress: bool,
+    compression: Option<JoinHandle<()>>,   // Compression (puis nettoyage) en cours
+}
+
+impl LogRotator {
+    pub async fn open(path: impl AsRef<Path>, max_size: u64, max_rotated: usize) -> tokio::io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = Self::open_file(&path).await?;
+        let current_size = file.metadata().await?.len();
+
+        Ok(LogRotator {
+            path,
+            file,
+            current_size,
+            max_size,
+            max_rotated,
+            compress: true,
+            compression: None,
+        })
+    }
+
+    // Activer ou non la compression gzip des fichiers archivés
+    pub fn set_compression(&mut self, compress: bool) {
+        self.compress = compress;
+    }
+
+    async fn open_file(path: &Path) -> tokio::io::Result<File> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await
+    }
+
+    // Écrire une entrée, en effectuant la rotation si la limite est dépassée
+    pub async fn write(&mut self, data: &[u8]) -> tokio::io::Result<()> {
+        if self.current_size >= self.max_size {
+            self.rotate().await?;
+        }
+
+        self.file.write_all(data).await?;
+        self.file.flush().await?;
+        self.current_size += data.len() as u64;
+
+        Ok(())
+    }
+
+    // Archiver le fichier courant et repartir sur un fichier vide
+    pub async fn rotate(&mut self) -> tokio::io::Result<()> {
+        self.file.flush().await?;
+
+        let timestamp = Local::now().format("%Y%m%d-%H%M%S%.3f");
+        let rotated_path = PathBuf::from(format!("{}.{}", self.path.display(), timestamp));
+        fs::rename(&self.path, &rotated_path).await?;
+
+        self.file = Self::open_file(&self.path).await?;
+        self.current_size = 0;
+
+        if !self.compress {
+            return prune_rotated_files(&self.path, self.max_rotated).await;
+        }
+
+        // flate2 est synchrone : compression hors du runtime, sans bloquer les écritures
+        // Les compressions s'enchaînent dans l'ordre des rotations, et le nettoyage n'a lieu
+        // qu'une fois l'archive terminée : jamais de `.gz` partiel supprimé ou compté
+        let previous = self.compression.take();
+        let log_path = self.path.clone();
+        let max_rotated = self.max_rotated;
+        self.compression = Some(tokio::spawn(async move {
+            if let Some(previous) = previous {
+                let _ = previous.await;
+            }
+
+            let path = rotated_path.clone();
+            match tokio::task::spawn_blocking(move || compress_log_file(&path)).await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => eprintln!("❌ Compression de {} impossible: {}", rotated_path.display(), e),
+                Err(e) => eprintln!("❌ Tâche de compression interrompue: {}", e),
+            }
+
+            if let Err(e) = prune_rotated_files(&log_path, max_rotated).await {
+                eprintln!("❌ Nettoyage des archives de {} impossible: {}", log_path.display(), e);
+            }
+        }));
+
+        Ok(())
+    }
+
+    // Attendre la fin des compressions lancées (arrêt du serveur)
+    pub async fn finish_compression(&mut self) {
+        if let Some(compression) = self.compression.take() {
+            let _ = compression.await;
+        }
+    }
+}
+
+// Supprimer les archives les plus anciennes au-delà de `max_rotated`
+// `server.log.<horodatage>` et `server.log.<horodatage>.gz` sont une seule archive
+async fn prune_rotated_files(path: &Path, max_rotated: usize) -> tokio::io::Result<()> {
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let prefix = match path.file_name() {
+        Some(name) => format!("{}.", name.to_string_lossy()),
+        None => return Ok(()),
+    };
+
+    // Fichiers regroupés par archive ; l'horodatage dans le nom donne l'ordre chronologique
+    let mut rotated: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    let mut entries = fs::read_dir(&directory).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.starts_with(&prefix) {
+            let stem = name.strip_suffix(".gz").unwrap_or(&name).to_string();
+            rotated.entry(stem).or_default().push(entry.path());
+        }
+    }
+
+    let excess = rotated.len().saturating_sub(max_rotated);
+    for old_file in rotated.into_values().take(excess).flatten() {
+        match fs::remove_file(&old_file).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+// Compresser `path` en `<path>.gz` puis supprimer l'original
+pub fn compress_log_file(path: &Path) -> IoResult<()> {
+    let compressed_path = PathBuf::from(format!("{}.gz", path.display()));
+    let mut input = std::fs::File::open(path)?;
+    let output = std::fs::File::create(&compressed_path)?;
+
+    let mut encoder = GzEncoder::new(output, Compression::default());
+    std::io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?.sync_all()?;
+
+    std::fs::remove_file(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    // Répertoire temporaire propre à un test, supprimé à la fin
+    struct TestDir(PathBuf);
+
+    impl TestDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("log_rotator_{}_{}", name, std::process::id()));
+            let _ = std::fs::remove_dir_all(&path);
+            std::fs::create_dir_all(&path).unwrap();
+            TestDir(path)
+        }
+
+        // Noms des fichiers présents, triés
+        fn files(&self) -> Vec<String> {
+            let mut names: Vec<String> = std::fs::read_dir(&self.0)
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+                .collect();
+            names.sort();
+            names
+        }
+    }
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[tokio::test]
+    async fn compressed_archives_are_pruned_once_complete() {
+        let dir = TestDir::new("compressed");
+        let mut rotator = LogRotator::open(dir.0.join("server.log"), 100, 2).await.unwrap();
+
+        // Chaque écriture dépasse la limite : une rotation par ligne après la première
+        for index in 0..6 {
+            rotator.write(format!("{:<99}\n", index).as_bytes()).await.unwrap();
+            // Horodatage à la milliseconde dans le nom des archives
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        rotator.finish_compression().await;
+
+        let files = dir.files();
+        assert_eq!(files.len(), 3, "{:?}", files);
+        assert_eq!(files[0], "server.log");
+        assert!(files[1..].iter().all(|name| name.ends_with(".gz")), "{:?}", files);
+
+        // Les deux archives gardées sont les plus récentes (lignes 3 et 4)
+        let mut kept = Vec::new();
+        for name in &files[1..] {
+            let mut decoder = flate2::read::GzDecoder::new(std::fs::File::open(dir.0.join(name)).unwrap());
+            let mut content = String::new();
+            std::io::Read::read_to_string(&mut decoder, &mut content).unwrap();
+            kept.push(content.trim().to_string());
+        }
+        assert_eq!(kept, ["3", "4"]);
+    }
+
+    #[tokio::test]
+    async fn original_and_partial_gz_count_as_one_archive() {
+        let dir = TestDir::new("stems");
+        for name in [
+            "server.log.20250101-000000.000",
+            "server.log.20250101-000000.000.gz",   // compression interrompue
+            "server.log.20250102-000000.000.gz",
+            "server.log.20250103-000000.000",
+            "server.log.20250103-000000.000.gz",
+            "autre.log.20240101-000000.000",
+        ] {
+            std::fs::write(dir.0.join(name), "x").unwrap();
+        }
+        let path = dir.0.join("server.log");
+
+        // Trois archives distinctes : rien à supprimer
+        prune_rotated_files(&path, 3).await.unwrap();
+        assert_eq!(dir.files().len(), 6);
+
+        prune_rotated_files(&path, 2).await.unwrap();
+        assert_eq!(dir.files(), [
+            "autre.log.20240101-000000.000",
+            "server.log.20250102-000000.000.gz",
+            "server.log.20250103-000000.000",
+            "server.log.20250103-000000.000.gz",
+        ]);
+    }
+
+    #[tokio::test]
+    async fn uncompressed_archives_are_pruned_at_rotation() {
+        let dir = TestDir::new("plain");
+        let mut rotator = LogRotator::open(dir.0.join("server.log"), 10, 1).await.unwrap();
+        rotator.set_compression(false);
+
+        for index in 0..4 {
+            rotator.write(format!("ligne {:<4}\n", index).as_bytes()).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        let files = dir.files();
+        assert_eq!(files.len(), 2, "{:?}", files);
+        assert_eq!(std::fs::read_to_string(dir.0.join(&files[1])).unwrap(), "ligne 2   \n");
+        assert_eq!(std::fs::read_to_string(dir.0.join("server.log")).unwrap(), "ligne 3   \n");
+    }
+
+    #[tokio::test]
+    async fn rotation_happens_once_the_size_limit_is_reached() {
+        let dir = TestDir::new("size");
+        let mut rotator = LogRotator::open(dir.0.join("server.log"), 20, 5).await.unwrap();
+        rotator.set_compression(false);
+
+        // 2 lignes de 10 octets tiennent dans la limite, la troisième part dans un nouveau fichier
+        for index in 0..3 {
+            rotator.write(format!("ligne {:<3}\n", index).as_bytes()).await.unwrap();
+        }
+
+        let files = dir.files();
+        assert_eq!(files.len(), 2, "{:?}", files);
+        assert_eq!(std::fs::read_to_string(dir.0.join(&files[1])).unwrap(), "ligne 0  \nligne 1  \n");
+        assert_eq!(std::fs::read_to_string(dir.0.join("server.log")).unwrap(), "ligne 2  \n");
+    }
+
+    #[tokio::test]
+    async fn existing_file_size_counts_towards_the_limit() {
+        let dir = TestDir::new("reopen");
+        std::fs::write(dir.0.join("server.log"), "x".repeat(30)).unwrap();
+
+        let mut rotator = LogRotator::open(dir.0.join("server.log"), 20, 5).await.unwrap();
+        rotator.set_compression(false);
+        rotator.write(b"nouvelle\n").await.unwrap();
+
+        assert_eq!(dir.files().len(), 2);
+        assert_eq!(std::fs::read_to_string(dir.0.join("server.log")).unwrap(), "nouvelle\n");
+    }
+
+    #[tokio::test]
+    async fn only_the_most_recent_archives_are_kept() {
+        let dir = TestDir::new("retention");
+        let mut rotator = LogRotator::open(dir.0.join("server.log"), 1, 3).await.unwrap();
+        rotator.set_compression(false);
+
+        for index in 0..8 {
+            rotator.write(format!("{}\n", index).as_bytes()).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        let files = dir.files();
+        assert_eq!(files.len(), 4, "{:?}", files);
+        let kept: Vec<String> = files[1..]
+            .iter()
+            .map(|name| std::fs::read_to_string(dir.0.join(name)).unwrap())
+            .collect();
+        assert_eq!(kept, ["4\n", "5\n", "6\n"]);
+    }
+}
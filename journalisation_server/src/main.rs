@@ -1,60 +1,261 @@
-use tokio::net::{TcpListener, TcpStream};
+mod client_output;
+mod http_api;
+mod log_format;
+mod log_rotator;
+mod metrics;
+mod rate_limiter;
+mod tls;
+
+use tokio::net::TcpListener;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::fs::{File, OpenOptions};
-use tokio::sync::Mutex;
+use tokio_rustls::TlsAcceptor;
+use tokio::sync::{broadcast, mpsc, watch, Mutex};
+use tokio::task::JoinSet;
 use std::sync::Arc;
 use std::net::SocketAddr;
 use chrono::{DateTime, Local};
+use clap::{Parser, ValueEnum};
+use regex::Regex;
+use serde::Serialize;
 use uuid::Uuid;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::time::{Instant, Interval};
+
+use client_output::{ClientOutput, Delivery};
+use http_api::HttpApiConfig;
+use log_format::{JsonFormatter, LogEntry, LogFormatter, LogLevel, LogSource, TextFormatter};
+use log_rotator::LogRotator;
+use metrics::Metrics;
+use rate_limiter::TokenBucket;
+use tls::ClientStream;
+
+#[derive(Parser)]
+#[command(name = "journalisation-server")]
+#[command(about = "Serveur de journalisation TCP asynchrone")]
+struct Args {
+    /// Port d'écoute TCP (sur 127.0.0.1)
+    #[arg(long, default_value = "8080")]
+    port: u16,
+
+    /// Taille maximale de logs/server.log en Mo avant rotation
+    #[arg(long, default_value = "10")]
+    max_log_size_mb: u64,
+
+    /// Nombre de fichiers archivés conservés après rotation
+    #[arg(long, default_value = "5")]
+    max_rotated_files: usize,
+
+    /// Ne pas compresser (gzip) les fichiers archivés
+    #[arg(long)]
+    no_compress_rotated: bool,
+
+    /// Format des entrées de log
+    #[arg(long, value_enum, default_value = "text")]
+    log_format: LogFormat,
+
+    /// Niveau minimal des entrées journalisées
+    #[arg(long, value_enum, default_value = "info")]
+    log_level: LogLevel,
+
+    /// Secret partagé : les clients doivent envoyer `AUTH <token>` avant tout message
+    #[arg(long)]
+    auth_token: Option<String>,
+
+    /// Réserver la commande LIST aux clients authentifiés (exige --auth-token)
+    #[arg(long, requires = "auth_token")]
+    list_requires_auth: bool,
+
+    /// Nombre maximal de messages par seconde et par client (0 = illimité)
+    #[arg(long, default_value = "10")]
+    client_rate_limit: u32,
+
+    /// Certificat TLS (PEM) : active le chiffrement des connexions
+    #[arg(long, requires = "tls_key")]
+    tls_cert: Option<PathBuf>,
+
+    /// Clé privée TLS (PEM) associée à --tls-cert
+    #[arg(long, requires = "tls_cert")]
+    tls_key: Option<PathBuf>,
+
+    /// Port de l'API HTTP d'administration (GET /logs, GET /clients, POST /broadcast)
+    #[arg(long)]
+    http_port: Option<u16>,
+
+    /// Jeton exigé par l'API HTTP (`Authorization: Bearer <token>`)
+    #[arg(long, requires = "http_port")]
+    http_token: Option<String>,
+
+    /// Port du serveur de métriques Prometheus (GET /metrics)
+    #[arg(long)]
+    metrics_port: Option<u16>,
+
+    /// Déconnecter un client silencieux après ce nombre de secondes (0 = jamais)
+    #[arg(long, default_value = "300")]
+    client_timeout_secs: u64,
+
+    /// Nombre de messages en attente d'envoi par client ; au-delà, ils sont abandonnés
+    #[arg(long, default_value_t = DEFAULT_CLIENT_BUFFER_SIZE, value_parser = clap::value_parser!(u64).range(1..))]
+    client_buffer_size: u64,
+
+    /// Envoyer `NOP` aux clients à cet intervalle (secondes) pour détecter les connexions mortes
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    keepalive_interval: Option<u64>,
+}
+
+// Délai accordé au client pour s'authentifier
+const AUTH_TIMEOUT: Duration = Duration::from_secs(3);
+
+// Nombre de messages diffusés mis en attente pour un client lent
+const BROADCAST_CAPACITY: usize = 32;
+
+// Nombre de messages privés en attente pour un client
+const PRIVATE_CAPACITY: usize = 32;
+
+// Taille par défaut de la file d'envoi de chaque client
+const DEFAULT_CLIENT_BUFFER_SIZE: u64 = 64;
+
+// Délai laissé à l'écrivain d'un client pour vider sa file à la déconnexion
+const OUTPUT_FLUSH_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Nombre maximal de lignes renvoyées par SEARCH / SEARCH_REGEX
+const SEARCH_MAX_RESULTS: usize = 100;
+
+// Délai laissé aux clients pour terminer lors de l'arrêt du serveur
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+// Commandes d'administration lues mais pas encore traitées
+const ADMIN_COMMAND_CAPACITY: usize = 16;
+
+#[derive(Clone, Copy, ValueEnum)]
+enum LogFormat {
+    Text,
+    Json,
+}
 
 // Structure pour gérer les informations du client
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 struct ClientInfo {
     id: String,
+    nickname: Option<String>,
     address: SocketAddr,
     connected_at: DateTime<Local>,
+    message_count: u64,
+    dropped_messages: u64,              // Messages abandonnés car le client lit trop lentement
+    #[serde(skip)]
+    private_tx: mpsc::Sender<String>,   // Messages privés, écrits par la tâche du client
+}
+
+impl ClientInfo {
+    // Pseudo s'il est défini, sinon l'ID court
+    fn display_name(&self) -> &str {
+        self.nickname.as_deref().unwrap_or(&self.id)
+    }
+}
+
+// Pseudo : 1 à 20 caractères alphanumériques
+fn is_valid_nickname(nickname: &str) -> bool {
+    (1..=20).contains(&nickname.len()) && nickname.chars().all(|c| c.is_ascii_alphanumeric())
 }
 
 // Structure principale du serveur
 struct LoggingServer {
-    log_file: Arc<Mutex<File>>,
+    log_path: PathBuf,                  // Fichier de log courant, lu par SEARCH et GET /logs
+    log_file: Arc<Mutex<LogRotator>>,
     active_clients: Arc<Mutex<Vec<ClientInfo>>>,
+    formatter: Arc<dyn LogFormatter>,
+    min_level: LogLevel,
+    auth_token: Option<String>,
+    list_requires_auth: bool,
+    client_rate_limit: u32,
+    client_buffer_size: usize,              // Capacité de la file d'envoi de chaque client
+    client_timeout: Option<Duration>,       // Silence maximal avant déconnexion
+    keepalive_interval: Option<Duration>,   // Intervalle des `NOP` envoyés aux clients
+    broadcast_tx: broadcast::Sender<String>,
+    shutdown_tx: watch::Sender<bool>,   // Passe à `true` lors de l'arrêt (Ctrl+C)
+    metrics: Metrics,
 }
 
 impl LoggingServer {
-    async fn new() -> tokio::io::Result<Self> {
+    async fn new(
+        max_log_size: u64,
+        max_rotated_files: usize,
+        formatter: Arc<dyn LogFormatter>,
+        min_level: LogLevel,
+        auth_token: Option<String>,
+        list_requires_auth: bool,
+        client_rate_limit: u32,
+    ) -> tokio::io::Result<Self> {
         // Créer le dossier logs s'il n'existe pas
         if !Path::new("logs").exists() {
             tokio::fs::create_dir("logs").await?;
             println!("📁 Dossier 'logs' créé");
         }
 
-        // Ouvrir/créer le fichier de log
-        let log_file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open("logs/server.log")
-            .await?;
+        // Ouvrir/créer le fichier de log (avec rotation par taille)
+        let log_path = PathBuf::from("logs/server.log");
+        let log_file = LogRotator::open(&log_path, max_log_size, max_rotated_files).await?;
 
         let server = LoggingServer {
+            log_path,
             log_file: Arc::new(Mutex::new(log_file)),
             active_clients: Arc::new(Mutex::new(Vec::new())),
+            formatter,
+            min_level,
+            auth_token,
+            list_requires_auth,
+            client_rate_limit,
+            client_buffer_size: DEFAULT_CLIENT_BUFFER_SIZE as usize,
+            client_timeout: None,
+            keepalive_interval: None,
+            broadcast_tx: broadcast::channel(BROADCAST_CAPACITY).0,
+            shutdown_tx: watch::channel(false).0,
+            metrics: Metrics::new(),
         };
 
-        server.log_server_message("🚀 Serveur de journalisation démarré").await?;
+        server.log_info("🚀 Serveur de journalisation démarré").await?;
 
         Ok(server)
     }
 
+    fn with_rotated_compression(self, compress: bool) -> Self {
+        // Serveur tout juste créé : personne d'autre ne détient le verrou
+        if let Ok(mut log_file) = self.log_file.try_lock() {
+            log_file.set_compression(compress);
+        }
+        self
+    }
+
+    fn with_client_buffer_size(mut self, client_buffer_size: usize) -> Self {
+        self.client_buffer_size = client_buffer_size;
+        self
+    }
+
+    // Délai d'inactivité des clients et intervalle des pings `NOP`
+    fn with_client_timeouts(mut self, client_timeout: Option<Duration>, keepalive_interval: Option<Duration>) -> Self {
+        self.client_timeout = client_timeout;
+        self.keepalive_interval = keepalive_interval;
+        self
+    }
+
     // Méthode pour logger un message du serveur
-    async fn log_server_message(&self, message: &str) -> tokio::io::Result<()> {
-        let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
-        let log_entry = format!("[{}] [SERVER] {}\n", timestamp, message);
+    async fn log_server_message(&self, level: LogLevel, message: &str) -> tokio::io::Result<()> {
+        if level < self.min_level {
+            return Ok(());
+        }
+
+        let log_entry = self.formatter.format(&LogEntry {
+            timestamp: Local::now(),
+            level,
+            source: LogSource::Server,
+            client_id: None,
+            nickname: None,
+            address: None,
+            message,
+        });
 
-        let mut file = self.log_file.lock().await;
-        file.write_all(log_entry.as_bytes()).await?;
-        file.flush().await?;
+        self.log_file.lock().await.write(log_entry.as_bytes()).await?;
+        self.metrics.log_bytes_written.inc_by(log_entry.len() as u64);
 
         // Afficher aussi dans la console
         print!("{}", log_entry);
@@ -63,14 +264,28 @@ impl LoggingServer {
     }
 
     // Méthode pour logger un message client
-    async fn log_client_message(&self, client_info: &ClientInfo, message: &str) -> tokio::io::Result<()> {
-        let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
-        let log_entry = format!("[{}] [CLIENT:{}] [{}] {}\n",
-                                timestamp, client_info.id, client_info.address, message);
+    async fn log_client_message(
+        &self,
+        level: LogLevel,
+        client_info: &ClientInfo,
+        message: &str,
+    ) -> tokio::io::Result<()> {
+        if level < self.min_level {
+            return Ok(());
+        }
+
+        let log_entry = self.formatter.format(&LogEntry {
+            timestamp: Local::now(),
+            level,
+            source: LogSource::Client,
+            client_id: Some(&client_info.id),
+            nickname: client_info.nickname.as_deref(),
+            address: Some(client_info.address),
+            message,
+        });
 
-        let mut file = self.log_file.lock().await;
-        file.write_all(log_entry.as_bytes()).await?;
-        file.flush().await?;
+        self.log_file.lock().await.write(log_entry.as_bytes()).await?;
+        self.metrics.log_bytes_written.inc_by(log_entry.len() as u64);
 
         // Afficher aussi dans la console avec couleur
         print!("💬 {}", log_entry);
@@ -78,12 +293,31 @@ impl LoggingServer {
         Ok(())
     }
 
+    // Raccourcis pour les messages du serveur
+    async fn log_debug(&self, message: &str) -> tokio::io::Result<()> {
+        self.log_server_message(LogLevel::Debug, message).await
+    }
+
+    async fn log_info(&self, message: &str) -> tokio::io::Result<()> {
+        self.log_server_message(LogLevel::Info, message).await
+    }
+
+    async fn log_warn(&self, message: &str) -> tokio::io::Result<()> {
+        self.log_server_message(LogLevel::Warn, message).await
+    }
+
+    async fn log_error(&self, message: &str) -> tokio::io::Result<()> {
+        self.log_server_message(LogLevel::Error, message).await
+    }
+
     // Ajouter un client à la liste des clients actifs
     async fn add_client(&self, client_info: ClientInfo) {
         let mut clients = self.active_clients.lock().await;
         clients.push(client_info.clone());
+        self.metrics.active_clients.inc();
+        self.metrics.total_connections.inc();
 
-        if let Err(e) = self.log_server_message(&format!(
+        if let Err(e) = self.log_info(&format!(
             "✅ Nouveau client connecté: {} [{}] - Total clients: {}",
             client_info.id, client_info.address, clients.len()
         )).await {
@@ -95,8 +329,9 @@ impl LoggingServer {
     async fn remove_client(&self, client_id: &str) {
         let mut clients = self.active_clients.lock().await;
         clients.retain(|client| client.id != client_id);
+        self.metrics.active_clients.dec();
 
-        if let Err(e) = self.log_server_message(&format!(
+        if let Err(e) = self.log_info(&format!(
             "❌ Client déconnecté: {} - Clients restants: {}",
             client_id, clients.len()
         )).await {
@@ -104,10 +339,158 @@ impl LoggingServer {
         }
     }
 
+    // Attribuer un pseudo à un client, en refusant les doublons
+    async fn set_nickname(&self, client_id: &str, nickname: &str) -> Result<(), String> {
+        if !is_valid_nickname(nickname) {
+            return Err("Le pseudo doit contenir 1 à 20 caractères alphanumériques".to_string());
+        }
+
+        let mut clients = self.active_clients.lock().await;
+        let taken = clients.iter().any(|client| {
+            client.id != client_id
+                && client.nickname.as_deref().is_some_and(|nick| nick.eq_ignore_ascii_case(nickname))
+        });
+        if taken {
+            return Err(format!("Le pseudo '{}' est déjà utilisé", nickname));
+        }
+
+        if let Some(client) = clients.iter_mut().find(|client| client.id == client_id) {
+            client.nickname = Some(nickname.to_string());
+        }
+        Ok(())
+    }
+
+    // Transmettre un message privé à un client désigné par son ID ou son pseudo
+    async fn send_private(&self, sender: &ClientInfo, target: &str, message: &str) -> Result<(), String> {
+        let target_tx = {
+            let clients = self.active_clients.lock().await;
+            clients.iter()
+                .find(|client| {
+                    client.id == target
+                        || client.nickname.as_deref().is_some_and(|nick| nick.eq_ignore_ascii_case(target))
+                })
+                .map(|client| client.private_tx.clone())
+        };
+
+        // Sans attente : deux clients qui s'écrivent ne doivent pas se bloquer mutuellement
+        let forwarded = format!("[PRIVATE from {}]: {}", sender.display_name(), message);
+        match target_tx.map(|target_tx| target_tx.try_send(forwarded)) {
+            Some(Ok(())) => Ok(()),
+            Some(Err(mpsc::error::TrySendError::Full(_))) => Err(format!("ERROR: {} busy", target)),
+            // Le destinataire vient peut-être de se déconnecter : son canal est alors fermé
+            _ => Err(format!("ERROR: {} not connected", target)),
+        }
+    }
+
+    // Envoyer un message au client via sa file ; `false` si la connexion est perdue
+    async fn deliver(&self, client_info: &ClientInfo, output: &mut ClientOutput, message: impl Into<String>) -> bool {
+        match output.send(message) {
+            Delivery::Sent => true,
+            Delivery::Dropped { first } => {
+                let total = self.record_dropped(&client_info.id).await;
+                // Un avertissement par série de pertes, pas un par message
+                if first {
+                    if let Err(e) = self.log_warn(&format!(
+                        "🐢 Client {} trop lent, messages abandonnés (total: {})",
+                        client_info.display_name(), total
+                    )).await {
+                        eprintln!("❌ Erreur lors du logging: {}", e);
+                    }
+                }
+                true
+            }
+            Delivery::Closed => false,
+        }
+    }
+
+    // Compter un message abandonné, renvoie le total pour ce client
+    async fn record_dropped(&self, client_id: &str) -> u64 {
+        self.metrics.dropped_messages.inc();
+        let mut clients = self.active_clients.lock().await;
+        match clients.iter_mut().find(|client| client.id == client_id) {
+            Some(client) => {
+                client.dropped_messages += 1;
+                client.dropped_messages
+            }
+            None => 0,
+        }
+    }
+
+    // Compter un message reçu d'un client
+    async fn record_message(&self, client_id: &str) {
+        let mut clients = self.active_clients.lock().await;
+        if let Some(client) = clients.iter_mut().find(|client| client.id == client_id) {
+            client.message_count += 1;
+        }
+        self.metrics.total_messages.inc();
+    }
+
+    // Liste des clients connectés, une ligne par client
+    async fn list_clients(&self) -> String {
+        let clients = self.active_clients.lock().await;
+        let mut list = format!("👥 Clients connectés: {}\n", clients.len());
+
+        for client in clients.iter() {
+            let duration = Local::now().signed_duration_since(client.connected_at);
+            list.push_str(&format!(
+                "   └─ {} [{}] - Connecté depuis {} ({} min) - {} message(s)\n",
+                client.display_name(),
+                client.address,
+                client.connected_at.format("%H:%M:%S"),
+                duration.num_minutes(),
+                client.message_count
+            ));
+        }
+
+        list
+    }
+
+    // Les `limit` lignes les plus récentes du log courant qui vérifient `matches`, dans l'ordre du fichier
+    async fn recent_log_lines(&self, limit: usize, matches: impl Fn(&str) -> bool) -> tokio::io::Result<Vec<String>> {
+        let content = tokio::fs::read_to_string(&self.log_path).await?;
+        let mut lines: Vec<String> = content
+            .lines()
+            .rev()
+            .filter(|line| matches(line))
+            .take(limit)
+            .map(str::to_string)
+            .collect();
+        lines.reverse();
+        Ok(lines)
+    }
+
+    // Chercher dans le log courant, réponse `RESULT: <ligne>` puis `SEARCH DONE: <n> results`
+    // Au-delà de SEARCH_MAX_RESULTS, seules les correspondances les plus récentes sont gardées
+    async fn search_log(&self, matches: impl Fn(&str) -> bool) -> String {
+        let results = match self.recent_log_lines(SEARCH_MAX_RESULTS, matches).await {
+            Ok(results) => results,
+            Err(e) => return format!("❌ Lecture du log impossible: {}\n", e),
+        };
+
+        let mut reply = String::new();
+        for line in &results {
+            reply.push_str(&format!("RESULT: {}\n", line));
+        }
+        reply.push_str(&format!("SEARCH DONE: {} results\n", results.len()));
+        reply
+    }
+
+    // Diffuser une annonce à tous les clients connectés
+    async fn broadcast(&self, announcement: &str) -> usize {
+        // send() échoue seulement s'il n'y a aucun abonné
+        let recipients = self.broadcast_tx.send(announcement.to_string()).unwrap_or(0);
+        if let Err(e) = self.log_info(&format!(
+            "📢 Diffusion à {} client(s): {}", recipients, announcement
+        )).await {
+            eprintln!("❌ Erreur lors du logging: {}", e);
+        }
+        recipients
+    }
+
     // Afficher les statistiques du serveur
     async fn show_stats(&self) {
         let clients = self.active_clients.lock().await;
-        if let Err(e) = self.log_server_message(&format!(
+        if let Err(e) = self.log_info(&format!(
             "📊 Statistiques - Clients actifs: {}", clients.len()
         )).await {
             eprintln!("❌ Erreur lors du logging: {}", e);
@@ -115,9 +498,9 @@ impl LoggingServer {
 
         for client in clients.iter() {
             let duration = Local::now().signed_duration_since(client.connected_at);
-            if let Err(e) = self.log_server_message(&format!(
+            if let Err(e) = self.log_debug(&format!(
                 "   └─ {} [{}] - Connecté depuis {} minutes",
-                client.id, client.address, duration.num_minutes()
+                client.display_name(), client.address, duration.num_minutes()
             )).await {
                 eprintln!("❌ Erreur lors du logging: {}", e);
             }
@@ -127,24 +510,26 @@ impl LoggingServer {
     // Méthode principale pour gérer un client
     async fn handle_client(
         server: Arc<LoggingServer>,
-        mut stream: TcpStream,
+        mut stream: ClientStream,
         client_addr: SocketAddr,
     ) -> tokio::io::Result<()> {
         // Créer les informations du client
-        let client_info = ClientInfo {
+        let (private_tx, mut private_rx) = mpsc::channel(PRIVATE_CAPACITY);
+        let mut client_info = ClientInfo {
             id: Uuid::new_v4().to_string()[..8].to_string(), // ID court
+            nickname: None,
             address: client_addr,
             connected_at: Local::now(),
+            message_count: 0,
+            dropped_messages: 0,
+            private_tx,
         };
 
-        // Ajouter le client à la liste
-        server.add_client(client_info.clone()).await;
-
         // Envoyer un message de bienvenue au client
-        let welcome_msg = format!("🎉 Bienvenue sur le serveur de journalisation!\n");
+        let welcome_msg = "🎉 Bienvenue sur le serveur de journalisation!\n";
         let welcome_msg2 = format!("📝 Votre ID: {} | Votre IP: {}\n", client_info.id, client_info.address);
-        let welcome_msg3 = format!("💡 Tapez vos messages (ils seront loggés avec horodatage)\n");
-        let welcome_msg4 = format!("🔚 Tapez 'quit' pour vous déconnecter\n\n");
+        let welcome_msg3 = "💡 Tapez vos messages (ils seront loggés avec horodatage)\n";
+        let welcome_msg4 = "🔚 Tapez 'quit' pour vous déconnecter\n\n";
 
         if let Err(e) = stream.write_all(welcome_msg.as_bytes()).await {
             eprintln!("❌ Erreur envoi message: {}", e);
@@ -160,20 +545,129 @@ impl LoggingServer {
         }
 
         // Séparer le stream en parties lecture et écriture
-        let (read_half, mut write_half) = stream.split();
+        let (read_half, mut write_half) = tokio::io::split(stream);
         let reader = BufReader::new(read_half);
         let mut lines = reader.lines();
 
+        // Authentification : la première ligne doit être `AUTH <token>`
+        let authenticated = server.auth_token.is_some();
+        if let Some(token) = &server.auth_token {
+            write_half.write_all("🔐 Authentification requise: envoyez AUTH <token>\n".as_bytes()).await?;
+
+            let authenticated = match tokio::time::timeout(AUTH_TIMEOUT, lines.next_line()).await {
+                Ok(Ok(Some(line))) => line.trim().strip_prefix("AUTH ") == Some(token.as_str()),
+                _ => false,
+            };
+
+            if !authenticated {
+                let _ = write_half.write_all(b"AUTH FAILED\n").await;
+                if let Err(e) = server.log_warn(&format!(
+                    "🔒 Authentification refusée pour {} [{}]", client_info.id, client_addr.ip()
+                )).await {
+                    eprintln!("❌ Erreur lors du logging: {}", e);
+                }
+                return Ok(());
+            }
+
+            write_half.write_all(b"AUTH OK\n").await?;
+        }
+
+        // Les réponses passent par une file vidée par une tâche dédiée
+        let mut output = ClientOutput::spawn(write_half, server.client_buffer_size);
+
+        // Ajouter le client à la liste
+        server.add_client(client_info.clone()).await;
+
+        // S'abonner aux messages diffusés par l'administrateur et à l'arrêt du serveur
+        let mut broadcast_rx = server.broadcast_tx.subscribe();
+        let mut shutdown_rx = server.shutdown_tx.subscribe();
+
+        // Limitation du débit de messages de ce client
+        let mut rate_limiter = (server.client_rate_limit > 0)
+            .then(|| TokenBucket::new(server.client_rate_limit));
+
+        // Ping périodique : une écriture sur une connexion à moitié ouverte finit par échouer
+        let mut keepalive = server.keepalive_interval
+            .map(|period| tokio::time::interval_at(Instant::now() + period, period));
+
+        // Échéance d'inactivité, repoussée à chaque ligne reçue du client
+        let mut idle_deadline = server.client_timeout.map(|timeout| Instant::now() + timeout);
+
         // Boucle principale pour lire les messages du client
-        while let Ok(Some(line)) = lines.next_line().await {
+        loop {
+            let next_line = tokio::select! {
+                result = read_line_until(&mut lines, idle_deadline) => match result {
+                    Some(result) => result,
+                    None => {
+                        server.deliver(&client_info, &mut output, "TIMEOUT. Disconnecting.\n").await;
+                        if let Err(e) = server.log_client_message(
+                            LogLevel::Info, &client_info, "DÉCONNEXION (INACTIVITÉ)"
+                        ).await {
+                            eprintln!("❌ Erreur lors du logging: {}", e);
+                        }
+                        break;
+                    }
+                },
+                _ = next_tick(&mut keepalive) => {
+                    if !server.deliver(&client_info, &mut output, "NOP\n").await {
+                        if let Err(e) = server.log_warn(&format!(
+                            "💔 Connexion perdue avec {} (keepalive)", client_info.id
+                        )).await {
+                            eprintln!("❌ Erreur lors du logging: {}", e);
+                        }
+                        break;
+                    }
+                    continue;
+                }
+                Some(private) = private_rx.recv() => {
+                    if !server.deliver(&client_info, &mut output, format!("{}\n", private)).await {
+                        break;
+                    }
+                    continue;
+                }
+                received = broadcast_rx.recv() => {
+                    // Un client trop lent peut manquer des messages (Lagged) : on continue
+                    if let Ok(announcement) = received {
+                        let announcement = format!("📢 [SERVEUR] {}\n", announcement);
+                        if !server.deliver(&client_info, &mut output, announcement).await {
+                            break;
+                        }
+                    }
+                    continue;
+                }
+                _ = shutdown_rx.changed() => {
+                    server.deliver(&client_info, &mut output, "🛑 Arrêt du serveur, déconnexion...\n").await;
+                    if let Err(e) = server.log_client_message(
+                        LogLevel::Info, &client_info, "DÉCONNEXION (ARRÊT DU SERVEUR)"
+                    ).await {
+                        eprintln!("❌ Erreur lors du logging: {}", e);
+                    }
+                    break;
+                }
+            };
+
+            let line = match next_line {
+                Ok(Some(line)) => line,
+                Ok(None) => break,
+                Err(e) => {
+                    // Données illisibles (UTF-8 invalide...) : on abandonne la connexion
+                    if let Err(log_err) = server.log_warn(&format!(
+                        "⚠️ Données invalides reçues de {}: {}", client_info.id, e
+                    )).await {
+                        eprintln!("❌ Erreur lors du logging: {}", log_err);
+                    }
+                    break;
+                }
+            };
             let message = line.trim();
+            idle_deadline = server.client_timeout.map(|timeout| Instant::now() + timeout);
 
             // Vérifier si le client veut se déconnecter
             if message.to_lowercase() == "quit" || message.to_lowercase() == "exit" {
                 let goodbye_msg = format!("👋 Au revoir {}! Déconnexion...\n", client_info.id);
-                let _ = write_half.write_all(goodbye_msg.as_bytes()).await;
+                server.deliver(&client_info, &mut output, goodbye_msg).await;
 
-                if let Err(e) = server.log_client_message(&client_info, "DÉCONNEXION VOLONTAIRE").await {
+                if let Err(e) = server.log_client_message(LogLevel::Info, &client_info, "DÉCONNEXION VOLONTAIRE").await {
                     eprintln!("❌ Erreur lors du logging: {}", e);
                 }
                 break;
@@ -184,41 +678,131 @@ impl LoggingServer {
                 continue;
             }
 
+            // Client trop bavard : message ignoré, non journalisé
+            if let Some(Err(wait)) = rate_limiter.as_mut().map(TokenBucket::try_consume) {
+                let limited_msg = format!("RATE_LIMITED. Wait {} seconds.\n", wait);
+                if !server.deliver(&client_info, &mut output, limited_msg).await {
+                    break;
+                }
+                continue;
+            }
+
+            // Changement de pseudo : `NICK <nom>`
+            if let Some(nickname) = message.strip_prefix("NICK ").map(str::trim) {
+                let reply = match server.set_nickname(&client_info.id, nickname).await {
+                    Ok(()) => {
+                        if let Err(e) = server.log_info(&format!(
+                            "✏️ Client {} renommé en {}", client_info.display_name(), nickname
+                        )).await {
+                            eprintln!("❌ Erreur lors du logging: {}", e);
+                        }
+                        client_info.nickname = Some(nickname.to_string());
+                        format!("✅ Pseudo défini: {}\n", nickname)
+                    }
+                    Err(e) => format!("❌ {}\n", e),
+                };
+                if !server.deliver(&client_info, &mut output, reply).await {
+                    break;
+                }
+                continue;
+            }
+
+            // Message privé : `MSG <id ou pseudo> <message>`
+            if let Some(rest) = message.strip_prefix("MSG ") {
+                let reply = match rest.trim().split_once(' ') {
+                    Some((target, text)) if !text.trim().is_empty() => {
+                        match server.send_private(&client_info, target, text.trim()).await {
+                            Ok(()) => {
+                                if let Err(e) = server.log_client_message(
+                                    LogLevel::Info, &client_info, &format!("[PRIVATE] à {}: {}", target, text.trim())
+                                ).await {
+                                    eprintln!("❌ Erreur lors du logging: {}", e);
+                                }
+                                server.record_message(&client_info.id).await;
+                                format!("✅ Message privé envoyé à {}\n", target)
+                            }
+                            Err(e) => format!("{}\n", e),
+                        }
+                    }
+                    _ => "❌ Usage: MSG <id ou pseudo> <message>\n".to_string(),
+                };
+                if !server.deliver(&client_info, &mut output, reply).await {
+                    break;
+                }
+                continue;
+            }
+
+            // Recherche dans les logs : traitée avant la journalisation et jamais journalisée,
+            // sinon chaque SEARCH trouverait sa propre ligne
+            let command = message.to_lowercase();
+            let search_reply = if command.starts_with("search_regex ") {
+                // Motif pris tel quel, `(?i)` pour ignorer la casse
+                let pattern = message.split_once(' ').map_or("", |(_, pattern)| pattern.trim());
+                Some(match Regex::new(pattern) {
+                    Ok(regex) => server.search_log(|line| regex.is_match(line)).await,
+                    Err(e) => format!("❌ Regex invalide: {}\n", e),
+                })
+            } else if let Some(needle) = command.strip_prefix("search ") {
+                // Recherche insensible à la casse
+                let needle = needle.trim();
+                Some(server.search_log(|line| line.to_lowercase().contains(needle)).await)
+            } else {
+                None
+            };
+            if let Some(reply) = search_reply {
+                if !server.deliver(&client_info, &mut output, reply).await {
+                    break;
+                }
+                continue;
+            }
+
             // Logger le message du client
-            if let Err(e) = server.log_client_message(&client_info, message).await {
+            if let Err(e) = server.log_client_message(LogLevel::Info, &client_info, message).await {
                 eprintln!("❌ Erreur lors du logging: {}", e);
                 continue;
             }
+            server.record_message(&client_info.id).await;
 
             // Envoyer une confirmation au client
             let confirmation = format!("✅ Message reçu et loggé: '{}'\n", message);
-            if let Err(e) = write_half.write_all(confirmation.as_bytes()).await {
-                eprintln!("❌ Erreur envoi confirmation: {}", e);
+            if !server.deliver(&client_info, &mut output, confirmation).await {
                 break;
             }
 
             // Commandes spéciales
-            match message.to_lowercase().as_str() {
+            match command.as_str() {
                 "stats" => {
                     server.show_stats().await;
                     let stats_msg = "📊 Statistiques affichées dans les logs du serveur\n";
-                    let _ = write_half.write_all(stats_msg.as_bytes()).await;
+                    server.deliver(&client_info, &mut output, stats_msg).await;
+                }
+                "list" => {
+                    let list_msg = if server.list_requires_auth && !authenticated {
+                        "❌ LIST réservé aux clients authentifiés\n".to_string()
+                    } else {
+                        server.list_clients().await
+                    };
+                    server.deliver(&client_info, &mut output, list_msg).await;
                 }
                 "ping" => {
                     let pong_msg = "🏓 Pong! Serveur actif\n";
-                    let _ = write_half.write_all(pong_msg.as_bytes()).await;
+                    server.deliver(&client_info, &mut output, pong_msg).await;
                 }
                 "help" => {
                     let help_msg = "🆘 Commandes disponibles:\n";
                     let help_msg2 = "   - stats: Afficher les statistiques\n";
                     let help_msg3 = "   - ping: Tester la connexion\n";
-                    let help_msg4 = "   - help: Afficher cette aide\n";
-                    let help_msg5 = "   - quit/exit: Se déconnecter\n\n";
-                    let _ = write_half.write_all(help_msg.as_bytes()).await;
-                    let _ = write_half.write_all(help_msg2.as_bytes()).await;
-                    let _ = write_half.write_all(help_msg3.as_bytes()).await;
-                    let _ = write_half.write_all(help_msg4.as_bytes()).await;
-                    let _ = write_half.write_all(help_msg5.as_bytes()).await;
+                    let help_msg4 = "   - NICK <nom>: Choisir un pseudo\n";
+                    let help_msg5 = "   - MSG <id|pseudo> <message>: Envoyer un message privé\n";
+                    let help_msg6 = "   - list: Lister les clients connectés\n";
+                    let help_msg7 = "   - SEARCH <texte> / SEARCH_REGEX <regex>: Chercher dans les logs\n";
+                    let help_msg8 = "   - help: Afficher cette aide\n";
+                    let help_msg9 = "   - quit/exit: Se déconnecter\n\n";
+                    // Une seule entrée dans la file pour toute l'aide
+                    let help = [
+                        help_msg, help_msg2, help_msg3, help_msg4, help_msg5, help_msg6, help_msg7, help_msg8, help_msg9,
+                    ].concat();
+                    server.deliver(&client_info, &mut output, help).await;
                 }
                 _ => {} // Message normal, déjà traité
             }
@@ -226,20 +810,91 @@ impl LoggingServer {
 
         // Nettoyer lors de la déconnexion
         server.remove_client(&client_info.id).await;
+        output.close(OUTPUT_FLUSH_TIMEOUT).await;
 
         Ok(())
     }
 
-    async fn start(&self, addr: &str) -> tokio::io::Result<()> {
+    // Lire les commandes de l'administrateur sur l'entrée standard
+    // La lecture bloquante se fait dans un thread système : une lecture tokio::io::stdin
+    // en attente empêcherait le runtime de s'arrêter tant qu'aucune ligne n'arrive
+    async fn admin_console(&self) {
+        let (command_tx, mut commands) = mpsc::channel::<String>(ADMIN_COMMAND_CAPACITY);
+        std::thread::spawn(move || {
+            for line in std::io::stdin().lines() {
+                let Ok(line) = line else { break };
+                if command_tx.blocking_send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        while let Some(command) = commands.recv().await {
+            let command = command.trim();
+
+            if let Some(announcement) = command.strip_prefix("BROADCAST ") {
+                self.broadcast(announcement).await;
+            } else if !command.is_empty() {
+                println!("💡 Commande admin inconnue. Utilisez: BROADCAST <message>");
+            }
+        }
+    }
+
+    async fn start(
+        &self,
+        addr: &str,
+        tls_acceptor: Option<TlsAcceptor>,
+        http_api: Option<HttpApiConfig>,
+        metrics_port: Option<u16>,
+    ) -> tokio::io::Result<()> {
         let listener = TcpListener::bind(addr).await?;
-        self.log_server_message(&format!("🎯 Serveur en écoute sur {}", addr)).await?;
+        let mode = if tls_acceptor.is_some() { "TLS" } else { "TCP" };
+        self.log_info(&format!("🎯 Serveur en écoute sur {} ({})", addr, mode)).await?;
 
         // Créer un Arc pour partager le serveur entre les tâches
         let server = Arc::new(LoggingServer {
+            log_path: self.log_path.clone(),
             log_file: self.log_file.clone(),
             active_clients: self.active_clients.clone(),
+            formatter: self.formatter.clone(),
+            min_level: self.min_level,
+            auth_token: self.auth_token.clone(),
+            list_requires_auth: self.list_requires_auth,
+            client_rate_limit: self.client_rate_limit,
+            client_buffer_size: self.client_buffer_size,
+            client_timeout: self.client_timeout,
+            keepalive_interval: self.keepalive_interval,
+            broadcast_tx: self.broadcast_tx.clone(),
+            shutdown_tx: self.shutdown_tx.clone(),
+            metrics: self.metrics.clone(),
         });
 
+        // Console d'administration : `BROADCAST <message>` sur l'entrée standard
+        let admin_server = server.clone();
+        tokio::spawn(async move {
+            admin_server.admin_console().await;
+        });
+
+        // API HTTP d'administration, si --http-port est fourni
+        if let Some(config) = http_api {
+            let http_server = server.clone();
+            tokio::spawn(async move {
+                if let Err(e) = http_api::serve(http_server, config).await {
+                    eprintln!("❌ Erreur de l'API HTTP: {}", e);
+                }
+            });
+        }
+
+        // Métriques Prometheus, si --metrics-port est fourni
+        if let Some(port) = metrics_port {
+            let metrics_server = server.clone();
+            tokio::spawn(async move {
+                if let Err(e) = metrics::serve(metrics_server, port).await {
+                    eprintln!("❌ Erreur du serveur de métriques: {}", e);
+                }
+            });
+        }
+
         // Tâche pour afficher les statistiques périodiquement
         let stats_server = server.clone();
         tokio::spawn(async move {
@@ -250,50 +905,165 @@ impl LoggingServer {
             }
         });
 
+        // Ctrl+C : prévenir toutes les tâches via le canal d'arrêt
+        let signal_server = server.clone();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                let _ = signal_server.shutdown_tx.send(true);
+            }
+        });
+
+        let mut shutdown_rx = server.shutdown_tx.subscribe();
+        let mut client_tasks = JoinSet::new();
+
         // Boucle principale d'acceptation des connexions
         loop {
-            match listener.accept().await {
-                Ok((stream, addr)) => {
-                    let server_clone = server.clone();
-
-                    // Spawner une nouvelle tâche pour chaque client
-                    tokio::spawn(async move {
-                        if let Err(e) = LoggingServer::handle_client(server_clone, stream, addr).await {
-                            eprintln!("❌ Erreur avec le client {}: {}", addr, e);
-                        }
-                    });
-                }
-                Err(e) => {
-                    eprintln!("❌ Erreur d'acceptation de connexion: {}", e);
-                    self.log_server_message(&format!("❌ Erreur connexion: {}", e)).await?;
-                }
+            tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, addr)) => {
+                        let server_clone = server.clone();
+                        let tls_acceptor = tls_acceptor.clone();
+
+                        // Spawner une nouvelle tâche pour chaque client
+                        client_tasks.spawn(async move {
+                            // La poignée de main TLS se fait dans la tâche du client
+                            let stream = match tls_acceptor {
+                                Some(acceptor) => match acceptor.accept(stream).await {
+                                    Ok(tls_stream) => ClientStream::Tls(Box::new(tls_stream)),
+                                    Err(e) => {
+                                        if let Err(log_err) = server_clone.log_warn(&format!(
+                                            "🔒 Échec de la poignée de main TLS avec {}: {}", addr, e
+                                        )).await {
+                                            eprintln!("❌ Erreur lors du logging: {}", log_err);
+                                        }
+                                        return;
+                                    }
+                                },
+                                None => ClientStream::Plain(stream),
+                            };
+
+                            if let Err(e) = LoggingServer::handle_client(server_clone, stream, addr).await {
+                                eprintln!("❌ Erreur avec le client {}: {}", addr, e);
+                            }
+                        });
+                    }
+                    Err(e) => {
+                        eprintln!("❌ Erreur d'acceptation de connexion: {}", e);
+                        self.log_error(&format!("❌ Erreur connexion: {}", e)).await?;
+                    }
+                },
+                // Libérer les tâches des clients déjà déconnectés
+                Some(_) = client_tasks.join_next(), if !client_tasks.is_empty() => {}
+                _ = shutdown_rx.changed() => break,
             }
         }
+
+        // Attendre que les clients terminent leur dernière écriture
+        self.log_info(&format!(
+            "🛑 Arrêt demandé, attente de {} connexion(s)...", client_tasks.len()
+        )).await?;
+
+        let drained = tokio::time::timeout(SHUTDOWN_GRACE_PERIOD, async {
+            while client_tasks.join_next().await.is_some() {}
+        }).await;
+
+        if drained.is_err() {
+            self.log_warn(&format!(
+                "⚠️ Délai d'arrêt dépassé, {} connexion(s) interrompue(s)", client_tasks.len()
+            )).await?;
+            client_tasks.abort_all();
+        }
+
+        self.log_info("👋 Serveur arrêté").await?;
+
+        // Ne pas laisser d'archive à moitié compressée
+        self.log_file.lock().await.finish_compression().await;
+        Ok(())
+    }
+}
+
+// Lire la ligne suivante avant `deadline` ; `None` si le délai est écoulé
+async fn read_line_until<R: tokio::io::AsyncBufRead + Unpin>(
+    lines: &mut tokio::io::Lines<R>,
+    deadline: Option<Instant>,
+) -> Option<tokio::io::Result<Option<String>>> {
+    match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline, lines.next_line()).await.ok(),
+        None => Some(lines.next_line().await),
+    }
+}
+
+// Prochain tick du keepalive, jamais s'il est désactivé
+async fn next_tick(keepalive: &mut Option<Interval>) {
+    match keepalive {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
     }
 }
 
 #[tokio::main]
 async fn main() -> tokio::io::Result<()> {
+    let args = Args::parse();
+
     println!("🌟 === SERVEUR DE JOURNALISATION ASYNCHRONE ===");
     println!("📋 Fonctionnalités:");
     println!("   • Support multi-clients simultanés");
     println!("   • Journalisation avec horodatage précis");
-    println!("   • Commandes intégrées (stats, ping, help)");
+    println!("   • Commandes intégrées (stats, list, ping, help)");
     println!("   • Gestion propre des déconnexions");
-    println!("   • Logs sauvegardés dans logs/server.log");
+    println!("   • Logs sauvegardés dans logs/server.log (rotation à {} Mo, {} archives{})",
+             args.max_log_size_mb, args.max_rotated_files,
+             if args.no_compress_rotated { "" } else { " compressées" });
     println!("{}", "=" .repeat(50));
 
+    // Charger le certificat avant tout : une erreur TLS doit arrêter le démarrage
+    let tls_acceptor = match (&args.tls_cert, &args.tls_key) {
+        (Some(cert), Some(key)) => match tls::load_acceptor(cert, key) {
+            Ok(acceptor) => Some(acceptor),
+            Err(e) => {
+                eprintln!("❌ Configuration TLS invalide: {}", e);
+                std::process::exit(1);
+            }
+        },
+        _ => None,
+    };
+
     // Créer le serveur
-    let server = LoggingServer::new().await?;
+    let formatter: Arc<dyn LogFormatter> = match args.log_format {
+        LogFormat::Text => Arc::new(TextFormatter),
+        LogFormat::Json => Arc::new(JsonFormatter),
+    };
+    let server = LoggingServer::new(
+        args.max_log_size_mb * 1024 * 1024,
+        args.max_rotated_files,
+        formatter,
+        args.log_level,
+        args.auth_token,
+        args.list_requires_auth,
+        args.client_rate_limit,
+    ).await?
+        .with_rotated_compression(!args.no_compress_rotated)
+        .with_client_buffer_size(args.client_buffer_size as usize)
+        .with_client_timeouts(
+            (args.client_timeout_secs > 0).then(|| Duration::from_secs(args.client_timeout_secs)),
+            args.keepalive_interval.map(Duration::from_secs),
+        );
 
     // Adresse d'écoute
-    let addr = "127.0.0.1:8080";
+    let addr = format!("127.0.0.1:{}", args.port);
 
     println!("🚀 Démarrage du serveur...");
-    println!("💡 Pour tester, utilisez: telnet 127.0.0.1 8080");
-    println!("💡 Ou: nc 127.0.0.1 8080");
+    println!("💡 Pour tester, utilisez: telnet 127.0.0.1 {}", args.port);
+    println!("💡 Ou: nc 127.0.0.1 {}", args.port);
     println!("💡 Ou utilisez le client de test ci-dessous");
     println!("{}", "=" .repeat(50));
 
-    server.start(addr).await
+    let http_api = args.http_port.map(|port| HttpApiConfig {
+        port,
+        token: args.http_token,
+    });
+
+    server.start(&addr, tls_acceptor, http_api, args.metrics_port).await
 }
\ No newline at end of file
@@ -0,0 +1,88 @@
+use std::sync::Arc;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::extract::State;
+use axum::Router;
+use prometheus::{Encoder, IntCounter, IntGauge, Registry, TextEncoder};
+use tokio::net::TcpListener;
+
+use crate::LoggingServer;
+
+// Compteurs exposés au format Prometheus ; les clones partagent les mêmes valeurs
+#[derive(Clone)]
+pub struct Metrics {
+    registry: Registry,
+    pub active_clients: IntGauge,
+    pub total_connections: IntCounter,
+    pub total_messages: IntCounter,
+    pub log_bytes_written: IntCounter,
+    pub dropped_messages: IntCounter,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+
+        let active_clients = IntGauge::new("logging_server_active_clients", "Clients actuellement connectés")
+            .expect("métrique invalide");
+        let total_connections = IntCounter::new("logging_server_total_connections", "Connexions de clients depuis le démarrage")
+            .expect("métrique invalide");
+        let total_messages = IntCounter::new("logging_server_total_messages", "Messages reçus des clients")
+            .expect("métrique invalide");
+        let log_bytes_written = IntCounter::new("logging_server_log_bytes_written", "Octets écrits dans logs/server.log")
+            .expect("métrique invalide");
+        let dropped_messages = IntCounter::new("logging_server_dropped_messages", "Messages abandonnés pour des clients lents")
+            .expect("métrique invalide");
+
+        // Noms distincts : l'enregistrement ne peut pas échouer
+        registry.register(Box::new(active_clients.clone())).expect("métrique déjà enregistrée");
+        registry.register(Box::new(total_connections.clone())).expect("métrique déjà enregistrée");
+        registry.register(Box::new(total_messages.clone())).expect("métrique déjà enregistrée");
+        registry.register(Box::new(log_bytes_written.clone())).expect("métrique déjà enregistrée");
+        registry.register(Box::new(dropped_messages.clone())).expect("métrique déjà enregistrée");
+
+        Metrics {
+            registry,
+            active_clients,
+            total_connections,
+            total_messages,
+            log_bytes_written,
+            dropped_messages,
+        }
+    }
+
+    // Toutes les métriques au format texte Prometheus
+    fn render(&self) -> Result<String, prometheus::Error> {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        String::from_utf8(buffer).map_err(|e| prometheus::Error::Msg(e.to_string()))
+    }
+}
+
+// Démarrer le serveur de métriques ; il s'arrête avec le serveur (Ctrl+C)
+pub async fn serve(server: Arc<LoggingServer>, port: u16) -> tokio::io::Result<()> {
+    let addr = format!("127.0.0.1:{}", port);
+    let listener = TcpListener::bind(&addr).await?;
+
+    let app = Router::new()
+        .route("/metrics", get(get_metrics))
+        .with_state(server.metrics.clone());
+
+    server.log_info(&format!("📈 Métriques Prometheus sur http://{}/metrics", addr)).await?;
+
+    let mut shutdown_rx = server.shutdown_tx.subscribe();
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async move {
+            let _ = shutdown_rx.changed().await;
+        })
+        .await
+}
+
+// GET /metrics
+async fn get_metrics(State(metrics): State<Metrics>) -> Response {
+    match metrics.render() {
+        Ok(body) => ([(header::CONTENT_TYPE, prometheus::TEXT_FORMAT)], body).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, format!("encodage des métriques impossible: {}\n", e)).into_response(),
+    }
+}
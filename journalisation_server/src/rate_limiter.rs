@@ -0,0 +1,79 @@
+use std::time::Instant;
+
+// Seau à jetons : chaque message consomme un jeton, les jetons se
+// rechargent à `rate` par seconde, dans la limite de `rate` jetons.
+pub struct TokenBucket {
+    rate: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(rate: u32) -> Self {
+        TokenBucket {
+            rate: rate as f64,
+            tokens: rate as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    // Consommer un jeton ; en cas d'échec, renvoie le nombre de secondes à attendre
+    pub fn try_consume(&mut self) -> Result<(), u64> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            let wait = (1.0 - self.tokens) / self.rate;
+            Err(wait.ceil().max(1.0) as u64)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn full_bucket_allows_a_burst_of_rate_messages() {
+        let mut bucket = TokenBucket::new(10);
+        for _ in 0..10 {
+            assert_eq!(bucket.try_consume(), Ok(()));
+        }
+        assert_eq!(bucket.try_consume(), Err(1));
+    }
+
+    #[test]
+    fn tokens_refill_over_time() {
+        let mut bucket = TokenBucket::new(10);
+        while bucket.try_consume().is_ok() {}
+
+        // 10 jetons par seconde : 2 jetons après 200 ms
+        std::thread::sleep(Duration::from_millis(210));
+        assert_eq!(bucket.try_consume(), Ok(()));
+        assert_eq!(bucket.try_consume(), Ok(()));
+        assert!(bucket.try_consume().is_err());
+    }
+
+    #[test]
+    fn refill_is_capped_at_rate() {
+        let mut bucket = TokenBucket::new(2);
+        std::thread::sleep(Duration::from_millis(1100));
+        assert_eq!(bucket.try_consume(), Ok(()));
+        assert_eq!(bucket.try_consume(), Ok(()));
+        assert!(bucket.try_consume().is_err());
+    }
+
+    #[test]
+    fn wait_hint_is_at_least_one_second() {
+        // Il manque une fraction de jeton : l'attente annoncée reste d'une seconde
+        let mut bucket = TokenBucket::new(100);
+        while bucket.try_consume().is_ok() {}
+        assert_eq!(bucket.try_consume(), Err(1));
+    }
+}
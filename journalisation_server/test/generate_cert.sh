@@ -0,0 +1,21 @@
+#!/bin/bash
+
+# Script pour générer un certificat auto-signé (tests TLS en local)
+# Usage: ./generate_cert.sh [dossier]   (par défaut: ./certs)
+# Puis: cargo run -- --tls-cert certs/cert.pem --tls-key certs/key.pem
+
+CERT_DIR=${1:-certs}
+mkdir -p "$CERT_DIR"
+
+echo "🔐 Génération d'un certificat auto-signé dans $CERT_DIR..."
+
+openssl req -x509 -newkey rsa:2048 -nodes \
+    -keyout "$CERT_DIR/key.pem" \
+    -out "$CERT_DIR/cert.pem" \
+    -days 365 \
+    -subj "/CN=localhost" \
+    -addext "subjectAltName=DNS:localhost,IP:127.0.0.1" || exit 1
+
+echo "✅ Certificat: $CERT_DIR/cert.pem"
+echo "✅ Clé privée: $CERT_DIR/key.pem"
+echo "💡 Test: openssl s_client -connect 127.0.0.1:8080 -CAfile $CERT_DIR/cert.pem"
@@ -0,0 +1,67 @@
+#!/bin/bash
+
+# Script pour vérifier la compression gzip des logs archivés après rotation
+# Usage: ./test_log_compression.sh (depuis le dossier où le serveur écrit logs/server.log)
+# Le serveur doit tourner avec une petite limite de taille et sans limite de débit:
+#   cargo run -- --max-log-size-mb 1 --client-rate-limit 0
+
+LINES=${LINES:-12000}
+
+echo "🧪 === TEST DE LA COMPRESSION DES LOGS ARCHIVÉS ==="
+echo "$LINES lignes connues (~1,2 Mo) sont envoyées pour déclencher une rotation"
+echo ""
+
+if ! exec 3<>/dev/tcp/127.0.0.1/8080; then
+    echo "❌ Le serveur ne répond pas sur 127.0.0.1:8080"
+    echo "💡 Démarrez d'abord le serveur avec: cargo run -- --max-log-size-mb 1 --client-rate-limit 0"
+    exit 1
+fi
+
+# Lire les réponses en continu : fermer avec des données non lues couperait la connexion
+timeout 60 cat <&3 >/dev/null &
+reader=$!
+
+token="compression-$$"
+padding=$(printf 'x%.0s' $(seq 1 60))
+for i in $(seq 1 "$LINES"); do
+    echo "$token $i $padding"
+done >&3
+echo "quit" >&3
+wait $reader
+exec 3<&-
+
+# Laisser la tâche de compression se terminer
+sleep 2
+
+failures=0
+archives=$(ls logs/server.log.*.gz 2>/dev/null)
+if [ -z "$archives" ]; then
+    echo "❌ Aucune archive .gz dans logs/"
+    exit 1
+fi
+echo "✅ Archive(s) compressée(s): $(echo $archives | wc -w)"
+
+if gzip -t $archives; then
+    echo "✅ Archives gzip valides"
+else
+    echo "❌ Archive gzip corrompue"
+    failures=$((failures + 1))
+fi
+
+if ls logs/server.log.* | grep -qv '\.gz$'; then
+    echo "❌ Archive non compressée restante"
+    failures=$((failures + 1))
+else
+    echo "✅ Originaux supprimés après compression"
+fi
+
+# Chaque ligne envoyée se trouve exactement une fois, dans une archive ou dans le log courant
+found=$( (gunzip -c $archives; cat logs/server.log) | grep -c "$token [0-9]* $padding")
+if [ "$found" -eq "$LINES" ]; then
+    echo "✅ Contenu décompressé identique: $found/$LINES lignes"
+else
+    echo "❌ Lignes retrouvées: $found/$LINES"
+    failures=$((failures + 1))
+fi
+
+exit $failures
@@ -0,0 +1,686 @@
+// Tests du serveur de journalisation lancé en sous-processus, chacun dans son propre
+// répertoire de travail (logs/server.log) et sur son propre port
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Port distinct par test, les tests tournent en parallèle
+const BASE_PORT: u16 = 18080;
+
+// Serveur arrêté et répertoire supprimé à la fin du test, même en cas d'échec
+struct ServerProcess {
+    child: Child,
+    port: u16,
+    dir: PathBuf,
+}
+
+impl ServerProcess {
+    fn start(port: u16, extra_args: &[&str]) -> Self {
+        Self::start_with_log(port, extra_args, None)
+    }
+
+    // `initial_log` : contenu de logs/server.log avant le démarrage (le serveur écrit à la suite)
+    fn start_with_log(port: u16, extra_args: &[&str], initial_log: Option<&str>) -> Self {
+        let dir = std::env::temp_dir().join(format!("journalisation_server_{}_{}", port, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        if let Some(log) = initial_log {
+            std::fs::create_dir_all(dir.join("logs")).unwrap();
+            std::fs::write(dir.join("logs/server.log"), log).unwrap();
+        }
+
+        // stdin reste ouvert sans jamais recevoir de ligne, comme un terminal inactif
+        let child = Command::new(env!("CARGO_BIN_EXE_journalisation_server"))
+            .args(["--port", &port.to_string()])
+            .args(extra_args)
+            .current_dir(&dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("impossible de lancer journalisation_server");
+        let server = ServerProcess { child, port, dir };
+        server.wait_ready();
+        server
+    }
+
+    fn wait_ready(&self) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Instant::now() < deadline {
+            if TcpStream::connect(("127.0.0.1", self.port)).is_ok() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        panic!("journalisation_server ne répond pas sur le port {}", self.port);
+    }
+
+    fn log(&self) -> String {
+        std::fs::read_to_string(self.dir.join("logs/server.log")).unwrap_or_default()
+    }
+
+    // Attendre qu'une ligne du log contienne `needle` (les écritures suivent la réponse au client)
+    fn wait_for_log(&self, needle: &str) -> String {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            let log = self.log();
+            if log.contains(needle) {
+                return log;
+            }
+            if Instant::now() > deadline {
+                panic!("'{}' absent du log:\n{}", needle, log);
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+    }
+
+    // Commande tapée dans la console d'administration
+    fn admin(&mut self, command: &str) {
+        let stdin = self.child.stdin.as_mut().unwrap();
+        writeln!(stdin, "{}", command).unwrap();
+        stdin.flush().unwrap();
+    }
+
+    // Ctrl+C, puis attente de la fin du processus
+    fn interrupt(&mut self, timeout: Duration) -> Option<ExitStatus> {
+        let status = Command::new("kill")
+            .args(["-INT", &self.child.id().to_string()])
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline {
+            if let Some(status) = self.child.try_wait().unwrap() {
+                return Some(status);
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        None
+    }
+}
+
+impl Drop for ServerProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+// Client connecté, message de bienvenue déjà lu
+struct Client {
+    stream: TcpStream,
+    reader: BufReader<TcpStream>,
+    id: String,
+}
+
+impl Client {
+    fn connect(port: u16) -> Self {
+        let stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        let reader = BufReader::new(stream.try_clone().unwrap());
+        let mut client = Client { stream, reader, id: String::new() };
+
+        let welcome = client.read_until("quit");
+        client.id = welcome
+            .iter()
+            .find_map(|line| line.split("Votre ID: ").nth(1))
+            .and_then(|rest| rest.split(' ').next())
+            .expect("ID absent du message de bienvenue")
+            .to_string();
+        client
+    }
+
+    fn send(&mut self, line: &str) {
+        writeln!(self.stream, "{}", line).unwrap();
+    }
+
+    // Envoyer une ligne et attendre la réponse qui contient `needle`
+    fn request(&mut self, line: &str, needle: &str) -> Vec<String> {
+        self.send(line);
+        self.read_until(needle)
+    }
+
+    // Lire jusqu'à une ligne contenant `needle` ; toutes les lignes lues sont renvoyées
+    fn read_until(&mut self, needle: &str) -> Vec<String> {
+        let mut lines = Vec::new();
+        loop {
+            let mut line = String::new();
+            match self.reader.read_line(&mut line) {
+                Ok(0) | Err(_) => panic!("'{}' non reçu, lignes lues: {:?}", needle, lines),
+                Ok(_) => {
+                    let found = line.contains(needle);
+                    lines.push(line);
+                    if found {
+                        return lines;
+                    }
+                }
+            }
+        }
+    }
+
+    // Lire la réponse à un message : confirmation ou refus pour excès de débit
+    fn read_reply(&mut self) -> Option<String> {
+        loop {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line).ok()? == 0 {
+                return None;
+            }
+            if line.contains("loggé") || line.starts_with("RATE_LIMITED") {
+                return Some(line);
+            }
+        }
+    }
+
+    // Vrai si le serveur a fermé la connexion
+    fn is_closed(&mut self) -> bool {
+        let mut rest = String::new();
+        std::io::Read::read_to_string(&mut self.reader, &mut rest).is_ok()
+    }
+}
+
+#[test]
+fn ctrl_c_during_writes_drains_clients_and_exits() {
+    let mut server = ServerProcess::start(BASE_PORT, &["--client-rate-limit", "0"]);
+    let mut client = Client::connect(server.port);
+
+    // Le client écrit sans interruption jusqu'à ce que le serveur ferme la connexion
+    let mut stream = client.stream.try_clone().unwrap();
+    let writer = thread::spawn(move || {
+        let mut sent = 0;
+        while writeln!(stream, "ligne {}", sent).is_ok() {
+            sent += 1;
+            thread::sleep(Duration::from_millis(1));
+        }
+        sent
+    });
+    thread::sleep(Duration::from_millis(300));
+
+    // Délai de grâce de 5 s côté serveur : au-delà, l'arrêt est bloqué
+    let status = server.interrupt(Duration::from_secs(8)).expect("le serveur ne s'arrête pas après Ctrl+C");
+    assert!(status.success(), "{:?}", status);
+
+    client.read_until("Arrêt du serveur");
+    assert!(writer.join().unwrap() > 0);
+
+    let log = server.log();
+    assert!(log.contains("ligne 0"));
+    assert!(log.contains("DÉCONNEXION (ARRÊT DU SERVEUR)"));
+    assert!(log.contains("Arrêt demandé, attente de 1 connexion(s)"));
+    assert!(log.trim_end().ends_with("Serveur arrêté"), "{}", log);
+}
+
+#[test]
+fn idle_clients_are_drained_on_shutdown() {
+    let mut server = ServerProcess::start(BASE_PORT + 1, &[]);
+    let mut clients: Vec<Client> = (0..2).map(|_| Client::connect(server.port)).collect();
+    for client in &mut clients {
+        client.request("ping", "Pong");
+    }
+
+    let status = server.interrupt(Duration::from_secs(8)).expect("le serveur ne s'arrête pas après Ctrl+C");
+    assert!(status.success());
+    for client in &mut clients {
+        client.read_until("Arrêt du serveur");
+        assert!(client.is_closed());
+    }
+
+    let log = server.log();
+    assert_eq!(log.matches("DÉCONNEXION (ARRÊT DU SERVEUR)").count(), 2);
+    assert!(!log.contains("Délai d'arrêt dépassé"));
+}
+
+#[test]
+fn json_log_lines_parse_with_serde_json() {
+    let server = ServerProcess::start(BASE_PORT + 2, &["--log-format", "json"]);
+    let mut client = Client::connect(server.port);
+    client.request("bonjour en JSON", "loggé");
+    client.request("NICK alice", "Pseudo défini");
+    client.request("toujours en JSON", "loggé");
+    let log = server.wait_for_log("toujours en JSON");
+
+    let entries: Vec<serde_json::Value> = log
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap_or_else(|e| panic!("ligne JSON invalide ({}): {}", e, line)))
+        .collect();
+    assert!(entries.iter().all(|entry| entry["timestamp"].is_string() && entry["level"].is_string()));
+
+    let message = entries.iter().find(|entry| entry["message"] == "bonjour en JSON").unwrap();
+    assert_eq!(message["level"], "INFO");
+    assert_eq!(message["source"], "CLIENT");
+    assert_eq!(message["client_id"], client.id.as_str());
+    assert!(message["address"].as_str().unwrap().starts_with("127.0.0.1:"));
+    assert!(message.get("nickname").is_none());
+
+    let renamed = entries.iter().find(|entry| entry["message"] == "toujours en JSON").unwrap();
+    assert_eq!(renamed["nickname"], "alice");
+    assert!(entries.iter().any(|entry| entry["source"] == "SERVER"));
+}
+
+#[test]
+fn log_level_warn_suppresses_info() {
+    let server = ServerProcess::start(BASE_PORT + 3, &["--log-level", "warn"]);
+    let mut client = Client::connect(server.port);
+    client.request("message ordinaire", "loggé");
+
+    // UTF-8 invalide : avertissement et déconnexion
+    let mut intruder = Client::connect(server.port);
+    intruder.stream.write_all(&[0xff, 0xfe, b'\n']).unwrap();
+    assert!(intruder.is_closed());
+
+    let log = server.wait_for_log("Données invalides");
+    assert!(!log.contains("message ordinaire"), "{}", log);
+    assert!(!log.contains("Nouveau client connecté"), "{}", log);
+    assert!(!log.contains("Serveur en écoute"), "{}", log);
+}
+
+#[test]
+fn wrong_auth_token_is_rejected_and_logged() {
+    let server = ServerProcess::start(BASE_PORT + 4, &["--auth-token", "secret"]);
+
+    let mut intruder = Client::connect(server.port);
+    intruder.read_until("Authentification requise");
+    intruder.request("AUTH mauvais", "AUTH FAILED");
+    assert!(intruder.is_closed());
+    let log = server.wait_for_log("Authentification refusée");
+    assert!(log.contains(&format!("Authentification refusée pour {} [127.0.0.1]", intruder.id)), "{}", log);
+
+    // Le bon jeton ouvre la session normalement
+    let mut client = Client::connect(server.port);
+    client.read_until("Authentification requise");
+    client.send("AUTH secret");
+    client.request("message authentifié", "loggé");
+    server.wait_for_log("message authentifié");
+}
+
+#[test]
+fn broadcast_reaches_three_clients() {
+    let mut server = ServerProcess::start(BASE_PORT + 5, &[]);
+    let mut clients: Vec<Client> = (0..3).map(|_| Client::connect(server.port)).collect();
+    // Une réponse reçue garantit que le client est inscrit à la diffusion
+    for client in &mut clients {
+        client.request("ping", "Pong");
+    }
+
+    server.admin("BROADCAST maintenance dans 60 secondes");
+    for client in &mut clients {
+        client.read_until("[SERVEUR] maintenance dans 60 secondes");
+    }
+}
+
+#[test]
+fn log_entries_use_the_nickname_after_nick() {
+    let server = ServerProcess::start(BASE_PORT + 6, &[]);
+    let mut client = Client::connect(server.port);
+    client.request("avant le pseudo", "loggé");
+    client.request("NICK alice", "Pseudo défini: alice");
+    client.request("après le pseudo", "loggé");
+    let log = server.wait_for_log("après le pseudo");
+
+    let line = |needle: &str| log.lines().find(|line| line.ends_with(needle)).unwrap().to_string();
+    assert!(line("avant le pseudo").contains(&format!("[CLIENT:{}]", client.id)));
+    assert!(line("après le pseudo").contains("[CLIENT:alice]"));
+    assert!(log.contains(&format!("Client {} renommé en alice", client.id)));
+
+    // Pseudo invalide ou déjà pris
+    let mut other = Client::connect(server.port);
+    other.request("NICK alice", "❌");
+    other.request("NICK pas valide!", "❌");
+}
+
+#[test]
+fn second_client_sees_the_first_in_list() {
+    let server = ServerProcess::start(BASE_PORT + 7, &[]);
+    let mut first = Client::connect(server.port);
+    first.request("NICK alice", "Pseudo défini");
+
+    let mut second = Client::connect(server.port);
+    second.send("list");
+    second.read_until("Clients connectés: 2");
+    let entries = [second.read_until("message(s)"), second.read_until("message(s)")].concat();
+    assert!(entries.iter().any(|line| line.contains("alice [127.0.0.1:")), "{:?}", entries);
+    assert!(entries.iter().any(|line| line.contains(&second.id)), "{:?}", entries);
+}
+
+#[test]
+fn list_requires_auth_without_a_token_is_rejected_at_startup() {
+    // Sans jeton personne ne peut s'authentifier : LIST serait toujours refusé
+    let output = Command::new(env!("CARGO_BIN_EXE_journalisation_server"))
+        .arg("--list-requires-auth")
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--auth-token"));
+}
+
+#[test]
+fn private_message_reaches_only_its_target() {
+    let server = ServerProcess::start(BASE_PORT + 13, &[]);
+    let mut alice = Client::connect(server.port);
+    alice.request("NICK alice", "Pseudo défini");
+    let mut bob = Client::connect(server.port);
+    bob.request("NICK bob", "Pseudo défini");
+    let mut carol = Client::connect(server.port);
+
+    // Par pseudo (sans tenir compte de la casse) puis par ID
+    bob.request("MSG Alice Salut Alice, c'est Bob", "Message privé envoyé à Alice");
+    alice.read_until("[PRIVATE from bob]: Salut Alice, c'est Bob");
+    let id = alice.id.clone();
+    carol.request(&format!("MSG {} coucou", id), "Message privé envoyé");
+    alice.read_until(&format!("[PRIVATE from {}]: coucou", carol.id));
+
+    let reply = bob.request("MSG fantome tu es là ?", "not connected");
+    assert_eq!(reply.last().unwrap().trim_end(), "ERROR: fantome not connected");
+    bob.request("MSG alice", "Usage: MSG");
+
+    // Carol ne reçoit pas le message adressé à Alice
+    let reply = carol.request("ping", "Pong");
+    assert!(reply.iter().all(|line| !line.contains("PRIVATE")), "{:?}", reply);
+
+    let log = server.wait_for_log("[PRIVATE] à Alice: Salut Alice, c'est Bob");
+    assert!(!log.contains("fantome"), "{}", log);
+}
+
+#[test]
+fn rate_limit_logs_about_ten_of_fifty_messages() {
+    let server = ServerProcess::start(BASE_PORT + 8, &[]);
+    let mut client = Client::connect(server.port);
+
+    let started = Instant::now();
+    for index in 0..50 {
+        client.send(&format!("rafale {}", index));
+    }
+    // Une réponse par message : loggé ou RATE_LIMITED
+    let replies: Vec<String> = (0..50).filter_map(|_| client.read_reply()).collect();
+    let elapsed = started.elapsed().as_secs_f64();
+
+    let logged = replies.iter().filter(|line| line.contains("loggé")).count();
+    let limited = replies.iter().filter(|line| line.starts_with("RATE_LIMITED. Wait ")).count();
+    assert_eq!(logged + limited, 50);
+    assert!(limited > 0);
+
+    // Seau plein au départ (10 jetons) puis 10 jetons par seconde
+    let allowed = 10 + (elapsed * 10.0).ceil() as usize;
+    assert!((10..=allowed).contains(&logged), "{} messages loggés en {:.2} s", logged, elapsed);
+    // Chaque message est journalisé avant la confirmation
+    assert_eq!(server.log().matches("rafale").count(), logged);
+}
+
+#[tokio::test]
+async fn silent_client_is_disconnected_after_the_timeout() {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+
+    let server = ServerProcess::start(BASE_PORT + 12, &["--client-timeout-secs", "2", "--keepalive-interval", "1"]);
+
+    let silent = tokio::net::TcpStream::connect(("127.0.0.1", server.port)).await.unwrap();
+    let mut silent = tokio::io::BufReader::new(silent).lines();
+    let chatty = tokio::net::TcpStream::connect(("127.0.0.1", server.port)).await.unwrap();
+    let (chatty_read, mut chatty_write) = chatty.into_split();
+    let mut chatty_lines = tokio::io::BufReader::new(chatty_read).lines();
+
+    // Un client qui parle toutes les 500 ms repousse son échéance et reste connecté
+    let chatter = tokio::spawn(async move {
+        for index in 0..6 {
+            chatty_write.write_all(format!("toujours là {}\n", index).as_bytes()).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+        chatty_write
+    });
+
+    // Les `NOP` du keepalive ne comptent pas comme de l'activité du client
+    let started = Instant::now();
+    let mut received = Vec::new();
+    let read_all = async {
+        while let Some(line) = silent.next_line().await.unwrap() {
+            received.push(line);
+        }
+    };
+    tokio::time::timeout(Duration::from_secs(6), read_all).await.expect("client silencieux toujours connecté");
+    let elapsed = started.elapsed();
+
+    assert!(elapsed >= Duration::from_millis(1900), "déconnecté après {:?}", elapsed);
+    assert_eq!(received.last().map(String::as_str), Some("TIMEOUT. Disconnecting."));
+    assert!(received.iter().any(|line| line == "NOP"), "{:?}", received);
+    server.wait_for_log("DÉCONNEXION (INACTIVITÉ)");
+
+    let mut chatty_write = chatter.await.unwrap();
+    chatty_write.write_all(b"ping\n").await.unwrap();
+    while let Some(line) = chatty_lines.next_line().await.unwrap() {
+        assert_ne!(line, "TIMEOUT. Disconnecting.");
+        if line.contains("Pong") {
+            break;
+        }
+    }
+}
+
+#[tokio::test]
+async fn tls_client_completes_handshake_and_is_logged() {
+    use std::sync::Arc;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+    use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName};
+    use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+    use tokio_rustls::TlsConnector;
+
+    // Certificat auto-signé pour localhost, écrit dans le répertoire du serveur
+    let certified = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+    let cert_dir = std::env::temp_dir().join(format!("journalisation_server_certs_{}", std::process::id()));
+    std::fs::create_dir_all(&cert_dir).unwrap();
+    let cert_path = cert_dir.join("cert.pem");
+    let key_path = cert_dir.join("key.pem");
+    std::fs::write(&cert_path, certified.cert.pem()).unwrap();
+    std::fs::write(&key_path, certified.signing_key.serialize_pem()).unwrap();
+
+    let server = ServerProcess::start(BASE_PORT + 9, &[
+        "--tls-cert", cert_path.to_str().unwrap(),
+        "--tls-key", key_path.to_str().unwrap(),
+    ]);
+    let _ = std::fs::remove_dir_all(&cert_dir);
+
+    let mut roots = RootCertStore::empty();
+    roots.add(CertificateDer::from(certified.cert.der().to_vec())).unwrap();
+    let config = ClientConfig::builder().with_root_certificates(roots).with_no_client_auth();
+    let connector = TlsConnector::from(Arc::new(config));
+
+    let tcp = tokio::net::TcpStream::connect(("127.0.0.1", server.port)).await.unwrap();
+    let tls = connector.connect(ServerName::try_from("localhost").unwrap(), tcp).await.unwrap();
+    let (read_half, mut write_half) = tokio::io::split(tls);
+    let mut lines = tokio::io::BufReader::new(read_half).lines();
+
+    let mut received = Vec::new();
+    while let Some(line) = lines.next_line().await.unwrap() {
+        let done = line.contains("quit");
+        received.push(line);
+        if done {
+            break;
+        }
+    }
+    assert!(received[0].contains("Bienvenue"));
+
+    write_half.write_all(b"message chiffre\n").await.unwrap();
+    while let Some(line) = lines.next_line().await.unwrap() {
+        if line.contains("loggé") {
+            break;
+        }
+    }
+    let log = server.wait_for_log("message chiffre");
+    assert!(log.contains("(TLS)"));
+
+    // Un client en clair échoue à la poignée de main, ce qui est journalisé
+    let mut plain = TcpStream::connect(("127.0.0.1", server.port)).unwrap();
+    plain.write_all(b"pas du TLS\n").unwrap();
+    server.wait_for_log("Échec de la poignée de main TLS");
+}
+
+#[tokio::test]
+async fn http_api_serves_logs_clients_and_broadcast() {
+    let http_port = BASE_PORT + 100;
+    let server = ServerProcess::start(BASE_PORT + 10, &[
+        "--http-port", &http_port.to_string(),
+        "--http-token", "secret",
+    ]);
+    let base = format!("http://127.0.0.1:{}", http_port);
+    let http = reqwest::Client::new();
+
+    // L'API démarre en parallèle du serveur TCP
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while http.get(format!("{}/clients", base)).send().await.is_err() {
+        assert!(Instant::now() < deadline, "API HTTP injoignable");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    let port = server.port;
+    let mut client = tokio::task::spawn_blocking(move || {
+        let mut client = Client::connect(port);
+        client.request("NICK alice", "Pseudo défini");
+        client.request("message pour l'API", "loggé");
+        client
+    }).await.unwrap();
+
+    let unauthorized = http.get(format!("{}/logs", base)).send().await.unwrap();
+    assert_eq!(unauthorized.status(), reqwest::StatusCode::UNAUTHORIZED);
+    let wrong = http.get(format!("{}/logs", base)).bearer_auth("faux").send().await.unwrap();
+    assert_eq!(wrong.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let logs: Vec<String> = http.get(format!("{}/logs?limit=500", base))
+        .bearer_auth("secret").send().await.unwrap()
+        .json().await.unwrap();
+    assert!(logs.iter().any(|line| line.ends_with("message pour l'API")), "{:?}", logs);
+
+    let limited: Vec<String> = http.get(format!("{}/logs?limit=1", base))
+        .bearer_auth("secret").send().await.unwrap()
+        .json().await.unwrap();
+    // Les lignes les plus récentes : le message vient d'être journalisé
+    assert_eq!(limited.len(), 1);
+    assert!(limited[0].ends_with("message pour l'API"), "{:?}", limited);
+
+    let future: Vec<String> = http.get(format!("{}/logs?since=2999-01-01T00:00:00", base))
+        .bearer_auth("secret").send().await.unwrap()
+        .json().await.unwrap();
+    assert!(future.is_empty());
+
+    let invalid = http.get(format!("{}/logs?since=hier", base)).bearer_auth("secret").send().await.unwrap();
+    assert_eq!(invalid.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    let clients: serde_json::Value = http.get(format!("{}/clients", base))
+        .bearer_auth("secret").send().await.unwrap()
+        .json().await.unwrap();
+    assert_eq!(clients.as_array().unwrap().len(), 1);
+    assert_eq!(clients[0]["nickname"], "alice");
+    assert_eq!(clients[0]["id"], client.id.as_str());
+
+    let broadcast: serde_json::Value = http.post(format!("{}/broadcast", base))
+        .bearer_auth("secret")
+        .json(&serde_json::json!({ "message": "annonce HTTP" }))
+        .send().await.unwrap()
+        .json().await.unwrap();
+    assert_eq!(broadcast["recipients"], 1);
+    tokio::task::spawn_blocking(move || client.read_until("[SERVEUR] annonce HTTP")).await.unwrap();
+}
+
+#[test]
+fn search_returns_the_newest_matches_and_never_itself() {
+    let fixture = include_str!("fixtures/search.log");
+    let server = ServerProcess::start_with_log(BASE_PORT + 11, &[], Some(fixture));
+    let mut client = Client::connect(server.port);
+
+    // 105 lignes correspondent : seules les 100 plus récentes sont renvoyées, dans l'ordre du log
+    let reply = client.request("SEARCH erreur DISQUE", "SEARCH DONE");
+    let results: Vec<&str> = reply.iter().map(|line| line.trim_end()).filter(|line| line.starts_with("RESULT: ")).collect();
+    assert_eq!(results.len(), 100);
+    assert!(results[0].ends_with("Erreur disque n°6"), "{}", results[0]);
+    assert!(results[99].ends_with("Erreur disque n°105"), "{}", results[99]);
+    assert_eq!(reply.last().unwrap().trim_end(), "SEARCH DONE: 100 results");
+
+    let reply = client.request(r"SEARCH_REGEX n°10\d$", "SEARCH DONE");
+    assert_eq!(reply.last().unwrap().trim_end(), "SEARCH DONE: 6 results");
+
+    // La commande n'est pas journalisée, elle ne se trouve donc jamais elle-même
+    let reply = client.request("SEARCH introuvable", "SEARCH DONE");
+    assert_eq!(reply, ["SEARCH DONE: 0 results\n"]);
+    client.request("SEARCH introuvable", "SEARCH DONE: 0 results");
+    assert!(!server.log().contains("introuvable"));
+}
+
+// Valeur d'une métrique dans le format texte Prometheus (`<nom> <valeur>`)
+fn metric(body: &str, name: &str) -> u64 {
+    body.lines()
+        .find_map(|line| line.strip_prefix(name)?.strip_prefix(' '))
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or_else(|| panic!("métrique {} absente:\n{}", name, body))
+}
+
+#[tokio::test]
+async fn metrics_follow_a_client_connection_and_messages() {
+    let metrics_port = BASE_PORT + 101;
+    let server = ServerProcess::start(BASE_PORT + 14, &["--metrics-port", &metrics_port.to_string()]);
+    let url = format!("http://127.0.0.1:{}/metrics", metrics_port);
+    let http = reqwest::Client::new();
+
+    // La connexion de `wait_ready` compte aussi : attendre qu'elle soit enregistrée puis fermée
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let before = loop {
+        if let Ok(response) = http.get(&url).send().await {
+            let body = response.text().await.unwrap();
+            if metric(&body, "logging_server_total_connections") == 1
+                && metric(&body, "logging_server_active_clients") == 0
+            {
+                break body;
+            }
+        }
+        assert!(Instant::now() < deadline, "serveur de métriques injoignable");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    };
+
+    // Chaque métrique a son aide, son type et sa valeur
+    for (name, kind) in [
+        ("logging_server_active_clients", "gauge"),
+        ("logging_server_total_connections", "counter"),
+        ("logging_server_total_messages", "counter"),
+        ("logging_server_log_bytes_written", "counter"),
+        ("logging_server_dropped_messages", "counter"),
+    ] {
+        assert!(before.contains(&format!("# HELP {} ", name)), "{}", before);
+        assert!(before.contains(&format!("# TYPE {} {}\n", name, kind)), "{}", before);
+    }
+    assert_eq!(metric(&before, "logging_server_total_messages"), 0);
+
+    let port = server.port;
+    let mut client = tokio::task::spawn_blocking(move || {
+        let mut client = Client::connect(port);
+        for index in 1..=3 {
+            client.request(&format!("message {}", index), "loggé");
+        }
+        client
+    }).await.unwrap();
+
+    let during = http.get(&url).send().await.unwrap().text().await.unwrap();
+    assert_eq!(metric(&during, "logging_server_active_clients"), 1);
+    assert_eq!(metric(&during, "logging_server_total_connections"), 2);
+    assert_eq!(metric(&during, "logging_server_total_messages"), 3);
+    assert!(metric(&during, "logging_server_log_bytes_written") > metric(&before, "logging_server_log_bytes_written"));
+
+    client = tokio::task::spawn_blocking(move || {
+        client.request("quit", "Au revoir");
+        client
+    }).await.unwrap();
+    server.wait_for_log(&format!("Client déconnecté: {}", client.id));
+
+    // Le compteur d'octets suit les écritures, qui précèdent de peu sa mise à jour
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        let after = http.get(&url).send().await.unwrap().text().await.unwrap();
+        assert_eq!(metric(&after, "logging_server_active_clients"), 0);
+        assert_eq!(metric(&after, "logging_server_total_connections"), 2);
+        if metric(&after, "logging_server_log_bytes_written") == server.log().len() as u64 {
+            break;
+        }
+        assert!(Instant::now() < deadline, "{}", after);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    drop(client);
+}
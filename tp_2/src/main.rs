@@ -1,55 +1,607 @@
-use std::io;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, Error, ErrorKind, Result as IoResult};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+use chrono::{DateTime, Datelike, Days, Local, Months, NaiveDate, SecondsFormat, Utc};
+use clap::Parser;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
 
+#[derive(Parser)]
+#[command(name = "bank")]
+#[command(about = "Système bancaire simple en Rust")]
+struct Args {
+    /// Fichier JSON des comptes : chargé au démarrage et sauvegardé en quittant
+    #[arg(long)]
+    data_file: Option<PathBuf>,
+
+    /// Devise des comptes créés pendant la session (EUR, USD, GBP, JPY, CHF)
+    #[arg(long, default_value = "EUR", value_parser = parse_currency)]
+    currency: Currency,
+}
+
+fn parse_currency(code: &str) -> Result<Currency, String> {
+    Currency::from_code(code).ok_or_else(|| {
+        let codes: Vec<&str> = Currency::ALL.iter().map(|currency| currency.code()).collect();
+        format!("devise inconnue '{}' (attendu: {})", code, codes.join(", "))
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum TransactionKind {
+    Deposit,
+    Withdrawal,
+    Transfer { counterpart: String },
+    Interest,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+enum AccountType {
+    #[default]
+    Checking,   // Compte courant : pas d'intérêts
+    Savings,    // Épargne : intérêts crédités
+    Credit,     // Crédit : intérêts débités sur le montant dû
+}
+
+impl AccountType {
+    fn label(&self) -> &'static str {
+        match self {
+            AccountType::Checking => "Courant",
+            AccountType::Savings => "Épargne",
+            AccountType::Credit => "Crédit",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+enum Currency {
+    #[default]
+    Eur,
+    Usd,
+    Gbp,
+    Jpy,
+    Chf,
+}
+
+impl Currency {
+    const ALL: [Currency; 5] = [Currency::Eur, Currency::Usd, Currency::Gbp, Currency::Jpy, Currency::Chf];
+
+    fn code(&self) -> &'static str {
+        match self {
+            Currency::Eur => "EUR",
+            Currency::Usd => "USD",
+            Currency::Gbp => "GBP",
+            Currency::Jpy => "JPY",
+            Currency::Chf => "CHF",
+        }
+    }
+
+    fn symbol(&self) -> &'static str {
+        match self {
+            Currency::Eur => "€",
+            Currency::Usd => "$",
+            Currency::Gbp => "£",
+            Currency::Jpy => "¥",
+            Currency::Chf => "CHF",
+        }
+    }
+
+    fn from_code(code: &str) -> Option<Currency> {
+        Currency::ALL.into_iter().find(|currency| currency.code().eq_ignore_ascii_case(code))
+    }
+}
+
+// Taux de change : 1 unité de la première devise = `rate` unités de la seconde
+struct ExchangeRates {
+    rates: HashMap<(Currency, Currency), Decimal>,
+    fetched_at: Instant,
+}
+
+impl ExchangeRates {
+    // Table fixe en attendant une API : chaque devise est cotée par rapport à l'euro
+    fn hardcoded() -> Self {
+        let rates = HashMap::from([
+            ((Currency::Eur, Currency::Usd), dec!(1.08)),
+            ((Currency::Eur, Currency::Gbp), dec!(0.85)),
+            ((Currency::Eur, Currency::Jpy), dec!(160)),
+            ((Currency::Eur, Currency::Chf), dec!(0.95)),
+        ]);
+        ExchangeRates { rates, fetched_at: Instant::now() }
+    }
+
+    // Taux direct, inverse, ou croisé via l'euro
+    fn rate(&self, from: Currency, to: Currency) -> Option<Decimal> {
+        if from == to {
+            return Some(Decimal::ONE);
+        }
+        if let Some(rate) = self.rates.get(&(from, to)) {
+            return Some(*rate);
+        }
+        if let Some(rate) = self.rates.get(&(to, from)) {
+            return Some(Decimal::ONE / rate);
+        }
+        if from != Currency::Eur && to != Currency::Eur {
+            return Some(self.rate(from, Currency::Eur)? * self.rate(Currency::Eur, to)?);
+        }
+        None
+    }
+}
+
+// Point d'entrée pour une future récupération des taux via une API HTTP
+fn load_exchange_rates() -> ExchangeRates {
+    ExchangeRates::hardcoded()
+}
+
+// Conversion exacte (sans arrondi) ; la table fixe couvre toutes les paires via l'euro
+fn convert(amount: Decimal, from: Currency, to: Currency, rates: &ExchangeRates) -> Decimal {
+    let rate = rates.rate(from, to)
+        .unwrap_or_else(|| panic!("Taux de change {} -> {} manquant", from.code(), to.code()));
+    amount * rate
+}
+
+impl TransactionKind {
+    fn label(&self) -> String {
+        match self {
+            TransactionKind::Deposit => "Dépôt".to_string(),
+            TransactionKind::Withdrawal => "Retrait".to_string(),
+            TransactionKind::Transfer { counterpart } => format!("Virement ({})", counterpart),
+            TransactionKind::Interest => "Intérêts".to_string(),
+        }
+    }
+}
+
+// Une ligne du relevé de compte
+// Pour un virement ou des intérêts, le montant est négatif côté compte débité
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Transaction {
+    kind: TransactionKind,
+    amount: Decimal,
+    balance_after: Decimal,
+    timestamp: DateTime<Local>,
+}
+
+impl Transaction {
+    // Montant signé : positif au crédit, négatif au débit
+    fn signed_amount(&self) -> Decimal {
+        match self.kind {
+            TransactionKind::Withdrawal => -self.amount,
+            _ => self.amount,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum RecurrenceInterval {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl RecurrenceInterval {
+    fn label(&self) -> &'static str {
+        match self {
+            RecurrenceInterval::Daily => "Quotidienne",
+            RecurrenceInterval::Weekly => "Hebdomadaire",
+            RecurrenceInterval::Monthly => "Mensuelle",
+            RecurrenceInterval::Yearly => "Annuelle",
+        }
+    }
+
+    // Échéance suivante ; les mois sont comptés depuis le jour d'ancrage (`anchor_day`) et non
+    // depuis l'échéance précédente : un 31 devient le 30 ou le 28 puis revient au 31
+    fn next_after(&self, date: NaiveDate, anchor_day: u32) -> Option<NaiveDate> {
+        let months = match self {
+            RecurrenceInterval::Daily => return date.checked_add_days(Days::new(1)),
+            RecurrenceInterval::Weekly => return date.checked_add_days(Days::new(7)),
+            RecurrenceInterval::Monthly => 1,
+            RecurrenceInterval::Yearly => 12,
+        };
+        let first_of_month = date.with_day(1)?.checked_add_months(Months::new(months))?;
+        let last_day = first_of_month.checked_add_months(Months::new(1))?.pred_opt()?.day();
+        first_of_month.with_day(anchor_day.min(last_day))
+    }
+}
+
+// Opération programmée (dépôt ou retrait), appliquée à chaque échéance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecurringTransaction {
+    id: Uuid,
+    description: String,
+    amount: Decimal,
+    kind: TransactionKind,
+    interval: RecurrenceInterval,
+    next_due: NaiveDate,
+    #[serde(default)]
+    anchor_day: Option<u32>,    // Jour du mois visé (jour de la première échéance)
+}
+
+// Les montants `Decimal` sont sérialisés en chaînes pour rester exacts en JSON
+#[derive(Serialize, Deserialize)]
 struct BankAccount {
     account_number: String,
     holder_name: String,
-    balance: f64,
+    balance: Decimal,
+    transactions: Vec<Transaction>,
+    pin_hash: Option<u64>,  // Empreinte du code PIN, jamais le code en clair
+    frozen: bool,           // Compte gelé : aucune opération autorisée
+    overdraft_limit: Decimal, // Découvert autorisé (le solde peut descendre jusqu'à -limite)
+    #[serde(default)]
+    interest_rate: f64,     // Taux annuel en pourcentage (ex: 12.0 pour 12%)
+    #[serde(default)]
+    account_type: AccountType,
+    #[serde(default)]
+    balance_currency: Currency,
+    #[serde(default)]
+    recurring_transactions: Vec<RecurringTransaction>,
+    #[serde(default)]
+    sealed: bool,           // Journal scellé : historique en ajout seul
+    #[serde(default)]
+    ledger_root_hash: Option<String>, // Dernier maillon de la chaîne SHA-256 des transactions
+}
+
+// Maillon de la chaîne : SHA-256 du maillon précédent suivi de la forme canonique de la transaction
+// Modifier une transaction passée change tous les maillons suivants, donc la racine
+fn chain_hash(previous: &str, transaction: &Transaction) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(previous.as_bytes());
+    hasher.update(canonical_entry(transaction).as_bytes());
+    hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+// Date en UTC (RFC 3339) : le JSON d'un DateTime<Local> dépend du fuseau de la machine qui
+// charge le fichier, la racine ne doit pas en dépendre
+fn canonical_entry(transaction: &Transaction) -> String {
+    let kind = serde_json::to_string(&transaction.kind).expect("transaction non sérialisable");
+    format!(
+        "{}|{}|{}|{}",
+        kind,
+        transaction.amount,
+        transaction.balance_after,
+        transaction.timestamp.with_timezone(&Utc).to_rfc3339_opts(SecondsFormat::Nanos, true)
+    )
+}
+
+// Racine de la chaîne pour tout un historique (chaîne vide pour un journal vide)
+fn ledger_root(transactions: &[Transaction]) -> String {
+    transactions.iter().fold(String::new(), |previous, transaction| chain_hash(&previous, transaction))
+}
+
+// Nombre d'essais de code PIN avant verrouillage du compte pour la session
+const MAX_PIN_ATTEMPTS: u32 = 3;
+
+// Empreinte FNV-1a du code PIN (simple mais stable d'une exécution à l'autre)
+fn hash_pin(pin: &str) -> u64 {
+    pin.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+// Algorithme de Luhn : en partant de la droite, un chiffre sur deux est doublé
+// (moins 9 s'il dépasse 9) et la somme totale doit être un multiple de 10
+fn is_valid_account_number(s: &str) -> bool {
+    if s.is_empty() || !s.chars().all(|c| c.is_ascii_digit()) {
+        return false;
+    }
+
+    let sum: u32 = s.chars()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(position, digit)| {
+            if position % 2 == 1 {
+                let doubled = digit * 2;
+                if doubled > 9 { doubled - 9 } else { doubled }
+            } else {
+                digit
+            }
+        })
+        .sum();
+
+    sum.is_multiple_of(10)
+}
+
+// Codes utilisés pour les numéros générés à la création d'un compte
+const BANK_CODE: &str = "10001";
+const BRANCH_CODE: &str = "00042";
+
+// Compteur des numéros générés
+static NEXT_ACCOUNT_SEQUENCE: AtomicU64 = AtomicU64::new(1);
+
+// Chiffre qui, ajouté à droite de `payload`, rend le tout valide pour Luhn
+fn luhn_check_digit(payload: &str) -> u32 {
+    // Le chiffre de contrôle occupera la position 0 : ceux du payload sont décalés d'un cran
+    let sum: u32 = payload.chars()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(position, digit)| {
+            if position % 2 == 0 {
+                let doubled = digit * 2;
+                if doubled > 9 { doubled - 9 } else { doubled }
+            } else {
+                digit
+            }
+        })
+        .sum();
+
+    (10 - sum % 10) % 10
+}
+
+// Numéro façon RIB : banque (5) + guichet (5) + compteur (11) + clé de Luhn (1)
+// Seuls les chiffres des codes sont retenus, pour que le numéro reste valide
+fn generate_account_number(bank_code: &str, branch_code: &str) -> String {
+    let sequence = NEXT_ACCOUNT_SEQUENCE.fetch_add(1, Ordering::Relaxed);
+    let payload: String = bank_code.chars()
+        .chain(branch_code.chars())
+        .filter(|c| c.is_ascii_digit())
+        .chain(format!("{:011}", sequence).chars())
+        .collect();
+
+    let check_digit = luhn_check_digit(&payload);
+    format!("{}{}", payload, check_digit)
 }
 
 impl BankAccount {
-    fn new(account_number: String, holder_name: String, starting_balance: f64) -> Self {
+    fn new_checked(account_number: String, holder_name: String, starting_balance: Decimal) -> Result<BankAccount, String> {
+        if !is_valid_account_number(&account_number) {
+            return Err(format!("Numéro de compte invalide (contrôle de Luhn): {}", account_number));
+        }
+        Ok(BankAccount::new_unchecked(account_number, holder_name, starting_balance))
+    }
+
+    // Sans validation du numéro (comptes existants, fichiers importés)
+    fn new_unchecked(account_number: String, holder_name: String, starting_balance: Decimal) -> Self {
         BankAccount {
             account_number,
             holder_name,
             balance: starting_balance,
+            transactions: Vec::new(),
+            pin_hash: None,
+            frozen: false,
+            overdraft_limit: Decimal::ZERO,
+            interest_rate: 0.0,
+            account_type: AccountType::Checking,
+            balance_currency: Currency::Eur,
+            recurring_transactions: Vec::new(),
+            sealed: false,
+            ledger_root_hash: None,
+        }
+    }
+
+    // Intérêts d'un mois pour un solde donné (positif = crédit, négatif = débit)
+    fn monthly_interest(&self, balance: Decimal) -> Decimal {
+        let monthly_rate = Decimal::try_from(self.interest_rate).unwrap_or_default() / dec!(100) / dec!(12);
+        match self.account_type {
+            AccountType::Savings if balance > Decimal::ZERO => balance * monthly_rate,
+            AccountType::Credit if balance < Decimal::ZERO => balance * monthly_rate,
+            _ => Decimal::ZERO,
+        }
+    }
+
+    // Appliquer les intérêts mensuels et retourner le montant appliqué
+    fn apply_monthly_interest(&mut self) -> Decimal {
+        let interest = self.monthly_interest(self.balance);
+        if interest != Decimal::ZERO {
+            self.balance += interest;
+            self.record_transaction(TransactionKind::Interest, interest);
         }
+        interest
+    }
+
+    // Solde projeté après `months` mois d'intérêts composés (sans modifier le compte)
+    // Arrondi au centime à la fin seulement, pour ne pas cumuler les erreurs d'arrondi
+    fn projected_balance(&self, months: u32) -> Decimal {
+        (0..months)
+            .fold(self.balance, |balance, _| balance + self.monthly_interest(balance))
+            .round_dp(2)
     }
 
-    fn withdraw_money(&mut self, amount: f64) -> bool {
-        if amount <= 0.0 {
+    fn set_overdraft_limit(&mut self, limit: Decimal) {
+        self.overdraft_limit = limit;
+    }
+
+    fn is_overdrawn(&self) -> bool {
+        self.balance < Decimal::ZERO
+    }
+
+    // Montant maximal pouvant être débité, découvert compris
+    fn available_funds(&self) -> Decimal {
+        self.balance + self.overdraft_limit
+    }
+
+    fn freeze(&mut self) {
+        self.frozen = true;
+    }
+
+    fn unfreeze(&mut self) {
+        self.frozen = false;
+    }
+
+    fn set_pin(&mut self, pin: &str) {
+        self.pin_hash = Some(hash_pin(pin));
+    }
+
+    // Un compte sans code PIN accepte n'importe quelle saisie
+    fn verify_pin(&self, pin: &str) -> bool {
+        match self.pin_hash {
+            Some(hash) => hash == hash_pin(pin),
+            None => true,
+        }
+    }
+
+    fn record_transaction(&mut self, kind: TransactionKind, amount: Decimal) {
+        self.push_transaction(Transaction {
+            kind,
+            amount,
+            balance_after: self.balance,
+            timestamp: Local::now(),
+        });
+    }
+
+    // Seul point d'ajout au journal : une fois scellé, la chaîne est vérifiée puis prolongée
+    fn push_transaction(&mut self, transaction: Transaction) {
+        if self.sealed {
+            if !self.verify_integrity() {
+                panic!("Journal du compte {} falsifié: ajout de transaction refusé", self.account_number);
+            }
+            let previous = self.ledger_root_hash.clone().unwrap_or_default();
+            self.ledger_root_hash = Some(chain_hash(&previous, &transaction));
+        }
+        self.transactions.push(transaction);
+    }
+
+    // Sceller le journal : l'historique ne pourra plus qu'être prolongé
+    fn seal_transaction_log(&mut self) {
+        self.ledger_root_hash = Some(ledger_root(&self.transactions));
+        self.sealed = true;
+    }
+
+    // Recalcule la chaîne depuis la première transaction et la compare à la racine scellée
+    // Un journal non scellé n'a aucune garantie à vérifier
+    fn verify_integrity(&self) -> bool {
+        if !self.sealed {
+            return true;
+        }
+        self.ledger_root_hash.as_deref() == Some(ledger_root(&self.transactions).as_str())
+    }
+
+    fn add_recurring_transaction(&mut self, recurring: RecurringTransaction) {
+        self.recurring_transactions.push(recurring);
+    }
+
+    fn cancel_recurring_transaction(&mut self, id: Uuid) -> Option<RecurringTransaction> {
+        let position = self.recurring_transactions.iter().position(|recurring| recurring.id == id)?;
+        Some(self.recurring_transactions.remove(position))
+    }
+
+    // Appliquer toutes les échéances passées jusqu'à `today` inclus (plusieurs par
+    // opération si le programme n'a pas tourné depuis longtemps)
+    // Un retrait sans provision est rejeté mais son échéance est consommée
+    fn apply_due_transactions(&mut self, today: NaiveDate) -> Vec<Transaction> {
+        let mut applied = Vec::new();
+        if self.frozen {
+            return applied;
+        }
+
+        let mut recurring_transactions = std::mem::take(&mut self.recurring_transactions);
+        for recurring in &mut recurring_transactions {
+            while recurring.next_due <= today {
+                let debit = recurring.kind == TransactionKind::Withdrawal;
+                if debit && recurring.amount > self.available_funds() {
+                    println!("⚠️  Prélèvement '{}' du {} rejeté: provision insuffisante",
+                        recurring.description, recurring.next_due);
+                } else {
+                    self.balance += if debit { -recurring.amount } else { recurring.amount };
+                    self.record_transaction(recurring.kind.clone(), recurring.amount);
+                    applied.extend(self.transactions.last().cloned());
+                }
+
+                let anchor_day = *recurring.anchor_day.get_or_insert(recurring.next_due.day());
+                match recurring.interval.next_after(recurring.next_due, anchor_day) {
+                    Some(next_due) => recurring.next_due = next_due,
+                    None => break,
+                }
+            }
+        }
+        self.recurring_transactions = recurring_transactions;
+
+        applied
+    }
+
+    fn print_recurring_transactions(&self) {
+        println!("\n==== OPÉRATIONS RÉCURRENTES DU COMPTE {} ====", self.account_number);
+        if self.recurring_transactions.is_empty() {
+            println!("Aucune opération récurrente.");
+            return;
+        }
+
+        for (index, recurring) in self.recurring_transactions.iter().enumerate() {
+            println!("{}. {} - {} {} - {} - prochaine échéance: {}",
+                index,
+                recurring.description,
+                recurring.kind.label(),
+                self.format_amount(recurring.amount),
+                recurring.interval.label(),
+                recurring.next_due
+            );
+        }
+        println!("================================");
+    }
+
+    fn withdraw_money(&mut self, amount: Decimal) -> bool {
+        if self.frozen {
+            println!("🧊 Compte gelé: retrait impossible!");
+            return false;
+        }
+
+        if amount <= Decimal::ZERO {
             println!("❌ Le montant doit être positif!");
             return false;
         }
         
-        if amount > self.balance {
-            println!("❌ Pas assez d'argent! Solde actuel: {:.2} €", self.balance);
+        if amount > self.available_funds() {
+            println!("❌ Pas assez d'argent! Solde actuel: {} (découvert autorisé: {})",
+                self.format_amount(self.balance), self.format_amount(self.overdraft_limit));
             return false;
         }
         
-        self.balance = self.balance - amount;
-        println!("✅ Retrait de {:.2} € effectué!", amount);
-        println!("💰 Nouveau solde: {:.2} €", self.balance);
-        return true;
+        self.balance -= amount;
+        self.record_transaction(TransactionKind::Withdrawal, amount);
+        println!("✅ Retrait de {} effectué!", self.format_amount(amount));
+        println!("💰 Nouveau solde: {}", self.format_amount(self.balance));
+        if self.is_overdrawn() {
+            println!("⚠️  Attention: compte à découvert!");
+        }
+        true
+    }
+
+    // Montant affiché dans la devise du compte, ex: "12.50 €" ou "12.50 $"
+    fn format_amount(&self, amount: Decimal) -> String {
+        format!("{:.2} {}", amount, self.balance_currency.symbol())
     }
 
     fn show_balance(&self) {
         println!("\n==== INFORMATIONS DU COMPTE ====");
         println!("📋 Numéro: {}", self.account_number);
         println!("👤 Titulaire: {}", self.holder_name);
-        println!("💰 Solde: {:.2} €", self.balance);
+        println!("💰 Solde: {}", self.format_amount(self.balance));
+        println!("🏷️  Type: {} ({}% par an)", self.account_type.label(), self.interest_rate);
+        if self.overdraft_limit > Decimal::ZERO {
+            println!("💳 Découvert autorisé: {}", self.format_amount(self.overdraft_limit));
+        }
+        if self.is_overdrawn() {
+            println!("⚠️  COMPTE À DÉCOUVERT");
+        }
+        if self.frozen {
+            println!("🧊 COMPTE GELÉ - aucune opération possible");
+        }
         println!("================================");
     }
 
-    fn deposit_money(&mut self, amount: f64) -> bool {
-        if amount <= 0.0 {
+    fn deposit_money(&mut self, amount: Decimal) -> bool {
+        if self.frozen {
+            println!("🧊 Compte gelé: dépôt impossible!");
+            return false;
+        }
+
+        if amount <= Decimal::ZERO {
             println!("❌ Le montant doit être positif!");
             return false;
         }
         
-        self.balance = self.balance + amount;
-        println!("✅ Dépôt de {:.2} € effectué!", amount);
-        println!("💰 Nouveau solde: {:.2} €", self.balance);
-        return true;
+        self.balance += amount;
+        self.record_transaction(TransactionKind::Deposit, amount);
+        println!("✅ Dépôt de {} effectué!", self.format_amount(amount));
+        println!("💰 Nouveau solde: {}", self.format_amount(self.balance));
+        true
     }
 
     fn renommer(&self, new_name: String) -> BankAccount {
@@ -57,10 +609,284 @@ impl BankAccount {
             account_number: self.account_number.clone(),
             holder_name: new_name,
             balance: self.balance,
+            transactions: self.transactions.clone(),
+            pin_hash: self.pin_hash,
+            frozen: self.frozen,
+            overdraft_limit: self.overdraft_limit,
+            interest_rate: self.interest_rate,
+            account_type: self.account_type,
+            balance_currency: self.balance_currency,
+            recurring_transactions: self.recurring_transactions.clone(),
+            sealed: self.sealed,
+            ledger_root_hash: self.ledger_root_hash.clone(),
+        }
+    }
+
+    // Transactions triées par date, éventuellement à partir de `since`
+    fn statement_entries(&self, since: Option<DateTime<Local>>) -> Vec<&Transaction> {
+        let mut entries: Vec<&Transaction> = self.transactions
+            .iter()
+            .filter(|t| since.is_none_or(|since| t.timestamp >= since))
+            .collect();
+        entries.sort_by_key(|t| t.timestamp);
+        entries
+    }
+
+    fn print_statement(&self, since: Option<DateTime<Local>>) {
+        println!("\n==== RELEVÉ DU COMPTE {} ====", self.account_number);
+
+        let entries = self.statement_entries(since);
+        if entries.is_empty() {
+            println!("Aucune opération.");
+            return;
+        }
+
+        println!("{:<20} {:<25} {:>12} {:>12}", "Date", "Opération", "Montant", "Solde");
+        for transaction in entries {
+            println!("{:<20} {:<25} {:>12.2} {:>12.2}",
+                transaction.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                transaction.kind.label(),
+                transaction.signed_amount(),
+                transaction.balance_after
+            );
+        }
+        println!("================================");
+    }
+
+    fn export_statement_csv(&self) -> String {
+        let mut csv = String::from("date,operation,amount,balance_after\n");
+        for transaction in self.statement_entries(None) {
+            csv.push_str(&format!("{},{},{:.2},{:.2}\n",
+                transaction.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                transaction.kind.label(),
+                transaction.signed_amount(),
+                transaction.balance_after
+            ));
+        }
+        csv
+    }
+
+    fn generate_monthly_statement(&self, year: i32, month: u32) -> String {
+        let period = (year, month);
+        let period_of = |t: &Transaction| (t.timestamp.year(), t.timestamp.month());
+
+        // Solde d'ouverture : solde actuel moins tout ce qui a été passé depuis le début du mois
+        let since_period: Decimal = self.transactions.iter()
+            .filter(|t| period_of(t) >= period)
+            .map(|t| t.signed_amount())
+            .sum();
+        let opening_balance = self.balance - since_period;
+
+        let entries: Vec<&Transaction> = self.statement_entries(None)
+            .into_iter()
+            .filter(|t| period_of(t) == period)
+            .collect();
+
+        let credits: Decimal = entries.iter()
+            .map(|t| t.signed_amount())
+            .filter(|amount| *amount > Decimal::ZERO)
+            .sum();
+        let debits: Decimal = entries.iter()
+            .map(|t| t.signed_amount())
+            .filter(|amount| *amount < Decimal::ZERO)
+            .map(|amount| -amount)
+            .sum();
+        let closing_balance = opening_balance + credits - debits;
+
+        let mut statement = String::new();
+        statement.push_str(&format!("==== RELEVÉ MENSUEL {:04}-{:02} ====\n", year, month));
+        statement.push_str(&format!("Compte: {} ({})\n", self.account_number, self.holder_name));
+        statement.push_str(&format!("Solde d'ouverture: {}\n\n", self.format_amount(opening_balance)));
+
+        if entries.is_empty() {
+            statement.push_str("Aucune opération ce mois-ci.\n");
         }
+        for transaction in &entries {
+            statement.push_str(&format!("{:<20} {:<25} {:>12.2} {:>12.2}\n",
+                transaction.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                transaction.kind.label(),
+                transaction.signed_amount(),
+                transaction.balance_after
+            ));
+        }
+
+        statement.push_str(&format!("\nTotal crédits: {}\n", self.format_amount(credits)));
+        statement.push_str(&format!("Total débits: {}\n", self.format_amount(debits)));
+        statement.push_str(&format!("Solde de clôture: {}\n", self.format_amount(closing_balance)));
+        statement
+    }
+
+    // En attendant un vrai rendu PDF, le relevé est écrit en texte brut
+    fn export_statement_pdf_placeholder(&self, year: i32, month: u32) -> io::Result<String> {
+        let path = format!("statement_{}_{:04}_{:02}.txt", self.account_number, year, month);
+        fs::write(&path, self.generate_monthly_statement(year, month))?;
+        Ok(path)
+    }
+}
+
+// Virement entre deux comptes : toutes les vérifications sont faites avant
+// de modifier un solde, pour ne jamais laisser un seul côté débité
+fn transfer(from: &mut BankAccount, to: &mut BankAccount, amount: Decimal) -> Result<(), String> {
+    if from.frozen || to.frozen {
+        return Err("Virement impossible: un des comptes est gelé!".to_string());
+    }
+
+    if from.balance_currency != to.balance_currency {
+        return Err(format!("Virement impossible: devises différentes ({} -> {})",
+            from.balance_currency.code(), to.balance_currency.code()));
+    }
+
+    if amount <= Decimal::ZERO {
+        return Err("Le montant doit être positif!".to_string());
+    }
+
+    if amount > from.available_funds() {
+        return Err(format!("Pas assez d'argent! Solde actuel: {} (découvert autorisé: {})",
+            from.format_amount(from.balance), from.format_amount(from.overdraft_limit)));
+    }
+
+    from.balance -= amount;
+    to.balance += amount;
+
+    let to_number = to.account_number.clone();
+    from.record_transaction(TransactionKind::Transfer { counterpart: to_number }, -amount);
+    let from_number = from.account_number.clone();
+    to.record_transaction(TransactionKind::Transfer { counterpart: from_number }, amount);
+
+    Ok(())
+}
+
+// Obtenir deux comptes distincts en emprunt mutable simultané
+fn two_accounts_mut(
+    accounts: &mut [BankAccount],
+    first: usize,
+    second: usize,
+) -> (&mut BankAccount, &mut BankAccount) {
+    if first < second {
+        let (left, right) = accounts.split_at_mut(second);
+        (&mut left[first], &mut right[0])
+    } else {
+        let (left, right) = accounts.split_at_mut(first);
+        (&mut right[0], &mut left[second])
     }
 }
 
+const ACCOUNTS_CSV_HEADER: &str = "account_number,holder_name,balance";
+
+// Champ CSV (RFC 4180) : entre guillemets s'il contient une virgule ou un guillemet, lui-même doublé
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// Découper une ligne CSV en champs, en tenant compte des guillemets
+fn split_csv_line(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            },
+            '"' if quoted => quoted = false,
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                quoted = true;
+            },
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("guillemet non fermé".to_string());
+    }
+    fields.push(field);
+    Ok(fields)
+}
+
+fn export_accounts_csv(accounts: &[BankAccount]) -> String {
+    let mut csv = format!("{}\n", ACCOUNTS_CSV_HEADER);
+    for account in accounts {
+        csv.push_str(&format!("{},{},{}\n",
+            csv_field(&account.account_number),
+            csv_field(&account.holder_name),
+            account.balance
+        ));
+    }
+    csv
+}
+
+fn import_accounts_csv(csv: &str) -> Result<Vec<BankAccount>, String> {
+    let mut accounts = Vec::new();
+
+    for (line_number, line) in csv.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || (line_number == 0 && line == ACCOUNTS_CSV_HEADER) {
+            continue;
+        }
+
+        let fields = split_csv_line(line).map_err(|e| format!("Ligne {}: {}", line_number + 1, e))?;
+        let fields: Vec<&str> = fields.iter().map(|field| field.trim()).collect();
+        if fields.len() != 3 {
+            return Err(format!("Ligne {}: 3 champs attendus, {} trouvé(s)", line_number + 1, fields.len()));
+        }
+
+        let account_number = fields[0];
+        if account_number.is_empty() {
+            return Err(format!("Ligne {}: numéro de compte vide", line_number + 1));
+        }
+
+        // Un solde négatif est possible avec le découvert autorisé
+        let balance = fields[2].parse::<Decimal>()
+            .map_err(|e| format!("Ligne {}: solde invalide ({})", line_number + 1, e))?;
+
+        accounts.push(BankAccount::new_unchecked(
+            account_number.to_string(),
+            fields[1].to_string(),
+            balance
+        ));
+    }
+
+    Ok(accounts)
+}
+
+// Demander le code PIN si le compte en possède un
+// Après MAX_PIN_ATTEMPTS échecs, le compte est verrouillé jusqu'à la fin de la session
+fn authenticate(account: &BankAccount, locked_accounts: &mut HashSet<String>) -> bool {
+    authenticate_with(account, locked_accounts, get_user_input)
+}
+
+// Même vérification, avec la saisie du code PIN fournie par `read_pin`
+fn authenticate_with(account: &BankAccount, locked_accounts: &mut HashSet<String>, mut read_pin: impl FnMut() -> String) -> bool {
+    if account.pin_hash.is_none() {
+        return true;
+    }
+
+    if locked_accounts.contains(&account.account_number) {
+        println!("🔒 Compte verrouillé pour cette session (trop d'essais de code PIN).");
+        return false;
+    }
+
+    for attempt in 1..=MAX_PIN_ATTEMPTS {
+        println!("🔐 Code PIN du compte {}:", account.account_number);
+        if account.verify_pin(&read_pin()) {
+            return true;
+        }
+        println!("❌ Code PIN incorrect ({}/{})", attempt, MAX_PIN_ATTEMPTS);
+    }
+
+    locked_accounts.insert(account.account_number.clone());
+    println!("🔒 Trop d'essais: compte verrouillé pour cette session.");
+    false
+}
+
 fn show_menu() {
     println!("\n🏦 === MENU BANCAIRE === 🏦");
     println!("1. 💰 Afficher solde");
@@ -68,9 +894,22 @@ fn show_menu() {
     println!("3. 💵 Dépôt");
     println!("4. 📋 Liste comptes");
     println!("5. ✏️  Renommer compte");
-    println!("6. 🚪 Quitter");
+    println!("6. 🔁 Virement");
+    println!("7. 🧾 Relevé de compte");
+    println!("8. 📂 Charger des comptes (CSV)");
+    println!("9. 💾 Sauvegarder les comptes (CSV)");
+    println!("10. ➕ Créer un compte");
+    println!("11. 🔐 Définir le code PIN");
+    println!("12. 🧊 Geler le compte");
+    println!("13. 🔥 Dégeler le compte");
+    println!("14. 💳 Découvert autorisé");
+    println!("15. 📈 Intérêts");
+    println!("16. 📅 Relevé mensuel");
+    println!("17. 🔄 Opérations récurrentes");
+    println!("18. 🔏 Sceller le journal");
+    println!("19. 🚪 Quitter");
     println!("========================");
-    print!("👉 Votre choix (1-6): ");
+    print!("👉 Votre choix (1-19): ");
 }
 
 fn get_user_input() -> String {
@@ -83,19 +922,20 @@ fn get_user_input() -> String {
     input.trim().to_string()
 }
 
-fn get_number_from_user(message: &str) -> f64 {
+// Les montants sont des décimaux exacts : pas d'erreur d'arrondi comme avec f64
+fn get_number_from_user(message: &str) -> Decimal {
     loop {
         println!("{}", message);
         let input = get_user_input();
         
-        match input.parse::<f64>() {
+        match input.parse::<Decimal>() {
             Ok(number) => return number,
-            Err(_) => println!("❌ Ce n'est pas un nombre valide. Essayez encore."),
+            Err(e) => println!("❌ Ce n'est pas un nombre valide ({}). Essayez encore.", e),
         }
     }
 }
 
-fn show_all_accounts(accounts: &Vec<BankAccount>) {
+fn show_all_accounts(accounts: &[BankAccount]) {
     println!("\n📋 === LISTE DES COMPTES ===");
     
     if accounts.is_empty() {
@@ -104,17 +944,18 @@ fn show_all_accounts(accounts: &Vec<BankAccount>) {
     }
     
     for (index, account) in accounts.iter().enumerate() {
-        println!("{}. {} - {} - {:.2} €", 
+        println!("{}. {} - {} - {}{}", 
             index, 
             account.account_number, 
             account.holder_name, 
-            account.balance
+            account.format_amount(account.balance),
+            if account.frozen { " 🧊 (gelé)" } else { "" }
         );
     }
     println!("===========================");
 }
 
-fn choose_account(accounts: &Vec<BankAccount>) -> Option<usize> {
+fn choose_account(accounts: &[BankAccount]) -> Option<usize> {
     if accounts.is_empty() {
         println!("❌ Aucun compte disponible!");
         return None;
@@ -123,7 +964,9 @@ fn choose_account(accounts: &Vec<BankAccount>) -> Option<usize> {
     show_all_accounts(accounts);
     
     loop {
-        let choice = get_number_from_user("👉 Choisissez un compte (tapez le numéro):") as usize;
+        let choice = get_number_from_user("👉 Choisissez un compte (tapez le numéro):")
+            .to_usize()
+            .unwrap_or(usize::MAX);
         
         if choice < accounts.len() {
             return Some(choice);
@@ -133,28 +976,77 @@ fn choose_account(accounts: &Vec<BankAccount>) -> Option<usize> {
     }
 }
 
+fn save_to_file(accounts: &[BankAccount], path: &Path) -> IoResult<()> {
+    let json = serde_json::to_string_pretty(accounts)
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    fs::write(path, json)
+}
+
+fn load_from_file(path: &Path) -> IoResult<Vec<BankAccount>> {
+    let json = fs::read_to_string(path)?;
+    serde_json::from_str(&json).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+}
+
+fn default_accounts(currency: Currency) -> Vec<BankAccount> {
+    let mut accounts = vec![
+        BankAccount::new_unchecked(
+            "123456".to_string(),
+            "Jean Dupont".to_string(),
+            dec!(1000.00)
+        ),
+        BankAccount::new_unchecked(
+            "789012".to_string(),
+            "Marie Martin".to_string(),
+            dec!(2500.00)
+        ),
+        BankAccount::new_unchecked(
+            "345678".to_string(),
+            "Pierre Durand".to_string(),
+            dec!(750.00)
+        ),
+    ];
+    for account in &mut accounts {
+        account.balance_currency = currency;
+    }
+    accounts
+}
+
 fn main() {
-    let mut bank_accounts: Vec<BankAccount> = Vec::new();
-    
-    bank_accounts.push(BankAccount::new(
-        "123456".to_string(),
-        "Jean Dupont".to_string(),
-        1000.0
-    ));
-    
-    bank_accounts.push(BankAccount::new(
-        "789012".to_string(),
-        "Marie Martin".to_string(),
-        2500.0
-    ));
-    
-    bank_accounts.push(BankAccount::new(
-        "345678".to_string(),
-        "Pierre Durand".to_string(),
-        750.0
-    ));
+    let args = Args::parse();
+
+    let mut bank_accounts = match &args.data_file {
+        Some(path) if path.exists() => match load_from_file(path) {
+            Ok(accounts) => {
+                println!("📂 {} compte(s) chargé(s) depuis {}", accounts.len(), path.display());
+                accounts
+            },
+            Err(e) => {
+                eprintln!("❌ Impossible de charger {}: {}", path.display(), e);
+                std::process::exit(1);
+            },
+        },
+        _ => default_accounts(args.currency),
+    };
+
+    // Un journal scellé modifié dans le fichier est signalé dès le chargement
+    for account in &bank_accounts {
+        if !account.verify_integrity() {
+            eprintln!("🚨 {}: journal scellé falsifié, tout nouvel ajout sera refusé", account.account_number);
+        }
+    }
     
-    let mut current_account_index: Option<usize> = Some(0);
+    // Échéances arrivées depuis le dernier lancement
+    let today = Local::now().date_naive();
+    for account in &mut bank_accounts {
+        let applied = account.apply_due_transactions(today);
+        if !applied.is_empty() {
+            println!("🔄 {}: {} opération(s) récurrente(s) appliquée(s)", account.account_number, applied.len());
+        }
+    }
+
+    let mut current_account_index: Option<usize> = if bank_accounts.is_empty() { None } else { Some(0) };
+    let mut locked_accounts: HashSet<String> = HashSet::new();
+    let exchange_rates = load_exchange_rates();
     
     println!("🏦 Bienvenue dans votre système bancaire! 🏦");
     
@@ -173,8 +1065,10 @@ fn main() {
             "2" => {
                 match current_account_index {
                     Some(index) => {
-                        let amount = get_number_from_user("💸 Combien voulez-vous retirer? (en €):");
-                        bank_accounts[index].withdraw_money(amount);
+                        if authenticate(&bank_accounts[index], &mut locked_accounts) {
+                            let amount = get_number_from_user(&format!("💸 Combien voulez-vous retirer? (en {}):", bank_accounts[index].balance_currency.symbol()));
+                            bank_accounts[index].withdraw_money(amount);
+                        }
                     },
                     None => println!("❌ Aucun compte sélectionné! Choisissez d'abord un compte."),
                 }
@@ -183,8 +1077,10 @@ fn main() {
             "3" => {
                 match current_account_index {
                     Some(index) => {
-                        let amount = get_number_from_user("💵 Combien voulez-vous déposer? (en €):");
-                        bank_accounts[index].deposit_money(amount);
+                        if authenticate(&bank_accounts[index], &mut locked_accounts) {
+                            let amount = get_number_from_user(&format!("💵 Combien voulez-vous déposer? (en {}):", bank_accounts[index].balance_currency.symbol()));
+                            bank_accounts[index].deposit_money(amount);
+                        }
                     },
                     None => println!("❌ Aucun compte sélectionné! Choisissez d'abord un compte."),
                 }
@@ -194,7 +1090,25 @@ fn main() {
                 match choose_account(&bank_accounts) {
                     Some(index) => {
                         current_account_index = Some(index);
-                        println!("✅ Compte sélectionné: {}", bank_accounts[index].account_number);
+                        let account = &bank_accounts[index];
+                        println!("✅ Compte sélectionné: {}", account.account_number);
+
+                        let codes: Vec<&str> = Currency::ALL.iter().map(|currency| currency.code()).collect();
+                        println!("💱 Devise d'affichage du solde ({}, vide pour aucune):", codes.join(", "));
+                        let input = get_user_input();
+                        if !input.is_empty() {
+                            match Currency::from_code(&input) {
+                                Some(display_currency) => {
+                                    let converted = convert(account.balance, account.balance_currency, display_currency, &exchange_rates);
+                                    println!("💰 Solde: {:.2} {} ≈ {:.2} {} (taux chargés il y a {} s)",
+                                        account.balance, account.balance_currency.symbol(),
+                                        converted, display_currency.symbol(),
+                                        exchange_rates.fetched_at.elapsed().as_secs()
+                                    );
+                                },
+                                None => println!("❌ Devise inconnue: {}", input),
+                            }
+                        }
                     },
                     None => println!("❌ Aucun compte sélectionné."),
                 }
@@ -219,16 +1133,716 @@ fn main() {
             },
             
             "6" => {
+                println!("📤 Compte source:");
+                let source = choose_account(&bank_accounts);
+                println!("📥 Compte destinataire:");
+                let destination = choose_account(&bank_accounts);
+
+                match (source, destination) {
+                    (Some(from), Some(to)) if from != to => {
+                        if authenticate(&bank_accounts[from], &mut locked_accounts) {
+                            let amount = get_number_from_user(&format!("🔁 Combien voulez-vous virer? (en {}):", bank_accounts[from].balance_currency.symbol()));
+                            let (from_account, to_account) = two_accounts_mut(&mut bank_accounts, from, to);
+                            match transfer(from_account, to_account, amount) {
+                                Ok(()) => {
+                                    println!("✅ Virement de {} effectué!", from_account.format_amount(amount));
+                                    println!("💰 {}: {} | {}: {}",
+                                        from_account.account_number, from_account.format_amount(from_account.balance),
+                                        to_account.account_number, to_account.format_amount(to_account.balance)
+                                    );
+                                },
+                                Err(e) => println!("❌ {}", e),
+                            }
+                        }
+                    },
+                    (Some(_), Some(_)) => println!("❌ Les comptes source et destinataire doivent être différents."),
+                    _ => println!("❌ Aucun compte sélectionné."),
+                }
+            },
+
+            "7" => {
+                match current_account_index {
+                    Some(index) => {
+                        let account = &bank_accounts[index];
+                        account.print_statement(None);
+                        println!("📤 Exporter le relevé en CSV? (o/n)");
+                        if get_user_input().eq_ignore_ascii_case("o") {
+                            let path = format!("releve_{}.csv", account.account_number);
+                            match fs::write(&path, account.export_statement_csv()) {
+                                Ok(()) => println!("✅ Relevé exporté dans {}", path),
+                                Err(e) => println!("❌ Erreur lors de l'export: {}", e),
+                            }
+                        }
+                    },
+                    None => println!("❌ Aucun compte sélectionné! Choisissez d'abord un compte."),
+                }
+            },
+
+            "8" => {
+                println!("📂 Chemin du fichier CSV:");
+                let path = get_user_input();
+                let loaded = fs::read_to_string(&path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|csv| import_accounts_csv(&csv));
+
+                match loaded {
+                    Ok(mut accounts) => {
+                        // Le CSV ne porte pas de devise : celle de la session s'applique
+                        for account in &mut accounts {
+                            account.balance_currency = args.currency;
+                        }
+                        println!("✅ {} compte(s) chargé(s) depuis {}", accounts.len(), path);
+                        current_account_index = if accounts.is_empty() { None } else { Some(0) };
+                        bank_accounts = accounts;
+                    },
+                    Err(e) => println!("❌ Erreur lors du chargement: {}", e),
+                }
+            },
+
+            "9" => {
+                println!("💾 Chemin du fichier CSV:");
+                let path = get_user_input();
+                match fs::write(&path, export_accounts_csv(&bank_accounts)) {
+                    Ok(()) => println!("✅ {} compte(s) sauvegardé(s) dans {}", bank_accounts.len(), path),
+                    Err(e) => println!("❌ Erreur lors de la sauvegarde: {}", e),
+                }
+            },
+
+            "10" => {
+                println!("📋 Numéro du compte (vide pour en générer un):");
+                let typed_number = get_user_input();
+                let account_number = if typed_number.is_empty() {
+                    // Le compteur repart à 1 à chaque lancement : sauter les numéros déjà attribués
+                    let generated = loop {
+                        let candidate = generate_account_number(BANK_CODE, BRANCH_CODE);
+                        if !bank_accounts.iter().any(|account| account.account_number == candidate) {
+                            break candidate;
+                        }
+                    };
+                    println!("🔢 Numéro généré: {}", generated);
+                    generated
+                } else {
+                    typed_number
+                };
+                println!("👤 Nom du titulaire:");
+                let holder_name = get_user_input();
+                let starting_balance = get_number_from_user(&format!("💰 Solde initial (en {}):", args.currency.symbol()));
+
+                if holder_name.is_empty() {
+                    println!("❌ Le nom ne peut pas être vide.");
+                } else if starting_balance < Decimal::ZERO {
+                    println!("❌ Le solde initial ne peut pas être négatif.");
+                } else if bank_accounts.iter().any(|account| account.account_number == account_number) {
+                    println!("❌ Un compte avec ce numéro existe déjà.");
+                } else {
+                    match BankAccount::new_checked(account_number, holder_name, starting_balance) {
+                        Ok(mut account) => {
+                            account.balance_currency = args.currency;
+                            println!("✅ Compte {} créé!", account.account_number);
+                            bank_accounts.push(account);
+                        },
+                        Err(e) => println!("❌ {}", e),
+                    }
+                }
+            },
+
+            "11" => {
+                match current_account_index {
+                    Some(index) => {
+                        if authenticate(&bank_accounts[index], &mut locked_accounts) {
+                            println!("🔐 Nouveau code PIN (4 à 6 chiffres):");
+                            let pin = get_user_input();
+                            if (4..=6).contains(&pin.len()) && pin.chars().all(|c| c.is_ascii_digit()) {
+                                bank_accounts[index].set_pin(&pin);
+                                println!("✅ Code PIN défini!");
+                            } else {
+                                println!("❌ Le code PIN doit contenir 4 à 6 chiffres.");
+                            }
+                        }
+                    },
+                    None => println!("❌ Aucun compte sélectionné! Choisissez d'abord un compte."),
+                }
+            },
+
+            "12" => {
+                match current_account_index {
+                    Some(index) => {
+                        // Geler ou dégeler le compte demande son code PIN
+                        if authenticate(&bank_accounts[index], &mut locked_accounts) {
+                            bank_accounts[index].freeze();
+                            println!("🧊 Compte {} gelé.", bank_accounts[index].account_number);
+                        }
+                    },
+                    None => println!("❌ Aucun compte sélectionné! Choisissez d'abord un compte."),
+                }
+            },
+
+            "13" => {
+                match current_account_index {
+                    Some(index) => {
+                        // Geler ou dégeler le compte demande son code PIN
+                        if authenticate(&bank_accounts[index], &mut locked_accounts) {
+                            bank_accounts[index].unfreeze();
+                            println!("🔥 Compte {} dégelé.", bank_accounts[index].account_number);
+                        }
+                    },
+                    None => println!("❌ Aucun compte sélectionné! Choisissez d'abord un compte."),
+                }
+            },
+
+            "14" => {
+                match current_account_index {
+                    Some(index) => {
+                        let account = &bank_accounts[index];
+                        println!("💳 Découvert autorisé actuel: {}", account.format_amount(account.overdraft_limit));
+                        println!("✏️  Nouvelle limite (vide pour conserver):");
+                        let input = get_user_input();
+                        if !input.is_empty() {
+                            match input.parse::<Decimal>() {
+                                Ok(limit) if limit >= Decimal::ZERO => {
+                                    bank_accounts[index].set_overdraft_limit(limit);
+                                    println!("✅ Découvert autorisé fixé à {}", bank_accounts[index].format_amount(limit));
+                                },
+                                Ok(_) => println!("❌ La limite ne peut pas être négative."),
+                                Err(e) => println!("❌ Ce n'est pas un nombre valide ({}).", e),
+                            }
+                        }
+                    },
+                    None => println!("❌ Aucun compte sélectionné! Choisissez d'abord un compte."),
+                }
+            },
+
+            "15" => {
+                match current_account_index {
+                    Some(index) => {
+                        let account = &mut bank_accounts[index];
+                        println!("🏷️  Type: {} | Taux annuel: {}%", account.account_type.label(), account.interest_rate);
+                        println!("a. Appliquer les intérêts du mois");
+                        println!("s. Simuler le solde sur N mois");
+                        println!("c. Configurer le type et le taux");
+                        match get_user_input().as_str() {
+                            "a" => {
+                                let interest = account.apply_monthly_interest();
+                                println!("✅ Intérêts appliqués: {}", account.format_amount(interest));
+                                println!("💰 Nouveau solde: {}", account.format_amount(account.balance));
+                            },
+                            "s" => {
+                                let months = get_number_from_user("📅 Nombre de mois:")
+                                    .to_u32()
+                                    .unwrap_or(0);
+                                println!("📈 Solde projeté après {} mois: {}", months, account.format_amount(account.projected_balance(months)));
+                            },
+                            "c" => {
+                                println!("🏷️  Type de compte (1. Courant, 2. Épargne, 3. Crédit):");
+                                let account_type = match get_user_input().as_str() {
+                                    "1" => Some(AccountType::Checking),
+                                    "2" => Some(AccountType::Savings),
+                                    "3" => Some(AccountType::Credit),
+                                    _ => None,
+                                };
+                                let rate = get_number_from_user("📈 Taux annuel (en %):").to_f64();
+
+                                match (account_type, rate) {
+                                    (Some(account_type), Some(rate)) if rate >= 0.0 => {
+                                        account.account_type = account_type;
+                                        account.interest_rate = rate;
+                                        println!("✅ Compte configuré: {} à {}%", account_type.label(), rate);
+                                    },
+                                    _ => println!("❌ Type ou taux invalide."),
+                                }
+                            },
+                            _ => println!("❌ Choix invalide."),
+                        }
+                    },
+                    None => println!("❌ Aucun compte sélectionné! Choisissez d'abord un compte."),
+                }
+            },
+
+            "16" => {
+                match current_account_index {
+                    Some(index) => {
+                        let year = get_number_from_user("📅 Année (ex: 2024):").to_i32();
+                        let month = get_number_from_user("📅 Mois (1-12):").to_u32();
+
+                        match (year, month) {
+                            (Some(year), Some(month)) if (1..=12).contains(&month) => {
+                                let account = &bank_accounts[index];
+                                println!("\n{}", account.generate_monthly_statement(year, month));
+                                match account.export_statement_pdf_placeholder(year, month) {
+                                    Ok(path) => println!("✅ Relevé exporté dans {}", path),
+                                    Err(e) => println!("❌ Erreur lors de l'export: {}", e),
+                                }
+                            },
+                            _ => println!("❌ Période invalide."),
+                        }
+                    },
+                    None => println!("❌ Aucun compte sélectionné! Choisissez d'abord un compte."),
+                }
+            },
+
+            "17" => {
+                match current_account_index {
+                    Some(index) => {
+                        if authenticate(&bank_accounts[index], &mut locked_accounts) {
+                            let account = &mut bank_accounts[index];
+                            println!("🔄 {} opération(s) récurrente(s)", account.recurring_transactions.len());
+                            println!("c. Créer une opération récurrente");
+                            println!("l. Lister les opérations récurrentes");
+                            println!("a. Annuler une opération récurrente");
+                            println!("e. Exécuter les échéances du jour");
+                            match get_user_input().as_str() {
+                                "c" => {
+                                    println!("📝 Description (ex: Loyer, Salaire):");
+                                    let description = get_user_input();
+                                    println!("🏷️  Type (1. Dépôt, 2. Retrait):");
+                                    let kind = match get_user_input().as_str() {
+                                        "1" => Some(TransactionKind::Deposit),
+                                        "2" => Some(TransactionKind::Withdrawal),
+                                        _ => None,
+                                    };
+                                    let amount = get_number_from_user(&format!("💶 Montant (en {}):", account.balance_currency.symbol()));
+                                    println!("🔁 Fréquence (1. Quotidienne, 2. Hebdomadaire, 3. Mensuelle, 4. Annuelle):");
+                                    let interval = match get_user_input().as_str() {
+                                        "1" => Some(RecurrenceInterval::Daily),
+                                        "2" => Some(RecurrenceInterval::Weekly),
+                                        "3" => Some(RecurrenceInterval::Monthly),
+                                        "4" => Some(RecurrenceInterval::Yearly),
+                                        _ => None,
+                                    };
+                                    println!("📅 Première échéance (AAAA-MM-JJ, vide pour aujourd'hui):");
+                                    let date_input = get_user_input();
+                                    let next_due = if date_input.is_empty() {
+                                        Some(Local::now().date_naive())
+                                    } else {
+                                        NaiveDate::parse_from_str(&date_input, "%Y-%m-%d").ok()
+                                    };
+
+                                    match (kind, interval, next_due) {
+                                        _ if description.is_empty() => println!("❌ La description ne peut pas être vide."),
+                                        _ if amount <= Decimal::ZERO => println!("❌ Le montant doit être positif!"),
+                                        (Some(kind), Some(interval), Some(next_due)) => {
+                                            println!("✅ {} de {} programmé ({}), première échéance le {}",
+                                                kind.label(), account.format_amount(amount), interval.label(), next_due);
+                                            account.add_recurring_transaction(RecurringTransaction {
+                                                id: Uuid::new_v4(),
+                                                description,
+                                                amount,
+                                                kind,
+                                                interval,
+                                                next_due,
+                                                anchor_day: Some(next_due.day()),
+                                            });
+                                        },
+                                        _ => println!("❌ Type, fréquence ou date invalide."),
+                                    }
+                                },
+                                "l" => account.print_recurring_transactions(),
+                                "a" => {
+                                    account.print_recurring_transactions();
+                                    if !account.recurring_transactions.is_empty() {
+                                        let choice = get_number_from_user("👉 Numéro de l'opération à annuler:")
+                                            .to_usize()
+                                            .and_then(|choice| account.recurring_transactions.get(choice))
+                                            .map(|recurring| recurring.id);
+                                        match choice.and_then(|id| account.cancel_recurring_transaction(id)) {
+                                            Some(cancelled) => println!("✅ Opération '{}' annulée.", cancelled.description),
+                                            None => println!("❌ Numéro invalide."),
+                                        }
+                                    }
+                                },
+                                "e" => {
+                                    let applied = account.apply_due_transactions(Local::now().date_naive());
+                                    println!("✅ {} opération(s) appliquée(s)", applied.len());
+                                    println!("💰 Nouveau solde: {}", account.format_amount(account.balance));
+                                },
+                                _ => println!("❌ Choix invalide."),
+                            }
+                        }
+                    },
+                    None => println!("❌ Aucun compte sélectionné! Choisissez d'abord un compte."),
+                }
+            },
+
+            "18" => {
+                match current_account_index {
+                    Some(index) => {
+                        let account = &mut bank_accounts[index];
+                        if account.sealed {
+                            if account.verify_integrity() {
+                                println!("✅ Journal scellé et intègre ({} transaction(s))", account.transactions.len());
+                            } else {
+                                println!("🚨 Journal falsifié: la chaîne ne correspond plus à la racine scellée!");
+                            }
+                        } else if authenticate(account, &mut locked_accounts) {
+                            account.seal_transaction_log();
+                            println!("🔏 Journal scellé ({} transaction(s))", account.transactions.len());
+                        }
+                        if let Some(root) = &account.ledger_root_hash {
+                            println!("🔗 Racine: {}", root);
+                        }
+                    },
+                    None => println!("❌ Aucun compte sélectionné! Choisissez d'abord un compte."),
+                }
+            },
+
+            "19" => {
+                if let Some(path) = &args.data_file {
+                    match save_to_file(&bank_accounts, path) {
+                        Ok(()) => println!("💾 Comptes sauvegardés dans {}", path.display()),
+                        Err(e) => println!("❌ Erreur lors de la sauvegarde: {}", e),
+                    }
+                }
                 println!("👋 Au revoir et merci d'avoir utilisé notre système bancaire!");
                 break;
             },
             
             _ => {
-                println!("❌ Choix invalide. Tapez 1, 2, 3, 4, 5 ou 6.");
+                println!("❌ Choix invalide. Tapez un nombre entre 1 et 19.");
             }
         }
         
         println!("\n⏸️  Appuyez sur Entrée pour continuer...");
         get_user_input();
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, TimeZone};
+
+    fn account(balance: Decimal) -> BankAccount {
+        BankAccount::new_unchecked("123456".to_string(), "Jean Dupont".to_string(), balance)
+    }
+
+    // Opération passée à une date donnée, comme si elle avait eu lieu ce jour-là
+    fn push_at(account: &mut BankAccount, kind: TransactionKind, amount: Decimal, timestamp: DateTime<Local>) {
+        let transaction = Transaction { kind, amount, balance_after: Decimal::ZERO, timestamp };
+        account.balance += transaction.signed_amount();
+        account.transactions.push(Transaction { balance_after: account.balance, ..transaction });
+    }
+
+    fn local(year: i32, month: u32, day: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(year, month, day, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn deposit_and_withdrawal_are_recorded_in_the_ledger() {
+        let mut account = account(dec!(1000));
+        assert!(account.deposit_money(dec!(100)));
+        assert!(account.withdraw_money(dec!(30)));
+
+        assert_eq!(account.transactions.len(), 2);
+        assert_eq!(account.transactions[0].kind, TransactionKind::Deposit);
+        assert_eq!(account.transactions[0].balance_after, dec!(1100));
+        assert_eq!(account.transactions[1].kind, TransactionKind::Withdrawal);
+        assert_eq!(account.transactions[1].balance_after, dec!(1070));
+    }
+
+    #[test]
+    fn decimal_deposits_add_up_exactly() {
+        let mut account = account(Decimal::ZERO);
+        for _ in 0..10 {
+            account.deposit_money(dec!(0.1));
+        }
+        assert_eq!(account.balance, dec!(1.0));
+    }
+
+    #[test]
+    fn failed_transfer_leaves_both_balances_unchanged() {
+        let mut from = account(dec!(100));
+        let mut to = BankAccount::new_unchecked("789012".to_string(), "Marie Martin".to_string(), dec!(50));
+
+        assert!(transfer(&mut from, &mut to, dec!(100.01)).is_err());
+        assert_eq!((from.balance, to.balance), (dec!(100), dec!(50)));
+        assert!(from.transactions.is_empty() && to.transactions.is_empty());
+
+        assert!(transfer(&mut from, &mut to, dec!(100)).is_ok());
+        assert_eq!((from.balance, to.balance), (Decimal::ZERO, dec!(150)));
+        assert_eq!(from.transactions[0].kind, TransactionKind::Transfer { counterpart: "789012".to_string() });
+    }
+
+    #[test]
+    fn luhn_validation_of_known_numbers() {
+        for valid in ["79927398713", "4539148803436467", "0"] {
+            assert!(is_valid_account_number(valid), "{} devrait être valide", valid);
+        }
+        for invalid in ["79927398710", "4539148803436468", "123456", "", "7992 7398 713", "FR76"] {
+            assert!(!is_valid_account_number(invalid), "{} devrait être invalide", invalid);
+        }
+        assert!(BankAccount::new_checked("79927398710".to_string(), "X".to_string(), Decimal::ZERO).is_err());
+        assert!(BankAccount::new_checked("79927398713".to_string(), "X".to_string(), Decimal::ZERO).is_ok());
+    }
+
+    #[test]
+    fn wrong_pin_does_not_touch_the_balance() {
+        let mut account = account(dec!(500));
+        account.set_pin("1234");
+        let mut locked_accounts = HashSet::new();
+
+        // Même enchaînement que le menu : l'opération n'a lieu qu'après authentification
+        let mut attempts = 0;
+        if authenticate_with(&account, &mut locked_accounts, || { attempts += 1; "0000".to_string() }) {
+            account.withdraw_money(dec!(100));
+        }
+        assert_eq!(attempts, MAX_PIN_ATTEMPTS);
+        assert_eq!(account.balance, dec!(500));
+        assert!(account.transactions.is_empty());
+
+        // Verrouillé pour la session, même avec le bon code
+        assert!(!authenticate_with(&account, &mut locked_accounts, || "1234".to_string()));
+        assert!(authenticate_with(&account, &mut HashSet::new(), || "1234".to_string()));
+    }
+
+    #[test]
+    fn frozen_account_refuses_withdrawals() {
+        let mut account = account(dec!(1000));
+        account.freeze();
+        assert!(!account.withdraw_money(dec!(10)));
+        assert!(!account.deposit_money(dec!(10)));
+        assert_eq!(account.balance, dec!(1000));
+        assert!(account.transactions.is_empty());
+
+        account.unfreeze();
+        assert!(account.withdraw_money(dec!(10)));
+    }
+
+    #[test]
+    fn wrong_pin_leaves_the_frozen_state_unchanged() {
+        let mut account = account(dec!(1000));
+        account.set_pin("1234");
+
+        // Même enchaînement que les menus 12 et 13
+        if authenticate_with(&account, &mut HashSet::new(), || "0000".to_string()) {
+            account.freeze();
+        }
+        assert!(!account.frozen);
+
+        account.freeze();
+        if authenticate_with(&account, &mut HashSet::new(), || "9999".to_string()) {
+            account.unfreeze();
+        }
+        assert!(account.frozen);
+
+        if authenticate_with(&account, &mut HashSet::new(), || "1234".to_string()) {
+            account.unfreeze();
+        }
+        assert!(!account.frozen);
+    }
+
+    #[test]
+    fn overdraft_allows_exactly_the_limit() {
+        let mut exact = account(dec!(50));
+        exact.set_overdraft_limit(dec!(100));
+        assert!(exact.withdraw_money(dec!(150)));
+        assert_eq!(exact.balance, dec!(-100));
+        assert!(exact.is_overdrawn());
+
+        let mut one_cent_more = account(dec!(50));
+        one_cent_more.set_overdraft_limit(dec!(100));
+        assert!(!one_cent_more.withdraw_money(dec!(150.01)));
+        assert_eq!(one_cent_more.balance, dec!(50));
+        assert!(!one_cent_more.is_overdrawn());
+    }
+
+    #[test]
+    fn json_round_trip_of_default_accounts() {
+        let path = std::env::temp_dir().join(format!("bank_accounts_{}.json", std::process::id()));
+        let mut accounts = default_accounts(Currency::Eur);
+        accounts[0].deposit_money(dec!(0.1));
+        accounts[1].set_pin("4321");
+
+        save_to_file(&accounts, &path).unwrap();
+        let loaded = load_from_file(&path);
+        let _ = fs::remove_file(&path);
+        let loaded = loaded.unwrap();
+
+        assert_eq!(loaded.len(), 3);
+        assert_eq!(serde_json::to_value(&loaded).unwrap(), serde_json::to_value(&accounts).unwrap());
+        assert_eq!(loaded[0].balance, dec!(1000.10));
+        assert!(loaded[1].verify_pin("4321"));
+        // Les montants sont des chaînes JSON, jamais des flottants
+        assert_eq!(serde_json::to_value(&accounts[2]).unwrap()["balance"], "750.00");
+    }
+
+    #[test]
+    fn csv_round_trip_of_three_accounts() {
+        let mut overdrawn = BankAccount::new_unchecked("345678".to_string(), "Pierre \"Pierrot\" Durand".to_string(), dec!(20));
+        overdrawn.set_overdraft_limit(dec!(100));
+        overdrawn.withdraw_money(dec!(45.50));
+        let accounts = vec![
+            BankAccount::new_unchecked("123456".to_string(), "Dupont, Jean".to_string(), dec!(1000.00)),
+            BankAccount::new_unchecked("789012".to_string(), "Marie Martin".to_string(), dec!(2500.10)),
+            overdrawn,
+        ];
+
+        let csv = export_accounts_csv(&accounts);
+        assert!(csv.contains("123456,\"Dupont, Jean\",1000.00\n"), "{}", csv);
+        let imported = import_accounts_csv(&csv).unwrap();
+
+        assert_eq!(imported.len(), 3);
+        for (original, copy) in accounts.iter().zip(&imported) {
+            assert_eq!(copy.account_number, original.account_number);
+            assert_eq!(copy.holder_name, original.holder_name);
+            assert_eq!(copy.balance, original.balance);
+        }
+        assert_eq!(imported[2].balance, dec!(-25.50));
+
+        assert!(import_accounts_csv("123456,\"Dupont, Jean,10").is_err());
+        assert!(import_accounts_csv(" ,Jean,10").is_err());
+    }
+
+    #[test]
+    fn monthly_debit_is_applied_once_per_month_on_its_day() {
+        let mut account = account(dec!(10000));
+        let date = |month, day| NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+        account.add_recurring_transaction(RecurringTransaction {
+            id: Uuid::new_v4(),
+            description: "Loyer".to_string(),
+            amount: dec!(100),
+            kind: TransactionKind::Withdrawal,
+            interval: RecurrenceInterval::Monthly,
+            next_due: date(1, 31),
+            anchor_day: Some(31),
+        });
+
+        assert_eq!(account.apply_due_transactions(date(1, 30)).len(), 0);
+        assert_eq!(account.apply_due_transactions(date(1, 31)).len(), 1);
+        assert_eq!(account.apply_due_transactions(date(1, 31)).len(), 0);
+        // Février : dernier jour du mois, puis retour au 31
+        assert_eq!(account.apply_due_transactions(date(2, 28)).len(), 0);
+        assert_eq!(account.apply_due_transactions(date(2, 29)).len(), 1);
+        assert_eq!(account.apply_due_transactions(date(3, 30)).len(), 0);
+        assert_eq!(account.apply_due_transactions(date(3, 31)).len(), 1);
+
+        // Après une longue absence : une échéance par mois manqué (30/04, 31/05, 30/06, 31/07)
+        assert_eq!(account.apply_due_transactions(date(7, 31)).len(), 4);
+        assert_eq!(account.recurring_transactions[0].next_due, date(8, 31));
+        assert_eq!(account.transactions.len(), 7);
+        assert_eq!(account.balance, dec!(9300));
+    }
+
+    #[test]
+    fn savings_interest_compounds_to_1126_83() {
+        let mut account = account(dec!(1000.00));
+        account.account_type = AccountType::Savings;
+        account.interest_rate = 12.0;
+
+        assert_eq!(account.projected_balance(12), dec!(1126.83));
+        for _ in 0..12 {
+            account.apply_monthly_interest();
+        }
+        assert_eq!(account.balance.round_dp(2), dec!(1126.83));
+        assert_eq!(account.transactions.len(), 12);
+        assert!(account.transactions.iter().all(|t| t.kind == TransactionKind::Interest));
+    }
+
+    #[test]
+    fn monthly_statement_totals_per_month() {
+        let mut account = account(Decimal::ZERO);
+        push_at(&mut account, TransactionKind::Deposit, dec!(500), local(2024, 1, 15));
+        push_at(&mut account, TransactionKind::Withdrawal, dec!(120.50), local(2024, 1, 20));
+        push_at(&mut account, TransactionKind::Deposit, dec!(200), local(2024, 2, 3));
+        push_at(&mut account, TransactionKind::Withdrawal, dec!(80), local(2024, 2, 10));
+        push_at(&mut account, TransactionKind::Transfer { counterpart: "789012".to_string() }, dec!(-50), local(2024, 2, 12));
+
+        let january = account.generate_monthly_statement(2024, 1);
+        assert!(january.contains("Solde d'ouverture: 0.00 €"), "{}", january);
+        assert!(january.contains("Total crédits: 500.00 €"), "{}", january);
+        assert!(january.contains("Total débits: 120.50 €"), "{}", january);
+        assert!(january.contains("Solde de clôture: 379.50 €"), "{}", january);
+
+        let february = account.generate_monthly_statement(2024, 2);
+        assert!(february.contains("Solde d'ouverture: 379.50 €"), "{}", february);
+        assert!(february.contains("Total crédits: 200.00 €"), "{}", february);
+        assert!(february.contains("Total débits: 130.00 €"), "{}", february);
+        assert!(february.contains("Solde de clôture: 449.50 €"), "{}", february);
+    }
+
+    #[test]
+    fn generated_account_numbers_pass_luhn() {
+        let mut numbers = HashSet::new();
+        for _ in 0..200 {
+            let number = generate_account_number(BANK_CODE, BRANCH_CODE);
+            assert!(is_valid_account_number(&number), "{} ne passe pas Luhn", number);
+            assert_eq!(number.len(), 22);
+            numbers.insert(number);
+        }
+        assert_eq!(numbers.len(), 200);
+
+        // Les caractères non numériques des codes sont ignorés
+        assert!(is_valid_account_number(&generate_account_number("FR-76", "A1B2")));
+    }
+
+    #[test]
+    fn conversion_uses_direct_inverse_and_cross_rates() {
+        let rates = ExchangeRates::hardcoded();
+        assert_eq!(convert(dec!(100), Currency::Eur, Currency::Usd, &rates), dec!(108));
+        assert_eq!(convert(dec!(108), Currency::Usd, Currency::Eur, &rates).round_dp(2), dec!(100));
+        assert_eq!(convert(dec!(42), Currency::Chf, Currency::Chf, &rates), dec!(42));
+        // GBP -> JPY via l'euro : 1 / 0.85 * 160
+        assert_eq!(convert(dec!(85), Currency::Gbp, Currency::Jpy, &rates).round_dp(2), dec!(16000));
+        assert_eq!(rates.rate(Currency::Gbp, Currency::Usd).unwrap().round_dp(4), dec!(1.2706));
+    }
+
+    #[test]
+    fn amounts_use_the_account_currency() {
+        assert_eq!(parse_currency("usd"), Ok(Currency::Usd));
+        assert!(parse_currency("XYZ").is_err());
+
+        let mut dollars = account(dec!(0));
+        dollars.balance_currency = Currency::Usd;
+        push_at(&mut dollars, TransactionKind::Deposit, dec!(500), local(2025, 1, 10));
+        assert_eq!(dollars.format_amount(dec!(12.5)), "12.50 $");
+        assert!(dollars.generate_monthly_statement(2025, 1).contains("Solde de clôture: 500.00 $"));
+
+        // Un virement ne convertit pas : les deux comptes doivent partager la devise
+        let mut euros = account(dec!(100));
+        assert!(transfer(&mut dollars, &mut euros, dec!(10)).is_err());
+        assert_eq!(dollars.balance, dec!(500));
+        assert_eq!(euros.balance, dec!(100));
+    }
+
+    fn sealed_account() -> BankAccount {
+        let mut account = account(dec!(1000));
+        push_at(&mut account, TransactionKind::Deposit, dec!(200), local(2025, 1, 10));
+        push_at(&mut account, TransactionKind::Withdrawal, dec!(50), local(2025, 2, 10));
+        account.seal_transaction_log();
+        assert!(account.deposit_money(dec!(25)));
+        account
+    }
+
+    #[test]
+    fn modifying_a_past_transaction_is_detected() {
+        let mut account = sealed_account();
+        assert!(account.verify_integrity());
+
+        account.transactions[0].amount = dec!(2000);
+        assert!(!account.verify_integrity());
+    }
+
+    #[test]
+    #[should_panic(expected = "falsifié")]
+    fn sealed_ledger_refuses_appends_after_tampering() {
+        let mut account = sealed_account();
+        account.transactions[1].timestamp = local(2025, 3, 10);
+        account.deposit_money(dec!(10));
+    }
+
+    #[test]
+    fn ledger_root_does_not_depend_on_the_timezone() {
+        let account = sealed_account();
+        let mut json = serde_json::to_value(&account).unwrap();
+
+        // Même instant, écrit depuis une machine à UTC+9
+        let tokyo = FixedOffset::east_opt(9 * 3600).unwrap();
+        for transaction in json["transactions"].as_array_mut().unwrap() {
+            let timestamp = DateTime::parse_from_rfc3339(transaction["timestamp"].as_str().unwrap()).unwrap();
+            transaction["timestamp"] = timestamp.with_timezone(&tokyo).to_rfc3339().into();
+        }
+
+        let loaded: BankAccount = serde_json::from_value(json).unwrap();
+        assert_eq!(loaded.ledger_root_hash, account.ledger_root_hash);
+        assert!(loaded.verify_integrity());
+    }
+}
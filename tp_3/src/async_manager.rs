@@ -0,0 +1,417 @@
+// Variante asynchrone du gestionnaire de fichiers, basée sur tokio::fs
+// Utilisable depuis un serveur async sans bloquer le thread de l'exécuteur
+// Seules les opérations de base de FileManager sont reprises : lecture texte, écriture,
+// ajout, suppression, copie, déplacement, listage, répertoires et empreinte SHA-256.
+// Pas d'annulation, de hooks, de lecture hexadécimale ni de journal persistant.
+use std::io;
+use std::path::Path;
+
+use chrono::{DateTime, Local};
+use sha2::{Digest, Sha256};
+use tokio::fs;
+use tokio::io::AsyncReadExt;
+
+use crate::OperationResult;
+
+// Structure pour le gestionnaire de fichiers asynchrone
+#[derive(Debug)]
+pub struct AsyncFileManager {
+    current_directory: String,
+    operations_count: u32,
+    session_start: DateTime<Local>,
+    operation_log: Vec<String>,
+}
+
+impl Default for AsyncFileManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AsyncFileManager {
+    // Constructeur
+    pub fn new() -> Self {
+        AsyncFileManager {
+            current_directory: String::from("."),
+            operations_count: 0,
+            session_start: Local::now(),
+            operation_log: Vec::new(),
+        }
+    }
+
+    pub fn current_directory(&self) -> &str {
+        &self.current_directory
+    }
+
+    pub fn operations_count(&self) -> u32 {
+        self.operations_count
+    }
+
+    pub fn session_start(&self) -> DateTime<Local> {
+        self.session_start
+    }
+
+    pub fn operation_log(&self) -> &[String] {
+        &self.operation_log
+    }
+
+    // Journal en mémoire, même format que FileManager
+    fn log_operation(&mut self, operation: &str, details: &str) {
+        let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S");
+        self.operation_log.push(format!("[{}] {}: {}", timestamp, operation, details));
+        self.operations_count += 1;
+    }
+
+    // Méthode pour lire un fichier texte
+    pub async fn read_file(&mut self, filename: &str) -> OperationResult {
+        match fs::read_to_string(filename).await {
+            Ok(content) => {
+                self.log_operation("LECTURE", filename);
+                OperationResult::Success(content)
+            },
+            Err(e) => {
+                self.log_operation("ERREUR_LECTURE", &format!("{}: {}", filename, e));
+                OperationResult::Error(format!("Erreur lors de la lecture: {}", e))
+            },
+        }
+    }
+
+    // Méthode pour écrire dans un fichier
+    pub async fn write_file(&mut self, filename: &str, content: &str) -> OperationResult {
+        match fs::write(filename, content).await {
+            Ok(_) => {
+                self.log_operation("ECRITURE", filename);
+                OperationResult::Success(format!("Fichier '{}' écrit avec succès", filename))
+            },
+            Err(e) => {
+                self.log_operation("ERREUR_ECRITURE", &format!("{}: {}", filename, e));
+                OperationResult::Error(format!("Erreur lors de l'écriture: {}", e))
+            },
+        }
+    }
+
+    // Méthode pour modifier un fichier (ajouter du contenu)
+    pub async fn modify_file(&mut self, filename: &str, additional_content: &str) -> OperationResult {
+        let existing_content = match fs::read_to_string(filename).await {
+            Ok(content) => content,
+            Err(e) => {
+                self.log_operation("ERREUR_MODIFICATION", &format!("{}: {}", filename, e));
+                return OperationResult::Error(format!("Erreur lors de la lecture: {}", e));
+            }
+        };
+
+        let new_content = format!("{}\n{}", existing_content, additional_content);
+        match fs::write(filename, new_content).await {
+            Ok(_) => {
+                self.log_operation("MODIFICATION", filename);
+                OperationResult::Success(format!("Fichier '{}' modifié avec succès", filename))
+            },
+            Err(e) => {
+                self.log_operation("ERREUR_MODIFICATION", &format!("{}: {}", filename, e));
+                OperationResult::Error(format!("Erreur lors de la modification: {}", e))
+            },
+        }
+    }
+
+    // Méthode pour supprimer un fichier
+    pub async fn delete_file(&mut self, filename: &str) -> OperationResult {
+        match fs::remove_file(filename).await {
+            Ok(_) => {
+                self.log_operation("SUPPRESSION", filename);
+                OperationResult::Success(format!("Fichier '{}' supprimé", filename))
+            },
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                self.log_operation("ERREUR_SUPPRESSION", &format!("{}: fichier introuvable", filename));
+                OperationResult::Error(format!("Le fichier '{}' n'existe pas", filename))
+            },
+            Err(e) => {
+                self.log_operation("ERREUR_SUPPRESSION", &format!("{}: {}", filename, e));
+                OperationResult::Error(format!("Erreur lors de la suppression: {}", e))
+            },
+        }
+    }
+
+    // Méthode pour copier un fichier, sans écraser la destination sauf si demandé
+    pub async fn copy_file(&mut self, source: &str, destination: &str, overwrite: bool) -> OperationResult {
+        if !fs::metadata(source).await.is_ok_and(|metadata| metadata.is_file()) {
+            self.log_operation("ERREUR_COPIE", &format!("{}: fichier introuvable", source));
+            return OperationResult::Error(format!("Le fichier '{}' n'existe pas", source));
+        }
+
+        // Comme pour FileManager : copier un fichier sur lui-même le viderait
+        if let (Ok(from), Ok(to)) = (fs::canonicalize(source).await, fs::canonicalize(destination).await)
+            && from == to
+        {
+            self.log_operation("ERREUR_COPIE", &format!("{} -> {}: même fichier", source, destination));
+            return OperationResult::Error(format!("'{}' et '{}' désignent le même fichier", source, destination));
+        }
+
+        if fs::try_exists(destination).await.unwrap_or(false) && !overwrite {
+            self.log_operation("ERREUR_COPIE", &format!("{}: destination existante", destination));
+            return OperationResult::Error(format!("Le fichier '{}' existe déjà", destination));
+        }
+
+        match fs::copy(source, destination).await {
+            Ok(bytes) => {
+                self.log_operation("COPIE", &format!("{} -> {}", source, destination));
+                OperationResult::Success(format!(
+                    "Fichier '{}' copié vers '{}' ({} octets)", source, destination, bytes
+                ))
+            },
+            Err(e) => {
+                self.log_operation("ERREUR_COPIE", &format!("{} -> {}: {}", source, destination, e));
+                OperationResult::Error(format!("Erreur lors de la copie: {}", e))
+            },
+        }
+    }
+
+    // Méthode pour déplacer ou renommer un fichier ou un répertoire
+    pub async fn move_file(&mut self, source: &str, destination: &str) -> OperationResult {
+        match fs::rename(source, destination).await {
+            Ok(_) => {
+                self.log_operation("DEPLACEMENT", &format!("{} -> {}", source, destination));
+                OperationResult::Success(format!("'{}' déplacé vers '{}'", source, destination))
+            },
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                self.log_operation("ERREUR_DEPLACEMENT", &format!("{}: introuvable", source));
+                OperationResult::Error(format!("Le fichier '{}' n'existe pas", source))
+            },
+            Err(e) => {
+                self.log_operation("ERREUR_DEPLACEMENT", &format!("{} -> {}: {}", source, destination, e));
+                OperationResult::Error(format!("Erreur lors du déplacement: {}", e))
+            },
+        }
+    }
+
+    // Méthode pour lister le répertoire courant, trié par nom
+    pub async fn list_files(&mut self) -> OperationResult {
+        let current_dir = self.current_directory.clone();
+
+        let mut entries = match fs::read_dir(&current_dir).await {
+            Ok(entries) => entries,
+            Err(e) => {
+                self.log_operation("ERREUR_LISTAGE", &format!("{}: {}", current_dir, e));
+                return OperationResult::Error(format!("Erreur lors du listage: {}", e));
+            },
+        };
+
+        let mut rows = Vec::new();
+        let mut total_size = 0;
+        loop {
+            let entry = match entries.next_entry().await {
+                Ok(Some(entry)) => entry,
+                Ok(None) => break,
+                Err(_) => continue,
+            };
+            let name = entry.file_name().to_string_lossy().into_owned();
+            match fs::metadata(entry.path()).await {
+                Ok(metadata) if metadata.is_dir() => rows.push((name, "[DIR] ", "-".to_string())),
+                Ok(metadata) => {
+                    total_size += metadata.len();
+                    rows.push((name, "[FILE]", format!("{} octets", metadata.len())));
+                },
+                Err(_) => rows.push((name, "[FILE]", "-".to_string())),
+            }
+        }
+        rows.sort();
+
+        self.log_operation("LISTAGE", &current_dir);
+
+        let lines: Vec<String> = rows
+            .iter()
+            .map(|(name, file_type, size)| format!("{} {:30} | {}", file_type, name, size))
+            .collect();
+        OperationResult::Success(format!("{}\nTotal: {} élément(s), {} octets", lines.join("\n"), rows.len(), total_size))
+    }
+
+    // Méthode pour changer de répertoire courant
+    pub async fn change_directory(&mut self, path: &str) -> OperationResult {
+        if fs::metadata(path).await.is_ok_and(|metadata| metadata.is_dir()) {
+            let old_dir = std::mem::replace(&mut self.current_directory, path.to_string());
+            self.log_operation("CHANGEMENT_REP", &format!("{} -> {}", old_dir, path));
+            OperationResult::Success(format!("Répertoire changé vers: {}", path))
+        } else {
+            self.log_operation("ERREUR_CHANGEMENT_REP", &format!("{}: répertoire introuvable", path));
+            OperationResult::Error(format!("Le répertoire '{}' n'existe pas", path))
+        }
+    }
+
+    // Méthode pour créer un répertoire
+    pub async fn create_directory(&mut self, path: &str) -> OperationResult {
+        match fs::create_dir_all(path).await {
+            Ok(_) => {
+                self.log_operation("CREATION_REP", path);
+                OperationResult::Success(format!("Répertoire '{}' créé avec succès", path))
+            },
+            Err(e) => {
+                self.log_operation("ERREUR_CREATION_REP", &format!("{}: {}", path, e));
+                OperationResult::Error(format!("Erreur lors de la création du répertoire: {}", e))
+            },
+        }
+    }
+
+    // Méthode pour calculer l'empreinte SHA-256 d'un fichier
+    pub async fn compute_checksum(&mut self, filename: &str) -> OperationResult {
+        match Self::sha256_hex(Path::new(filename)).await {
+            Ok(digest) => {
+                self.log_operation("EMPREINTE", &format!("{}: {}", filename, digest));
+                OperationResult::Success(digest)
+            },
+            Err(e) => {
+                self.log_operation("ERREUR_EMPREINTE", &format!("{}: {}", filename, e));
+                OperationResult::Error(format!("Erreur lors du calcul de l'empreinte: {}", e))
+            },
+        }
+    }
+
+    // Lecture par blocs de 64 Ko pour ne pas charger tout le fichier en mémoire
+    async fn sha256_hex(path: &Path) -> io::Result<String> {
+        let mut file = fs::File::open(path).await?;
+        let mut hasher = Sha256::new();
+        let mut buffer = vec![0u8; 64 * 1024];
+
+        loop {
+            let read = file.read(&mut buffer).await?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+        }
+
+        Ok(hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    // Répertoire temporaire propre à un test, supprimé à la fin
+    struct TestDir(PathBuf);
+
+    impl TestDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("async_file_manager_{}_{}", name, std::process::id()));
+            let _ = std::fs::remove_dir_all(&path);
+            std::fs::create_dir_all(&path).unwrap();
+            TestDir(path)
+        }
+
+        fn path(&self, name: &str) -> String {
+            self.0.join(name).to_string_lossy().into_owned()
+        }
+    }
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn success(result: OperationResult) -> String {
+        match result {
+            OperationResult::Success(output) => output,
+            OperationResult::Error(e) => panic!("opération en échec: {}", e),
+        }
+    }
+
+    fn is_error(result: &OperationResult) -> bool {
+        matches!(result, OperationResult::Error(_))
+    }
+
+    #[tokio::test]
+    async fn write_modify_read_and_delete() {
+        let dir = TestDir::new("crud");
+        let mut manager = AsyncFileManager::new();
+        let file = dir.path("notes.txt");
+
+        success(manager.write_file(&file, "ligne 1").await);
+        success(manager.modify_file(&file, "ligne 2").await);
+        assert_eq!(success(manager.read_file(&file).await), "ligne 1\nligne 2");
+
+        success(manager.delete_file(&file).await);
+        assert!(is_error(&manager.read_file(&file).await));
+        assert!(is_error(&manager.delete_file(&file).await));
+        assert!(is_error(&manager.modify_file(&file, "x").await));
+
+        assert_eq!(manager.operations_count(), 7);
+        assert!(manager.operation_log()[0].ends_with(&format!("ECRITURE: {}", file)));
+        assert!(manager.operation_log()[5].contains("ERREUR_SUPPRESSION"));
+    }
+
+    #[tokio::test]
+    async fn copy_refuses_to_overwrite_unless_asked() {
+        let dir = TestDir::new("copy");
+        let mut manager = AsyncFileManager::new();
+        let source = dir.path("source.txt");
+        let destination = dir.path("copie.txt");
+        std::fs::write(&source, "original").unwrap();
+        std::fs::write(&destination, "ancien").unwrap();
+
+        assert!(is_error(&manager.copy_file(&source, &destination, false).await));
+        assert_eq!(std::fs::read_to_string(&destination).unwrap(), "ancien");
+
+        assert!(success(manager.copy_file(&source, &destination, true).await).ends_with("(8 octets)"));
+        assert_eq!(std::fs::read_to_string(&destination).unwrap(), "original");
+        assert!(is_error(&manager.copy_file(&dir.path("absent.txt"), &destination, true).await));
+    }
+
+    #[tokio::test]
+    async fn copy_onto_itself_keeps_the_content() {
+        let dir = TestDir::new("copy_self");
+        let mut manager = AsyncFileManager::new();
+        let source = dir.path("source.txt");
+        std::fs::write(&source, "contenu").unwrap();
+        std::fs::create_dir(dir.path("sous")).unwrap();
+
+        assert!(is_error(&manager.copy_file(&source, &source, true).await));
+        assert!(is_error(&manager.copy_file(&source, &dir.path("sous/../source.txt"), true).await));
+        assert_eq!(std::fs::read_to_string(&source).unwrap(), "contenu");
+    }
+
+    #[tokio::test]
+    async fn move_renames_across_directories() {
+        let dir = TestDir::new("move");
+        let mut manager = AsyncFileManager::new();
+        success(manager.create_directory(&dir.path("a/b")).await);
+        std::fs::write(dir.path("fichier.txt"), "contenu").unwrap();
+
+        success(manager.move_file(&dir.path("fichier.txt"), &dir.path("a/b/renomme.txt")).await);
+        assert!(!dir.0.join("fichier.txt").exists());
+        assert_eq!(std::fs::read_to_string(dir.path("a/b/renomme.txt")).unwrap(), "contenu");
+        assert!(is_error(&manager.move_file(&dir.path("fichier.txt"), &dir.path("x.txt")).await));
+    }
+
+    #[tokio::test]
+    async fn listing_is_sorted_with_a_total() {
+        let dir = TestDir::new("list");
+        let mut manager = AsyncFileManager::new();
+        std::fs::write(dir.path("b.txt"), "12345").unwrap();
+        std::fs::write(dir.path("a.txt"), "123").unwrap();
+        std::fs::create_dir(dir.path("sous")).unwrap();
+
+        success(manager.change_directory(&dir.path("")).await);
+        assert_eq!(manager.current_directory(), dir.path(""));
+        let output = success(manager.list_files().await);
+        let names: Vec<&str> = output.lines().filter_map(|line| line.split_whitespace().nth(1)).collect();
+        assert_eq!(&names[..3], ["a.txt", "b.txt", "sous"]);
+        assert!(output.ends_with("Total: 3 élément(s), 8 octets"));
+
+        assert!(is_error(&manager.change_directory(&dir.path("absent")).await));
+        assert_eq!(manager.current_directory(), dir.path(""));
+    }
+
+    #[tokio::test]
+    async fn checksum_of_known_sha256_vector() {
+        let dir = TestDir::new("checksum");
+        let mut manager = AsyncFileManager::new();
+        std::fs::write(dir.path("abc.txt"), "abc").unwrap();
+
+        assert_eq!(
+            success(manager.compute_checksum(&dir.path("abc.txt")).await),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert!(is_error(&manager.compute_checksum(&dir.path("absent.txt")).await));
+    }
+}
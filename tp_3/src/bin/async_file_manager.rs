@@ -0,0 +1,120 @@
+// Gestionnaire de fichiers asynchrone : même menu de base que tp_3, sur l'exécuteur tokio
+use std::io::Write;
+
+use tokio::io::{AsyncBufReadExt, BufReader, Lines, Stdin};
+use tp_3::OperationResult;
+use tp_3::async_manager::AsyncFileManager;
+
+// Lecture d'une ligne sans bloquer l'exécuteur (None en fin d'entrée)
+async fn get_user_input(lines: &mut Lines<BufReader<Stdin>>, prompt: &str) -> Option<String> {
+    print!("{}", prompt);
+    std::io::stdout().flush().expect("Erreur lors de l'affichage");
+    lines.next_line().await.expect("Erreur lors de la lecture").map(|line| line.trim().to_string())
+}
+
+fn print_result(result: OperationResult) {
+    match result {
+        OperationResult::Success(msg) => println!("✅ {}", msg),
+        OperationResult::Error(e) => println!("❌ {}", e),
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    println!("=== Gestionnaire de Fichiers Asynchrone (tokio) ===");
+    let mut file_manager = AsyncFileManager::new();
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+
+    loop {
+        println!("\n--- Menu Principal ---");
+        println!("1. Lire un fichier");
+        println!("2. Écrire un fichier");
+        println!("3. Modifier un fichier");
+        println!("4. Supprimer un fichier");
+        println!("5. Lister le répertoire");
+        println!("6. Changer de répertoire");
+        println!("7. Créer un répertoire");
+        println!("8. Copier un fichier");
+        println!("9. Déplacer/renommer");
+        println!("10. Calculer une empreinte SHA-256");
+        println!("11. Afficher le journal");
+        println!("12. Quitter");
+
+        let Some(choice) = get_user_input(&mut lines, "\nChoisissez une option (1-12): ").await else {
+            break;
+        };
+
+        match choice.as_str() {
+            "1" => {
+                let Some(filename) = get_user_input(&mut lines, "Nom du fichier à lire: ").await else { break };
+                match file_manager.read_file(&filename).await {
+                    OperationResult::Success(content) => {
+                        println!("=== Contenu de '{}' ===", filename);
+                        println!("{}", content);
+                        println!("=== Fin du fichier ===");
+                    }
+                    OperationResult::Error(e) => println!("❌ {}", e),
+                }
+            }
+            "2" => {
+                let Some(filename) = get_user_input(&mut lines, "Nom du fichier à écrire: ").await else { break };
+                let Some(content) = get_user_input(&mut lines, "Contenu: ").await else { break };
+                print_result(file_manager.write_file(&filename, &content).await);
+            }
+            "3" => {
+                let Some(filename) = get_user_input(&mut lines, "Nom du fichier à modifier: ").await else { break };
+                let Some(content) = get_user_input(&mut lines, "Contenu à ajouter: ").await else { break };
+                print_result(file_manager.modify_file(&filename, &content).await);
+            }
+            "4" => {
+                let Some(filename) = get_user_input(&mut lines, "Nom du fichier à supprimer: ").await else { break };
+                print_result(file_manager.delete_file(&filename).await);
+            }
+            "5" => match file_manager.list_files().await {
+                OperationResult::Success(listing) => {
+                    println!("=== Fichiers dans '{}' ===", file_manager.current_directory());
+                    println!("{}", listing);
+                    println!("=== Fin de la liste ===");
+                }
+                OperationResult::Error(e) => println!("❌ {}", e),
+            },
+            "6" => {
+                let Some(path) = get_user_input(&mut lines, "Nouveau répertoire: ").await else { break };
+                print_result(file_manager.change_directory(&path).await);
+            }
+            "7" => {
+                let Some(path) = get_user_input(&mut lines, "Nom du répertoire à créer: ").await else { break };
+                print_result(file_manager.create_directory(&path).await);
+            }
+            "8" => {
+                let Some(source) = get_user_input(&mut lines, "Fichier source: ").await else { break };
+                let Some(destination) = get_user_input(&mut lines, "Destination: ").await else { break };
+                print_result(file_manager.copy_file(&source, &destination, false).await);
+            }
+            "9" => {
+                let Some(source) = get_user_input(&mut lines, "Source: ").await else { break };
+                let Some(destination) = get_user_input(&mut lines, "Destination: ").await else { break };
+                print_result(file_manager.move_file(&source, &destination).await);
+            }
+            "10" => {
+                let Some(filename) = get_user_input(&mut lines, "Nom du fichier: ").await else { break };
+                match file_manager.compute_checksum(&filename).await {
+                    OperationResult::Success(digest) => println!("🔐 SHA-256: {}", digest),
+                    OperationResult::Error(e) => println!("❌ {}", e),
+                }
+            }
+            "11" => {
+                println!("=== Journal des Opérations ===");
+                for log_entry in file_manager.operation_log() {
+                    println!("{}", log_entry);
+                }
+                println!("=============================");
+            }
+            "12" => break,
+            _ => println!("❌ Option invalide. Veuillez choisir entre 1 et 12."),
+        }
+    }
+
+    println!("👋 Merci d'avoir utilisé le gestionnaire de fichiers!");
+    println!("Opérations effectuées: {}", file_manager.operations_count());
+}
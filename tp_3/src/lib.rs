@@ -0,0 +1,9 @@
+// Types partagés entre le gestionnaire synchrone (main.rs) et sa variante asynchrone
+pub mod async_manager;
+
+// Énumération pour les résultats d'opération
+#[derive(Debug)]
+pub enum OperationResult {
+    Success(String),
+    Error(String),
+}
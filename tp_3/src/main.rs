@@ -1,8 +1,34 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
 use std::fs;
-use std::io::{self, Write};
-use std::path::Path;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use chrono::{DateTime, Local, Utc};
+use clap::Parser;
+use glob::Pattern;
+use notify::event::{ModifyKind, RenameMode};
+use notify::{EventKind, RecursiveMode, Watcher};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tp_3::OperationResult;
+
+#[derive(Parser)]
+#[command(name = "file-manager")]
+#[command(about = "Gestionnaire de fichiers en Rust")]
+struct Args {
+    /// Journal des opérations conservé d'une session à l'autre (une entrée par ligne)
+    #[arg(long)]
+    log_file: Option<PathBuf>,
+
+    /// Fichier de session JSON : répertoire courant restauré au démarrage, sauvegardé en quittant
+    #[arg(long)]
+    session_file: Option<PathBuf>,
+}
 
 // Structure pour le gestionnaire de fichiers
 #[derive(Debug)]
@@ -11,6 +37,156 @@ struct FileManager {
     operations_count: u32,
     session_start: DateTime<Local>,
     operation_log: Vec<String>,
+    undo_stack: Vec<UndoEntry>,
+    log_file_path: Option<PathBuf>,
+    hooks: Vec<Hook>,
+}
+
+// Critère de tri du listage
+#[derive(Debug, Default, Clone, Copy)]
+enum SortBy {
+    #[default]
+    Name,
+    Size,
+    Modified,
+}
+
+// Options du listage : filtres puis tri
+#[derive(Debug, Default)]
+struct ListOptions {
+    filter_extension: Option<String>,   // `*.rs`, `.rs` ou `rs`
+    min_size: Option<u64>,
+    max_size: Option<u64>,
+    sort_by: SortBy,
+    sort_desc: bool,
+}
+
+impl ListOptions {
+    // Avec un filtre actif, seuls les fichiers correspondants sont affichés
+    fn matches(&self, entry: &ListedEntry) -> bool {
+        let filtering = self.filter_extension.is_some() || self.min_size.is_some() || self.max_size.is_some();
+        if !filtering {
+            return true;
+        }
+        if entry.is_dir {
+            return false;
+        }
+
+        if let Some(filter) = &self.filter_extension {
+            let extension = filter.trim_start_matches('*').trim_start_matches('.');
+            let matches_extension = Path::new(&entry.name)
+                .extension()
+                .is_some_and(|ext| ext.to_string_lossy().eq_ignore_ascii_case(extension));
+            if !matches_extension {
+                return false;
+            }
+        }
+
+        self.min_size.is_none_or(|min| entry.size >= min) && self.max_size.is_none_or(|max| entry.size <= max)
+    }
+}
+
+// Entrée de répertoire collectée avant filtrage et tri
+struct ListedEntry {
+    name: String,
+    is_dir: bool,
+    size: u64,
+    modified: Option<SystemTime>,
+    link_target: Option<PathBuf>,   // cible si l'entrée est un lien symbolique
+    broken_link: bool,
+}
+
+// Totaux affichés sous l'arborescence
+#[derive(Debug, Default)]
+struct TreeTotals {
+    directories: usize,
+    files: usize,
+    size: u64,
+}
+
+// Type d'entrée recherché par `find`
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FindType {
+    File,
+    Dir,
+    Symlink,
+}
+
+// Critères de `find`, tous optionnels et combinés (ET)
+#[derive(Debug, Default)]
+struct FindCriteria {
+    name_pattern: Option<String>,         // Motif glob sur le nom : `*.rs`, `test?.txt`
+    min_size_bytes: Option<u64>,
+    max_size_bytes: Option<u64>,
+    modified_within_secs: Option<u64>,
+    file_type: Option<FindType>,
+}
+
+// Résultat du calcul de la taille d'un répertoire
+#[derive(Debug, Default)]
+struct DirectorySize {
+    total_bytes: u64,
+    files: usize,
+    directories: usize,
+    unreadable: usize,
+    largest_files: Vec<(u64, PathBuf)>,   // Du plus gros au plus petit
+}
+
+// Nombre de plus gros fichiers affichés par compute_directory_size
+const LARGEST_FILES_SHOWN: usize = 5;
+
+// État de la session conservé entre deux lancements
+#[derive(Debug, Serialize, Deserialize)]
+struct SessionState {
+    current_directory: String,
+    operations_count: u32,
+}
+
+// Sauvegarde prise avant une opération destructrice, pour pouvoir l'annuler
+#[derive(Debug)]
+struct UndoEntry {
+    operation: String,
+    original_path: String,
+    backup_path: String,
+}
+
+// Moments où les hooks sont appelés, autour des opérations qui modifient les fichiers
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum HookEvent {
+    BeforeWrite,
+    AfterWrite,
+    BeforeDelete,
+    AfterDelete,
+}
+
+// Ce que voit un hook : l'événement, le fichier et le contenu écrit quand il est connu
+// (None pour une suppression, une copie, un déplacement, une annulation, un lien ou un répertoire)
+#[derive(Debug)]
+struct HookContext<'a> {
+    event: HookEvent,
+    path: &'a str,
+    content: Option<&'a str>,
+}
+
+// Un hook `Before*` qui renvoie Abort empêche l'opération (le motif est affiché)
+#[derive(Debug, PartialEq)]
+enum HookResult {
+    Continue,
+    #[cfg_attr(not(test), allow(dead_code))] // Aucun hook bloquant n'est enregistré par défaut
+    Abort(String),
+}
+
+// Callback utilisateur enregistré pour un événement
+struct Hook {
+    event: HookEvent,
+    callback: Box<dyn Fn(&HookContext) -> HookResult>,
+}
+
+// Les closures n'implémentent pas Debug : seul l'événement est affiché
+impl fmt::Debug for Hook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Hook").field("event", &self.event).finish_non_exhaustive()
+    }
 }
 
 // Énumération pour les différentes opérations
@@ -26,13 +202,20 @@ enum FileOperation {
     ShowLog,
     ShowStats,
     Exit,
-}
-
-// Énumération pour les résultats d'opération
-#[derive(Debug)]
-enum OperationResult {
-    Success(String),
-    Error(String),
+    Copy,
+    Move,
+    RecursiveList,
+    Search,
+    Checksum,
+    Undo,
+    Tree,
+    Metadata,
+    Watch,
+    DirectorySize,
+    Find,
+    Duplicates,
+    CreateSymlink,
+    ResolveSymlink,
 }
 
 // Implémentation des méthodes pour FileManager
@@ -44,23 +227,121 @@ impl FileManager {
             operations_count: 0,
             session_start: Local::now(),
             operation_log: Vec::new(),
+            undo_stack: Vec::new(),
+            log_file_path: None,
+            hooks: Vec::new(),
         }
     }
 
+    // Constructeur avec journal persistant : reprend les entrées des sessions précédentes
+    fn with_log_file(path: &Path) -> io::Result<Self> {
+        let mut manager = FileManager::new();
+        manager.open_log_file(path)?;
+        Ok(manager)
+    }
+
+    // Charger les entrées existantes puis journaliser dans ce fichier
+    fn open_log_file(&mut self, path: &Path) -> io::Result<()> {
+        match fs::read_to_string(path) {
+            Ok(content) => {
+                self.operation_log = content.lines().map(String::from).collect();
+            },
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {},
+            Err(e) => return Err(e),
+        }
+
+        self.log_file_path = Some(path.to_path_buf());
+        Ok(())
+    }
+
+    // Sauvegarder le répertoire courant et le compteur d'opérations (JSON)
+    fn save_session(&self, path: &Path) -> io::Result<()> {
+        let session = SessionState {
+            current_directory: self.current_directory.clone(),
+            operations_count: self.operations_count,
+        };
+        let json = serde_json::to_string_pretty(&session)?;
+        fs::write(path, json)
+    }
+
+    // Restaurer une session ; si le répertoire n'existe plus, on repart de `.`
+    fn load_session(path: &Path) -> io::Result<FileManager> {
+        let content = fs::read_to_string(path)?;
+        let session: SessionState = serde_json::from_str(&content)?;
+
+        let mut manager = FileManager::new();
+        manager.operations_count = session.operations_count;
+        if Path::new(&session.current_directory).is_dir() {
+            manager.current_directory = session.current_directory;
+        } else {
+            println!("⚠️  Le répertoire '{}' n'existe plus, retour à '.'", session.current_directory);
+        }
+        Ok(manager)
+    }
+
+    // Copier un fichier dans le dossier temporaire avant de le modifier ou supprimer
+    fn create_backup(&self, filename: &str) -> io::Result<String> {
+        let backup_name = format!(
+            "file_manager_undo_{}_{}_{}",
+            std::process::id(), self.operations_count, self.undo_stack.len()
+        );
+        let backup_path = std::env::temp_dir().join(backup_name);
+        fs::copy(filename, &backup_path)?;
+        Ok(backup_path.to_string_lossy().into_owned())
+    }
+
     // Méthode utilitaire pour logger les opérations avec timestamps
     fn log_operation(&mut self, operation: &str, details: &str) {
         let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S");
         let log_entry = format!("[{}] {}: {}", timestamp, operation, details);
+
+        // Écriture immédiate dans le journal persistant
+        if let Some(path) = &self.log_file_path {
+            let written = fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut file| writeln!(file, "{}", log_entry));
+            if let Err(e) = written {
+                eprintln!("⚠️  Impossible d'écrire dans le journal '{}': {}", path.display(), e);
+            }
+        }
+
         self.operation_log.push(log_entry);
         self.operations_count += 1;
     }
 
+    // Les hooks sont appelés par toutes les opérations qui écrivent ou suppriment un fichier
+    fn register_hook(&mut self, hook: Hook) {
+        self.hooks.push(hook);
+    }
+
+    // Appelle les hooks de l'événement dans l'ordre d'enregistrement ; le premier Abort arrête tout
+    fn run_hooks(&mut self, event: HookEvent, path: &str, content: Option<&str>) -> Result<(), String> {
+        let context = HookContext { event, path, content };
+        let aborted = self.hooks
+            .iter()
+            .filter(|hook| hook.event == event)
+            .find_map(|hook| match (hook.callback)(&context) {
+                HookResult::Continue => None,
+                HookResult::Abort(reason) => Some(reason),
+            });
+
+        match aborted {
+            Some(reason) => {
+                self.log_operation("HOOK_ANNULATION", &format!("{:?} {}: {}", event, path, reason));
+                Err(reason)
+            },
+            None => Ok(()),
+        }
+    }
+
     // Méthode pour formater une date système
     fn format_system_time(time: SystemTime) -> String {
         match time.duration_since(UNIX_EPOCH) {
             Ok(duration) => {
                 let datetime: DateTime<Utc> = DateTime::from_timestamp(duration.as_secs() as i64, 0)
-                    .unwrap_or_else(|| Utc::now());
+                    .unwrap_or_else(Utc::now);
                 datetime.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string()
             },
             Err(_) => "Date inconnue".to_string(),
@@ -68,11 +349,27 @@ impl FileManager {
     }
 
     // Méthode pour lire un fichier (démontre ownership)
-    fn read_file(&mut self, filename: &str) -> OperationResult {
-        match fs::read_to_string(filename) {
-            Ok(content) => {
-                self.log_operation("LECTURE", filename);
-                OperationResult::Success(content)
+    // Les fichiers binaires (ou `force_hex`) sont affichés en hexadécimal
+    fn read_file(&mut self, filename: &str, force_hex: bool) -> OperationResult {
+        match fs::read(filename) {
+            Ok(data) => {
+                // UTF-8 invalide : affiché en hexadécimal également
+                let text = if force_hex || is_binary(&data) {
+                    Err(data)
+                } else {
+                    String::from_utf8(data).map_err(|e| e.into_bytes())
+                };
+
+                match text {
+                    Ok(content) => {
+                        self.log_operation("LECTURE", filename);
+                        OperationResult::Success(content)
+                    },
+                    Err(data) => {
+                        self.log_operation("LECTURE_HEX", filename);
+                        OperationResult::Success(hex_dump(&data))
+                    },
+                }
             },
             Err(e) => {
                 self.log_operation("ERREUR_LECTURE", &format!("{}: {}", filename, e));
@@ -83,9 +380,15 @@ impl FileManager {
 
     // Méthode pour écrire dans un fichier
     fn write_file(&mut self, filename: &str, content: &str) -> OperationResult {
+        if let Err(reason) = self.run_hooks(HookEvent::BeforeWrite, filename, Some(content)) {
+            return OperationResult::Error(format!("Écriture annulée par un hook: {}", reason));
+        }
+
         match fs::write(filename, content) {
             Ok(_) => {
                 self.log_operation("ECRITURE", filename);
+                // Un Abort après coup ne peut plus rien empêcher : il est seulement journalisé
+                let _ = self.run_hooks(HookEvent::AfterWrite, filename, Some(content));
                 OperationResult::Success(format!("Fichier '{}' écrit avec succès", filename))
             },
             Err(e) => {
@@ -97,6 +400,10 @@ impl FileManager {
 
     // Méthode pour modifier un fichier (ajouter du contenu)
     fn modify_file(&mut self, filename: &str, additional_content: &str) -> OperationResult {
+        if let Err(reason) = self.run_hooks(HookEvent::BeforeWrite, filename, Some(additional_content)) {
+            return OperationResult::Error(format!("Modification annulée par un hook: {}", reason));
+        }
+
         // Lire le contenu existant
         let existing_content = match fs::read_to_string(filename) {
             Ok(content) => content,
@@ -109,75 +416,227 @@ impl FileManager {
         // Combiner avec le nouveau contenu
         let new_content = format!("{}\n{}", existing_content, additional_content);
 
+        // Sauvegarder l'original pour pouvoir annuler
+        let backup_path = match self.create_backup(filename) {
+            Ok(path) => path,
+            Err(e) => {
+                self.log_operation("ERREUR_MODIFICATION", &format!("{}: sauvegarde impossible: {}", filename, e));
+                return OperationResult::Error(format!("Erreur lors de la sauvegarde: {}", e));
+            }
+        };
+
         match fs::write(filename, new_content) {
             Ok(_) => {
+                self.undo_stack.push(UndoEntry {
+                    operation: "MODIFICATION".to_string(),
+                    original_path: filename.to_string(),
+                    backup_path,
+                });
                 self.log_operation("MODIFICATION", filename);
+                let _ = self.run_hooks(HookEvent::AfterWrite, filename, Some(additional_content));
                 OperationResult::Success(format!("Fichier '{}' modifié avec succès", filename))
             },
             Err(e) => {
+                let _ = fs::remove_file(&backup_path);
                 self.log_operation("ERREUR_MODIFICATION", &format!("{}: {}", filename, e));
                 OperationResult::Error(format!("Erreur lors de la modification: {}", e))
             },
         }
     }
 
-    // Méthode pour supprimer définitivement un fichier
+    // Méthode pour supprimer un fichier (annulable pendant la session)
     fn delete_file(&mut self, filename: &str) -> OperationResult {
         if !Path::new(filename).exists() {
             self.log_operation("ERREUR_SUPPRESSION", &format!("{}: fichier introuvable", filename));
             return OperationResult::Error(format!("Le fichier '{}' n'existe pas", filename));
         }
 
+        if let Err(reason) = self.run_hooks(HookEvent::BeforeDelete, filename, None) {
+            return OperationResult::Error(format!("Suppression annulée par un hook: {}", reason));
+        }
+
+        // Sauvegarder le fichier pour pouvoir annuler
+        let backup_path = match self.create_backup(filename) {
+            Ok(path) => path,
+            Err(e) => {
+                self.log_operation("ERREUR_SUPPRESSION", &format!("{}: sauvegarde impossible: {}", filename, e));
+                return OperationResult::Error(format!("Erreur lors de la sauvegarde: {}", e));
+            }
+        };
+
         match fs::remove_file(filename) {
             Ok(_) => {
+                self.undo_stack.push(UndoEntry {
+                    operation: "SUPPRESSION".to_string(),
+                    original_path: filename.to_string(),
+                    backup_path,
+                });
                 self.log_operation("SUPPRESSION", filename);
-                OperationResult::Success(format!("Fichier '{}' supprimé définitivement", filename))
+                let _ = self.run_hooks(HookEvent::AfterDelete, filename, None);
+                OperationResult::Success(format!("Fichier '{}' supprimé", filename))
             },
             Err(e) => {
+                let _ = fs::remove_file(&backup_path);
                 self.log_operation("ERREUR_SUPPRESSION", &format!("{}: {}", filename, e));
                 OperationResult::Error(format!("Erreur lors de la suppression: {}", e))
             },
         }
     }
 
-    // Méthode pour lister les fichiers du répertoire courant
-    fn list_files(&mut self) -> OperationResult {
+    // Méthode pour annuler la dernière modification ou suppression
+    fn undo_last_operation(&mut self) -> OperationResult {
+        let entry = match self.undo_stack.pop() {
+            Some(entry) => entry,
+            None => return OperationResult::Error("Aucune opération à annuler".to_string()),
+        };
+
+        // Restaurer l'original réécrit le fichier : les hooks peuvent s'y opposer
+        if let Err(reason) = self.run_hooks(HookEvent::BeforeWrite, &entry.original_path, None) {
+            self.undo_stack.push(entry);
+            return OperationResult::Error(format!("Annulation refusée par un hook: {}", reason));
+        }
+
+        match fs::copy(&entry.backup_path, &entry.original_path) {
+            Ok(_) => {
+                let _ = fs::remove_file(&entry.backup_path);
+                self.log_operation("ANNULATION", &format!("{} {}", entry.operation, entry.original_path));
+                let _ = self.run_hooks(HookEvent::AfterWrite, &entry.original_path, None);
+                OperationResult::Success(format!(
+                    "{} de '{}' annulée", entry.operation, entry.original_path
+                ))
+            },
+            Err(e) => {
+                self.log_operation("ERREUR_ANNULATION", &format!("{}: {}", entry.original_path, e));
+                // On garde la sauvegarde pour une nouvelle tentative
+                self.undo_stack.push(entry);
+                OperationResult::Error(format!("Erreur lors de l'annulation: {}", e))
+            },
+        }
+    }
+
+    // Méthode pour copier un fichier
+    fn copy_file(&mut self, source: &str, destination: &str, overwrite: bool) -> OperationResult {
+        if !Path::new(source).is_file() {
+            self.log_operation("ERREUR_COPIE", &format!("{}: fichier introuvable", source));
+            return OperationResult::Error(format!("Le fichier '{}' n'existe pas", source));
+        }
+
+        // fs::copy tronque la destination avant de lire la source : copier un fichier
+        // sur lui-même (même sous un autre chemin ou via un lien) le viderait
+        if let (Ok(from), Ok(to)) = (fs::canonicalize(source), fs::canonicalize(destination))
+            && from == to
+        {
+            self.log_operation("ERREUR_COPIE", &format!("{} -> {}: même fichier", source, destination));
+            return OperationResult::Error(format!("'{}' et '{}' désignent le même fichier", source, destination));
+        }
+
+        if Path::new(destination).exists() && !overwrite {
+            self.log_operation("ERREUR_COPIE", &format!("{}: destination existante", destination));
+            return OperationResult::Error(format!("Le fichier '{}' existe déjà", destination));
+        }
+
+        if let Err(reason) = self.run_hooks(HookEvent::BeforeWrite, destination, None) {
+            return OperationResult::Error(format!("Copie annulée par un hook: {}", reason));
+        }
+
+        match fs::copy(source, destination) {
+            Ok(bytes) => {
+                self.log_operation("COPIE", &format!("{} -> {}", source, destination));
+                let _ = self.run_hooks(HookEvent::AfterWrite, destination, None);
+                OperationResult::Success(format!(
+                    "Fichier '{}' copié vers '{}' ({} octets)", source, destination, bytes
+                ))
+            },
+            Err(e) => {
+                self.log_operation("ERREUR_COPIE", &format!("{} -> {}: {}", source, destination, e));
+                OperationResult::Error(format!("Erreur lors de la copie: {}", e))
+            },
+        }
+    }
+
+    // Méthode pour déplacer ou renommer un fichier (ou un répertoire)
+    fn move_file(&mut self, source: &str, destination: &str) -> OperationResult {
+        let source_path = Path::new(source);
+        if !source_path.exists() {
+            self.log_operation("ERREUR_DEPLACEMENT", &format!("{}: introuvable", source));
+            return OperationResult::Error(format!("Le fichier '{}' n'existe pas", source));
+        }
+
+        // Un déplacement supprime la source et écrit la destination
+        let allowed = self.run_hooks(HookEvent::BeforeDelete, source, None)
+            .and_then(|_| self.run_hooks(HookEvent::BeforeWrite, destination, None));
+        if let Err(reason) = allowed {
+            return OperationResult::Error(format!("Déplacement annulé par un hook: {}", reason));
+        }
+
+        // Chemins absolus pour savoir si le répertoire courant est concerné
+        let source_abs = fs::canonicalize(source_path).ok();
+        let current_abs = fs::canonicalize(&self.current_directory).ok();
+
+        let moved = match fs::rename(source, destination) {
+            Ok(_) => Ok(()),
+            // Autre système de fichiers : copie puis suppression de l'original
+            Err(e) if e.kind() == io::ErrorKind::CrossesDevices && source_path.is_file() => {
+                fs::copy(source, destination).and_then(|_| fs::remove_file(source))
+            },
+            Err(e) => Err(e),
+        };
+
+        match moved {
+            Ok(_) => {
+                self.log_operation("DEPLACEMENT", &format!("{} -> {}", source, destination));
+                let _ = self.run_hooks(HookEvent::AfterDelete, source, None);
+                let _ = self.run_hooks(HookEvent::AfterWrite, destination, None);
+
+                // Le répertoire courant était dans l'ancien chemin : on le suit
+                if let (Some(source_abs), Some(current_abs)) = (source_abs, current_abs)
+                    && let Ok(relative) = current_abs.strip_prefix(&source_abs)
+                {
+                    let new_directory = Path::new(destination).join(relative);
+                    self.current_directory = new_directory.to_string_lossy().into_owned();
+                }
+
+                OperationResult::Success(format!("'{}' déplacé vers '{}'", source, destination))
+            },
+            Err(e) => {
+                self.log_operation("ERREUR_DEPLACEMENT", &format!("{} -> {}: {}", source, destination, e));
+                OperationResult::Error(format!("Erreur lors du déplacement: {}", e))
+            },
+        }
+    }
+
+    // Méthode pour lister les fichiers du répertoire courant (filtrés et triés)
+    fn list_files(&mut self, options: &ListOptions) -> OperationResult {
         // Clone current_directory to avoid borrow conflicts
         let current_dir = self.current_directory.clone();
 
         match fs::read_dir(&current_dir) {
             Ok(entries) => {
-                let mut files = Vec::new();
+                let mut files: Vec<ListedEntry> = Vec::new();
 
                 // Utilisation d'une boucle for pour parcourir les entrées
                 for entry in entries {
                     match entry {
                         Ok(entry) => {
                             let path = entry.path();
-                            if let Some(name) = path.file_name() {
-                                if let Some(name_str) = name.to_str() {
-                                    let file_type = if path.is_dir() { "[DIR] " } else { "[FILE]" };
-
-                                    // Obtenir les métadonnées pour la date et la taille
-                                    match path.metadata() {
-                                        Ok(metadata) => {
-                                            let modified_time = metadata.modified()
-                                                .map(|time| Self::format_system_time(time))
-                                                .unwrap_or_else(|_| "Date inconnue".to_string());
-
-                                            let size = if path.is_file() {
-                                                format!("{} octets", metadata.len())
-                                            } else {
-                                                "-".to_string()
-                                            };
-
-                                            files.push(format!("{} {:30} | {:20} | {}",
-                                                               file_type, name_str, modified_time, size));
-                                        },
-                                        Err(_) => {
-                                            files.push(format!("{} {}", file_type, name_str));
-                                        }
-                                    }
+                            if let Some(name_str) = path.file_name().and_then(|name| name.to_str()) {
+                                // Obtenir les métadonnées pour la date et la taille (en suivant les liens)
+                                let metadata = path.metadata().ok();
+                                let link_target = fs::symlink_metadata(&path)
+                                    .is_ok_and(|metadata| metadata.file_type().is_symlink())
+                                    .then(|| fs::read_link(&path).ok())
+                                    .flatten();
+                                let listed = ListedEntry {
+                                    name: name_str.to_string(),
+                                    is_dir: path.is_dir(),
+                                    size: metadata.as_ref().map(|metadata| metadata.len()).unwrap_or(0),
+                                    broken_link: link_target.is_some() && metadata.is_none(),
+                                    modified: metadata.and_then(|metadata| metadata.modified().ok()),
+                                    link_target,
+                                };
+
+                                if options.matches(&listed) {
+                                    files.push(listed);
                                 }
                             }
                         }
@@ -185,19 +644,828 @@ impl FileManager {
                     }
                 }
 
-                // Now we can safely log since we're using the cloned string
-                self.log_operation("LISTAGE", &current_dir);
+                match options.sort_by {
+                    SortBy::Name => files.sort_by(|a, b| a.name.cmp(&b.name)),
+                    SortBy::Size => files.sort_by_key(|entry| entry.size),
+                    SortBy::Modified => files.sort_by_key(|entry| entry.modified),
+                }
+                if options.sort_desc {
+                    files.reverse();
+                }
+
+                // Now we can safely log since we're using the cloned string
+                self.log_operation("LISTAGE", &current_dir);
+
+                let rows: Vec<String> = files
+                    .iter()
+                    .map(|entry| {
+                        let file_type = match (&entry.link_target, entry.is_dir) {
+                            (Some(_), _) if entry.broken_link => "[BROKEN LINK]",
+                            (Some(_), _) => "[LINK]",
+                            (None, true) => "[DIR] ",
+                            (None, false) => "[FILE]",
+                        };
+                        let name = match &entry.link_target {
+                            Some(target) => format!("{} -> {}", entry.name, target.display()),
+                            None => entry.name.clone(),
+                        };
+                        let modified_time = entry.modified
+                            .map(Self::format_system_time)
+                            .unwrap_or_else(|| "Date inconnue".to_string());
+                        let size = if entry.is_dir || entry.broken_link { "-".to_string() } else { format!("{} octets", entry.size) };
+                        format!("{} {:30} | {:20} | {}", file_type, name, modified_time, size)
+                    })
+                    .collect();
+
+                let total_size: u64 = files.iter().filter(|entry| !entry.is_dir && entry.link_target.is_none()).map(|entry| entry.size).sum();
+                let header = format!("Type  | {:30} | {:20} | Taille", "Nom", "Date de modification");
+                let separator = "-".repeat(80);
+                let result = format!("{}\n{}\n{}\n{}\nTotal: {} élément(s), {} octets",
+                                     header, separator, rows.join("\n"), separator, files.len(), total_size);
+
+                OperationResult::Success(result)
+            }
+            Err(e) => {
+                // Use the cloned string here too
+                self.log_operation("ERREUR_LISTAGE", &format!("{}: {}", current_dir, e));
+                OperationResult::Error(format!("Erreur lors du listage: {}", e))
+            }
+        }
+    }
+
+    // Méthode pour lister récursivement le répertoire courant (0 = profondeur illimitée)
+    fn list_files_recursive(&mut self, max_depth: usize) -> OperationResult {
+        let current_dir = self.current_directory.clone();
+
+        let root_metadata = match fs::metadata(&current_dir) {
+            Ok(metadata) if metadata.is_dir() => metadata,
+            Ok(_) => {
+                self.log_operation("ERREUR_LISTAGE_RECURSIF", &format!("{}: pas un répertoire", current_dir));
+                return OperationResult::Error(format!("'{}' n'est pas un répertoire", current_dir));
+            },
+            Err(e) => {
+                self.log_operation("ERREUR_LISTAGE_RECURSIF", &format!("{}: {}", current_dir, e));
+                return OperationResult::Error(format!("Erreur lors du listage: {}", e));
+            },
+        };
+
+        // Répertoires déjà parcourus, pour ne pas boucler sur un lien symbolique
+        let mut visited_inodes = HashSet::new();
+        if let Some(key) = Self::inode_key(&root_metadata) {
+            visited_inodes.insert(key);
+        }
+
+        let mut lines = vec![current_dir.clone()];
+        Self::walk_directory(Path::new(&current_dir), "", 1, max_depth, &mut visited_inodes, &mut lines);
+
+        self.log_operation("LISTAGE_RECURSIF", &format!("{} (profondeur {})", current_dir, max_depth));
+        OperationResult::Success(lines.join("\n"))
+    }
+
+    // Parcours d'un niveau de l'arborescence, avec préfixe `│   ` par niveau
+    fn walk_directory(
+        path: &Path,
+        prefix: &str,
+        depth: usize,
+        max_depth: usize,
+        visited_inodes: &mut HashSet<(u64, u64)>,
+        lines: &mut Vec<String>,
+    ) {
+        let mut entries: Vec<_> = match fs::read_dir(path) {
+            Ok(entries) => entries.filter_map(|entry| entry.ok()).collect(),
+            Err(e) => {
+                lines.push(format!("{}└── [illisible: {}]", prefix, e));
+                return;
+            },
+        };
+        entries.sort_by_key(|entry| entry.file_name());
+
+        for (index, entry) in entries.iter().enumerate() {
+            let is_last = index == entries.len() - 1;
+            let connector = if is_last { "└── " } else { "├── " };
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let entry_path = entry.path();
+
+            // fs::metadata suit les liens symboliques
+            let metadata = match fs::metadata(&entry_path) {
+                Ok(metadata) => metadata,
+                Err(_) => {
+                    lines.push(format!("{}{}{} [lien cassé]", prefix, connector, name));
+                    continue;
+                },
+            };
+
+            if !metadata.is_dir() {
+                lines.push(format!("{}{}{} ({} octets)", prefix, connector, name, metadata.len()));
+                continue;
+            }
+
+            if let Some(key) = Self::inode_key(&metadata)
+                && !visited_inodes.insert(key)
+            {
+                lines.push(format!("{}{}{}/ [boucle ignorée]", prefix, connector, name));
+                continue;
+            }
+
+            lines.push(format!("{}{}{}/", prefix, connector, name));
+            if max_depth == 0 || depth < max_depth {
+                let child_prefix = format!("{}{}", prefix, if is_last { "    " } else { "│   " });
+                Self::walk_directory(&entry_path, &child_prefix, depth + 1, max_depth, visited_inodes, lines);
+            }
+        }
+    }
+
+    // Identifiant unique d'un répertoire (périphérique, inode)
+    #[cfg(unix)]
+    fn inode_key(metadata: &fs::Metadata) -> Option<(u64, u64)> {
+        use std::os::unix::fs::MetadataExt;
+        Some((metadata.dev(), metadata.ino()))
+    }
+
+    #[cfg(not(unix))]
+    fn inode_key(_metadata: &fs::Metadata) -> Option<(u64, u64)> {
+        None
+    }
+
+    // Méthode pour calculer la taille totale d'un répertoire, avec le détail et les plus gros fichiers
+    fn compute_directory_size(&mut self, path: &str) -> OperationResult {
+        let root_metadata = match fs::metadata(path) {
+            Ok(metadata) if metadata.is_dir() => metadata,
+            Ok(_) => {
+                self.log_operation("ERREUR_TAILLE_REP", &format!("{}: pas un répertoire", path));
+                return OperationResult::Error(format!("'{}' n'est pas un répertoire", path));
+            },
+            Err(e) => {
+                self.log_operation("ERREUR_TAILLE_REP", &format!("{}: {}", path, e));
+                return OperationResult::Error(format!("Erreur lors du calcul de la taille: {}", e));
+            },
+        };
+
+        // Inodes déjà comptés : pas de boucle sur un lien symbolique, pas de doublon (liens durs)
+        let mut visited_inodes = HashSet::new();
+        if let Some(key) = Self::inode_key(&root_metadata) {
+            visited_inodes.insert(key);
+        }
+
+        let mut size = DirectorySize::default();
+        Self::accumulate_size(Path::new(path), &mut visited_inodes, &mut size);
+
+        self.log_operation("TAILLE_REP", &format!("{}: {} octets, {} fichier(s)", path, size.total_bytes, size.files));
+
+        let total = size.total_bytes as f64;
+        let mut lines = vec![
+            format!("Taille de '{}'", path),
+            format!(
+                "   Total: {} octets | {:.2} Ko | {:.2} Mo | {:.2} Go",
+                size.total_bytes, total / 1024.0, total / (1024.0 * 1024.0), total / (1024.0 * 1024.0 * 1024.0)
+            ),
+            format!("   Fichiers: {}", size.files),
+            format!("   Sous-répertoires: {}", size.directories),
+        ];
+        if size.unreadable > 0 {
+            lines.push(format!("   ⚠️  Entrées illisibles ignorées: {}", size.unreadable));
+        }
+        if !size.largest_files.is_empty() {
+            lines.push(format!("   {} plus gros fichiers:", size.largest_files.len()));
+            for (rank, (bytes, file_path)) in size.largest_files.iter().enumerate() {
+                let relative = file_path.strip_prefix(path).unwrap_or(file_path);
+                lines.push(format!("      {}. {:>11}  {}", rank + 1, human_size(*bytes), relative.display()));
+            }
+        }
+        OperationResult::Success(lines.join("\n"))
+    }
+
+    // Parcours récursif (liens symboliques suivis) en cumulant tailles et compteurs
+    fn accumulate_size(path: &Path, visited_inodes: &mut HashSet<(u64, u64)>, size: &mut DirectorySize) {
+        let entries = match fs::read_dir(path) {
+            Ok(entries) => entries,
+            Err(_) => {
+                size.unreadable += 1;
+                return;
+            },
+        };
+
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let entry_path = entry.path();
+            let metadata = match fs::metadata(&entry_path) {
+                Ok(metadata) => metadata,
+                Err(_) => {
+                    size.unreadable += 1;
+                    continue;
+                },
+            };
+
+            if let Some(key) = Self::inode_key(&metadata)
+                && !visited_inodes.insert(key)
+            {
+                continue;
+            }
+
+            if metadata.is_dir() {
+                size.directories += 1;
+                Self::accumulate_size(&entry_path, visited_inodes, size);
+            } else {
+                size.files += 1;
+                size.total_bytes += metadata.len();
+                size.largest_files.push((metadata.len(), entry_path));
+                size.largest_files.sort_by_key(|(bytes, _)| Reverse(*bytes));
+                size.largest_files.truncate(LARGEST_FILES_SHOWN);
+            }
+        }
+    }
+
+    // Méthode pour chercher récursivement depuis le répertoire courant les entrées
+    // correspondant à tous les critères (liens symboliques non suivis)
+    fn find(&mut self, criteria: FindCriteria) -> OperationResult {
+        let current_dir = self.current_directory.clone();
+
+        let pattern = match criteria.name_pattern.as_deref().map(Pattern::new).transpose() {
+            Ok(pattern) => pattern,
+            Err(e) => {
+                self.log_operation("ERREUR_RECHERCHE_FICHIERS", &format!("{:?}: {}", criteria.name_pattern, e));
+                return OperationResult::Error(format!("Motif invalide: {}", e));
+            },
+        };
+        if !Path::new(&current_dir).is_dir() {
+            self.log_operation("ERREUR_RECHERCHE_FICHIERS", &format!("{}: répertoire introuvable", current_dir));
+            return OperationResult::Error(format!("Le répertoire '{}' n'existe pas", current_dir));
+        }
+
+        let mut matches = Vec::new();
+        Self::find_in(Path::new(&current_dir), &criteria, pattern.as_ref(), SystemTime::now(), &mut matches);
+        matches.sort();
+
+        self.log_operation("RECHERCHE_FICHIERS", &format!("{}: {} résultat(s)", current_dir, matches.len()));
+
+        if matches.is_empty() {
+            return OperationResult::Success("Aucun fichier ne correspond aux critères".to_string());
+        }
+        let mut lines: Vec<String> = matches.iter().map(|path| path.display().to_string()).collect();
+        lines.push(format!("{} résultat(s)", matches.len()));
+        OperationResult::Success(lines.join("\n"))
+    }
+
+    fn find_in(
+        path: &Path,
+        criteria: &FindCriteria,
+        pattern: Option<&Pattern>,
+        now: SystemTime,
+        matches: &mut Vec<PathBuf>,
+    ) {
+        let entries = match fs::read_dir(path) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let entry_path = entry.path();
+            // symlink_metadata : les liens sont reconnus comme tels et jamais parcourus
+            let metadata = match fs::symlink_metadata(&entry_path) {
+                Ok(metadata) => metadata,
+                Err(_) => continue,
+            };
+            let file_type = if metadata.file_type().is_symlink() {
+                FindType::Symlink
+            } else if metadata.is_dir() {
+                FindType::Dir
+            } else {
+                FindType::File
+            };
+
+            if Self::matches_criteria(&entry.file_name().to_string_lossy(), file_type, &metadata, criteria, pattern, now) {
+                matches.push(entry_path.clone());
+            }
+            if file_type == FindType::Dir {
+                Self::find_in(&entry_path, criteria, pattern, now, matches);
+            }
+        }
+    }
+
+    // Les critères de taille ne retiennent que des fichiers, comme le filtre du listage
+    fn matches_criteria(
+        name: &str,
+        file_type: FindType,
+        metadata: &fs::Metadata,
+        criteria: &FindCriteria,
+        pattern: Option<&Pattern>,
+        now: SystemTime,
+    ) -> bool {
+        if criteria.file_type.is_some_and(|wanted| wanted != file_type) {
+            return false;
+        }
+        if pattern.is_some_and(|pattern| !pattern.matches(name)) {
+            return false;
+        }
+
+        let size_filtering = criteria.min_size_bytes.is_some() || criteria.max_size_bytes.is_some();
+        if size_filtering {
+            let size = metadata.len();
+            if file_type != FindType::File
+                || criteria.min_size_bytes.is_some_and(|min| size < min)
+                || criteria.max_size_bytes.is_some_and(|max| size > max)
+            {
+                return false;
+            }
+        }
+
+        // Date dans le futur : considérée comme modifiée à l'instant
+        criteria.modified_within_secs.is_none_or(|secs| {
+            metadata
+                .modified()
+                .map(|modified| now.duration_since(modified).unwrap_or(Duration::ZERO))
+                .is_ok_and(|age| age.as_secs() <= secs)
+        })
+    }
+
+    // Méthode pour afficher l'arborescence façon `tree(1)` (liens symboliques non suivis)
+    fn display_tree(&mut self, max_depth: Option<usize>) -> OperationResult {
+        let current_dir = self.current_directory.clone();
+
+        if !Path::new(&current_dir).is_dir() {
+            self.log_operation("ERREUR_ARBORESCENCE", &format!("{}: répertoire introuvable", current_dir));
+            return OperationResult::Error(format!("Le répertoire '{}' n'existe pas", current_dir));
+        }
+
+        let mut lines = vec![current_dir.clone()];
+        let mut totals = TreeTotals::default();
+        Self::render_tree(Path::new(&current_dir), "", 1, max_depth, &mut lines, &mut totals);
+
+        lines.push(String::new());
+        lines.push(format!(
+            "{} répertoire(s), {} fichier(s), {} octets",
+            totals.directories, totals.files, totals.size
+        ));
+
+        self.log_operation("ARBORESCENCE", &current_dir);
+        OperationResult::Success(lines.join("\n"))
+    }
+
+    fn render_tree(
+        path: &Path,
+        prefix: &str,
+        depth: usize,
+        max_depth: Option<usize>,
+        lines: &mut Vec<String>,
+        totals: &mut TreeTotals,
+    ) {
+        let mut entries: Vec<_> = match fs::read_dir(path) {
+            Ok(entries) => entries.filter_map(|entry| entry.ok()).collect(),
+            Err(_) => return,
+        };
+        entries.sort_by_key(|entry| entry.file_name());
+
+        for (index, entry) in entries.iter().enumerate() {
+            let is_last = index == entries.len() - 1;
+            let connector = if is_last { "└── " } else { "├── " };
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let entry_path = entry.path();
+
+            // symlink_metadata ne suit pas les liens : pas de risque de boucle
+            let metadata = match fs::symlink_metadata(&entry_path) {
+                Ok(metadata) => metadata,
+                Err(_) => continue,
+            };
+
+            if metadata.file_type().is_symlink() {
+                let target = fs::read_link(&entry_path)
+                    .map(|target| target.display().to_string())
+                    .unwrap_or_default();
+                lines.push(format!("{}{}{} -> {}", prefix, connector, name, target));
+                totals.files += 1;
+            } else if metadata.is_dir() {
+                lines.push(format!("{}{}{}", prefix, connector, name));
+                totals.directories += 1;
+                if max_depth.is_none_or(|max| depth < max) {
+                    let child_prefix = format!("{}{}", prefix, if is_last { "    " } else { "│   " });
+                    Self::render_tree(&entry_path, &child_prefix, depth + 1, max_depth, lines, totals);
+                }
+            } else {
+                lines.push(format!("{}{}{}", prefix, connector, name));
+                totals.files += 1;
+                totals.size += metadata.len();
+            }
+        }
+    }
+
+    // Méthode pour afficher toutes les métadonnées d'un fichier
+    fn show_file_metadata(&mut self, filename: &str) -> OperationResult {
+        // symlink_metadata pour reconnaître les liens symboliques eux-mêmes
+        let metadata = match fs::symlink_metadata(filename) {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                self.log_operation("ERREUR_METADONNEES", &format!("{}: {}", filename, e));
+                return OperationResult::Error(format!("Erreur lors de la lecture des métadonnées: {}", e));
+            },
+        };
+
+        let absolute_path = std::path::absolute(filename)
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|_| filename.to_string());
+        let file_type = if metadata.file_type().is_symlink() {
+            "lien symbolique"
+        } else if metadata.is_dir() {
+            "répertoire"
+        } else if metadata.is_file() {
+            "fichier régulier"
+        } else {
+            "spécial"
+        };
+        let format_time = |time: io::Result<SystemTime>| {
+            time.map(Self::format_system_time).unwrap_or_else(|_| "non disponible".to_string())
+        };
+
+        let mut lines = vec![
+            format!("Chemin absolu      : {}", absolute_path),
+            format!("Type               : {}", file_type),
+            format!("Taille             : {}", if metadata.len() < 1024 {
+                human_size(metadata.len())
+            } else {
+                format!("{} octets ({})", metadata.len(), human_size(metadata.len()))
+            }),
+        ];
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            let mode = metadata.mode() & 0o7777;
+            lines.push(format!("Permissions        : {:04o} ({})", mode, permissions_string(mode)));
+            lines.push(format!("Inode              : {}", metadata.ino()));
+            lines.push(format!("Liens physiques    : {}", metadata.nlink()));
+        }
+        #[cfg(not(unix))]
+        lines.push(format!("Lecture seule      : {}", metadata.permissions().readonly()));
+
+        lines.push(format!("Création           : {}", format_time(metadata.created())));
+        lines.push(format!("Dernier accès      : {}", format_time(metadata.accessed())));
+        lines.push(format!("Dernière modif.    : {}", format_time(metadata.modified())));
+
+        self.log_operation("METADONNEES", filename);
+        OperationResult::Success(lines.join("\n"))
+    }
+
+    // Méthode pour créer un lien symbolique `link_name` pointant vers `target`
+    fn create_symlink(&mut self, target: &str, link_name: &str) -> OperationResult {
+        if let Err(reason) = self.run_hooks(HookEvent::BeforeWrite, link_name, None) {
+            return OperationResult::Error(format!("Création du lien annulée par un hook: {}", reason));
+        }
+
+        #[cfg(unix)]
+        let result = std::os::unix::fs::symlink(target, link_name);
+        #[cfg(not(unix))]
+        let result: io::Result<()> = Err(io::Error::new(io::ErrorKind::Unsupported, "liens symboliques Unix uniquement"));
+
+        match result {
+            Ok(()) => {
+                self.log_operation("CREATION_LIEN", &format!("{} -> {}", link_name, target));
+                let _ = self.run_hooks(HookEvent::AfterWrite, link_name, None);
+                // Une cible absente n'empêche pas la création, mais on le signale
+                if fs::metadata(link_name).is_err() {
+                    OperationResult::Success(format!("Lien '{}' -> '{}' créé (⚠️ la cible n'existe pas: lien cassé)", link_name, target))
+                } else {
+                    OperationResult::Success(format!("Lien '{}' -> '{}' créé avec succès", link_name, target))
+                }
+            },
+            Err(e) => {
+                self.log_operation("ERREUR_CREATION_LIEN", &format!("{} -> {}: {}", link_name, target, e));
+                OperationResult::Error(format!("Erreur lors de la création du lien: {}", e))
+            },
+        }
+    }
+
+    // Méthode pour résoudre un lien symbolique et vérifier que sa cible existe
+    fn resolve_symlink(&mut self, link_name: &str) -> OperationResult {
+        match fs::symlink_metadata(link_name) {
+            Ok(metadata) if metadata.file_type().is_symlink() => {},
+            Ok(_) => {
+                self.log_operation("ERREUR_RESOLUTION_LIEN", &format!("{}: pas un lien symbolique", link_name));
+                return OperationResult::Error(format!("'{}' n'est pas un lien symbolique", link_name));
+            },
+            Err(e) => {
+                self.log_operation("ERREUR_RESOLUTION_LIEN", &format!("{}: {}", link_name, e));
+                return OperationResult::Error(format!("Erreur lors de la lecture du lien: {}", e));
+            },
+        }
+
+        let target = match fs::read_link(link_name) {
+            Ok(target) => target,
+            Err(e) => {
+                self.log_operation("ERREUR_RESOLUTION_LIEN", &format!("{}: {}", link_name, e));
+                return OperationResult::Error(format!("Erreur lors de la lecture du lien: {}", e));
+            },
+        };
+
+        // fs::canonicalize suit toute la chaîne de liens, relative au répertoire du lien
+        match fs::canonicalize(link_name) {
+            Ok(resolved) => {
+                self.log_operation("RESOLUTION_LIEN", &format!("{} -> {}", link_name, resolved.display()));
+                OperationResult::Success(format!("[LINK] {} -> {}\nCible résolue: {}", link_name, target.display(), resolved.display()))
+            },
+            Err(_) => {
+                self.log_operation("RESOLUTION_LIEN", &format!("{} -> {} (lien cassé)", link_name, target.display()));
+                OperationResult::Success(format!("[BROKEN LINK] {} -> {}\nLa cible n'existe pas", link_name, target.display()))
+            },
+        }
+    }
+
+    // Méthode pour chercher une chaîne dans les fichiers du répertoire courant
+    fn search_in_files(&mut self, pattern: &str, case_sensitive: bool) -> OperationResult {
+        if case_sensitive {
+            self.search_with(pattern, |line| line.contains(pattern))
+        } else {
+            let pattern_lower = pattern.to_lowercase();
+            self.search_with(pattern, |line| line.to_lowercase().contains(&pattern_lower))
+        }
+    }
+
+    // Variante de la recherche avec une expression régulière
+    fn search_in_files_regex(&mut self, pattern: &str) -> OperationResult {
+        match Regex::new(pattern) {
+            Ok(regex) => self.search_with(pattern, |line| regex.is_match(line)),
+            Err(e) => {
+                self.log_operation("ERREUR_RECHERCHE", &format!("{}: {}", pattern, e));
+                OperationResult::Error(format!("Expression régulière invalide: {}", e))
+            },
+        }
+    }
+
+    // Parcourir les fichiers texte du répertoire courant et collecter les lignes correspondantes
+    fn search_with(&mut self, pattern: &str, matches: impl Fn(&str) -> bool) -> OperationResult {
+        let current_dir = self.current_directory.clone();
+
+        let mut paths: Vec<_> = match fs::read_dir(&current_dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file())
+                .collect(),
+            Err(e) => {
+                self.log_operation("ERREUR_RECHERCHE", &format!("{}: {}", current_dir, e));
+                return OperationResult::Error(format!("Erreur lors de la recherche: {}", e));
+            },
+        };
+        paths.sort();
+
+        // (fichier, numéro de ligne, contenu)
+        let mut results: Vec<(String, usize, String)> = Vec::new();
+        for path in paths {
+            let data = match fs::read(&path) {
+                Ok(data) => data,
+                Err(_) => continue,
+            };
+            // Fichier binaire : on l'ignore
+            if data.contains(&0) {
+                continue;
+            }
+
+            let filename = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+            let content = String::from_utf8_lossy(&data);
+            for (index, line) in content.lines().enumerate() {
+                if matches(line) {
+                    results.push((filename.clone(), index + 1, line.trim().to_string()));
+                }
+            }
+        }
+
+        self.log_operation("RECHERCHE", &format!("'{}' dans {}: {} résultat(s)", pattern, current_dir, results.len()));
+
+        if results.is_empty() {
+            return OperationResult::Success(format!("Aucune occurrence de '{}'", pattern));
+        }
+
+        let header = format!("{:30} | {:>6} | Contenu", "Fichier", "Ligne");
+        let separator = "-".repeat(80);
+        let rows: Vec<String> = results
+            .iter()
+            .map(|(filename, line_number, line)| format!("{:30} | {:>6} | {}", filename, line_number, line))
+            .collect();
+
+        OperationResult::Success(format!("{}\n{}\n{}\n{}\n{} résultat(s)",
+                                         header, separator, rows.join("\n"), separator, results.len()))
+    }
+
+    // Méthode pour calculer l'empreinte SHA-256 d'un fichier (en hexadécimal)
+    fn compute_checksum(&mut self, filename: &str) -> OperationResult {
+        match Self::sha256_hex(filename) {
+            Ok(digest) => {
+                self.log_operation("EMPREINTE", &format!("{}: {}", filename, digest));
+                OperationResult::Success(digest)
+            },
+            Err(e) => {
+                self.log_operation("ERREUR_EMPREINTE", &format!("{}: {}", filename, e));
+                OperationResult::Error(format!("Erreur lors du calcul de l'empreinte: {}", e))
+            },
+        }
+    }
+
+    // Méthode pour vérifier l'empreinte SHA-256 d'un fichier
+    fn verify_checksum(&mut self, filename: &str, expected_hex: &str) -> OperationResult {
+        let digest = match Self::sha256_hex(filename) {
+            Ok(digest) => digest,
+            Err(e) => {
+                self.log_operation("ERREUR_VERIFICATION", &format!("{}: {}", filename, e));
+                return OperationResult::Error(format!("Erreur lors du calcul de l'empreinte: {}", e));
+            },
+        };
+
+        if digest.eq_ignore_ascii_case(expected_hex.trim()) {
+            self.log_operation("VERIFICATION", &format!("{}: empreinte conforme", filename));
+            OperationResult::Success(format!("Empreinte conforme: {}", digest))
+        } else {
+            self.log_operation("ERREUR_VERIFICATION", &format!("{}: empreinte différente", filename));
+            OperationResult::Error(format!("Empreinte différente!\n   attendue: {}\n   calculée: {}",
+                                           expected_hex.trim(), digest))
+        }
+    }
+
+    // Lecture par blocs de 64 Ko pour ne pas charger tout le fichier en mémoire
+    fn sha256_hex(filename: &str) -> io::Result<String> {
+        let mut file = fs::File::open(filename)?;
+        let mut hasher = Sha256::new();
+        let mut buffer = vec![0u8; 64 * 1024];
+
+        loop {
+            let read = file.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+        }
+
+        Ok(hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect())
+    }
+
+    // Méthode pour surveiller un répertoire et afficher les événements au fil de l'eau
+    // Arrêt après `duration_secs` secondes, ou sur `q` + Entrée
+    fn watch_directory(&mut self, path: &str, duration_secs: u64) -> OperationResult {
+        // Le clavier est lu dans un thread, lancé seulement une fois la surveillance en place
+        let mut keyboard = None;
+        let result = self.watch_directory_with_keys(path, duration_secs, || {
+            let (key_tx, key_rx) = mpsc::channel::<String>();
+            keyboard = Some(thread::spawn(move || {
+                let mut line = String::new();
+                while matches!(io::stdin().read_line(&mut line), Ok(read) if read > 0) {
+                    if key_tx.send(line.trim().to_lowercase()).is_err() {
+                        break;
+                    }
+                    line.clear();
+                }
+            }));
+            key_rx
+        });
+
+        // Le thread attend encore une ligne : libérer le clavier avant de revenir au menu
+        if let Some(keyboard) = keyboard {
+            if !keyboard.is_finished() {
+                println!("⏱️  Durée écoulée, appuyez sur Entrée pour revenir au menu");
+            }
+            let _ = keyboard.join();
+        }
+        result
+    }
+
+    // Boucle de surveillance ; `keys` fournit les touches saisies (`q` arrête la surveillance)
+    fn watch_directory_with_keys(
+        &mut self,
+        path: &str,
+        duration_secs: u64,
+        keys: impl FnOnce() -> mpsc::Receiver<String>,
+    ) -> OperationResult {
+        if !Path::new(path).is_dir() {
+            self.log_operation("ERREUR_SURVEILLANCE", &format!("{}: répertoire introuvable", path));
+            return OperationResult::Error(format!("Le répertoire '{}' n'existe pas", path));
+        }
+
+        // inotify (Linux), kqueue/FSEvents (macOS) selon la plateforme
+        let (event_tx, event_rx) = mpsc::channel();
+        let watching = notify::recommended_watcher(event_tx)
+            .and_then(|mut watcher| watcher.watch(Path::new(path), RecursiveMode::Recursive).map(|_| watcher));
+        let watcher = match watching {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                self.log_operation("ERREUR_SURVEILLANCE", &format!("{}: {}", path, e));
+                return OperationResult::Error(format!("Erreur lors de la surveillance: {}", e));
+            },
+        };
+
+        let key_rx = keys();
+        println!("👀 Surveillance de '{}' pendant {} s (q + Entrée pour arrêter)", path, duration_secs);
+
+        let deadline = Instant::now() + Duration::from_secs(duration_secs);
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut stopped_by_user = false;
+
+        loop {
+            if key_rx.try_iter().any(|key| key == "q") {
+                stopped_by_user = true;
+                break;
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+
+            match event_rx.recv_timeout(remaining.min(Duration::from_millis(100))) {
+                Ok(Ok(event)) => {
+                    let label = match event.kind {
+                        // Moitiés d'un renommage suivi : l'événement complet (ancien -> nouveau) suit
+                        EventKind::Modify(ModifyKind::Name(RenameMode::From | RenameMode::To))
+                            if event.tracker().is_some() => continue,
+                        EventKind::Create(_) => "CRÉATION",
+                        EventKind::Modify(ModifyKind::Name(_)) => "RENOMMAGE",
+                        EventKind::Modify(_) => "MODIFICATION",
+                        EventKind::Remove(_) => "SUPPRESSION",
+                        // Accès (ouverture, fermeture...) : trop bavard, ignoré
+                        _ => continue,
+                    };
+                    let paths: Vec<String> = event.paths.iter().map(|path| path.display().to_string()).collect();
+                    println!("[{}] {:12} {}", Local::now().format("%H:%M:%S%.3f"), label, paths.join(" -> "));
+                    *counts.entry(label).or_insert(0) += 1;
+                },
+                Ok(Err(e)) => println!("⚠️  Erreur de surveillance: {}", e),
+                Err(RecvTimeoutError::Timeout) => {},
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+        drop(watcher);
+        // Le thread du clavier s'arrête à la prochaine ligne lue
+        drop(key_rx);
+
+        let total: usize = counts.values().sum();
+        let reason = if stopped_by_user { "arrêt demandé" } else { "durée écoulée" };
+        self.log_operation("SURVEILLANCE", &format!("{}: {} événement(s), {}", path, total, reason));
+
+        let mut lines = vec![format!("Surveillance de '{}' terminée ({}): {} événement(s)", path, reason, total)];
+        for (label, count) in &counts {
+            lines.push(format!("   {:12} {}", label, count));
+        }
+        OperationResult::Success(lines.join("\n"))
+    }
+
+    // Méthode pour trouver les fichiers identiques sous le répertoire courant
+    // Deux passes : regroupement par taille, puis SHA-256 seulement pour les tailles partagées
+    fn find_duplicates(&mut self) -> OperationResult {
+        let current_dir = self.current_directory.clone();
+        if !Path::new(&current_dir).is_dir() {
+            self.log_operation("ERREUR_DOUBLONS", &format!("{}: répertoire introuvable", current_dir));
+            return OperationResult::Error(format!("Le répertoire '{}' n'existe pas", current_dir));
+        }
+
+        let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+        Self::collect_files_by_size(Path::new(&current_dir), &mut by_size);
+
+        // (empreinte, taille, fichiers)
+        let mut groups: Vec<(String, u64, Vec<PathBuf>)> = Vec::new();
+        for (size, paths) in by_size.into_iter().filter(|(_, paths)| paths.len() > 1) {
+            let mut by_hash: HashMap<String, Vec<PathBuf>> = HashMap::new();
+            for path in paths {
+                if let Ok(digest) = Self::sha256_hex(&path.to_string_lossy()) {
+                    by_hash.entry(digest).or_default().push(path);
+                }
+            }
+            for (digest, mut paths) in by_hash.into_iter().filter(|(_, paths)| paths.len() > 1) {
+                paths.sort();
+                groups.push((digest, size, paths));
+            }
+        }
+        // Les groupes qui libèrent le plus de place d'abord
+        groups.sort_by_key(|(_, size, paths)| Reverse(size * (paths.len() as u64 - 1)));
+
+        let wasted: u64 = groups.iter().map(|(_, size, paths)| size * (paths.len() as u64 - 1)).sum();
+        self.log_operation("DOUBLONS", &format!("{}: {} groupe(s), {} octets en double", current_dir, groups.len(), wasted));
+
+        if groups.is_empty() {
+            return OperationResult::Success("Aucun fichier en double".to_string());
+        }
+
+        let mut lines = Vec::new();
+        for (digest, size, paths) in &groups {
+            lines.push(format!("{} ({}, {} fichiers)", digest, human_size(*size), paths.len()));
+            for path in paths {
+                lines.push(format!("   {}", path.display()));
+            }
+        }
+        lines.push(format!("{} groupe(s) de doublons, {} récupérables", groups.len(), human_size(wasted)));
+        OperationResult::Success(lines.join("\n"))
+    }
+
+    // Fichiers réguliers non vides regroupés par taille (liens symboliques ignorés)
+    fn collect_files_by_size(path: &Path, by_size: &mut HashMap<u64, Vec<PathBuf>>) {
+        let entries = match fs::read_dir(path) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
 
-                let header = format!("Type  | {:30} | {:20} | Taille", "Nom", "Date de modification");
-                let separator = "-".repeat(80);
-                let result = format!("{}\n{}\n{}", header, separator, files.join("\n"));
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let entry_path = entry.path();
+            let metadata = match fs::symlink_metadata(&entry_path) {
+                Ok(metadata) => metadata,
+                Err(_) => continue,
+            };
 
-                OperationResult::Success(result)
-            }
-            Err(e) => {
-                // Use the cloned string here too
-                self.log_operation("ERREUR_LISTAGE", &format!("{}: {}", current_dir, e));
-                OperationResult::Error(format!("Erreur lors du listage: {}", e))
+            if metadata.is_dir() {
+                Self::collect_files_by_size(&entry_path, by_size);
+            } else if metadata.is_file() && metadata.len() > 0 {
+                by_size.entry(metadata.len()).or_default().push(entry_path);
             }
         }
     }
@@ -217,9 +1485,14 @@ impl FileManager {
 
     // Méthode pour créer un répertoire
     fn create_directory(&mut self, path: &str) -> OperationResult {
+        if let Err(reason) = self.run_hooks(HookEvent::BeforeWrite, path, None) {
+            return OperationResult::Error(format!("Création du répertoire annulée par un hook: {}", reason));
+        }
+
         match fs::create_dir_all(path) {
             Ok(_) => {
                 self.log_operation("CREATION_REP", path);
+                let _ = self.run_hooks(HookEvent::AfterWrite, path, None);
                 OperationResult::Success(format!("Répertoire '{}' créé avec succès", path))
             },
             Err(e) => {
@@ -270,6 +1543,71 @@ impl FileManager {
     }
 }
 
+// Les sauvegardes d'annulation ne survivent pas à la session
+impl Drop for FileManager {
+    fn drop(&mut self) {
+        for entry in &self.undo_stack {
+            let _ = fs::remove_file(&entry.backup_path);
+        }
+    }
+}
+
+// Taille lisible : octets, Ko, Mo ou Go
+fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["octets", "Ko", "Mo", "Go"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+// Permissions Unix au format `rwxr-xr-x`
+#[cfg(unix)]
+fn permissions_string(mode: u32) -> String {
+    let flags = [
+        (0o400, 'r'), (0o200, 'w'), (0o100, 'x'),
+        (0o040, 'r'), (0o020, 'w'), (0o010, 'x'),
+        (0o004, 'r'), (0o002, 'w'), (0o001, 'x'),
+    ];
+    flags.iter().map(|&(bit, flag)| if mode & bit != 0 { flag } else { '-' }).collect()
+}
+
+// Un octet nul dans les 512 premiers octets indique un fichier binaire
+fn is_binary(data: &[u8]) -> bool {
+    data.iter().take(512).any(|&byte| byte == 0)
+}
+
+// Affichage façon `xxd` : décalage, 16 octets en hexadécimal, puis ASCII
+fn hex_dump(data: &[u8]) -> String {
+    let mut lines = Vec::new();
+
+    for (index, chunk) in data.chunks(16).enumerate() {
+        let mut hex = String::new();
+        for (position, byte) in chunk.iter().enumerate() {
+            if position > 0 && position % 2 == 0 {
+                hex.push(' ');
+            }
+            hex.push_str(&format!("{:02x}", byte));
+        }
+
+        let ascii: String = chunk
+            .iter()
+            .map(|&byte| if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' })
+            .collect();
+
+        lines.push(format!("{:08x}: {:39}  {}", index * 16, hex, ascii));
+    }
+
+    lines.join("\n")
+}
+
 // Fonction pour obtenir l'entrée utilisateur
 fn get_user_input(prompt: &str) -> String {
     print!("{}", prompt);
@@ -293,6 +1631,20 @@ fn parse_operation(input: &str) -> Option<FileOperation> {
         "8" | "journal" | "log" => Some(FileOperation::ShowLog),
         "9" | "stats" | "statistiques" => Some(FileOperation::ShowStats),
         "10" | "quitter" | "exit" => Some(FileOperation::Exit),
+        "11" | "copier" | "copy" => Some(FileOperation::Copy),
+        "12" | "deplacer" | "move" | "renommer" | "rename" => Some(FileOperation::Move),
+        "13" | "lister-recursif" | "rlist" => Some(FileOperation::RecursiveList),
+        "14" | "chercher" | "search" | "grep" => Some(FileOperation::Search),
+        "15" | "empreinte" | "checksum" | "sha256" => Some(FileOperation::Checksum),
+        "16" | "annuler" | "undo" => Some(FileOperation::Undo),
+        "17" | "arbre" | "tree" => Some(FileOperation::Tree),
+        "18" | "metadonnees" | "metadata" | "stat" => Some(FileOperation::Metadata),
+        "19" | "surveiller" | "watch" => Some(FileOperation::Watch),
+        "20" | "taille" | "du" => Some(FileOperation::DirectorySize),
+        "21" | "trouver" | "find" => Some(FileOperation::Find),
+        "22" | "doublons" | "duplicates" => Some(FileOperation::Duplicates),
+        "23" | "lien" | "ln" | "symlink" => Some(FileOperation::CreateSymlink),
+        "24" | "resoudre" | "readlink" => Some(FileOperation::ResolveSymlink),
         _ => None,
     }
 }
@@ -303,7 +1655,47 @@ fn main() {
     println!("Utilise les concepts: Ownership, Loops, Match, Impl");
     println!("========================================");
 
-    let mut file_manager = FileManager::new();
+    let args = Args::parse();
+
+    // Session restaurée en priorité, puis journal persistant
+    let session_file = args.session_file.as_deref().filter(|path| path.exists());
+    let loaded = match (session_file, &args.log_file) {
+        (Some(session_path), log_path) => FileManager::load_session(session_path).and_then(|mut manager| {
+            if let Some(log_path) = log_path {
+                manager.open_log_file(log_path)?;
+            }
+            Ok(manager)
+        }),
+        (None, Some(log_path)) => FileManager::with_log_file(log_path),
+        (None, None) => Ok(FileManager::new()),
+    };
+
+    let mut file_manager = match loaded {
+        Ok(manager) => manager,
+        Err(e) => {
+            eprintln!("❌ Impossible de charger la session ou le journal: {}", e);
+            std::process::exit(1);
+        },
+    };
+
+    if let Some(path) = session_file {
+        println!("💾 Session restaurée depuis '{}' (répertoire: {})", path.display(), file_manager.current_directory);
+    }
+    if let Some(path) = &args.log_file {
+        println!("📜 Journal '{}' chargé ({} entrée(s))", path.display(), file_manager.operation_log.len());
+    }
+
+    // Hook d'exemple après écriture : signale un fichier vidé par erreur
+    file_manager.register_hook(Hook {
+        event: HookEvent::AfterWrite,
+        callback: Box::new(|context| {
+            if context.content.is_some_and(|content| content.trim().is_empty()) {
+                println!("⚠️  {:?}: '{}' ne contient que des blancs", context.event, context.path);
+            }
+            HookResult::Continue
+        }),
+    });
+    println!("🪝 Hook actif: alerte sur écriture vide");
 
     // Boucle principale (utilisation de loop)
     loop {
@@ -318,8 +1710,22 @@ fn main() {
         println!("8. Afficher le journal");
         println!("9. Afficher les statistiques");
         println!("10. Quitter");
+        println!("11. Copier un fichier");
+        println!("12. Déplacer/renommer un fichier");
+        println!("13. Lister récursivement");
+        println!("14. Chercher dans les fichiers");
+        println!("15. Calculer une empreinte SHA-256");
+        println!("16. Annuler la dernière opération");
+        println!("17. Afficher l'arborescence");
+        println!("18. Afficher les métadonnées d'un fichier");
+        println!("19. Surveiller un répertoire");
+        println!("20. Taille d'un répertoire");
+        println!("21. Trouver des fichiers");
+        println!("22. Trouver les doublons");
+        println!("23. Créer un lien symbolique");
+        println!("24. Résoudre un lien symbolique");
 
-        let choice = get_user_input("\nChoisissez une option (1-10): ");
+        let choice = get_user_input("\nChoisissez une option (1-24): ");
 
         // Utilisation de match pour traiter les choix
         match parse_operation(&choice) {
@@ -328,7 +1734,9 @@ fn main() {
                 match operation {
                     FileOperation::Read => {
                         let filename = get_user_input("Nom du fichier à lire: ");
-                        let result = file_manager.read_file(&filename);
+                        let hex_input = get_user_input("Forcer l'affichage hexadécimal? (oui/non): ");
+                        let force_hex = matches!(hex_input.to_lowercase().as_str(), "oui" | "o" | "yes" | "y");
+                        let result = file_manager.read_file(&filename, force_hex);
 
                         match result {
                             OperationResult::Success(content) => {
@@ -364,7 +1772,7 @@ fn main() {
 
                     FileOperation::Delete => {
                         let filename = get_user_input("Nom du fichier à supprimer: ");
-                        println!("⚠️  ATTENTION: Cette action est irréversible!");
+                        println!("⚠️  ATTENTION: Le fichier ne pourra être restauré (option 16) que pendant cette session!");
                         let confirmation = get_user_input("Confirmez-vous? (oui/non): ");
 
                         // Utilisation d'une boucle while pour la confirmation
@@ -387,7 +1795,25 @@ fn main() {
                     }
 
                     FileOperation::List => {
-                        let result = file_manager.list_files();
+                        let filter_input = get_user_input("Filtre d'extension (ex: *.rs, vide = aucun): ");
+                        let min_input = get_user_input("Taille minimale en octets (vide = aucune): ");
+                        let max_input = get_user_input("Taille maximale en octets (vide = aucune): ");
+                        let sort_input = get_user_input("Trier par (nom/taille/date): ");
+                        let desc_input = get_user_input("Ordre décroissant? (oui/non): ");
+
+                        let options = ListOptions {
+                            filter_extension: Some(filter_input).filter(|filter| !filter.is_empty()),
+                            min_size: min_input.parse().ok(),
+                            max_size: max_input.parse().ok(),
+                            sort_by: match sort_input.to_lowercase().as_str() {
+                                "taille" | "size" => SortBy::Size,
+                                "date" | "modified" => SortBy::Modified,
+                                _ => SortBy::Name,
+                            },
+                            sort_desc: matches!(desc_input.to_lowercase().as_str(), "oui" | "o" | "yes" | "y"),
+                        };
+
+                        let result = file_manager.list_files(&options);
                         match result {
                             OperationResult::Success(files) => {
                                 println!("=== Fichiers dans '{}' ===", file_manager.current_directory);
@@ -416,6 +1842,182 @@ fn main() {
                         }
                     }
 
+                    FileOperation::Copy => {
+                        let source = get_user_input("Fichier source: ");
+                        let destination = get_user_input("Fichier de destination: ");
+                        let overwrite_input = get_user_input("Écraser la destination si elle existe? (oui/non): ");
+                        let overwrite = matches!(overwrite_input.to_lowercase().as_str(), "oui" | "o" | "yes" | "y");
+
+                        let result = file_manager.copy_file(&source, &destination, overwrite);
+                        match result {
+                            OperationResult::Success(msg) => println!("✅ {}", msg),
+                            OperationResult::Error(e) => println!("❌ {}", e),
+                        }
+                    }
+
+                    FileOperation::Move => {
+                        let source = get_user_input("Fichier à déplacer: ");
+                        let destination = get_user_input("Nouveau chemin: ");
+                        let result = file_manager.move_file(&source, &destination);
+                        match result {
+                            OperationResult::Success(msg) => println!("✅ {}", msg),
+                            OperationResult::Error(e) => println!("❌ {}", e),
+                        }
+                    }
+
+                    FileOperation::RecursiveList => {
+                        let depth_input = get_user_input("Profondeur maximale (0 ou vide = illimitée): ");
+                        let max_depth = depth_input.trim().parse::<usize>().unwrap_or(0);
+                        let result = file_manager.list_files_recursive(max_depth);
+                        match result {
+                            OperationResult::Success(tree) => {
+                                println!("=== Arborescence de '{}' ===", file_manager.current_directory);
+                                println!("{}", tree);
+                                println!("=== Fin de la liste ===");
+                            }
+                            OperationResult::Error(e) => println!("❌ {}", e),
+                        }
+                    }
+
+                    FileOperation::Search => {
+                        let pattern = get_user_input("Texte à rechercher: ");
+                        let mode = get_user_input("Mode (1 = texte, 2 = texte sensible à la casse, 3 = regex): ");
+                        let result = match mode.trim() {
+                            "2" => file_manager.search_in_files(&pattern, true),
+                            "3" | "regex" => file_manager.search_in_files_regex(&pattern),
+                            _ => file_manager.search_in_files(&pattern, false),
+                        };
+                        match result {
+                            OperationResult::Success(table) => {
+                                println!("=== Recherche dans '{}' ===", file_manager.current_directory);
+                                println!("{}", table);
+                                println!("=== Fin de la recherche ===");
+                            }
+                            OperationResult::Error(e) => println!("❌ {}", e),
+                        }
+                    }
+
+                    FileOperation::Checksum => {
+                        let filename = get_user_input("Nom du fichier: ");
+                        let expected = get_user_input("Empreinte attendue (laissez vide pour simplement calculer): ");
+                        let result = if expected.is_empty() {
+                            file_manager.compute_checksum(&filename)
+                        } else {
+                            file_manager.verify_checksum(&filename, &expected)
+                        };
+                        match result {
+                            OperationResult::Success(msg) => println!("✅ SHA-256 ({}): {}", filename, msg),
+                            OperationResult::Error(e) => println!("❌ {}", e),
+                        }
+                    }
+
+                    FileOperation::Undo => {
+                        let result = file_manager.undo_last_operation();
+                        match result {
+                            OperationResult::Success(msg) => println!("✅ {}", msg),
+                            OperationResult::Error(e) => println!("❌ {}", e),
+                        }
+                    }
+
+                    FileOperation::Tree => {
+                        let depth_input = get_user_input("Profondeur maximale (vide = illimitée): ");
+                        let max_depth = depth_input.trim().parse::<usize>().ok().filter(|&depth| depth > 0);
+                        match file_manager.display_tree(max_depth) {
+                            OperationResult::Success(tree) => println!("{}", tree),
+                            OperationResult::Error(e) => println!("❌ {}", e),
+                        }
+                    }
+
+                    FileOperation::Metadata => {
+                        let filename = get_user_input("Nom du fichier: ");
+                        match file_manager.show_file_metadata(&filename) {
+                            OperationResult::Success(details) => {
+                                println!("=== Métadonnées de '{}' ===", filename);
+                                println!("{}", details);
+                                println!("=== Fin des métadonnées ===");
+                            }
+                            OperationResult::Error(e) => println!("❌ {}", e),
+                        }
+                    }
+
+                    FileOperation::Watch => {
+                        let path_input = get_user_input("Répertoire à surveiller (vide = répertoire courant): ");
+                        let duration_input = get_user_input("Durée en secondes (vide = 30): ");
+                        let path = if path_input.is_empty() { file_manager.current_directory.clone() } else { path_input };
+                        let duration_secs = duration_input.trim().parse::<u64>().unwrap_or(30);
+                        match file_manager.watch_directory(&path, duration_secs) {
+                            OperationResult::Success(summary) => println!("✅ {}", summary),
+                            OperationResult::Error(e) => println!("❌ {}", e),
+                        }
+                    }
+
+                    FileOperation::DirectorySize => {
+                        let path_input = get_user_input("Répertoire (vide = répertoire courant): ");
+                        let path = if path_input.is_empty() { file_manager.current_directory.clone() } else { path_input };
+                        match file_manager.compute_directory_size(&path) {
+                            OperationResult::Success(breakdown) => println!("{}", breakdown),
+                            OperationResult::Error(e) => println!("❌ {}", e),
+                        }
+                    }
+
+                    FileOperation::Find => {
+                        let name_input = get_user_input("Motif du nom (ex: *.rs, test?.txt, vide = tous): ");
+                        let min_input = get_user_input("Taille minimale en octets (vide = aucune): ");
+                        let max_input = get_user_input("Taille maximale en octets (vide = aucune): ");
+                        let modified_input = get_user_input("Modifié depuis moins de N secondes (vide = peu importe): ");
+                        let type_input = get_user_input("Type (fichier/dossier/lien, vide = tous): ");
+
+                        let criteria = FindCriteria {
+                            name_pattern: Some(name_input).filter(|pattern| !pattern.is_empty()),
+                            min_size_bytes: min_input.parse().ok(),
+                            max_size_bytes: max_input.parse().ok(),
+                            modified_within_secs: modified_input.parse().ok(),
+                            file_type: match type_input.to_lowercase().as_str() {
+                                "fichier" | "file" | "f" => Some(FindType::File),
+                                "dossier" | "repertoire" | "dir" | "d" => Some(FindType::Dir),
+                                "lien" | "symlink" | "l" => Some(FindType::Symlink),
+                                _ => None,
+                            },
+                        };
+
+                        match file_manager.find(criteria) {
+                            OperationResult::Success(paths) => {
+                                println!("=== Résultats dans '{}' ===", file_manager.current_directory);
+                                println!("{}", paths);
+                                println!("=== Fin des résultats ===");
+                            }
+                            OperationResult::Error(e) => println!("❌ {}", e),
+                        }
+                    }
+
+                    FileOperation::Duplicates => {
+                        match file_manager.find_duplicates() {
+                            OperationResult::Success(groups) => {
+                                println!("=== Doublons dans '{}' ===", file_manager.current_directory);
+                                println!("{}", groups);
+                                println!("=== Fin des doublons ===");
+                            }
+                            OperationResult::Error(e) => println!("❌ {}", e),
+                        }
+                    }
+
+                    FileOperation::CreateSymlink => {
+                        let target = get_user_input("Cible du lien: ");
+                        let link_name = get_user_input("Nom du lien à créer: ");
+                        match file_manager.create_symlink(&target, &link_name) {
+                            OperationResult::Success(msg) => println!("✅ {}", msg),
+                            OperationResult::Error(e) => println!("❌ {}", e),
+                        }
+                    }
+
+                    FileOperation::ResolveSymlink => {
+                        let link_name = get_user_input("Nom du lien à résoudre: ");
+                        match file_manager.resolve_symlink(&link_name) {
+                            OperationResult::Success(resolution) => println!("🔗 {}", resolution),
+                            OperationResult::Error(e) => println!("❌ {}", e),
+                        }
+                    }
+
                     FileOperation::ShowLog => {
                         let limit_input = get_user_input("Nombre d'entrées à afficher (laissez vide pour tout): ");
                         let limit = if limit_input.trim().is_empty() {
@@ -433,12 +2035,19 @@ fn main() {
                     FileOperation::Exit => {
                         println!("👋 Merci d'avoir utilisé le gestionnaire de fichiers!");
                         file_manager.show_stats();
+
+                        if let Some(path) = &args.session_file {
+                            match file_manager.save_session(path) {
+                                Ok(()) => println!("💾 Session sauvegardée dans '{}'", path.display()),
+                                Err(e) => eprintln!("❌ Impossible de sauvegarder la session: {}", e),
+                            }
+                        }
                         break; // Sort de la boucle principale
                     }
                 }
             }
             None => {
-                println!("❌ Option invalide. Veuillez choisir entre 1 et 10.");
+                println!("❌ Option invalide. Veuillez choisir entre 1 et 24.");
 
                 // Démonstration d'une boucle while avec compteur
                 let mut attempts = 3;
@@ -447,11 +2056,565 @@ fn main() {
                     attempts -= 1;
 
                     if attempts == 0 {
-                        println!("💡 Astuce: Utilisez les numéros 1-10 pour naviguer dans le menu");
+                        println!("💡 Astuce: Utilisez les numéros 1-24 pour naviguer dans le menu");
                         break;
                     }
                 }
             }
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Répertoire temporaire propre à un test, supprimé à la fin
+    struct TestDir(PathBuf);
+
+    impl TestDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("file_manager_{}_{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&path);
+            fs::create_dir_all(&path).unwrap();
+            TestDir(path)
+        }
+
+        fn path(&self, name: &str) -> String {
+            self.0.join(name).to_string_lossy().into_owned()
+        }
+    }
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn is_success(result: &OperationResult) -> bool {
+        matches!(result, OperationResult::Success(_))
+    }
+
+    // Le hook d'exemple retiré de main : les fichiers de verrou ne s'écrivent pas à la main
+    fn lock_hook() -> Hook {
+        Hook {
+            event: HookEvent::BeforeWrite,
+            callback: Box::new(|context| {
+                if Path::new(context.path).extension().is_some_and(|ext| ext == "lock") {
+                    HookResult::Abort(format!("'{}' est un fichier de verrou", context.path))
+                } else {
+                    HookResult::Continue
+                }
+            }),
+        }
+    }
+
+    #[test]
+    fn lock_hook_refuses_every_write_to_a_lock_file() {
+        let dir = TestDir::new("hooks_lock");
+        let mut manager = FileManager::new();
+        manager.register_hook(lock_hook());
+
+        let source = dir.path("notes.txt");
+        let lock = dir.path("app.lock");
+        assert!(is_success(&manager.write_file(&source, "contenu")));
+
+        assert!(!is_success(&manager.write_file(&lock, "1234")));
+        assert!(!is_success(&manager.copy_file(&source, &lock, false)));
+        assert!(!is_success(&manager.move_file(&source, &lock)));
+        assert!(!is_success(&manager.create_directory(&lock)));
+        #[cfg(unix)]
+        assert!(!is_success(&manager.create_symlink(&source, &lock)));
+
+        assert!(fs::symlink_metadata(&lock).is_err());
+        assert_eq!(fs::read_to_string(&source).unwrap(), "contenu");
+        assert!(manager.operation_log.iter().any(|entry| entry.contains("HOOK_ANNULATION")));
+
+        // Les autres fichiers ne sont pas concernés
+        assert!(is_success(&manager.copy_file(&source, &dir.path("copie.txt"), false)));
+    }
+
+    #[test]
+    fn before_delete_hook_blocks_delete_and_move() {
+        let dir = TestDir::new("hooks_delete");
+        let mut manager = FileManager::new();
+        manager.register_hook(Hook {
+            event: HookEvent::BeforeDelete,
+            callback: Box::new(|context| {
+                if context.path.ends_with("garder.txt") {
+                    HookResult::Abort("fichier protégé".to_string())
+                } else {
+                    HookResult::Continue
+                }
+            }),
+        });
+
+        let protected = dir.path("garder.txt");
+        fs::write(&protected, "important").unwrap();
+
+        assert!(!is_success(&manager.delete_file(&protected)));
+        assert!(!is_success(&manager.move_file(&protected, &dir.path("ailleurs.txt"))));
+        assert_eq!(fs::read_to_string(&protected).unwrap(), "important");
+        assert!(fs::metadata(dir.path("ailleurs.txt")).is_err());
+    }
+
+    #[test]
+    fn undo_restore_goes_through_the_hooks() {
+        let dir = TestDir::new("hooks_undo");
+        let mut manager = FileManager::new();
+        let file = dir.path("config.txt");
+        fs::write(&file, "v1").unwrap();
+        assert!(is_success(&manager.delete_file(&file)));
+
+        let refused = std::rc::Rc::new(std::cell::Cell::new(true));
+        let flag = refused.clone();
+        manager.register_hook(Hook {
+            event: HookEvent::BeforeWrite,
+            callback: Box::new(move |_| {
+                if flag.get() { HookResult::Abort("restauration interdite".to_string()) } else { HookResult::Continue }
+            }),
+        });
+
+        // Refusée : le fichier reste supprimé et l'annulation reste possible
+        assert!(!is_success(&manager.undo_last_operation()));
+        assert!(fs::metadata(&file).is_err());
+
+        refused.set(false);
+        assert!(is_success(&manager.undo_last_operation()));
+        assert_eq!(fs::read_to_string(&file).unwrap(), "v1");
+    }
+
+    #[test]
+    fn after_hooks_see_both_ends_of_a_move() {
+        let dir = TestDir::new("hooks_after");
+        let mut manager = FileManager::new();
+        let seen = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        for event in [HookEvent::AfterDelete, HookEvent::AfterWrite] {
+            let seen = seen.clone();
+            manager.register_hook(Hook {
+                event,
+                callback: Box::new(move |context| {
+                    seen.borrow_mut().push((context.event, context.path.to_string()));
+                    HookResult::Continue
+                }),
+            });
+        }
+
+        let source = dir.path("a.txt");
+        let destination = dir.path("b.txt");
+        fs::write(&source, "x").unwrap();
+        assert!(is_success(&manager.move_file(&source, &destination)));
+
+        assert_eq!(*seen.borrow(), vec![(HookEvent::AfterDelete, source), (HookEvent::AfterWrite, destination)]);
+    }
+
+    fn success(result: OperationResult) -> String {
+        match result {
+            OperationResult::Success(output) => output,
+            OperationResult::Error(e) => panic!("opération en échec: {}", e),
+        }
+    }
+
+    // Gestionnaire placé dans le répertoire du test
+    fn manager_in(dir: &TestDir) -> FileManager {
+        let mut manager = FileManager::new();
+        success(manager.change_directory(&dir.0.to_string_lossy()));
+        manager
+    }
+
+    #[test]
+    fn copy_gives_identical_content() {
+        let dir = TestDir::new("copy");
+        let mut manager = FileManager::new();
+        let source = dir.path("source.txt");
+        fs::write(&source, "ligne 1\nligne 2\n").unwrap();
+
+        success(manager.copy_file(&source, &dir.path("copie.txt"), false));
+        assert_eq!(fs::read(dir.path("copie.txt")).unwrap(), fs::read(&source).unwrap());
+
+        // Sans `overwrite`, une destination existante est conservée
+        fs::write(dir.path("autre.txt"), "autre").unwrap();
+        assert!(!is_success(&manager.copy_file(&source, &dir.path("autre.txt"), false)));
+        assert_eq!(fs::read_to_string(dir.path("autre.txt")).unwrap(), "autre");
+    }
+
+    #[test]
+    fn copy_onto_itself_keeps_the_content() {
+        let dir = TestDir::new("copy_self");
+        let mut manager = FileManager::new();
+        let source = dir.path("source.txt");
+        fs::write(&source, "contenu").unwrap();
+        fs::create_dir(dir.path("sous")).unwrap();
+
+        assert!(!is_success(&manager.copy_file(&source, &source, true)));
+        // Même fichier sous un autre chemin
+        assert!(!is_success(&manager.copy_file(&source, &dir.path("sous/../source.txt"), true)));
+        assert_eq!(fs::read_to_string(&source).unwrap(), "contenu");
+    }
+
+    #[test]
+    fn rename_and_move_to_another_directory() {
+        let dir = TestDir::new("move");
+        let mut manager = FileManager::new();
+        fs::write(dir.path("a.txt"), "contenu").unwrap();
+        fs::create_dir(dir.path("sous")).unwrap();
+
+        success(manager.move_file(&dir.path("a.txt"), &dir.path("b.txt")));
+        assert!(fs::metadata(dir.path("a.txt")).is_err());
+        assert_eq!(fs::read_to_string(dir.path("b.txt")).unwrap(), "contenu");
+
+        success(manager.move_file(&dir.path("b.txt"), &dir.path("sous/b.txt")));
+        assert!(fs::metadata(dir.path("b.txt")).is_err());
+        assert_eq!(fs::read_to_string(dir.path("sous/b.txt")).unwrap(), "contenu");
+    }
+
+    #[test]
+    fn recursive_listing_shows_three_levels() {
+        let dir = TestDir::new("recursive");
+        fs::create_dir_all(dir.path("a/b")).unwrap();
+        fs::write(dir.path("racine.txt"), "1").unwrap();
+        fs::write(dir.path("a/niveau2.txt"), "22").unwrap();
+        fs::write(dir.path("a/b/niveau3.txt"), "333").unwrap();
+        let mut manager = manager_in(&dir);
+
+        let output = success(manager.list_files_recursive(0));
+        let lines: Vec<&str> = output.lines().skip(1).collect();
+        assert_eq!(lines, [
+            "├── a/",
+            "│   ├── b/",
+            "│   │   └── niveau3.txt (3 octets)",
+            "│   └── niveau2.txt (2 octets)",
+            "└── racine.txt (1 octets)",
+        ]);
+
+        // Profondeur 1 : seul le premier niveau est affiché
+        let shallow = success(manager.list_files_recursive(1));
+        assert!(!shallow.contains("niveau2.txt"));
+    }
+
+    #[test]
+    fn search_reports_matches_across_five_files() {
+        let dir = TestDir::new("search");
+        for index in 1..=5 {
+            let content = if index % 2 == 1 { format!("début\nTODO numéro {}\nfin", index) } else { "rien ici".to_string() };
+            fs::write(dir.path(&format!("fichier{}.txt", index)), content).unwrap();
+        }
+        let mut manager = manager_in(&dir);
+
+        let output = success(manager.search_in_files("todo", false));
+        assert!(output.ends_with("3 résultat(s)"));
+        for index in [1, 3, 5] {
+            assert!(output.contains(&format!("{:30} | {:>6} | TODO numéro {}", format!("fichier{}.txt", index), 2, index)));
+        }
+        assert!(success(manager.search_in_files("todo", true)).starts_with("Aucune occurrence"));
+        assert!(success(manager.search_in_files_regex(r"numéro [35]$")).ends_with("2 résultat(s)"));
+    }
+
+    #[test]
+    fn checksum_of_known_sha256_vector() {
+        let dir = TestDir::new("checksum");
+        let mut manager = FileManager::new();
+        let file = dir.path("abc.txt");
+        fs::write(&file, "abc").unwrap();
+
+        let expected = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert_eq!(success(manager.compute_checksum(&file)), expected);
+        assert!(is_success(&manager.verify_checksum(&file, &expected.to_uppercase())));
+        assert!(!is_success(&manager.verify_checksum(&file, &expected.replace('b', "c"))));
+    }
+
+    #[test]
+    fn undo_restores_deleted_and_modified_files() {
+        let dir = TestDir::new("undo");
+        let mut manager = FileManager::new();
+        let deleted = dir.path("supprime.txt");
+        let modified = dir.path("modifie.txt");
+        fs::write(&deleted, "à garder").unwrap();
+        fs::write(&modified, "v1").unwrap();
+
+        success(manager.delete_file(&deleted));
+        success(manager.modify_file(&modified, "v2"));
+        assert_eq!(fs::read_to_string(&modified).unwrap(), "v1\nv2");
+
+        // Annulation dans l'ordre inverse des opérations
+        success(manager.undo_last_operation());
+        assert_eq!(fs::read_to_string(&modified).unwrap(), "v1");
+        success(manager.undo_last_operation());
+        assert_eq!(fs::read_to_string(&deleted).unwrap(), "à garder");
+        assert!(!is_success(&manager.undo_last_operation()));
+    }
+
+    #[test]
+    fn log_entries_survive_a_restart() {
+        let dir = TestDir::new("log");
+        let log_path = dir.0.join("operations.log");
+        let file = dir.path("a.txt");
+
+        {
+            let mut manager = FileManager::with_log_file(&log_path).unwrap();
+            success(manager.write_file(&file, "x"));
+            success(manager.compute_checksum(&file));
+        }
+
+        let mut manager = FileManager::with_log_file(&log_path).unwrap();
+        assert_eq!(manager.operation_log.len(), 2);
+        assert!(manager.operation_log[0].contains(&format!("ECRITURE: {}", file)));
+        assert!(manager.operation_log[1].contains("EMPREINTE"));
+
+        // Les nouvelles entrées s'ajoutent à la suite
+        success(manager.read_file(&file, false));
+        assert_eq!(fs::read_to_string(&log_path).unwrap().lines().count(), 3);
+    }
+
+    #[test]
+    fn list_filters_log_files_and_sorts_by_size() {
+        let dir = TestDir::new("list");
+        fs::write(dir.path("gros.log"), "x".repeat(300)).unwrap();
+        fs::write(dir.path("petit.log"), "x".repeat(10)).unwrap();
+        fs::write(dir.path("moyen.LOG"), "x".repeat(100)).unwrap();
+        fs::write(dir.path("notes.txt"), "x".repeat(50)).unwrap();
+        fs::create_dir(dir.path("archives.log")).unwrap();
+        let mut manager = manager_in(&dir);
+
+        let options = ListOptions {
+            filter_extension: Some("*.log".to_string()),
+            sort_by: SortBy::Size,
+            ..ListOptions::default()
+        };
+        let output = success(manager.list_files(&options));
+        let names: Vec<&str> = output
+            .lines()
+            .filter(|line| line.starts_with("[FILE]"))
+            .map(|line| line.split_whitespace().nth(1).unwrap())
+            .collect();
+        assert_eq!(names, ["petit.log", "moyen.LOG", "gros.log"]);
+        assert!(output.ends_with("Total: 3 élément(s), 410 octets"));
+
+        let descending = ListOptions { sort_desc: true, ..options };
+        let output = success(manager.list_files(&descending));
+        assert!(output.find("gros.log").unwrap() < output.find("petit.log").unwrap());
+    }
+
+    #[test]
+    fn binary_file_is_shown_as_hex_dump() {
+        let dir = TestDir::new("hex");
+        let mut manager = FileManager::new();
+        let file = dir.path("data.bin");
+        let mut data = b"ABCDEFGHIJKLMNOP".to_vec();
+        data.extend([0x00, 0x01, 0xff, b'z']);
+        fs::write(&file, &data).unwrap();
+
+        let output = success(manager.read_file(&file, false));
+        assert_eq!(output.lines().collect::<Vec<_>>(), [
+            "00000000: 4142 4344 4546 4748 494a 4b4c 4d4e 4f50  ABCDEFGHIJKLMNOP",
+            "00000010: 0001 ff7a                                ...z",
+        ]);
+
+        // Un fichier texte n'est en hexadécimal que sur demande
+        fs::write(dir.path("texte.txt"), "hi").unwrap();
+        assert_eq!(success(manager.read_file(&dir.path("texte.txt"), false)), "hi");
+        assert!(success(manager.read_file(&dir.path("texte.txt"), true)).starts_with("00000000: 6869"));
+    }
+
+    #[test]
+    fn tree_limited_to_two_levels() {
+        let dir = TestDir::new("tree");
+        fs::create_dir_all(dir.path("src/module/profond")).unwrap();
+        fs::write(dir.path("Cargo.toml"), "12345").unwrap();
+        fs::write(dir.path("src/main.rs"), "123").unwrap();
+        fs::write(dir.path("src/module/mod.rs"), "1").unwrap();
+        let mut manager = manager_in(&dir);
+
+        let output = success(manager.display_tree(Some(2)));
+        let lines: Vec<&str> = output.lines().skip(1).collect();
+        assert_eq!(lines, [
+            "├── Cargo.toml",
+            "└── src",
+            "    ├── main.rs",
+            "    └── module",
+            "",
+            "2 répertoire(s), 2 fichier(s), 8 octets",
+        ]);
+    }
+
+    #[test]
+    fn metadata_of_a_known_file() {
+        let dir = TestDir::new("metadata");
+        let mut manager = FileManager::new();
+        let file = dir.path("connu.txt");
+        fs::write(&file, "0123456789").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&file, fs::Permissions::from_mode(0o640)).unwrap();
+        }
+
+        let output = success(manager.show_file_metadata(&file));
+        assert!(output.contains(&format!("Chemin absolu      : {}", file)));
+        assert!(output.contains("Type               : fichier régulier"));
+        assert!(output.contains("Taille             : 10 octets"));
+        #[cfg(unix)]
+        assert!(output.contains("Permissions        : 0640 (rw-r-----)"));
+        assert!(!is_success(&manager.show_file_metadata(&dir.path("absent.txt"))));
+    }
+
+    #[test]
+    fn session_survives_a_restart() {
+        let dir = TestDir::new("session");
+        let session_path = dir.0.join("session.json");
+        fs::create_dir(dir.path("travail")).unwrap();
+
+        let mut manager = FileManager::new();
+        success(manager.change_directory(&dir.path("travail")));
+        manager.save_session(&session_path).unwrap();
+
+        let restored = FileManager::load_session(&session_path).unwrap();
+        assert_eq!(restored.current_directory, dir.path("travail"));
+        assert_eq!(restored.operations_count, manager.operations_count);
+
+        // Répertoire disparu entre-temps : retour à `.`
+        fs::remove_dir(dir.path("travail")).unwrap();
+        assert_eq!(FileManager::load_session(&session_path).unwrap().current_directory, ".");
+    }
+
+    #[test]
+    fn watcher_reports_events_from_a_writer_thread() {
+        let dir = TestDir::new("watch");
+        let mut manager = FileManager::new();
+        let watched = dir.0.clone();
+        let writer = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(300));
+            for index in 0..3 {
+                fs::write(watched.join(format!("nouveau{}.txt", index)), "x").unwrap();
+            }
+            fs::remove_file(watched.join("nouveau0.txt")).unwrap();
+        });
+
+        // Aucune touche : la surveillance dure jusqu'au bout
+        let output = success(manager.watch_directory_with_keys(&dir.path(""), 2, || mpsc::channel().1));
+        writer.join().unwrap();
+
+        assert!(output.contains("durée écoulée"));
+        let count = |label: &str| {
+            output.lines()
+                .find_map(|line| line.trim().strip_prefix(label))
+                .and_then(|count| count.trim().parse::<usize>().ok())
+                .unwrap_or(0)
+        };
+        assert!(count("CRÉATION") >= 3, "{}", output);
+        assert!(count("SUPPRESSION") >= 1, "{}", output);
+    }
+
+    #[test]
+    fn watcher_stops_on_q() {
+        let dir = TestDir::new("watch_q");
+        let mut manager = FileManager::new();
+        let started = Instant::now();
+
+        let output = success(manager.watch_directory_with_keys(&dir.path(""), 30, || {
+            let (key_tx, key_rx) = mpsc::channel();
+            key_tx.send("q".to_string()).unwrap();
+            key_rx
+        }));
+        assert!(output.contains("arrêt demandé"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn directory_size_of_a_synthetic_tree() {
+        let dir = TestDir::new("size");
+        fs::create_dir_all(dir.path("a/b")).unwrap();
+        fs::create_dir(dir.path("c")).unwrap();
+        for (name, bytes) in [("un.bin", 1000), ("a/deux.bin", 2000), ("a/b/trois.bin", 3000), ("c/vide", 0)] {
+            fs::write(dir.path(name), vec![7u8; bytes]).unwrap();
+        }
+        let mut manager = FileManager::new();
+
+        let output = success(manager.compute_directory_size(&dir.path("")));
+        assert!(output.contains("Total: 6000 octets"));
+        assert!(output.contains("Fichiers: 4"));
+        assert!(output.contains("Sous-répertoires: 3"));
+        // Plus gros fichier en premier
+        let first = output.lines().find(|line| line.contains("1.")).unwrap();
+        assert!(first.contains("trois.bin"), "{}", first);
+    }
+
+    #[test]
+    fn find_combines_its_criteria() {
+        let dir = TestDir::new("find");
+        fs::create_dir_all(dir.path("src/tests")).unwrap();
+        fs::write(dir.path("src/main.rs"), "x".repeat(2000)).unwrap();
+        fs::write(dir.path("src/lib.rs"), "x".repeat(50)).unwrap();
+        fs::write(dir.path("src/tests/test1.rs"), "x".repeat(500)).unwrap();
+        fs::write(dir.path("notes.txt"), "x".repeat(2000)).unwrap();
+        let mut manager = manager_in(&dir);
+
+        let mut find = |criteria: FindCriteria| -> Vec<String> {
+            let output = success(manager.find(criteria));
+            output.lines()
+                .filter(|line| !line.ends_with("résultat(s)") && !line.starts_with("Aucun"))
+                .map(|line| Path::new(line).strip_prefix(&dir.0).unwrap().to_string_lossy().into_owned())
+                .collect()
+        };
+
+        assert_eq!(find(FindCriteria { name_pattern: Some("*.rs".to_string()), ..Default::default() }),
+                   ["src/lib.rs", "src/main.rs", "src/tests/test1.rs"]);
+        assert_eq!(find(FindCriteria {
+            name_pattern: Some("*.rs".to_string()),
+            min_size_bytes: Some(100),
+            max_size_bytes: Some(1000),
+            ..Default::default()
+        }), ["src/tests/test1.rs"]);
+        assert_eq!(find(FindCriteria { min_size_bytes: Some(1000), ..Default::default() }),
+                   ["notes.txt", "src/main.rs"]);
+        assert_eq!(find(FindCriteria { file_type: Some(FindType::Dir), ..Default::default() }),
+                   ["src", "src/tests"]);
+        assert_eq!(find(FindCriteria {
+            name_pattern: Some("test?.rs".to_string()),
+            modified_within_secs: Some(3600),
+            ..Default::default()
+        }), ["src/tests/test1.rs"]);
+        assert!(find(FindCriteria { name_pattern: Some("*.md".to_string()), ..Default::default() }).is_empty());
+    }
+
+    #[test]
+    fn duplicates_found_in_three_pairs_and_two_unique_files() {
+        let dir = TestDir::new("duplicates");
+        fs::create_dir(dir.path("sous")).unwrap();
+        for (index, content) in ["alpha", "beta-beta", "gamma-gamma-gamma"].iter().enumerate() {
+            fs::write(dir.path(&format!("copie{}_a.txt", index)), content).unwrap();
+            fs::write(dir.path(&format!("sous/copie{}_b.txt", index)), content).unwrap();
+        }
+        // Même taille qu'une paire, contenu différent
+        fs::write(dir.path("unique1.txt"), "ALPHA").unwrap();
+        fs::write(dir.path("unique2.txt"), "seul").unwrap();
+        let mut manager = manager_in(&dir);
+
+        let output = success(manager.find_duplicates());
+        assert!(output.contains("3 groupe(s) de doublons"), "{}", output);
+        assert!(!output.contains("unique"));
+        // La paire qui libère le plus de place d'abord
+        assert!(output.find("copie2_a").unwrap() < output.find("copie0_a").unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_created_resolved_and_detected_broken() {
+        let dir = TestDir::new("symlink");
+        let mut manager = manager_in(&dir);
+        let target = dir.path("cible.txt");
+        let link = dir.path("lien");
+        fs::write(&target, "contenu").unwrap();
+
+        assert!(success(manager.create_symlink(&target, &link)).ends_with("créé avec succès"));
+        assert_eq!(success(manager.read_file(&link, false)), "contenu");
+        let resolved = success(manager.resolve_symlink(&link));
+        assert!(resolved.starts_with("[LINK]"));
+        assert!(resolved.contains(&format!("Cible résolue: {}", fs::canonicalize(&target).unwrap().display())));
+
+        fs::remove_file(&target).unwrap();
+        assert!(success(manager.resolve_symlink(&link)).starts_with("[BROKEN LINK]"));
+        assert!(success(manager.list_files(&ListOptions::default())).contains("[BROKEN LINK] lien -> "));
+        assert!(success(manager.create_symlink(&dir.path("absente"), &dir.path("lien2"))).contains("lien cassé"));
+        assert!(!is_success(&manager.resolve_symlink(&dir.path("absente"))));
+    }
+}
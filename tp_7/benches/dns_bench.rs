@@ -0,0 +1,161 @@
+// Mesures de performance de la sérialisation, du parsing et de la base locale
+// Lancement : cargo bench (résultats de référence dans BENCHMARKS.md)
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::net::Ipv4Addr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use dns_client_server::{
+    DnsHeader, DnsMessage, DnsMessageBuilder, DnsQuestion, DnsRecord, DnsRecordType, SimpleDnsDatabase,
+    PERMANENT_TTL, UDP_MAX_SIZE,
+};
+
+// Nombre de threads lisant la base en parallèle
+const READER_THREADS: usize = 4;
+
+// Allocateur comptant les allocations, pour comparer les deux parseurs
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn count_allocations<T>(f: impl FnOnce() -> T) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    black_box(f());
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+// Réponse typique : une question, un enregistrement A
+fn a_record_response() -> DnsMessage {
+    let query = DnsMessage::new_query("www.example.com".to_string(), DnsRecordType::A);
+    let mut response = DnsMessage::new_response(&query);
+    response.answers.push(DnsRecord::new_a_record("www.example.com".to_string(), Ipv4Addr::new(93, 184, 216, 34), 300));
+    response.header.ancount = 1;
+    response
+}
+
+// Réponse remplie d'enregistrements A jusqu'à la limite UDP de 512 octets
+fn full_udp_packet() -> Vec<u8> {
+    let mut builder = DnsMessageBuilder::new().query("www.example.com".to_string(), DnsRecordType::A);
+    for i in 0..=255 {
+        builder = builder.answer(DnsRecord::new_a_record("www.example.com".to_string(), Ipv4Addr::new(10, 0, 0, i), 300));
+    }
+    let mut response = builder.build();
+    response.truncate(UDP_MAX_SIZE);
+    response.to_bytes()
+}
+
+// Paquet dont le second nom pointe vers le premier : "mail" + pointeur vers "example.com"
+fn compressed_name_packet() -> (Vec<u8>, usize) {
+    let mut packet = DnsHeader::new().to_bytes();
+    packet.extend_from_slice(&DnsQuestion::encode_name("www.example.com"));
+    let offset = packet.len();
+    packet.extend_from_slice(&[4, b'm', b'a', b'i', b'l', 0xC0, 12 + 4]);
+    (packet, offset)
+}
+
+fn bench_serialization(c: &mut Criterion) {
+    let header = DnsHeader::new();
+    c.bench_function("DnsHeader::to_bytes", |b| b.iter(|| black_box(&header).to_bytes()));
+
+    let response = a_record_response();
+    c.bench_function("DnsMessage::to_bytes (réponse A)", |b| b.iter(|| black_box(&response).to_bytes()));
+}
+
+fn bench_parsing(c: &mut Criterion) {
+    let packet = full_udp_packet();
+    let shared = bytes::Bytes::from(packet.clone());
+    println!(
+        "Allocations par parsing (512 octets) : from_bytes = {}, from_shared_bytes = {}, from_bytes_zero_copy = {}",
+        count_allocations(|| DnsMessage::from_bytes(&packet).unwrap()),
+        count_allocations(|| DnsMessage::from_shared_bytes(shared.clone()).unwrap()),
+        count_allocations(|| DnsMessage::from_bytes_zero_copy(&packet).unwrap())
+    );
+    c.bench_function("DnsMessage::from_bytes (512 octets)", |b| {
+        b.iter(|| DnsMessage::from_bytes(black_box(&packet)).unwrap())
+    });
+    c.bench_function("DnsMessage::from_bytes_zero_copy (512 octets)", |b| {
+        b.iter(|| DnsMessage::from_bytes_zero_copy(black_box(&packet)).unwrap())
+    });
+
+    let (packet, offset) = compressed_name_packet();
+    c.bench_function("DnsQuestion::decode_name (compressé)", |b| {
+        b.iter(|| {
+            let mut position = offset;
+            DnsQuestion::decode_name(black_box(&packet), &mut position).unwrap()
+        })
+    });
+}
+
+// Lectures concurrentes sur la base partagée, comme le fait le serveur
+fn bench_database(c: &mut Criterion) {
+    let database = Arc::new(RwLock::new(SimpleDnsDatabase::new()));
+
+    c.bench_function("SimpleDnsDatabase::lookup (1 thread)", |b| {
+        let database = database.read().unwrap();
+        b.iter(|| database.lookup(black_box("google.com")))
+    });
+
+    c.bench_function(&format!("SimpleDnsDatabase::lookup ({} threads)", READER_THREADS), |b| {
+        b.iter_custom(|iterations| {
+            let per_thread = iterations / READER_THREADS as u64 + 1;
+            let start = Instant::now();
+            let readers: Vec<_> = (0..READER_THREADS)
+                .map(|_| {
+                    let database = database.clone();
+                    thread::spawn(move || {
+                        for _ in 0..per_thread {
+                            black_box(database.read().unwrap().lookup(black_box("google.com")));
+                        }
+                    })
+                })
+                .collect();
+            for reader in readers {
+                reader.join().unwrap();
+            }
+            // Temps ramené au nombre d'itérations demandé par criterion
+            let elapsed = start.elapsed();
+            Duration::from_secs_f64(elapsed.as_secs_f64() * iterations as f64 / (per_thread * READER_THREADS as u64) as f64)
+        })
+    });
+}
+
+// Recherche servie par SQLite (nom absent de la mémoire) contre la HashMap
+fn bench_record_store(c: &mut Criterion) {
+    let path = std::env::temp_dir().join(format!("dns_bench_{}.sqlite", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let reader = SimpleDnsDatabase::open(&path).unwrap();
+    let mut writer = SimpleDnsDatabase::open(&path).unwrap();
+    writer.add_record_persistent("google.com".to_string(), Ipv4Addr::new(8, 8, 8, 8), PERMANENT_TTL).unwrap();
+
+    c.bench_function("SimpleDnsDatabase::lookup (HashMap)", |b| {
+        b.iter(|| writer.lookup(black_box("google.com")))
+    });
+    c.bench_function("SimpleDnsDatabase::lookup (SQLite)", |b| {
+        b.iter(|| reader.lookup(black_box("google.com")))
+    });
+
+    drop((reader, writer));
+    let _ = std::fs::remove_file(&path);
+}
+
+criterion_group!(benches, bench_serialization, bench_parsing, bench_database, bench_record_store);
+criterion_main!(benches);
@@ -0,0 +1,110 @@
+// Débit du serveur UDP avec 1 et 4 threads (--threads), puis avec et sans cache (--no-cache),
+// sous une charge simulée de plusieurs clients envoyant leurs requêtes en parallèle
+// Lancement : cargo bench --bench server_throughput
+use std::net::UdpSocket;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use dns_client_server::{DnsMessage, DnsRecordType};
+
+// Clients simultanés et requêtes envoyées par chacun à chaque itération
+const CLIENTS: usize = 8;
+const QUERIES_PER_CLIENT: usize = 32;
+
+const BASE_PORT: u16 = 15353;
+
+// Serveur lancé en sous-processus, arrêté à la fin du benchmark
+struct ServerProcess(Child);
+
+impl ServerProcess {
+    fn start(port: u16, threads: u16, extra_args: &[&str]) -> Self {
+        let child = Command::new(env!("CARGO_BIN_EXE_dns_server"))
+            .args(["--port", &port.to_string(), "--threads", &threads.to_string()])
+            .args(["--no-tcp", "--rate-limit", "0", "--stats-interval", "0"])
+            .args(extra_args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("impossible de lancer dns_server");
+        let server = ServerProcess(child);
+        server.wait_ready(port);
+        server
+    }
+
+    fn wait_ready(&self, port: u16) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Instant::now() < deadline {
+            if query_batch(port, 1).is_ok() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        panic!("dns_server ne répond pas sur le port {}", port);
+    }
+}
+
+impl Drop for ServerProcess {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+// Envoyer `count` requêtes l'une après l'autre en attendant chaque réponse
+fn query_batch(port: u16, count: usize) -> std::io::Result<()> {
+    let socket = UdpSocket::bind("127.0.0.1:0")?;
+    socket.set_read_timeout(Some(Duration::from_millis(500)))?;
+    socket.connect(("127.0.0.1", port))?;
+
+    let query = DnsMessage::new_query("google.com".to_string(), DnsRecordType::A).to_bytes();
+    let mut buffer = [0u8; 512];
+    for _ in 0..count {
+        socket.send(&query)?;
+        socket.recv(&mut buffer)?;
+    }
+    Ok(())
+}
+
+// Une itération : tous les clients envoient leur lot en parallèle
+fn run_clients(port: u16) {
+    let clients: Vec<_> = (0..CLIENTS)
+        .map(|_| thread::spawn(move || query_batch(port, QUERIES_PER_CLIENT)))
+        .collect();
+    for client in clients {
+        client.join().unwrap().expect("requête perdue");
+    }
+}
+
+fn bench_throughput(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dns_server UDP");
+    group.throughput(Throughput::Elements((CLIENTS * QUERIES_PER_CLIENT) as u64));
+    group.sample_size(20);
+
+    for (i, threads) in [1u16, 4].into_iter().enumerate() {
+        let port = BASE_PORT + i as u16;
+        let _server = ServerProcess::start(port, threads, &[]);
+        group.bench_function(format!("--threads {}", threads), |b| b.iter(|| run_clients(port)));
+    }
+
+    group.finish();
+}
+
+// Même charge sur un seul thread : réponses servies par DnsCache ou reconstruites depuis la base
+fn bench_cache(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dns_server UDP cache");
+    group.throughput(Throughput::Elements((CLIENTS * QUERIES_PER_CLIENT) as u64));
+    group.sample_size(20);
+
+    for (i, (label, extra_args)) in [("avec cache", &[][..]), ("--no-cache", &["--no-cache"][..])].into_iter().enumerate() {
+        let port = BASE_PORT + 2 + i as u16;
+        let _server = ServerProcess::start(port, 1, extra_args);
+        group.bench_function(label, |b| b.iter(|| run_clients(port)));
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_throughput, bench_cache);
+criterion_main!(benches);
@@ -0,0 +1,15 @@
+#![no_main]
+
+// Un paquet quelconque ne doit jamais faire paniquer ni boucler le parseur :
+// il est soit accepté, soit rejeté avec une erreur
+use dns_client_server::DnsMessage;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(message) = DnsMessage::from_bytes(data) {
+        // Le serveur renvoie les questions reçues : la resérialisation et
+        // l'affichage des données décodées ne doivent pas paniquer non plus
+        let _ = message.to_bytes();
+        let _ = message.to_string();
+    }
+});
@@ -1,18 +1,35 @@
 use tokio::net::UdpSocket;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 use colored::*;
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use futures::stream::{FuturesUnordered, StreamExt};
+use serde::Serialize;
 
 use dns_client_server::{
-    DnsMessage, DnsRecordType, DnsQuestion
+    display_diff, ipv4_to_arpa, DnsMessage, DnsMessageBuilder, DnsQuestion, DnsRecord, DnsRecordType
 };
+use std::net::{Ipv4Addr, SocketAddr};
 
 #[derive(Parser)]
 #[command(name = "dns-client")]
 #[command(about = "Un client DNS simple en Rust")]
 struct Args {
-    /// Nom de domaine à résoudre
-    domain: String,
+    /// Nom de domaine à résoudre (ou adresse IPv4 avec --reverse)
+    #[arg(required_unless_present = "batch_file")]
+    domain: Option<String>,
+
+    /// Fichier de requêtes, une par ligne : `domaine [type]` (type par défaut : --query-type)
+    #[arg(long, conflicts_with_all = ["domain", "reverse", "compare_with_public", "trace"])]
+    batch_file: Option<PathBuf>,
+
+    /// Nombre maximal de requêtes simultanées en mode lot
+    #[arg(long, default_value = "10", value_parser = clap::value_parser!(u16).range(1..))]
+    concurrent: u16,
+
+    /// Recherche inverse (PTR) : le domaine est une adresse IPv4
+    #[arg(short = 'x', long)]
+    reverse: bool,
 
     /// Serveur DNS à utiliser
     #[arg(short, long, default_value = "127.0.0.1:5353")]
@@ -33,26 +50,190 @@ struct Args {
     /// Serveur DNS public pour comparaison
     #[arg(long)]
     compare_with_public: bool,
+
+    /// Nombre de nouvelles tentatives après un échec
+    #[arg(long, default_value = "2")]
+    retries: u32,
+
+    /// Délai initial entre deux tentatives en millisecondes (doublé à chaque essai)
+    #[arg(long, default_value = "500")]
+    backoff_ms: u64,
+
+    /// Format de sortie des résultats
+    #[arg(long, value_enum, default_value = "text")]
+    output_format: OutputFormat,
+
+    /// Taille du tampon UDP annoncée via EDNS0 (désactivé par défaut)
+    #[arg(long)]
+    edns_buffer_size: Option<u16>,
+
+    /// URL DNS-over-HTTPS (RFC 8484) à utiliser à la place de --server,
+    /// ex. https://cloudflare-dns.com/dns-query
+    #[arg(long, conflicts_with_all = ["trace", "batch_file"])]
+    doh_url: Option<String>,
+
+    /// Résolution itérative depuis les serveurs racine, en affichant chaque délégation
+    #[arg(long, conflicts_with = "compare_with_public")]
+    trace: bool,
+
+    /// Serveur racine pour --trace (répétable, par défaut les 13 serveurs racine) ;
+    /// les serveurs délégués sont interrogés sur le même port
+    #[arg(long, requires = "trace")]
+    root_server: Vec<SocketAddr>,
+}
+
+// Les 13 serveurs racine (adresses IPv4 publiées par l'IANA)
+const ROOT_SERVERS: [(&str, Ipv4Addr); 13] = [
+    ("a.root-servers.net", Ipv4Addr::new(198, 41, 0, 4)),
+    ("b.root-servers.net", Ipv4Addr::new(170, 247, 170, 2)),
+    ("c.root-servers.net", Ipv4Addr::new(192, 33, 4, 12)),
+    ("d.root-servers.net", Ipv4Addr::new(199, 7, 91, 13)),
+    ("e.root-servers.net", Ipv4Addr::new(192, 203, 230, 10)),
+    ("f.root-servers.net", Ipv4Addr::new(192, 5, 5, 241)),
+    ("g.root-servers.net", Ipv4Addr::new(192, 112, 36, 4)),
+    ("h.root-servers.net", Ipv4Addr::new(198, 97, 190, 53)),
+    ("i.root-servers.net", Ipv4Addr::new(192, 36, 148, 17)),
+    ("j.root-servers.net", Ipv4Addr::new(192, 58, 128, 30)),
+    ("k.root-servers.net", Ipv4Addr::new(193, 0, 14, 129)),
+    ("l.root-servers.net", Ipv4Addr::new(199, 7, 83, 42)),
+    ("m.root-servers.net", Ipv4Addr::new(202, 12, 27, 33)),
+];
+
+// Type MIME des messages DNS transportés en HTTP (RFC 8484 §6)
+const DOH_CONTENT_TYPE: &str = "application/dns-message";
+
+// Délégations suivies au plus par --trace (évite les boucles de références)
+const MAX_TRACE_HOPS: usize = 16;
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+}
+
+// Résultat d'une requête au format JSON
+// En mode lot, une requête échouée a un rcode nul et un message d'erreur
+#[derive(Serialize)]
+struct JsonResult {
+    domain: String,
+    query_type: String,
+    rcode: Option<String>,
+    answers: Vec<JsonAnswer>,
+    response_time_ms: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct JsonAnswer {
+    name: String,
+    #[serde(rename = "type")]
+    rtype: String,
+    ttl: u32,
+    address: Option<String>,
+}
+
+// Étape de --trace : un serveur interrogé et sa réponse
+#[derive(Serialize)]
+struct TraceStep {
+    zone: String,               // Zone servie par le serveur ("." pour la racine)
+    server: String,
+    address: String,
+    rcode: Option<String>,      // None si aucun serveur de la zone n'a répondu
+    referral: Option<String>,   // Zone déléguée désignée par le serveur
+    records: Vec<String>,       // NS et glue d'une délégation, sinon la réponse
+    response_time_ms: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl TraceStep {
+    fn failed(zone: &str, error: String) -> Self {
+        TraceStep {
+            zone: zone_label(zone),
+            server: String::new(),
+            address: String::new(),
+            rcode: None,
+            referral: None,
+            records: Vec::new(),
+            response_time_ms: 0.0,
+            error: Some(error),
+        }
+    }
+}
+
+fn zone_label(zone: &str) -> String {
+    if zone.is_empty() { ".".to_string() } else { zone.to_string() }
+}
+
+// `name` est-il `zone` ou l'un de ses sous-domaines ? (la racine contient tout)
+fn in_zone(name: &str, zone: &str) -> bool {
+    let (name, zone) = (name.to_lowercase(), zone.to_lowercase());
+    zone.is_empty() || name == zone || name.ends_with(&format!(".{}", zone))
 }
 
 struct DnsClient {
     socket: UdpSocket,
     verbose: bool,
+    retries: u32,
+    backoff_ms: u64,
+    edns_buffer_size: Option<u16>,
+    http: reqwest::Client,
 }
 
 impl DnsClient {
-    async fn new(verbose: bool) -> Result<Self, Box<dyn std::error::Error>> {
+    async fn new(
+        verbose: bool,
+        retries: u32,
+        backoff_ms: u64,
+        edns_buffer_size: Option<u16>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
         let socket = UdpSocket::bind("0.0.0.0:0").await?;
-        Ok(DnsClient { socket, verbose })
+        let http = reqwest::Client::builder()
+            .user_agent(concat!("dns_client/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+        Ok(DnsClient { socket, verbose, retries, backoff_ms, edns_buffer_size, http })
     }
 
+    // Effectuer une requête avec nouvelles tentatives et attente exponentielle
+    // Chaque tentative utilise un nouvel ID de transaction (RFC 5452)
     async fn query(
         &self,
         domain: &str,
         server: &str,
         qtype: DnsRecordType,
         timeout_ms: u64,
-    ) -> Result<DnsMessage, Box<dyn std::error::Error>> {
+    ) -> Result<(DnsMessage, Vec<u8>), Box<dyn std::error::Error>> {
+        let mut attempt = 0;
+
+        loop {
+            match self.query_once(domain, server, qtype, timeout_ms).await {
+                Ok(result) => return Ok(result),
+                Err(e) if attempt < self.retries => {
+                    let delay = self.backoff_ms.saturating_mul(1 << attempt.min(16));
+                    if self.verbose {
+                        println!("⚠️  {} Tentative {} échouée ({}), nouvel essai dans {}ms",
+                                 "RETRY".yellow().bold(),
+                                 attempt + 1,
+                                 e,
+                                 delay.to_string().magenta()
+                        );
+                    }
+                    tokio::time::sleep(Duration::from_millis(delay)).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    async fn query_once(
+        &self,
+        domain: &str,
+        server: &str,
+        qtype: DnsRecordType,
+        timeout_ms: u64,
+    ) -> Result<(DnsMessage, Vec<u8>), Box<dyn std::error::Error>> {
 
         if self.verbose {
             println!("🔍 {} Création de la requête DNS", "QUERY".blue().bold());
@@ -62,7 +243,170 @@ impl DnsClient {
         }
 
         // Créer la requête DNS
-        let query = DnsMessage::new_query(domain.to_string(), qtype);
+        let mut builder = DnsMessageBuilder::new().query(domain.to_string(), qtype);
+        if let Some(size) = self.edns_buffer_size {
+            builder = builder.edns(size);
+        }
+        let query = builder.build();
+
+        // Une URL désigne un serveur DNS-over-HTTPS, sinon une adresse UDP
+        if server.starts_with("https://") || server.starts_with("http://") {
+            self.exchange_doh(&query, server, timeout_ms).await
+        } else {
+            self.exchange(&query, server, timeout_ms).await
+        }
+    }
+
+    // Résolution itérative à la manière de `drill -T` : partir des serveurs racine et suivre
+    // les délégations (NS de l'autorité, adresses glue de la section additionnelle) jusqu'au
+    // serveur qui répond. Les requêtes sont non récursives ; seule l'adresse d'un serveur
+    // de noms sans glue est demandée au serveur configuré (`resolver`)
+    async fn trace_resolve(
+        &self,
+        domain: &str,
+        qtype: DnsRecordType,
+        roots: &[(String, SocketAddr)],
+        resolver: &str,
+        timeout_ms: u64,
+    ) -> Vec<TraceStep> {
+        let mut steps = Vec::new();
+        let mut zone = String::new();
+        let mut servers = roots.to_vec();
+
+        for _ in 0..MAX_TRACE_HOPS {
+            // Premier serveur de la zone qui répond
+            let mut answer = None;
+            let mut errors = Vec::new();
+            for (name, addr) in &servers {
+                let query = DnsMessageBuilder::new()
+                    .query(domain.to_string(), qtype)
+                    .recursion_desired(false)
+                    .build();
+                let start = Instant::now();
+                match self.exchange(&query, &addr.to_string(), timeout_ms).await {
+                    Ok((response, raw)) => {
+                        answer = Some((name.clone(), *addr, response, raw, start.elapsed()));
+                        break;
+                    }
+                    Err(e) => errors.push(format!("{} ({}): {}", name, addr, e)),
+                }
+            }
+            let Some((server, address, response, raw, elapsed)) = answer else {
+                let error = if errors.is_empty() { "aucun serveur de noms".to_string() } else { errors.join(", ") };
+                steps.push(TraceStep::failed(&zone, error));
+                return steps;
+            };
+
+            let mut step = TraceStep {
+                zone: zone_label(&zone),
+                server,
+                address: address.to_string(),
+                rcode: Some(response.header.rcode_name().to_string()),
+                referral: None,
+                records: Vec::new(),
+                response_time_ms: elapsed.as_secs_f64() * 1000.0,
+                error: None,
+            };
+
+            // Délégation : pas de réponse, des NS pour une zone plus proche du nom demandé
+            let child = response.authorities.iter()
+                .filter(|record| record.rtype == DnsRecordType::NS)
+                .map(|record| record.name.clone())
+                .find(|child| in_zone(domain, child) && child.len() > zone.len() && in_zone(child, &zone));
+            let child = child.filter(|_| response.header.rcode == 0 && response.answers.is_empty());
+
+            let Some(child) = child else {
+                // Réponse finale (ou NXDOMAIN et son SOA)
+                step.records = response.answers.iter().chain(&response.authorities)
+                    .map(|record| Self::describe_record(record, &raw))
+                    .collect();
+                steps.push(step);
+                return steps;
+            };
+
+            let nameservers: Vec<String> = response.authorities.iter()
+                .filter(|record| record.name.eq_ignore_ascii_case(&child))
+                .filter_map(|record| record.get_ns(&raw))
+                .collect();
+            let glue: Vec<&DnsRecord> = response.additionals.iter()
+                .filter(|record| nameservers.iter().any(|ns| ns.eq_ignore_ascii_case(&record.name)))
+                .filter(|record| record.get_ip().is_some())
+                .collect();
+
+            step.referral = Some(child.clone());
+            step.records = response.authorities.iter()
+                .filter(|record| record.rtype == DnsRecordType::NS)
+                .chain(glue.iter().copied())
+                .map(|record| Self::describe_record(record, &raw))
+                .collect();
+            steps.push(step);
+
+            servers = glue.iter()
+                .filter_map(|record| record.get_ip().map(|ip| (record.name.clone(), SocketAddr::new(ip.into(), address.port()))))
+                .collect();
+            if servers.is_empty() {
+                // Pas de glue : demander l'adresse des serveurs de noms au serveur configuré
+                for nameserver in &nameservers {
+                    if let Ok((response, _)) = self.query(nameserver, resolver, DnsRecordType::A, timeout_ms).await {
+                        servers.extend(response.answers.iter()
+                            .filter_map(|record| record.get_ip())
+                            .map(|ip| (nameserver.clone(), SocketAddr::new(ip.into(), address.port()))));
+                    }
+                    if !servers.is_empty() {
+                        break;
+                    }
+                }
+            }
+            zone = child;
+        }
+
+        steps.push(TraceStep::failed(&zone, format!("plus de {} délégations", MAX_TRACE_HOPS)));
+        steps
+    }
+
+    // Enregistrement au format d'un fichier de zone : nom TTL TYPE valeur
+    fn describe_record(record: &DnsRecord, raw: &[u8]) -> String {
+        let value = Self::format_answer(record, raw)
+            .unwrap_or_else(|| format!("[{} octets]", record.data.len()));
+        // format_answer préfixe déjà certaines valeurs par leur type (NS, CNAME...)
+        let value = value.strip_prefix(&format!("{} ", record.rtype)).map(str::to_string).unwrap_or(value);
+        format!("{} {} {} {}", zone_label(&record.name), record.ttl, record.rtype, value)
+    }
+
+    fn display_trace(domain: &str, qtype: DnsRecordType, steps: &[TraceStep]) {
+        println!("\n{} {} {}", "🧭 TRACE".blue().bold(), domain.yellow(), qtype);
+        println!("{}", "=".repeat(40).blue());
+
+        for step in steps {
+            if let Some(error) = &step.error {
+                println!("\n[{}] ❌ {}", step.zone.cyan(), error.red());
+                continue;
+            }
+            let outcome = match (&step.referral, step.rcode.as_deref()) {
+                (Some(child), _) => format!("délégation vers {}", child.yellow()),
+                (None, Some("NOERROR")) => "réponse".green().to_string(),
+                (None, rcode) => rcode.unwrap_or("?").red().to_string(),
+            };
+            println!("\n[{}] {} ({}) en {:.2}ms : {}",
+                     step.zone.cyan(),
+                     step.server,
+                     step.address.magenta(),
+                     step.response_time_ms,
+                     outcome
+            );
+            for record in &step.records {
+                println!("   {}", record);
+            }
+        }
+    }
+
+    // Envoyer un message déjà construit et attendre la réponse portant le même ID
+    async fn exchange(
+        &self,
+        query: &DnsMessage,
+        server: &str,
+        timeout_ms: u64,
+    ) -> Result<(DnsMessage, Vec<u8>), Box<dyn std::error::Error>> {
         let query_bytes = query.to_bytes();
 
         if self.verbose {
@@ -81,17 +425,31 @@ impl DnsClient {
         self.socket.send_to(&query_bytes, server).await?;
 
         // Attendre la réponse avec timeout
-        let mut buffer = vec![0u8; 512];
-
-        let response_result = tokio::time::timeout(
-            Duration::from_millis(timeout_ms),
-            self.socket.recv(&mut buffer)
-        ).await;
+        let mut buffer = vec![0u8; query.max_udp_size()];
+
+        let deadline = tokio::time::Instant::now() + Duration::from_millis(timeout_ms);
+        let expected_id = query.header.id.to_be_bytes();
+
+        // Ignorer les réponses dont l'ID ne correspond pas (réponses tardives ou usurpées)
+        let response_size = loop {
+            let response_result = tokio::time::timeout_at(
+                deadline,
+                self.socket.recv(&mut buffer)
+            ).await;
+
+            let size = match response_result {
+                Ok(Ok(size)) => size,
+                Ok(Err(e)) => return Err(format!("Erreur réception: {}", e).into()),
+                Err(_) => return Err(format!("Timeout après {}ms", timeout_ms).into()),
+            };
+
+            if size >= 2 && buffer[..2] == expected_id {
+                break size;
+            }
 
-        let response_size = match response_result {
-            Ok(Ok(size)) => size,
-            Ok(Err(e)) => return Err(format!("Erreur réception: {}", e).into()),
-            Err(_) => return Err(format!("Timeout après {}ms", timeout_ms).into()),
+            if self.verbose {
+                println!("⚠️  {} Réponse ignorée: ID de transaction inattendu", "RECEIVE".yellow().bold());
+            }
         };
 
         let response_time = start_time.elapsed();
@@ -103,27 +461,160 @@ impl DnsClient {
         }
 
         // Parser la réponse
-        let response = DnsMessage::from_bytes(&buffer[..response_size])?;
+        buffer.truncate(response_size);
+        let response = DnsMessage::from_bytes(&buffer)?;
 
         if self.verbose {
             println!("🔍 {} Réponse parsée", "PARSE".blue().bold());
-            println!("   ID: {}", response.header.id.to_string().magenta());
-            println!("   QR: {}", if response.header.qr { "Response".green() } else { "Query".red() });
-            println!("   RCODE: {}",
-                     match response.header.rcode {
-                         0 => "NOERROR".green(),
-                         3 => "NXDOMAIN".red(),
-                         4 => "NOTIMP".yellow(),
-                         _ => "UNKNOWN".red(),
-                     }
-            );
-            println!("   Réponses: {}", response.header.ancount.to_string().cyan());
+            println!("{}", response.to_dig_string(&buffer));
+        }
+
+        Ok((response, buffer))
+    }
+
+    // DNS-over-HTTPS (RFC 8484) : le message DNS est le corps d'un POST
+    // HTTP associe déjà la réponse à la requête, l'ID de transaction n'est pas vérifié
+    async fn exchange_doh(
+        &self,
+        query: &DnsMessage,
+        url: &str,
+        timeout_ms: u64,
+    ) -> Result<(DnsMessage, Vec<u8>), Box<dyn std::error::Error>> {
+        let query_bytes = query.to_bytes();
+
+        if self.verbose {
+            println!("   ID de transaction: {}", query.header.id.to_string().magenta());
+            println!("   Taille de la requête: {} bytes", query_bytes.len().to_string().cyan());
+            println!("📤 {} POST {}", "DOH".green().bold(), url.cyan());
+        }
+
+        let start_time = Instant::now();
+
+        let response = self.http.post(url)
+            .header(reqwest::header::CONTENT_TYPE, DOH_CONTENT_TYPE)
+            .header(reqwest::header::ACCEPT, DOH_CONTENT_TYPE)
+            .timeout(Duration::from_millis(timeout_ms))
+            .body(query_bytes)
+            .send()
+            .await
+            .map_err(|e| {
+                if e.is_timeout() {
+                    format!("Timeout après {}ms", timeout_ms)
+                } else {
+                    // reqwest place la cause réelle (DNS, TLS, connexion refusée...) dans la source
+                    let cause = std::error::Error::source(&e).map(|source| format!(": {}", source)).unwrap_or_default();
+                    format!("Échec de la requête HTTP vers {}{}", url, cause)
+                }
+            })?;
+
+        let status = response.status();
+        if status.is_client_error() || status.is_server_error() {
+            let side = if status.is_client_error() {
+                "requête rejetée par le serveur DoH"
+            } else {
+                "erreur interne du serveur DoH"
+            };
+            // Premier extrait du corps, souvent un message d'erreur lisible
+            let body = response.text().await.unwrap_or_default();
+            let detail: String = body.lines().next().unwrap_or("").trim().chars().take(200).collect();
+            return Err(if detail.is_empty() {
+                format!("HTTP {} ({})", status, side)
+            } else {
+                format!("HTTP {} ({}): {}", status, side, detail)
+            }.into());
+        }
+
+        let content_type = response.headers().get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("")
+            .to_string();
+        if !content_type.starts_with(DOH_CONTENT_TYPE) {
+            return Err(format!("Réponse HTTP {} inattendue: Content-Type '{}' au lieu de {}",
+                               status, content_type, DOH_CONTENT_TYPE).into());
+        }
+
+        let body = response.bytes().await
+            .map_err(|e| format!("Erreur de lecture de la réponse HTTP: {}", e))?
+            .to_vec();
+        let response_time = start_time.elapsed();
+
+        if self.verbose {
+            println!("📨 {} Réponse HTTP {}", "RECEIVE".green().bold(), status);
+            println!("   Taille: {} bytes", body.len().to_string().cyan());
+            println!("   Temps: {:.2}ms", response_time.as_secs_f64() * 1000.0);
+        }
+
+        let response = DnsMessage::from_bytes(&body)?;
+
+        if self.verbose {
+            println!("🔍 {} Réponse parsée", "PARSE".blue().bold());
+            println!("{}", response.to_dig_string(&body));
         }
 
-        Ok(response)
+        Ok((response, body))
+    }
+
+    // Valeur lisible d'une réponse, `raw` sert à décoder les noms compressés
+    fn format_answer(answer: &DnsRecord, raw: &[u8]) -> Option<String> {
+        answer.get_ip()
+            .map(|ip| ip.to_string())
+            .or_else(|| answer.get_aaaa().map(|ip| ip.to_string()))
+            .or_else(|| answer.get_txt().map(|strings| format!("\"{}\"", strings.join("\" \""))))
+            .or_else(|| answer.get_cname(raw).map(|canonical| format!("CNAME {}", canonical)))
+            .or_else(|| answer.get_ptr(raw))
+            .or_else(|| answer.get_ns(raw).map(|nameserver| format!("NS {}", nameserver)))
+            .or_else(|| answer.get_mx(raw).map(|mx| format!("{} (priorité {})", mx.exchange, mx.priority)))
+            .or_else(|| answer.get_srv(raw).map(|srv| {
+                format!("{}:{} (priorité {}, poids {})", srv.target, srv.port, srv.priority, srv.weight)
+            }))
+            .or_else(|| answer.get_naptr(raw).map(|naptr| {
+                let target = if naptr.regexp.is_empty() { naptr.replacement } else { naptr.regexp };
+                format!("NAPTR {} {} \"{}\" {} {}", naptr.order, naptr.preference, naptr.flags, naptr.services, target)
+            }))
+            .or_else(|| answer.get_caa().map(|caa| format!("CAA {} {} \"{}\"", caa.flags, caa.tag, caa.value)))
+            .or_else(|| answer.get_soa(raw).map(|soa| {
+                format!("SOA {} {} (série {}, minimum {}s)", soa.mname, soa.rname, soa.serial, soa.minimum)
+            }))
+            .or_else(|| answer.get_hinfo().map(|(cpu, os)| format!("HINFO \"{}\" \"{}\"", cpu, os)))
     }
 
-    fn display_results(&self, domain: &str, response: &DnsMessage, response_time: Duration) {
+    fn display_json(
+        &self,
+        domain: &str,
+        qtype: DnsRecordType,
+        response: &DnsMessage,
+        raw: &[u8],
+        response_time: Duration,
+    ) -> Result<(), serde_json::Error> {
+        let result = Self::json_result(domain, qtype, response, raw, response_time);
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        Ok(())
+    }
+
+    fn json_result(
+        domain: &str,
+        qtype: DnsRecordType,
+        response: &DnsMessage,
+        raw: &[u8],
+        response_time: Duration,
+    ) -> JsonResult {
+        JsonResult {
+            domain: domain.to_string(),
+            query_type: qtype.to_string(),
+            rcode: Some(response.header.rcode_name().to_string()),
+            answers: response.answers.iter().map(|answer| JsonAnswer {
+                name: answer.name.clone(),
+                rtype: answer.rtype.to_string(),
+                ttl: answer.ttl,
+                address: Self::format_answer(answer, raw),
+            }).collect(),
+            response_time_ms: response_time.as_secs_f64() * 1000.0,
+            error: None,
+        }
+    }
+
+    // `raw` contient le message brut pour décoder les noms compressés
+    fn display_results(&self, domain: &str, response: &DnsMessage, raw: &[u8], response_time: Duration) {
         println!("\n{}", "📊 RÉSULTATS".blue().bold());
         println!("{}", "=".repeat(40).blue());
 
@@ -140,15 +631,30 @@ impl DnsClient {
                  }
         );
 
-        if !response.answers.is_empty() {
+        let reverse_lookup = !response.answers.is_empty()
+            && response.answers.iter().all(|answer| answer.rtype == DnsRecordType::PTR);
+
+        if reverse_lookup {
+            // Recherche inverse : afficher les noms d'hôte associés
+            println!("\n🔁 {} Noms d'hôte trouvés:", "RÉPONSES".green().bold());
+            for (i, answer) in response.answers.iter().enumerate() {
+                let hostname = answer.get_ptr(raw).unwrap_or_else(|| "[nom invalide]".to_string());
+                println!("   {}. {} -> {} (TTL: {}s)",
+                         i + 1,
+                         answer.name.yellow(),
+                         hostname.green(),
+                         answer.ttl.to_string().cyan()
+                );
+            }
+        } else if !response.answers.is_empty() {
             println!("\n📍 {} Adresses trouvées:", "RÉPONSES".green().bold());
             for (i, answer) in response.answers.iter().enumerate() {
-                match answer.get_ip() {
+                match Self::format_answer(answer, raw) {
                     Some(ip) => {
                         println!("   {}. {} -> {} (TTL: {}s)",
                                  i + 1,
                                  answer.name.yellow(),
-                                 ip.to_string().green(),
+                                 ip.green(),
                                  answer.ttl.to_string().cyan()
                         );
                     }
@@ -166,7 +672,27 @@ impl DnsClient {
         }
     }
 
-    async fn compare_with_public_dns(&self, domain: &str, qtype: DnsRecordType) {
+    // Colorer un diff unifié : suppressions en rouge, ajouts en vert
+    fn print_diff(diff: &str) {
+        for line in diff.lines() {
+            let line = if line.starts_with("---") || line.starts_with("+++") {
+                line.bold()
+            } else if line.starts_with("@@") {
+                line.cyan()
+            } else if line.starts_with('-') {
+                line.red()
+            } else if line.starts_with('+') {
+                line.green()
+            } else {
+                line.normal()
+            };
+            println!("   {}", line);
+        }
+    }
+
+    // Comparer la réponse obtenue (`reference`) à celle des DNS publics
+    // Les différences sont affichées façon diff unifié
+    async fn compare_with_public_dns(&self, domain: &str, qtype: DnsRecordType, reference: &DnsMessage, reference_raw: &[u8]) {
         println!("\n{}", "🌐 COMPARAISON AVEC DNS PUBLICS".yellow().bold());
         println!("{}", "=".repeat(40).yellow());
 
@@ -180,15 +706,24 @@ impl DnsClient {
             print!("🔍 Test avec {} ({})... ", name.cyan(), server.yellow());
 
             match self.query(domain, server, qtype, 3000).await {
-                Ok(response) => {
+                Ok((response, raw)) => {
                     if response.header.rcode == 0 && !response.answers.is_empty() {
-                        let ip = response.answers[0].get_ip()
+                        let answer = &response.answers[0];
+                        let ip = answer.get_ip()
                             .map(|ip| ip.to_string())
+                            .or_else(|| answer.get_aaaa().map(|ip| ip.to_string()))
                             .unwrap_or_else(|| "Données binaires".to_string());
                         println!("✅ {}", ip.green());
                     } else {
                         println!("❌ {}", "Aucune réponse".red());
                     }
+
+                    let diffs = DnsMessage::diff(reference, reference_raw, &response, &raw);
+                    if diffs.is_empty() {
+                        println!("   {}", "Réponses identiques".green());
+                    } else {
+                        Self::print_diff(&display_diff(&diffs, "serveur interrogé", name));
+                    }
                 }
                 Err(e) => {
                     println!("❌ {}", format!("Erreur: {}", e).red());
@@ -198,14 +733,157 @@ impl DnsClient {
     }
 }
 
+// Requête du mode lot, avec sa position dans le fichier
+struct BatchQuery {
+    index: usize,
+    domain: String,
+    qtype: DnsRecordType,
+}
+
+// Lire le fichier du mode lot ; lignes vides et commentaires (#) ignorés
+// Toutes les lignes sont vérifiées avant d'envoyer la première requête
+fn read_batch_file(path: &Path, default_type: DnsRecordType) -> Result<Vec<BatchQuery>, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Impossible de lire {}: {}", path.display(), e))?;
+
+    let mut queries = Vec::new();
+    for (number, line) in content.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let invalid = |reason: String| format!("{}:{}: {}", path.display(), number + 1, reason);
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let (domain, qtype) = match fields[..] {
+            [domain] => (domain, default_type),
+            [domain, qtype] => (domain, parse_query_type(qtype).map_err(invalid)?),
+            _ => return Err(invalid(format!("ligne invalide '{}' (attendu: domaine [type])", line))),
+        };
+        DnsQuestion::validate_name(domain)
+            .map_err(|e| invalid(format!("nom de domaine invalide '{}': {}", domain, e)))?;
+
+        queries.push(BatchQuery { index: queries.len(), domain: domain.to_string(), qtype });
+    }
+    Ok(queries)
+}
+
+// Une requête du lot, sur son propre socket pour ne pas recevoir la réponse d'une autre
+// Sans mode verbeux : les traces des requêtes simultanées se mélangeraient
+async fn run_batch_query(args: &Args, query: BatchQuery) -> (BatchQuery, Result<(DnsMessage, Vec<u8>), String>, Duration) {
+    let start = Instant::now();
+    let result = match DnsClient::new(false, args.retries, args.backoff_ms, args.edns_buffer_size).await {
+        Ok(client) => client.query(&query.domain, &args.server, query.qtype, args.timeout).await,
+        Err(e) => Err(e),
+    };
+    (query, result.map_err(|e| e.to_string()), start.elapsed())
+}
+
+// Mode lot : interroger toutes les requêtes du fichier, au plus `--concurrent` à la fois
+// Le texte est affiché au fil des réponses, le JSON est un tableau dans l'ordre du fichier
+async fn run_batch(args: &Args, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let json_output = args.output_format == OutputFormat::Json;
+    let queries = read_batch_file(path, parse_query_type(&args.query_type)?)?;
+    let total = queries.len();
+
+    if !json_output {
+        println!("📋 {} {} requête(s) depuis {} ({} simultanées) vers {}",
+                 "BATCH".blue().bold(),
+                 total.to_string().cyan(),
+                 path.display().to_string().yellow(),
+                 args.concurrent.to_string().magenta(),
+                 args.server.cyan()
+        );
+    }
+
+    let start = Instant::now();
+    let mut pending = queries.into_iter();
+    let mut in_flight = FuturesUnordered::new();
+    let mut results: Vec<(usize, JsonResult)> = Vec::with_capacity(total);
+    let mut failures = 0;
+
+    loop {
+        while in_flight.len() < args.concurrent as usize {
+            match pending.next() {
+                Some(query) => in_flight.push(run_batch_query(args, query)),
+                None => break,
+            }
+        }
+        let Some((query, result, elapsed)) = in_flight.next().await else {
+            break;
+        };
+
+        let json = match &result {
+            Ok((response, raw)) => {
+                if !json_output {
+                    let values: Vec<String> = response.answers.iter()
+                        .filter_map(|answer| DnsClient::format_answer(answer, raw))
+                        .map(|value| format!(" {}", value.green()))
+                        .collect();
+                    let status = if response.header.rcode == 0 { "✅" } else { "❌" };
+                    println!("{} {} {} {}{} ({:.2}ms)",
+                             status,
+                             query.domain.yellow(),
+                             query.qtype,
+                             response.header.rcode_name(),
+                             values.join(","),
+                             elapsed.as_secs_f64() * 1000.0
+                    );
+                }
+                DnsClient::json_result(&query.domain, query.qtype, response, raw, elapsed)
+            }
+            Err(e) => {
+                failures += 1;
+                if !json_output {
+                    println!("❌ {} {} {}", query.domain.yellow(), query.qtype, format!("Erreur: {}", e).red());
+                }
+                JsonResult {
+                    domain: query.domain.clone(),
+                    query_type: query.qtype.to_string(),
+                    rcode: None,
+                    answers: Vec::new(),
+                    response_time_ms: elapsed.as_secs_f64() * 1000.0,
+                    error: Some(e.clone()),
+                }
+            }
+        };
+        results.push((query.index, json));
+    }
+
+    if json_output {
+        results.sort_by_key(|(index, _)| *index);
+        let results: Vec<JsonResult> = results.into_iter().map(|(_, result)| result).collect();
+        println!("{}", serde_json::to_string_pretty(&results)?);
+    } else {
+        println!("\n📊 {} {} requête(s) en {:.2}ms : {} réussie(s), {} en erreur",
+                 "RÉSUMÉ".blue().bold(),
+                 total,
+                 start.elapsed().as_secs_f64() * 1000.0,
+                 (total - failures).to_string().green(),
+                 failures.to_string().red()
+        );
+    }
+
+    if failures > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
 fn parse_query_type(type_str: &str) -> Result<DnsRecordType, String> {
     match type_str.to_uppercase().as_str() {
         "A" => Ok(DnsRecordType::A),
         "NS" => Ok(DnsRecordType::NS),
         "CNAME" => Ok(DnsRecordType::CNAME),
+        "SOA" => Ok(DnsRecordType::SOA),
         "PTR" => Ok(DnsRecordType::PTR),
         "MX" => Ok(DnsRecordType::MX),
+        "TXT" => Ok(DnsRecordType::TXT),
+        "SRV" => Ok(DnsRecordType::SRV),
+        "NAPTR" => Ok(DnsRecordType::NAPTR),
         "AAAA" => Ok(DnsRecordType::AAAA),
+        "CAA" => Ok(DnsRecordType::CAA),
+        "ANY" => Ok(DnsRecordType::ANY),
         _ => Err(format!("Type de requête non supporté: {}", type_str)),
     }
 }
@@ -213,46 +891,116 @@ fn parse_query_type(type_str: &str) -> Result<DnsRecordType, String> {
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
+    let json_output = args.output_format == OutputFormat::Json;
 
-    println!("{}", "🔍 CLIENT DNS SIMPLE EN RUST 🦀".blue().bold());
-    println!("{}", "=".repeat(40).blue());
+    // En JSON, seule la réponse est écrite sur la sortie standard
+    if !json_output {
+        println!("{}", "🔍 CLIENT DNS SIMPLE EN RUST 🦀".blue().bold());
+        println!("{}", "=".repeat(40).blue());
+    }
+
+    if let Some(path) = &args.batch_file {
+        return run_batch(&args, path).await;
+    }
+    // Sans --batch-file, clap exige le domaine
+    let domain_arg = args.domain.clone().unwrap_or_default();
 
     // Parser le type de requête
-    let query_type = parse_query_type(&args.query_type)?;
+    let mut query_type = parse_query_type(&args.query_type)?;
+
+    // Recherche inverse : construire le nom in-addr.arpa et interroger en PTR
+    let domain = if args.reverse {
+        let addr: Ipv4Addr = match domain_arg.parse() {
+            Ok(addr) => addr,
+            Err(_) => return Err(format!("Adresse IPv4 invalide: {}", domain_arg).into()),
+        };
+        query_type = DnsRecordType::PTR;
+        ipv4_to_arpa(addr)
+    } else {
+        domain_arg
+    };
+    DnsQuestion::validate_name(&domain)
+        .map_err(|e| format!("Nom de domaine invalide '{}': {}", domain, e))?;
+
+    // Serveur interrogé : l'URL DoH si elle est donnée, sinon l'adresse UDP
+    let server = match &args.doh_url {
+        Some(url) => {
+            let parsed = reqwest::Url::parse(url).map_err(|e| format!("URL DoH invalide '{}': {}", url, e))?;
+            if !matches!(parsed.scheme(), "https" | "http") {
+                return Err(format!("URL DoH invalide '{}': schéma https attendu", url).into());
+            }
+            url.clone()
+        }
+        None => args.server.clone(),
+    };
 
     if args.verbose {
         println!("📋 {} Configuration:", "CLIENT".blue().bold());
-        println!("   • Domaine: {}", args.domain.yellow());
-        println!("   • Serveur: {}", args.server.cyan());
+        println!("   • Domaine: {}", domain.yellow());
+        println!("   • Serveur: {}", server.cyan());
         println!("   • Type: {:?}", query_type);
         println!("   • Timeout: {}ms", args.timeout.to_string().magenta());
+        println!("   • Tentatives supplémentaires: {} (attente initiale {}ms)",
+                 args.retries.to_string().magenta(),
+                 args.backoff_ms.to_string().magenta()
+        );
         println!();
     }
 
     // Créer le client
-    let client = DnsClient::new(args.verbose).await?;
+    let client = DnsClient::new(args.verbose, args.retries, args.backoff_ms, args.edns_buffer_size).await?;
+
+    if args.trace {
+        let roots: Vec<(String, SocketAddr)> = if args.root_server.is_empty() {
+            ROOT_SERVERS.iter().map(|(name, ip)| (name.to_string(), SocketAddr::new((*ip).into(), 53))).collect()
+        } else {
+            args.root_server.iter().map(|addr| (addr.ip().to_string(), *addr)).collect()
+        };
+        let steps = client.trace_resolve(&domain, query_type, &roots, &args.server, args.timeout).await;
+
+        if json_output {
+            println!("{}", serde_json::to_string_pretty(&steps)?);
+        } else {
+            DnsClient::display_trace(&domain, query_type, &steps);
+        }
+        // Échec si la chaîne s'est arrêtée faute de réponse
+        if steps.last().is_some_and(|step| step.error.is_some()) {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
 
     // Mesurer le temps total
     let total_start = Instant::now();
 
     // Effectuer la requête
-    match client.query(&args.domain, &args.server, query_type, args.timeout).await {
-        Ok(response) => {
+    match client.query(&domain, &server, query_type, args.timeout).await {
+        Ok((response, raw)) => {
             let total_time = total_start.elapsed();
-            client.display_results(&args.domain, &response, total_time);
+
+            if json_output {
+                client.display_json(&domain, query_type, &response, &raw, total_time)?;
+                return Ok(());
+            }
+
+            client.display_results(&domain, &response, &raw, total_time);
 
             // Comparaison avec DNS publics si demandée
             if args.compare_with_public {
-                client.compare_with_public_dns(&args.domain, query_type).await;
+                client.compare_with_public_dns(&domain, query_type, &response, &raw).await;
             }
         }
+        Err(e) if json_output => {
+            eprintln!("❌ {} {}", "ERREUR".red().bold(), e);
+            std::process::exit(1);
+        }
         Err(e) => {
             println!("\n❌ {} Erreur lors de la requête:", "ERREUR".red().bold());
             println!("   {}", e.to_string().red());
 
             println!("\n💡 {} Suggestions:", "DÉPANNAGE".yellow().bold());
             println!("   • Vérifiez que le serveur DNS est démarré");
-            println!("   • Vérifiez l'adresse du serveur: {}", args.server.cyan());
+            println!("   • Vérifiez l'adresse du serveur: {}", server.cyan());
             println!("   • Essayez d'augmenter le timeout avec -t");
             println!("   • Utilisez -v pour plus de détails");
 
@@ -1,11 +1,21 @@
-use tokio::net::UdpSocket;
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::{mpsc, Mutex, RwLock, RwLockReadGuard};
+use std::ops::Deref;
 use std::sync::Arc;
-use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, ToSocketAddrs};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 use colored::*;
 use clap::Parser;
 
 use dns_client_server::{
-    DnsMessage, DnsRecordType, DnsRecord, SimpleDnsDatabase
+    DnsCache, NegativeAnswer, DnsClass, DnsMessageBuilder, DnsOpcode, DnsRcode, DEFAULT_NEGATIVE_TTL, PERMANENT_TTL, DnsMessage, DnsQuestion, DnsRecordType, DnsRecord, SimpleDnsDatabase,
+    QueryLogEntry, utc_date, Cidr, IpAcl, ZoneRouter,
+    EDNS_BUFFER_SIZE, UDP_MAX_SIZE,
 };
 
 #[derive(Parser)]
@@ -23,39 +33,763 @@ struct Args {
     /// Mode verbeux
     #[arg(short, long)]
     verbose: bool,
+
+    /// Serveur DNS amont pour les noms inconnus (ex: 8.8.8.8:53)
+    #[arg(long)]
+    upstream: Option<String>,
+
+    /// Désactiver le cache des réponses
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Durée de mise en cache des réponses NXDOMAIN et NODATA en secondes (0 = désactivé)
+    #[arg(long, default_value_t = DEFAULT_NEGATIVE_TTL)]
+    negative_ttl: u32,
+
+    /// Écouter aussi en TCP sur le même port (activé par défaut)
+    #[arg(long, overrides_with = "no_tcp")]
+    tcp: bool,
+
+    /// Désactiver l'écoute TCP
+    #[arg(long)]
+    no_tcp: bool,
+
+    /// Fichier au format /etc/hosts à charger au démarrage
+    #[arg(long, conflicts_with = "zone_file")]
+    hosts_file: Option<PathBuf>,
+
+    /// Fichier de zone (RFC 1035) à charger au démarrage
+    #[arg(long)]
+    zone_file: Option<PathBuf>,
+
+    /// Base SQLite conservant les enregistrements ajoutés par le socket d'administration
+    #[arg(long)]
+    database: Option<PathBuf>,
+
+    /// Nombre maximal de requêtes par seconde et par IP (0 = illimité)
+    #[arg(long, default_value = "100")]
+    rate_limit: u32,
+
+    /// Nombre de requêtes tolérées en rafale (par défaut égal à --rate-limit)
+    #[arg(long)]
+    rate_limit_burst: Option<u32>,
+
+    /// Intervalle d'affichage des statistiques en secondes (0 = désactivé)
+    #[arg(long, default_value = "60")]
+    stats_interval: u64,
+
+    /// Socket Unix d'administration (ADD, DEL, LIST, STATS, RELOAD)
+    #[arg(long, requires = "admin_secret")]
+    admin_socket: Option<PathBuf>,
+
+    /// Secret partagé attendu en première ligne sur le socket d'administration
+    #[arg(long, requires = "admin_socket")]
+    admin_secret: Option<String>,
+
+    /// Nombre d'instances du serveur, une par thread, sur le même port (SO_REUSEPORT)
+    #[arg(long, default_value = "1", value_parser = clap::value_parser!(u16).range(1..))]
+    threads: u16,
+
+    /// Rejeter (FORMERR) les noms dont les labels ne sont pas de l'UTF-8 valide
+    #[arg(long)]
+    strict_names: bool,
+
+    /// Version annoncée aux requêtes `version.bind. CH TXT`
+    #[arg(long, default_value = concat!("dns_server ", env!("CARGO_PKG_VERSION")))]
+    server_version: String,
+
+    /// Fichier d'ACL et de zones par client (split-horizon), syntaxe inspirée de BIND
+    #[arg(long)]
+    acl_file: Option<PathBuf>,
+
+    /// Adresses ou réseaux autorisés à transférer les zones en AXFR (TCP), séparés par des
+    /// virgules (ex: 10.0.0.2,192.168.1.0/24) ; sans cette option, AXFR est refusé à tous
+    #[arg(long, value_delimiter = ',', value_parser = Cidr::parse)]
+    allow_axfr: Vec<Cidr>,
+
+    /// Zone secondaire et son primaire (IP ou IP:port, port 53 par défaut) : la zone est
+    /// transférée en AXFR au démarrage puis à chaque NOTIFY du primaire (répétable)
+    #[arg(long, num_args = 2, value_names = ["ZONE", "PRIMAIRE"])]
+    secondary_for: Vec<String>,
+
+    /// Journal des requêtes, une ligne par requête, archivé chaque jour à minuit (UTC)
+    #[arg(long)]
+    query_log: Option<PathBuf>,
+
+    /// Répondre aux requêtes ANY par un seul HINFO au lieu de tous les enregistrements (RFC 8482)
+    #[arg(long)]
+    rfc8482: bool,
+}
+
+// TTL maximal annoncé dans les réponses (5 minutes)
+const MAX_RESPONSE_TTL: u32 = 300;
+
+// Nombre maximal de CNAME suivis avant d'abandonner (évite les cycles)
+const MAX_CNAME_HOPS: usize = 8;
+
+// Intervalle de purge des enregistrements expirés
+const PURGE_INTERVAL: Duration = Duration::from_secs(60);
+
+// Nombre maximal d'entrées dans le cache des réponses
+const CACHE_MAX_ENTRIES: usize = 10_000;
+
+// Délai d'attente de la réponse du serveur amont
+const UPSTREAM_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Lignes du journal des requêtes en attente d'écriture ; au-delà elles sont perdues
+const QUERY_LOG_BUFFER: usize = 10_000;
+
+// Durée maximale d'un transfert de zone depuis le primaire
+const AXFR_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Délai d'inactivité d'une connexion TCP cliente (RFC 7766 §6.2.3)
+const TCP_IDLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Journal des requêtes : les gestionnaires déposent les entrées sans attendre,
+// une tâche dédiée les écrit et change de fichier à minuit
+#[derive(Clone)]
+struct QueryLog {
+    tx: mpsc::Sender<QueryLogEntry>,
+}
+
+impl QueryLog {
+    // Lancer la tâche d'écriture dans le runtime courant
+    fn spawn(path: PathBuf) -> Self {
+        let (tx, rx) = mpsc::channel(QUERY_LOG_BUFFER);
+        tokio::spawn(async move {
+            if let Err(e) = Self::write_entries(&path, rx).await {
+                eprintln!("❌ {} Journal des requêtes {} arrêté: {}",
+                          "QUERY_LOG".red().bold(), path.display(), e);
+            }
+        });
+        QueryLog { tx }
+    }
+
+    // File pleine : l'entrée est abandonnée plutôt que de bloquer le gestionnaire
+    fn send(&self, entry: QueryLogEntry) {
+        let _ = self.tx.try_send(entry);
+    }
+
+    async fn write_entries(path: &std::path::Path, mut rx: mpsc::Receiver<QueryLogEntry>) -> std::io::Result<()> {
+        // Date du fichier ouvert, et son écrivain
+        let mut current: Option<(String, tokio::io::BufWriter<tokio::fs::File>)> = None;
+
+        while let Some(entry) = rx.recv().await {
+            let date = entry.date();
+            if current.as_ref().map(|(open_date, _)| open_date != &date).unwrap_or(true) {
+                let previous_date = match current.take() {
+                    Some((open_date, mut writer)) => {
+                        writer.flush().await?;
+                        Some(open_date)
+                    }
+                    // Au démarrage, un fichier d'un jour précédent est archivé d'après sa date de modification
+                    None => tokio::fs::metadata(path).await.ok()
+                        .and_then(|metadata| metadata.modified().ok())
+                        .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+                        .map(|modified| utc_date(modified.as_millis() as u64)),
+                };
+                if let Some(previous_date) = previous_date.filter(|previous_date| previous_date != &date) {
+                    let archive = format!("{}.{}", path.display(), previous_date);
+                    tokio::fs::rename(path, &archive).await?;
+                    println!("🗂️  {} Journal des requêtes archivé: {}", "QUERY_LOG".blue().bold(), archive.cyan());
+                }
+
+                let file = tokio::fs::OpenOptions::new().create(true).append(true).open(path).await?;
+                current = Some((date, tokio::io::BufWriter::new(file)));
+            }
+
+            if let Some((_, writer)) = current.as_mut() {
+                writer.write_all(format!("{}\n", entry).as_bytes()).await?;
+                // Écrire dès que la file est vide : le fichier reste à jour sans appel système par ligne
+                if rx.is_empty() {
+                    writer.flush().await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Zones secondaires (--secondary-for) : copies des zones d'un primaire, transférées en AXFR
+// Partagées par toutes les instances de --threads, quelle que soit celle qui reçoit le NOTIFY
+struct SecondaryZones {
+    primaries: HashMap<String, SocketAddr>,
+    zones: RwLock<HashMap<String, SimpleDnsDatabase>>,
+}
+
+impl SecondaryZones {
+    fn new(primaries: HashMap<String, SocketAddr>) -> Self {
+        SecondaryZones { primaries, zones: RwLock::new(HashMap::new()) }
+    }
+
+    // Zone secondaire la plus précise contenant `name`
+    fn zone_for(&self, name: &str) -> Option<&String> {
+        let name = name.trim_end_matches('.').to_lowercase();
+        let mut candidate = name.as_str();
+        loop {
+            if let Some((zone, _)) = self.primaries.get_key_value(candidate) {
+                return Some(zone);
+            }
+            candidate = candidate.split_once('.')?.1;
+        }
+    }
+
+    // Transférer la zone et journaliser le résultat
+    async fn refresh(&self, zone: &str) {
+        match self.pull(zone).await {
+            Ok(Some((serial, count))) => {
+                println!("📥 {} Zone {} transférée depuis {} (serial {}, {} enregistrement(s))",
+                         "AXFR".green().bold(),
+                         zone.yellow(),
+                         self.primaries[zone].to_string().cyan(),
+                         serial.to_string().magenta(),
+                         count.to_string().green()
+                );
+            }
+            Ok(None) => {
+                println!("✅ {} Zone {} déjà à jour", "AXFR".green().bold(), zone.yellow());
+            }
+            Err(e) => {
+                eprintln!("❌ {} Transfert de {} depuis {} impossible: {}",
+                          "AXFR".red().bold(), zone, self.primaries[zone], e);
+            }
+        }
+    }
+
+    // Remplacer la copie locale si le serial transféré est plus récent (RFC 1982)
+    // Retourne (serial, nombre d'enregistrements), ou None si la copie était à jour
+    async fn pull(&self, zone: &str) -> Result<Option<(u32, usize)>, Box<dyn std::error::Error + Send + Sync>> {
+        let messages = Self::fetch_axfr(self.primaries[zone], zone).await?;
+        let database = SimpleDnsDatabase::import_axfr(zone, &messages)?;
+        let serial = database.lookup_soa(zone).map_or(0, |soa| soa.serial);
+
+        let mut zones = self.zones.write().await;
+        let current = zones.get(zone).and_then(|database| database.lookup_soa(zone)).map(|soa| soa.serial);
+        if current.is_some_and(|current| serial.wrapping_sub(current) as i32 <= 0) {
+            return Ok(None);
+        }
+        let count = database.zone_records(zone, MAX_RESPONSE_TTL).len();
+        zones.insert(zone.to_string(), database);
+        Ok(Some((serial, count)))
+    }
+
+    // Requête AXFR sur TCP : lire les messages jusqu'au second SOA
+    async fn fetch_axfr(
+        primary: SocketAddr,
+        zone: &str,
+    ) -> Result<Vec<Vec<u8>>, Box<dyn std::error::Error + Send + Sync>> {
+        let query = DnsMessageBuilder::new()
+            .query(zone.to_string(), DnsRecordType::AXFR)
+            .recursion_desired(false)
+            .build();
+
+        let transfer = async {
+            let mut stream = TcpStream::connect(primary).await?;
+            let bytes = query.to_bytes();
+            stream.write_u16(bytes.len() as u16).await?;
+            stream.write_all(&bytes).await?;
+
+            let mut messages = Vec::new();
+            let mut soa_count = 0;
+            while soa_count < 2 {
+                let mut data = vec![0u8; stream.read_u16().await? as usize];
+                stream.read_exact(&mut data).await?;
+                let message = DnsMessage::from_bytes(&data)?;
+                if message.header.id != query.header.id {
+                    return Err("ID de transaction inattendu".into());
+                }
+                if message.header.rcode != DnsRcode::NOERROR as u8 {
+                    return Err(format!("transfert refusé ({})", message.header.rcode_name()).into());
+                }
+                soa_count += message.answers.iter().filter(|record| record.rtype == DnsRecordType::SOA).count();
+                messages.push(data);
+            }
+            Ok(messages)
+        };
+
+        match tokio::time::timeout(AXFR_TIMEOUT, transfer).await {
+            Ok(result) => result,
+            Err(_) => Err(format!("Timeout après {}s", AXFR_TIMEOUT.as_secs()).into()),
+        }
+    }
+}
+
+// Limiteur de débit par IP (seau à jetons)
+// Chaque IP dispose de `burst` jetons, rechargés à raison de `qps` par seconde
+struct RateLimiter {
+    clients: HashMap<IpAddr, (u32, Instant)>,
+    qps: u32,
+    burst: u32,
+}
+
+impl RateLimiter {
+    fn new(qps: u32, burst: u32) -> Self {
+        RateLimiter {
+            clients: HashMap::new(),
+            qps,
+            burst,
+        }
+    }
+
+    // Consommer un jeton pour cette IP, retourne false si la limite est atteinte
+    fn check(&mut self, ip: IpAddr) -> bool {
+        let now = Instant::now();
+        let (tokens, last_refill) = self.clients.entry(ip).or_insert((self.burst, now));
+
+        // Recharger les jetons gagnés depuis la dernière recharge
+        let elapsed_ms = now.duration_since(*last_refill).as_millis() as u64;
+        let earned = elapsed_ms * self.qps as u64 / 1000;
+        if earned > 0 {
+            *tokens = (*tokens as u64 + earned).min(self.burst as u64) as u32;
+            *last_refill += Duration::from_millis(earned * 1000 / self.qps as u64);
+        }
+
+        if *tokens == 0 {
+            return false;
+        }
+        *tokens -= 1;
+        true
+    }
+
+    // Oublier les IPs inactives depuis plus d'une minute
+    fn purge_idle(&mut self) {
+        self.clients.retain(|_, (_, last_refill)| last_refill.elapsed() < PURGE_INTERVAL);
+    }
+}
+
+// Statistiques du serveur, partagées entre les tâches sans verrou
+#[derive(Default)]
+struct ServerStats {
+    total_queries: AtomicU64,
+    noerror_count: AtomicU64,
+    nxdomain_count: AtomicU64,
+    notimp_count: AtomicU64,
+    bytes_received: AtomicU64,
+    bytes_sent: AtomicU64,
+    total_latency_us: AtomicU64,
+}
+
+impl ServerStats {
+    // Enregistrer une requête traitée et la réponse envoyée
+    fn record(&self, received: usize, response: &[u8], latency: Duration) {
+        self.total_queries.fetch_add(1, Ordering::Relaxed);
+        self.bytes_received.fetch_add(received as u64, Ordering::Relaxed);
+        self.bytes_sent.fetch_add(response.len() as u64, Ordering::Relaxed);
+        self.total_latency_us.fetch_add(latency.as_micros() as u64, Ordering::Relaxed);
+
+        // Le RCODE occupe les 4 bits de poids faible du 4e octet de l'en-tête
+        let counter = match response.get(3).map(|flags| flags & 0xF) {
+            Some(0) => &self.noerror_count,
+            Some(3) => &self.nxdomain_count,
+            Some(4) => &self.notimp_count,
+            _ => return,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // Résumé sans couleurs, pour le socket d'administration
+    fn summary(&self) -> String {
+        format!(
+            "requetes={} noerror={} nxdomain={} notimp={} recus={} envoyes={} latence_moyenne_us={}",
+            self.total_queries.load(Ordering::Relaxed),
+            self.noerror_count.load(Ordering::Relaxed),
+            self.nxdomain_count.load(Ordering::Relaxed),
+            self.notimp_count.load(Ordering::Relaxed),
+            self.bytes_received.load(Ordering::Relaxed),
+            self.bytes_sent.load(Ordering::Relaxed),
+            self.avg_latency_us()
+        )
+    }
+
+    fn avg_latency_us(&self) -> u64 {
+        let total = self.total_queries.load(Ordering::Relaxed);
+        if total == 0 {
+            return 0;
+        }
+        self.total_latency_us.load(Ordering::Relaxed) / total
+    }
+
+    fn print(&self) {
+        println!("📊 {} Requêtes: {} | NOERROR: {} | NXDOMAIN: {} | NOTIMP: {}",
+                 "STATS".blue().bold(),
+                 self.total_queries.load(Ordering::Relaxed).to_string().cyan(),
+                 self.noerror_count.load(Ordering::Relaxed).to_string().green(),
+                 self.nxdomain_count.load(Ordering::Relaxed).to_string().red(),
+                 self.notimp_count.load(Ordering::Relaxed).to_string().yellow()
+        );
+        println!("   Reçus: {} bytes | Envoyés: {} bytes | Latence moyenne: {}µs",
+                 self.bytes_received.load(Ordering::Relaxed).to_string().magenta(),
+                 self.bytes_sent.load(Ordering::Relaxed).to_string().magenta(),
+                 self.avg_latency_us().to_string().cyan()
+        );
+    }
 }
 
 struct DnsServer {
     socket: Arc<UdpSocket>,
-    database: SimpleDnsDatabase,
+    tcp_listener: Option<TcpListener>,
+    database: Arc<RwLock<SimpleDnsDatabase>>,
+    cache: Option<Arc<Mutex<DnsCache>>>,
+    rate_limiter: Option<Arc<Mutex<RateLimiter>>>,
+    stats: Arc<ServerStats>,
+    upstream: Option<String>,
     verbose: bool,
+    strict_names: bool,
+    server_version: String,
+    rfc8482: bool,
+    query_log: Option<QueryLog>,
+    zone_router: Option<Arc<ZoneRouter>>,
+    axfr_clients: Option<IpAcl>,
+    secondary: Option<Arc<SecondaryZones>>,
 }
 
-impl DnsServer {
-    async fn new(addr: &str, verbose: bool) -> Result<Self, Box<dyn std::error::Error>> {
-        let socket = UdpSocket::bind(addr).await?;
-        println!("🚀 {} Serveur DNS démarré sur {}", "INFO".green().bold(), addr.cyan());
+// Base consultée pour une question : la base principale (verrouillée en lecture)
+// ou celle d'une zone routée selon le client
+enum DatabaseView<'a> {
+    Main(RwLockReadGuard<'a, SimpleDnsDatabase>),
+    Routed(&'a SimpleDnsDatabase),
+}
 
-        let database = SimpleDnsDatabase::new();
+impl Deref for DatabaseView<'_> {
+    type Target = SimpleDnsDatabase;
 
-        // Afficher les enregistrements disponibles
-        println!("📚 {} Enregistrements DNS chargés:", "DATABASE".blue().bold());
-        for (name, ip) in database.list_records() {
-            println!("   {} -> {}", name.yellow(), ip.to_string().green());
+    fn deref(&self) -> &SimpleDnsDatabase {
+        match self {
+            DatabaseView::Main(guard) => guard,
+            DatabaseView::Routed(database) => database,
         }
-        println!();
+    }
+}
+
+impl DnsServer {
+    // Les sockets sont liés au préalable (voir `bind_sockets`) et convertis
+    // dans le runtime courant : chaque thread de --threads a sa propre instance
+    fn new(
+        sockets: (std::net::UdpSocket, Option<std::net::TcpListener>),
+        database: SimpleDnsDatabase,
+        upstream: Option<String>,
+        cache: Option<DnsCache>,
+        rate_limit: Option<(u32, u32)>,
+        stats: Arc<ServerStats>,
+        verbose: bool,
+    ) -> std::io::Result<Self> {
+        let (udp_socket, tcp_listener) = sockets;
 
         Ok(DnsServer {
-            socket: Arc::new(socket),
-            database,
+            socket: Arc::new(UdpSocket::from_std(udp_socket)?),
+            tcp_listener: tcp_listener.map(TcpListener::from_std).transpose()?,
+            database: Arc::new(RwLock::new(database)),
+            cache: cache.map(|cache| Arc::new(Mutex::new(cache))),
+            rate_limiter: rate_limit.map(|(qps, burst)| Arc::new(Mutex::new(RateLimiter::new(qps, burst)))),
+            stats,
+            upstream,
             verbose,
+            strict_names: false,
+            server_version: String::new(),
+            rfc8482: false,
+            query_log: None,
+            zone_router: None,
+            axfr_clients: None,
+            secondary: None,
         })
     }
 
-    async fn start(&self) -> Result<(), Box<dyn std::error::Error>> {
-        println!("👂 {} En attente de requêtes DNS...\n", "LISTENING".green().bold());
+    // Décoder les noms des questions sans remplacer les octets invalides
+    fn with_strict_names(mut self, strict_names: bool) -> Self {
+        self.strict_names = strict_names;
+        self
+    }
+
+    fn with_server_version(mut self, server_version: String) -> Self {
+        self.server_version = server_version;
+        self
+    }
+
+    fn with_zone_router(mut self, zone_router: Option<Arc<ZoneRouter>>) -> Self {
+        self.zone_router = zone_router;
+        self
+    }
+
+    // Clients autorisés à transférer les zones (--allow-axfr)
+    fn with_axfr_clients(mut self, axfr_clients: Option<IpAcl>) -> Self {
+        self.axfr_clients = axfr_clients;
+        self
+    }
+
+    // Transfert de zone AXFR (RFC 5936) : la zone entière encadrée par son SOA
+    // Refusé si le client n'est pas autorisé ou si le serveur ne fait pas autorité
+    async fn transfer_zone(&self, query: &DnsMessage, client_ip: IpAddr) -> Vec<DnsMessage> {
+        let zone = query.questions[0].name.trim_end_matches('.').to_lowercase();
+
+        if !self.axfr_clients.as_ref().is_some_and(|clients| clients.contains(client_ip)) {
+            println!("⛔ {} Transfert de {} refusé pour {}",
+                     "AXFR".red().bold(),
+                     zone.yellow(),
+                     client_ip.to_string().cyan()
+            );
+            return vec![DnsMessage::new_error_response(query, DnsRcode::REFUSED)];
+        }
+
+        let database = self.database_for(&zone, client_ip).await;
+        let Some(soa) = database.lookup_soa(&zone) else {
+            println!("❌ {} Pas de SOA pour {}, transfert refusé",
+                     "AXFR".red().bold(),
+                     zone.yellow()
+            );
+            return vec![DnsMessage::new_error_response(query, DnsRcode::REFUSED)];
+        };
+        let soa = DnsRecord::new_soa_record(zone.clone(), soa, MAX_RESPONSE_TTL);
+        let records = database.zone_records(&zone, MAX_RESPONSE_TTL);
+        drop(database);
+
+        // Même ID dans tous les messages, la question dans le premier seulement
+        let mut messages = DnsMessage::new_axfr_response(&records, &soa);
+        for message in &mut messages {
+            message.header.id = query.header.id;
+        }
+        messages[0].questions = query.questions.clone();
+        messages[0].header.qdcount = query.questions.len() as u16;
+
+        println!("📦 {} Zone {} -> {} enregistrement(s) pour {}",
+                 "AXFR".green().bold(),
+                 zone.yellow(),
+                 records.len().to_string().green(),
+                 client_ip.to_string().cyan()
+        );
+        messages
+    }
+
+    fn with_secondary_zones(mut self, secondary: Option<Arc<SecondaryZones>>) -> Self {
+        self.secondary = secondary;
+        self
+    }
 
-        let mut buffer = vec![0u8; 512]; // Buffer standard pour DNS
+    // Base à consulter pour `name` selon l'adresse du client (--acl-file),
+    // puis la copie d'une zone secondaire une fois transférée
+    async fn database_for(&self, name: &str, client_ip: IpAddr) -> DatabaseView<'_> {
+        if let Some(database) = self.zone_router.as_ref().and_then(|router| router.route(name, client_ip)) {
+            return DatabaseView::Routed(database);
+        }
+        if let Some(secondary) = &self.secondary {
+            if let Some(zone) = secondary.zone_for(name) {
+                let zones = secondary.zones.read().await;
+                if let Ok(database) = RwLockReadGuard::try_map(zones, |zones| zones.get(zone)) {
+                    return DatabaseView::Main(database);
+                }
+            }
+        }
+        DatabaseView::Main(self.database.read().await)
+    }
+
+    // Cache partagé par tous les clients : inutilisable pour un nom dont la réponse dépend du client
+    // ou dont la zone peut être remplacée à tout moment par un transfert
+    fn cache_for(&self, name: &str) -> Option<&Arc<Mutex<DnsCache>>> {
+        let routed = self.zone_router.as_ref().is_some_and(|router| router.covers(name));
+        let secondary = self.secondary.as_ref().is_some_and(|secondary| secondary.zone_for(name).is_some());
+        self.cache.as_ref().filter(|_| !routed && !secondary)
+    }
+
+    // NOTIFY (RFC 1996) : le primaire signale une modification de zone. Accepté uniquement
+    // du primaire déclaré par --secondary-for, il déclenche un transfert AXFR en arrière-plan
+    fn handle_notify(&self, query: &DnsMessage, client_ip: IpAddr) -> DnsMessage {
+        let Some(question) = query.questions.first() else {
+            return DnsMessage::new_error_response(query, DnsRcode::FORMERR);
+        };
+        let zone = question.name.trim_end_matches('.').to_lowercase();
+        println!("🔔 {} Zone {} modifiée, notifiée par {}",
+                 "NOTIFY".cyan().bold(),
+                 zone.yellow(),
+                 client_ip.to_string().cyan()
+        );
+
+        let secondary = self.secondary.as_ref().filter(|secondary| {
+            secondary.primaries.get(&zone).is_some_and(|primary| primary.ip() == client_ip.to_canonical())
+        });
+        let Some(secondary) = secondary.cloned() else {
+            println!("⛔ {} {} n'est pas le primaire de {}, notification refusée",
+                     "NOTIFY".red().bold(),
+                     client_ip.to_string().cyan(),
+                     zone.yellow()
+            );
+            return DnsMessage::new_error_response(query, DnsRcode::REFUSED);
+        };
+
+        tokio::spawn(async move {
+            secondary.refresh(&zone).await;
+        });
+
+        // Réponse : le message reçu avec QR positionné
+        DnsMessage::new_response(query)
+    }
+
+    fn with_query_log(mut self, query_log: Option<QueryLog>) -> Self {
+        self.query_log = query_log;
+        self
+    }
+
+    // Journaliser une requête traitée (première question, rcode de la réponse)
+    fn log_query(&self, client_ip: IpAddr, query: &[u8], response: &[u8], latency: Duration) {
+        let Some(query_log) = &self.query_log else {
+            return;
+        };
+        // La requête a déjà été analysée par process_query : seul l'en-tête de la question est relu
+        let Ok(message) = DnsMessage::from_bytes_zero_copy(query) else {
+            return;
+        };
+        if let Some(question) = message.questions.first() {
+            let rcode = response.get(3).map_or(0, |flags| flags & 0x0F);
+            query_log.send(QueryLogEntry::new(client_ip, question.name.to_string(), question.qtype, rcode, latency));
+        }
+    }
+
+    // Réponse minimale aux requêtes ANY (RFC 8482)
+    fn with_rfc8482(mut self, rfc8482: bool) -> Self {
+        self.rfc8482 = rfc8482;
+        self
+    }
+
+    // Requête ANY : tous les enregistrements du nom, quel que soit leur type
+    // Avec --rfc8482, un seul HINFO "RFC8482" suffit à signaler que le nom existe
+    async fn resolve_any(&self, question: &DnsQuestion, client_ip: IpAddr) -> Result<Vec<DnsRecord>, DnsRcode> {
+        let database = self.database_for(&question.name, client_ip).await;
+        let name = &question.name;
+        let mut records = Vec::new();
+
+        if let Some(entry) = database.lookup_fresh(name) {
+            let ttl = entry.response_ttl(MAX_RESPONSE_TTL);
+            records.extend(entry.ips.iter().map(|ip| DnsRecord::new_a_record(name.clone(), *ip, ttl)));
+        } else if let Some(ip) = database.lookup(name) {
+            records.push(DnsRecord::new_a_record(name.clone(), ip, MAX_RESPONSE_TTL));
+        }
+        if let Some(canonical) = database.lookup_cname(name) {
+            records.push(DnsRecord::new_cname_record(name.clone(), canonical.clone(), MAX_RESPONSE_TTL));
+        }
+        if let Some(strings) = database.lookup_txt(name) {
+            let parts: Vec<&str> = strings.iter().map(|s| s.as_str()).collect();
+            records.push(DnsRecord::new_txt_record(name.clone(), &parts, MAX_RESPONSE_TTL));
+        }
+        for srv in database.lookup_srv(name).into_iter().flatten() {
+            records.push(DnsRecord::new_srv_record(
+                name.clone(), srv.priority, srv.weight, srv.port, srv.target.clone(), MAX_RESPONSE_TTL
+            ));
+        }
+        for naptr in database.lookup_naptr(name).into_iter().flatten() {
+            records.push(DnsRecord::new_naptr_record(name.clone(), naptr, MAX_RESPONSE_TTL));
+        }
+        for caa in database.lookup_caa(name).into_iter().flatten() {
+            records.push(DnsRecord::new_caa_record(name.clone(), caa, MAX_RESPONSE_TTL));
+        }
+        for nameserver in database.lookup_ns(name).into_iter().flatten() {
+            records.push(DnsRecord::new_ns_record(name.clone(), nameserver.clone(), MAX_RESPONSE_TTL));
+        }
+        if let Some(soa) = database.lookup_soa(name) {
+            records.push(DnsRecord::new_soa_record(name.clone(), soa, MAX_RESPONSE_TTL));
+        }
+
+        if records.is_empty() {
+            println!("❌ {} Domaine non trouvé: {}",
+                     "NXDOMAIN".red().bold(),
+                     name.yellow()
+            );
+            return Err(DnsRcode::NXDOMAIN);
+        }
+
+        if self.rfc8482 {
+            println!("✅ {} {} ANY -> HINFO (RFC 8482)",
+                     "RESOLVED".green().bold(),
+                     name.yellow()
+            );
+            return Ok(vec![DnsRecord::new_hinfo_record(name.clone(), "RFC8482", "", MAX_RESPONSE_TTL)]);
+        }
+
+        println!("✅ {} Résolu: {} ANY -> {} enregistrement(s)",
+                 "RESOLVED".green().bold(),
+                 name.yellow(),
+                 records.len().to_string().green()
+        );
+        Ok(records)
+    }
+
+    // Requêtes de classe CHAOS : identification du logiciel et de l'hôte
+    // Seuls les TXT de version.bind, hostname.bind et id.server sont servis (sinon REFUSED)
+    fn resolve_chaos(&self, question: &DnsQuestion) -> Result<Vec<DnsRecord>, DnsRcode> {
+        if question.qtype != DnsRecordType::TXT {
+            return Err(DnsRcode::REFUSED);
+        }
+
+        let value = match question.name.trim_end_matches('.').to_lowercase().as_str() {
+            "version.bind" => self.server_version.clone(),
+            "hostname.bind" | "id.server" => host_name(),
+            _ => return Err(DnsRcode::REFUSED),
+        };
+        println!("✅ {} {} CH TXT -> {}",
+                 "CHAOS".green().bold(),
+                 question.name.yellow(),
+                 value.green()
+        );
+
+        let mut record = DnsRecord::new_txt_record(question.name.clone(), &[&value], 0);
+        record.class = DnsClass::CH;
+        Ok(vec![record])
+    }
+
+    async fn start(self: Arc<Self>) -> Result<(), Box<dyn std::error::Error>> {
+        // Tâche périodique pour purger les enregistrements et le cache expirés
+        let purge_server = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(PURGE_INTERVAL);
+            loop {
+                interval.tick().await;
+                let purged = purge_server.database.write().await.purge_expired();
+                if purged > 0 {
+                    println!("🧹 {} {} enregistrement(s) expiré(s) supprimé(s)",
+                             "PURGE".blue().bold(),
+                             purged.to_string().magenta()
+                    );
+                }
+
+                if let Some(rate_limiter) = &purge_server.rate_limiter {
+                    rate_limiter.lock().await.purge_idle();
+                }
+
+                if let Some(cache) = &purge_server.cache {
+                    let evicted = cache.lock().await.evict_expired();
+                    if evicted > 0 {
+                        println!("🧹 {} {} entrée(s) expirée(s) retirée(s) du cache",
+                                 "CACHE".blue().bold(),
+                                 evicted.to_string().magenta()
+                        );
+                    }
+                }
+            }
+        });
+
+        // Tâche pour accepter les connexions TCP
+        if self.tcp_listener.is_some() {
+            let tcp_server = self.clone();
+            tokio::spawn(async move {
+                let Some(listener) = &tcp_server.tcp_listener else { return };
+                loop {
+                    match listener.accept().await {
+                        Ok((stream, client_addr)) => {
+                            let server = tcp_server.clone();
+                            tokio::spawn(async move {
+                                if let Err(e) = Self::handle_tcp_client(server, stream, client_addr).await {
+                                    eprintln!("❌ {} Erreur connexion TCP {}: {}",
+                                              "ERROR".red().bold(), client_addr, e);
+                                }
+                            });
+                        }
+                        Err(e) => {
+                            eprintln!("❌ {} Erreur acceptation TCP: {}", "ERROR".red().bold(), e);
+                        }
+                    }
+                }
+            });
+        }
+
+        let mut buffer = vec![0u8; EDNS_BUFFER_SIZE as usize]; // Assez grand pour les requêtes EDNS0
 
         loop {
             match self.socket.recv_from(&mut buffer).await {
@@ -69,19 +803,11 @@ impl DnsServer {
                     }
 
                     // Traiter la requête dans une tâche séparée
-                    let socket_clone = self.socket.clone();
+                    let server = self.clone();
                     let data = buffer[..size].to_vec();
-                    let database = self.database.clone();
-                    let verbose = self.verbose;
 
                     tokio::spawn(async move {
-                        if let Err(e) = Self::handle_query(
-                            socket_clone,
-                            client_addr,
-                            data,
-                            database,
-                            verbose
-                        ).await {
+                        if let Err(e) = Self::handle_query(server, client_addr, data).await {
                             eprintln!("❌ {} Erreur traitement requête: {}",
                                       "ERROR".red().bold(), e);
                         }
@@ -94,24 +820,477 @@ impl DnsServer {
         }
     }
 
-    async fn handle_query(
-        socket: Arc<UdpSocket>,
-        client_addr: SocketAddr,
-        data: Vec<u8>,
-        database: SimpleDnsDatabase,
-        verbose: bool,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    // Socket d'administration : la première ligne doit être le secret partagé,
+    // puis une commande par ligne (ADD <nom> <ip>, DEL <nom>, LIST, STATS, RELOAD <fichier>)
+    #[cfg(unix)]
+    async fn run_admin_socket(
+        servers: Arc<[Arc<DnsServer>]>,
+        path: PathBuf,
+        secret: String,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        use std::os::unix::fs::PermissionsExt;
+        use tokio::net::UnixListener;
+
+        // Un socket laissé par une exécution précédente empêcherait le bind
+        if path.exists() {
+            std::fs::remove_file(&path)?;
+        }
+        let listener = UnixListener::bind(&path)?;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+        println!("🔧 {} Socket d'administration: {}", "ADMIN".blue().bold(), path.display().to_string().cyan());
+
+        let secret = Arc::new(secret);
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let servers = servers.clone();
+            let secret = secret.clone();
+
+            tokio::spawn(async move {
+                if let Err(e) = Self::handle_admin_client(&servers, stream, &secret).await {
+                    eprintln!("❌ {} Erreur connexion admin: {}", "ERROR".red().bold(), e);
+                }
+            });
+        }
+    }
+
+    #[cfg(unix)]
+    async fn handle_admin_client(
+        servers: &[Arc<DnsServer>],
+        stream: tokio::net::UnixStream,
+        secret: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let (read_half, mut write_half) = stream.into_split();
+        let mut lines = BufReader::new(read_half).lines();
+
+        if lines.next_line().await?.as_deref().map(str::trim) != Some(secret) {
+            eprintln!("⚠️  {} Secret d'administration invalide", "ADMIN".yellow().bold());
+            write_half.write_all(b"ERR authentification refusee\n").await?;
+            return Ok(());
+        }
+        write_half.write_all(b"OK\n").await?;
+
+        while let Some(line) = lines.next_line().await? {
+            let reply = Self::admin_command(servers, line.trim()).await;
+            write_half.write_all(reply.as_bytes()).await?;
+        }
+
+        Ok(())
+    }
+
+    // Exécuter une commande d'administration et produire la réponse (terminée par '\n')
+    // Avec --threads, chaque instance a sa copie de la base : les modifications
+    // sont appliquées à toutes, les lectures se font sur la première
+    async fn admin_command(servers: &[Arc<DnsServer>], line: &str) -> String {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let command = fields.first().map(|command| command.to_uppercase()).unwrap_or_default();
+
+        let reply = match (command.as_str(), &fields[1..]) {
+            ("ADD", [name, address]) => match address.parse::<Ipv4Addr>() {
+                Ok(ip) => {
+                    // Écriture idempotente : chaque instance peut la refaire
+                    let mut result = Ok(());
+                    for server in servers {
+                        result = result.and(server.database.write().await.add_record_persistent(name.to_string(), ip, PERMANENT_TTL));
+                        server.invalidate_cache().await;
+                    }
+                    match result {
+                        Ok(()) => {
+                            println!("🔧 {} Ajout: {} -> {}", "ADMIN".blue().bold(), name.yellow(), ip.to_string().green());
+                            format!("OK {} -> {}", name, ip)
+                        }
+                        Err(e) => format!("ERR {}", e),
+                    }
+                }
+                Err(_) => format!("ERR adresse invalide '{}'", address),
+            },
+            ("DEL", [name]) => {
+                let mut result = Ok(false);
+                for server in servers {
+                    let removed = server.database.write().await.remove_record_persistent(name);
+                    result = result.and_then(|previous| removed.map(|removed| previous || removed));
+                    server.invalidate_cache().await;
+                }
+                match result {
+                    Ok(true) => {
+                        println!("🔧 {} Suppression: {}", "ADMIN".blue().bold(), name.yellow());
+                        format!("OK {} supprimé", name)
+                    }
+                    Ok(false) => format!("ERR nom inconnu '{}'", name),
+                    Err(e) => format!("ERR {}", e),
+                }
+            }
+            ("LIST", []) => {
+                let database = servers[0].database.read().await;
+                let mut names: Vec<_> = database.list_records().iter().collect();
+                names.sort_by_key(|(name, _)| name.as_str());
+
+                let mut reply = String::new();
+                for (name, entry) in &names {
+                    let ips: Vec<String> = entry.ips.iter().map(|ip| ip.to_string()).collect();
+                    reply.push_str(&format!("{} {}\n", name, ips.join(",")));
+                }
+                reply.push_str(&format!("OK {} enregistrement(s)", names.len()));
+                reply
+            }
+            ("STATS", []) => {
+                // Les statistiques sont partagées, les caches additionnés
+                let mut reply = servers[0].stats.summary();
+                if servers[0].cache.is_some() {
+                    let (mut hits, mut misses, mut size, mut negative_size) = (0, 0, 0, 0);
+                    for cache in servers.iter().filter_map(|server| server.cache.as_ref()) {
+                        let cache_stats = cache.lock().await.stats();
+                        hits += cache_stats.hits;
+                        misses += cache_stats.misses;
+                        size += cache_stats.size;
+                        negative_size += cache_stats.negative_size;
+                    }
+                    reply.push_str(&format!(" cache_hits={} cache_misses={} cache_taille={} cache_negatif={}",
+                                            hits, misses, size, negative_size));
+                }
+                format!("{}\nOK", reply)
+            }
+            ("RELOAD", [path]) => match Self::reload_database(servers, std::path::Path::new(path)).await {
+                Ok(database) => {
+                    let count = database.list_records().len();
+                    for server in servers {
+                        *server.database.write().await = database.clone();
+                        server.invalidate_cache().await;
+                    }
+                    println!("🔧 {} Rechargement depuis {} ({} noms)", "ADMIN".blue().bold(), path.cyan(), count);
+                    format!("OK {} enregistrement(s) chargé(s)", count)
+                }
+                Err(e) => format!("ERR {}", e),
+            },
+            _ => "ERR commande inconnue (ADD <nom> <ip>, DEL <nom>, LIST, STATS, RELOAD <fichier>)".to_string(),
+        };
+
+        format!("{}\n", reply)
+    }
+
+    // Recharger un fichier hosts en gardant la base SQLite attachée et ses ajouts
+    async fn reload_database(servers: &[Arc<DnsServer>], path: &std::path::Path) -> std::io::Result<SimpleDnsDatabase> {
+        let database = SimpleDnsDatabase::load_from_hosts_file(path)?;
+        let store_path = servers[0].database.read().await.store().map(|store| store.path().to_path_buf());
+        match store_path {
+            Some(store_path) => database.with_store(&store_path),
+            None => Ok(database),
+        }
+    }
+
+    // Les réponses en cache ne reflètent plus la base après une modification
+    async fn invalidate_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.lock().await.clear();
+        }
+    }
+
+    // Relayer la requête brute vers le serveur amont et retourner sa réponse
+    async fn forward_query(
+        upstream: &str,
+        data: &[u8],
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+        let socket = UdpSocket::bind("0.0.0.0:0").await?;
+        socket.send_to(data, upstream).await?;
+
+        let mut buffer = vec![0u8; EDNS_BUFFER_SIZE as usize];
+        let size = match tokio::time::timeout(UPSTREAM_TIMEOUT, socket.recv(&mut buffer)).await {
+            Ok(result) => result?,
+            Err(_) => return Err(format!("Timeout du serveur amont {}", upstream).into()),
+        };
+        buffer.truncate(size);
+
+        // Vérifier que la réponse est valide avant de la relayer
+        let query_id = DnsMessage::from_bytes(data)?.header.id;
+        let response = DnsMessage::from_bytes(&buffer)?;
+        if !response.header.qr || response.header.id != query_id {
+            return Err("Réponse amont invalide".into());
+        }
+
+        Ok(buffer)
+    }
+
+    // Réponse quand le type demandé est absent pour ce nom (RFC 2308 §2.1-2.2) :
+    // NODATA (NOERROR sans réponse) si le nom existe, NXDOMAIN sinon
+    fn not_found(database: &SimpleDnsDatabase, question: &DnsQuestion) -> Result<Vec<DnsRecord>, DnsRcode> {
+        if database.name_exists(&question.name) {
+            println!("∅  {} Aucun enregistrement {:?} pour {}",
+                     "NODATA".yellow().bold(),
+                     question.qtype,
+                     question.name.yellow()
+            );
+            Ok(Vec::new())
+        } else {
+            println!("❌ {} Domaine non trouvé: {}",
+                     "NXDOMAIN".red().bold(),
+                     question.name.yellow()
+            );
+            Err(DnsRcode::NXDOMAIN)
+        }
+    }
+
+    // Résoudre une question depuis la base locale
+    // Retourne les enregistrements trouvés ou le code de réponse d'erreur
+    // `client_ip` choisit la base quand le nom appartient à une zone routée (--acl-file)
+    async fn resolve_question(&self, question: &DnsQuestion, client_ip: IpAddr) -> Result<Vec<DnsRecord>, DnsRcode> {
+        match question.qtype {
+            DnsRecordType::A => {
+                let database = self.database_for(&question.name, client_ip).await;
+                let mut records = Vec::new();
+                let mut name = question.name.clone();
+
+                // Suivre la chaîne de CNAME (RFC 1034 §3.6.2)
+                while let Some(canonical) = database.lookup_cname(&name) {
+                    if records.len() >= MAX_CNAME_HOPS {
+                        println!("❌ {} Chaîne CNAME trop longue pour {}",
+                                 "SERVFAIL".red().bold(),
+                                 question.name.yellow()
+                        );
+                        return Err(DnsRcode::SERVFAIL);
+                    }
+
+                    println!("↪️  {} {} -> {}",
+                             "CNAME".cyan().bold(),
+                             name.yellow(),
+                             canonical.yellow()
+                    );
+                    records.push(DnsRecord::new_cname_record(
+                        name.clone(),
+                        canonical.clone(),
+                        MAX_RESPONSE_TTL
+                    ));
+                    name = canonical.clone();
+                }
+
+                let found = database
+                    .lookup_fresh(&name)
+                    .and_then(|entry| {
+                        entry.ips.first().map(|ip| (*ip, entry.response_ttl(MAX_RESPONSE_TTL)))
+                    })
+                    // Puis la base SQLite (--database), qu'un autre processus a pu compléter
+                    .or_else(|| database.lookup(&name).map(|ip| (ip, MAX_RESPONSE_TTL)));
+
+                if let Some((ip, ttl)) = found {
+                    println!("✅ {} Résolu: {} -> {}",
+                             "RESOLVED".green().bold(),
+                             question.name.yellow(),
+                             ip.to_string().green()
+                    );
+                    records.push(DnsRecord::new_a_record(name, ip, ttl));
+                    Ok(records)
+                } else if !records.is_empty() {
+                    // CNAME sans adresse finale : on renvoie la chaîne seule
+                    Ok(records)
+                } else {
+                    Self::not_found(&database, question)
+                }
+            }
+            DnsRecordType::CNAME => {
+                let database = self.database_for(&question.name, client_ip).await;
+                let found = database.lookup_cname(&question.name).cloned();
+
+                if let Some(canonical) = found {
+                    println!("✅ {} Résolu: {} -> {}",
+                             "RESOLVED".green().bold(),
+                             question.name.yellow(),
+                             canonical.green()
+                    );
+                    Ok(vec![DnsRecord::new_cname_record(
+                        question.name.clone(),
+                        canonical,
+                        MAX_RESPONSE_TTL
+                    )])
+                } else {
+                    Self::not_found(&database, question)
+                }
+            }
+            DnsRecordType::TXT => {
+                let database = self.database_for(&question.name, client_ip).await;
+                let found = database.lookup_txt(&question.name).cloned();
+
+                if let Some(strings) = found {
+                    println!("✅ {} Résolu: {} -> {:?}",
+                             "RESOLVED".green().bold(),
+                             question.name.yellow(),
+                             strings
+                    );
+                    let parts: Vec<&str> = strings.iter().map(|s| s.as_str()).collect();
+                    Ok(vec![DnsRecord::new_txt_record(
+                        question.name.clone(),
+                        &parts,
+                        MAX_RESPONSE_TTL
+                    )])
+                } else {
+                    Self::not_found(&database, question)
+                }
+            }
+            DnsRecordType::SRV => {
+                let database = self.database_for(&question.name, client_ip).await;
+                let found = database.lookup_srv(&question.name).cloned();
+
+                if let Some(services) = found {
+                    println!("✅ {} Résolu: {} -> {} service(s)",
+                             "RESOLVED".green().bold(),
+                             question.name.yellow(),
+                             services.len().to_string().green()
+                    );
+                    Ok(services.into_iter().map(|srv| DnsRecord::new_srv_record(
+                        question.name.clone(),
+                        srv.priority,
+                        srv.weight,
+                        srv.port,
+                        srv.target,
+                        MAX_RESPONSE_TTL
+                    )).collect())
+                } else {
+                    Self::not_found(&database, question)
+                }
+            }
+            DnsRecordType::NAPTR => {
+                let database = self.database_for(&question.name, client_ip).await;
+                let found = database.lookup_naptr(&question.name).cloned();
+
+                if let Some(rules) = found {
+                    println!("✅ {} Résolu: {} -> {} règle(s) NAPTR",
+                             "RESOLVED".green().bold(),
+                             question.name.yellow(),
+                             rules.len().to_string().green()
+                    );
+                    Ok(rules.iter().map(|naptr| DnsRecord::new_naptr_record(
+                        question.name.clone(),
+                        naptr,
+                        MAX_RESPONSE_TTL
+                    )).collect())
+                } else {
+                    Self::not_found(&database, question)
+                }
+            }
+            DnsRecordType::CAA => {
+                let database = self.database_for(&question.name, client_ip).await;
+                let found = database.lookup_caa(&question.name).cloned();
+
+                if let Some(entries) = found {
+                    println!("✅ {} Résolu: {} -> {} restriction(s) CAA",
+                             "RESOLVED".green().bold(),
+                             question.name.yellow(),
+                             entries.len().to_string().green()
+                    );
+                    Ok(entries.iter().map(|caa| DnsRecord::new_caa_record(
+                        question.name.clone(),
+                        caa,
+                        MAX_RESPONSE_TTL
+                    )).collect())
+                } else {
+                    Self::not_found(&database, question)
+                }
+            }
+            DnsRecordType::NS => {
+                let database = self.database_for(&question.name, client_ip).await;
+                let found = database.lookup_ns(&question.name).cloned();
+
+                if let Some(nameservers) = found {
+                    println!("✅ {} Résolu: {} -> {} serveur(s) de noms",
+                             "RESOLVED".green().bold(),
+                             question.name.yellow(),
+                             nameservers.len().to_string().green()
+                    );
+                    Ok(nameservers.into_iter().map(|nameserver| DnsRecord::new_ns_record(
+                        question.name.clone(),
+                        nameserver,
+                        MAX_RESPONSE_TTL
+                    )).collect())
+                } else {
+                    Self::not_found(&database, question)
+                }
+            }
+            DnsRecordType::SOA => {
+                let database = self.database_for(&question.name, client_ip).await;
+                let found = database.lookup_soa(&question.name).cloned();
+
+                if let Some(soa) = found {
+                    println!("✅ {} Résolu: {} -> SOA {}",
+                             "RESOLVED".green().bold(),
+                             question.name.yellow(),
+                             soa.mname.green()
+                    );
+                    Ok(vec![DnsRecord::new_soa_record(
+                        question.name.clone(),
+                        &soa,
+                        MAX_RESPONSE_TTL
+                    )])
+                } else {
+                    Self::not_found(&database, question)
+                }
+            }
+            DnsRecordType::ANY => self.resolve_any(question, client_ip).await,
+            DnsRecordType::AXFR => {
+                // Les transferts de zone passent par TCP (voir `transfer_zone`)
+                println!("❌ {} AXFR uniquement en TCP: {}",
+                         "REFUSED".red().bold(),
+                         question.name.yellow()
+                );
+                Err(DnsRcode::REFUSED)
+            }
+            _ => {
+                // Type de requête non supporté
+                println!("❌ {} Type de requête non supporté: {:?}",
+                         "UNSUPPORTED".red().bold(),
+                         question.qtype
+                );
+                Err(DnsRcode::NOTIMP)
+            }
+        }
+    }
+
+    // Relire les noms des questions avec le décodeur strict (--strict-names)
+    fn check_strict_names(data: &[u8], qdcount: usize) -> std::io::Result<()> {
+        let mut offset = 12;
+        for _ in 0..qdcount {
+            DnsQuestion::decode_name_strict(data, &mut offset)?;
+            offset += 4; // QTYPE et QCLASS
+        }
+        Ok(())
+    }
+
+    // Traiter une requête brute et produire la réponse à renvoyer
+    // `max_size` limite la taille de la réponse (UDP) : au-delà le bit TC est positionné
+    async fn process_query(
+        server: &DnsServer,
+        client_ip: IpAddr,
+        data: &[u8],
+        max_size: Option<usize>,
+    ) -> Option<Vec<u8>> {
+        let verbose = server.verbose;
 
         // Parser la requête DNS
-        let query = match DnsMessage::from_bytes(&data) {
+        let query = match DnsMessage::from_bytes(data) {
             Ok(msg) => msg,
             Err(e) => {
                 eprintln!("❌ {} Impossible de parser la requête: {}",
                           "PARSE_ERROR".red().bold(), e);
-                return Ok(());
+                return None;
             }
         };
 
+        // En-tête non conforme ou nom rejeté en mode strict : répondre FORMERR sans traiter les questions
+        let mut reasons: Vec<String> = match query.header.validate() {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors.iter().map(|error| error.to_string()).collect(),
+        };
+        if server.strict_names {
+            if let Err(e) = Self::check_strict_names(data, query.questions.len()) {
+                reasons.push(e.to_string());
+            }
+        }
+        if !reasons.is_empty() {
+            eprintln!("❌ {} Requête invalide: {}",
+                      "FORMERR".red().bold(), reasons.join(", "));
+            return Some(DnsMessage::new_error_response(&query, DnsRcode::FORMERR).to_bytes());
+        }
+
+        if query.header.opcode == DnsOpcode::NOTIFY as u8 {
+            return Some(server.handle_notify(&query, client_ip).to_bytes());
+        }
+
         if verbose {
             println!("🔍 {} Requête parsée:", "PARSE".blue().bold());
             println!("   ID: {}", query.header.id.to_string().cyan());
@@ -126,67 +1305,192 @@ impl DnsServer {
             }
         }
 
+        // EDNS0 : le client peut accepter des réponses UDP plus grandes que 512 octets
+        let max_size = max_size.map(|size| {
+            size.max(query.max_udp_size().min(EDNS_BUFFER_SIZE as usize))
+        });
+
         // Créer la réponse
         let mut response = DnsMessage::new_response(&query);
 
         // Traiter chaque question
         for question in &query.questions {
-            match question.qtype {
-                DnsRecordType::A => {
-                    if let Some(ip) = database.lookup(&question.name) {
-                        let record = DnsRecord::new_a_record(
-                            question.name.clone(),
-                            ip,
-                            300  // TTL de 5 minutes
-                        );
-                        response.answers.push(record);
-                        response.header.ancount += 1;
+            // Classe CHAOS : ni cache ni base, qui ne contiennent que des enregistrements IN
+            if question.qclass == DnsClass::CH {
+                match server.resolve_chaos(question) {
+                    Ok(records) => {
+                        response.header.ancount += records.len() as u16;
+                        response.answers.extend(records);
+                    }
+                    Err(rcode) => return Some(DnsMessage::new_error_response(&query, rcode).to_bytes()),
+                }
+                continue;
+            }
 
-                        println!("✅ {} Résolu: {} -> {}",
-                                 "RESOLVED".green().bold(),
+            // Consulter le cache avant la base de données
+            let cache = server.cache_for(&question.name);
+            if let Some(cache) = cache {
+                let cache = cache.lock().await;
+                if let Some(negative) = cache.get_negative(&question.name, question.qtype) {
+                    if verbose {
+                        println!("⚡ {} {} ({:?}) {:?}",
+                                 "CACHE_HIT".cyan().bold(),
                                  question.name.yellow(),
-                                 ip.to_string().green()
+                                 question.qtype,
+                                 negative
                         );
-                    } else {
-                        // Domain non trouvé
-                        response.header.rcode = 3; // NXDOMAIN
-                        println!("❌ {} Domaine non trouvé: {}",
-                                 "NXDOMAIN".red().bold(),
-                                 question.name.yellow()
+                    }
+                    // NODATA : NOERROR sans réponse, le SOA est ajouté avec l'autorité
+                    if negative == NegativeAnswer::NxDomain {
+                        response.header.rcode = DnsRcode::NXDOMAIN as u8;
+                    }
+                    continue;
+                }
+                if let Some(records) = cache.get(&question.name, question.qtype) {
+                    if verbose {
+                        println!("⚡ {} {} ({:?})",
+                                 "CACHE_HIT".cyan().bold(),
+                                 question.name.yellow(),
+                                 question.qtype
                         );
                     }
+                    response.header.ancount += records.len() as u16;
+                    response.answers.extend(records);
+                    continue;
                 }
-                _ => {
-                    // Type de requête non supporté
-                    response.header.rcode = 4; // NOTIMP
-                    println!("❌ {} Type de requête non supporté: {:?}",
-                             "UNSUPPORTED".red().bold(),
-                             question.qtype
-                    );
+            }
+
+            match server.resolve_question(question, client_ip).await {
+                Ok(records) => {
+                    if let Some(cache) = cache {
+                        let mut cache = cache.lock().await;
+                        if records.is_empty() {
+                            cache.insert_nodata(&question.name, question.qtype);
+                        } else {
+                            let ttl = records.iter().map(|r| r.ttl).min().unwrap_or(0);
+                            cache.insert(&question.name, question.qtype, records.clone(), ttl);
+                        }
+                    }
+                    response.header.ancount += records.len() as u16;
+                    response.answers.extend(records);
                 }
+                Err(DnsRcode::NXDOMAIN) => {
+                    if let Some(cache) = cache {
+                        cache.lock().await.insert_nxdomain(&question.name);
+                    }
+                    response.header.rcode = DnsRcode::NXDOMAIN as u8;
+                }
+                // Autres erreurs : réponse minimale, sans les réponses déjà trouvées
+                Err(rcode) => return Some(DnsMessage::new_error_response(&query, rcode).to_bytes()),
             }
         }
 
-        // Envoyer la réponse
+        // Relayer vers le serveur amont si le nom est inconnu localement
+        if response.header.rcode == DnsRcode::NXDOMAIN as u8 {
+            if let Some(upstream) = &server.upstream {
+                match Self::forward_query(upstream, data).await {
+                    Ok(upstream_bytes) => {
+                        println!("↪️  {} Réponse relayée depuis {}",
+                                 "FORWARD".cyan().bold(),
+                                 upstream.yellow()
+                        );
+                        return Some(upstream_bytes);
+                    }
+                    Err(e) => {
+                        eprintln!("⚠️  {} Échec du relais vers {}: {}",
+                                  "UPSTREAM".yellow().bold(), upstream, e);
+                    }
+                }
+            }
+        }
+
+        // Section d'autorité pour les zones locales, dans la base qui a servi la première question
+        let first_name = query.questions.first().map_or("", |question| question.name.as_str());
+        let database = server.database_for(first_name, client_ip).await;
+        // NODATA : NOERROR sans aucune réponse, le nom existe mais pas le type demandé
+        let nodata = response.header.rcode == DnsRcode::NOERROR as u8 && response.answers.is_empty();
+        if response.header.rcode == DnsRcode::NXDOMAIN as u8 || nodata {
+            // RFC 2308 : le SOA de la zone dans l'autorité permet au client de cacher la réponse négative
+            for question in &query.questions {
+                if let Some((zone, soa)) = database.find_zone(&question.name) {
+                    let ttl = soa.minimum.min(MAX_RESPONSE_TTL);
+                    response.authorities.push(DnsRecord::new_soa_record(zone.clone(), soa, ttl));
+                    response.header.nscount += 1;
+                    response.header.aa = true;
+                    break;
+                }
+            }
+        } else if response.header.rcode == DnsRcode::NOERROR as u8 {
+            // Serveurs de noms de la zone, sauf s'ils sont déjà la réponse à une requête NS
+            let answered_ns = response.answers.iter().any(|record| record.rtype == DnsRecordType::NS);
+            let authority = query.questions.first()
+                .and_then(|question| database.find_authority(&question.name));
+            if let (Some((zone, nameservers)), false) = (authority, answered_ns) {
+                response.authorities.extend(nameservers.iter().map(|nameserver| {
+                    DnsRecord::new_ns_record(zone.clone(), nameserver.clone(), MAX_RESPONSE_TTL)
+                }));
+                response.header.nscount += nameservers.len() as u16;
+            }
+            response.header.aa |= authority.is_some();
+        }
+        drop(database);
+
+        // Réponse trop grande : tronquer et laisser le client réessayer en TCP
+        if let Some(max_size) = max_size {
+            response.truncate(max_size);
+        }
         let response_bytes = response.to_bytes();
 
-        match socket.send_to(&response_bytes, client_addr).await {
+        if verbose {
+            println!("   Réponses: {}", response.header.ancount.to_string().cyan());
+            println!("   Code: {}",
+                     match response.header.rcode {
+                         0 => "NOERROR".green(),
+                         3 => "NXDOMAIN".red(),
+                         4 => "NOTIMP".yellow(),
+                         _ => "UNKNOWN".red(),
+                     }
+            );
+        }
+
+        Some(response_bytes)
+    }
+
+    async fn handle_query(
+        server: Arc<DnsServer>,
+        client_addr: SocketAddr,
+        data: Vec<u8>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let received_at = Instant::now();
+
+        // Limiter le débit en UDP pour éviter les attaques par amplification
+        if let Some(rate_limiter) = &server.rate_limiter {
+            if !rate_limiter.lock().await.check(client_addr.ip()) {
+                eprintln!("⚠️  {} Limite de débit dépassée pour {}, requête ignorée",
+                          "RATE_LIMIT".yellow().bold(),
+                          client_addr.ip()
+                );
+                return Ok(());
+            }
+        }
+
+        let response_bytes = match Self::process_query(&server, client_addr.ip(), &data, Some(UDP_MAX_SIZE)).await {
+            Some(bytes) => bytes,
+            None => return Ok(()),
+        };
+
+        // Envoyer la réponse
+        match server.socket.send_to(&response_bytes, client_addr).await {
             Ok(sent) => {
-                if verbose {
+                server.stats.record(data.len(), &response_bytes, received_at.elapsed());
+                server.log_query(client_addr.ip(), &data, &response_bytes, received_at.elapsed());
+
+                if server.verbose {
                     println!("📤 {} Réponse envoyée à {} ({} bytes)",
                              "RESPONSE".green().bold(),
                              client_addr.to_string().yellow(),
                              sent.to_string().magenta()
                     );
-                    println!("   Réponses: {}", response.header.ancount.to_string().cyan());
-                    println!("   Code: {}",
-                             match response.header.rcode {
-                                 0 => "NOERROR".green(),
-                                 3 => "NXDOMAIN".red(),
-                                 4 => "NOTIMP".yellow(),
-                                 _ => "UNKNOWN".red(),
-                             }
-                    );
                     println!(); // Ligne vide pour la lisibilité
                 }
             }
@@ -197,6 +1501,171 @@ impl DnsServer {
 
         Ok(())
     }
+
+    // Gérer une connexion TCP : chaque message est préfixé par sa longueur (2 octets)
+    async fn handle_tcp_client(
+        server: Arc<DnsServer>,
+        mut stream: TcpStream,
+        client_addr: SocketAddr,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        loop {
+            // Chaque lecture est bornée : un client muet ne garde pas la connexion ouverte
+            let length = match tokio::time::timeout(TCP_IDLE_TIMEOUT, stream.read_u16()).await {
+                Ok(Ok(length)) => length as usize,
+                Ok(Err(e)) if e.kind() == ErrorKind::UnexpectedEof => break,
+                Ok(Err(e)) => return Err(e.into()),
+                Err(_) => {
+                    if server.verbose {
+                        println!("⏱️  {} Connexion TCP inactive fermée: {}",
+                                 "TIMEOUT".yellow().bold(),
+                                 client_addr.to_string().yellow()
+                        );
+                    }
+                    break;
+                }
+            };
+
+            let mut data = vec![0u8; length];
+            tokio::time::timeout(TCP_IDLE_TIMEOUT, stream.read_exact(&mut data))
+                .await
+                .map_err(|_| format!("message incomplet après {}s", TCP_IDLE_TIMEOUT.as_secs()))??;
+            let received_at = Instant::now();
+
+            if server.verbose {
+                println!("📨 {} Requête TCP reçue de {} ({} bytes)",
+                         "REQUEST".cyan().bold(),
+                         client_addr.to_string().yellow(),
+                         length.to_string().magenta()
+                );
+            }
+
+            // Transfert de zone : plusieurs messages en réponse à une seule requête
+            let axfr = DnsMessage::from_bytes(&data).ok().filter(|query| {
+                !query.header.qr && query.questions.first().is_some_and(|question| question.qtype == DnsRecordType::AXFR)
+            });
+            if let Some(query) = axfr {
+                let messages = server.transfer_zone(&query, client_addr.ip()).await;
+                let first = messages[0].to_bytes();
+                for message in &messages {
+                    let bytes = message.to_bytes();
+                    stream.write_u16(bytes.len() as u16).await?;
+                    stream.write_all(&bytes).await?;
+                }
+                server.stats.record(data.len(), &first, received_at.elapsed());
+                server.log_query(client_addr.ip(), &data, &first, received_at.elapsed());
+                continue;
+            }
+
+            let response_bytes = match Self::process_query(&server, client_addr.ip(), &data, None).await {
+                Some(bytes) => bytes,
+                None => break,
+            };
+
+            stream.write_u16(response_bytes.len() as u16).await?;
+            stream.write_all(&response_bytes).await?;
+            server.stats.record(data.len(), &response_bytes, received_at.elapsed());
+            server.log_query(client_addr.ip(), &data, &response_bytes, received_at.elapsed());
+
+            if server.verbose {
+                println!("📤 {} Réponse TCP envoyée à {} ({} bytes)",
+                         "RESPONSE".green().bold(),
+                         client_addr.to_string().yellow(),
+                         response_bytes.len().to_string().magenta()
+                );
+                println!();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Lier les sockets UDP (et TCP) en mode non bloquant
+// Avec `reuse_port`, plusieurs instances peuvent écouter sur la même adresse :
+// le noyau répartit alors les paquets entre elles (SO_REUSEPORT)
+fn bind_sockets(
+    addr: SocketAddr,
+    use_tcp: bool,
+    reuse_port: bool,
+) -> std::io::Result<(std::net::UdpSocket, Option<std::net::TcpListener>)> {
+    use socket2::{Domain, Protocol, Socket, Type};
+
+    let bind = |socket_type: Type, protocol: Protocol| -> std::io::Result<Socket> {
+        let socket = Socket::new(Domain::for_address(addr), socket_type, Some(protocol))?;
+        #[cfg(unix)]
+        socket.set_reuse_port(reuse_port)?;
+        #[cfg(not(unix))]
+        let _ = reuse_port;
+        socket.set_nonblocking(true)?;
+        socket.bind(&addr.into())?;
+        Ok(socket)
+    };
+
+    let udp_socket = bind(Type::DGRAM, Protocol::UDP)?;
+    let tcp_listener = if use_tcp {
+        let socket = bind(Type::STREAM, Protocol::TCP)?;
+        socket.listen(1024)?;
+        Some(socket.into())
+    } else {
+        None
+    };
+
+    Ok((udp_socket.into(), tcp_listener))
+}
+
+// Nom de la machine pour hostname.bind et id.server
+fn host_name() -> String {
+    std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .or_else(|_| std::fs::read_to_string("/etc/hostname"))
+        .map(|name| name.trim().to_string())
+        .ok()
+        .filter(|name| !name.is_empty())
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .unwrap_or_else(|| "localhost".to_string())
+}
+
+// Afficher les enregistrements disponibles
+fn print_database(database: &SimpleDnsDatabase) {
+    println!("📚 {} Enregistrements DNS chargés:", "DATABASE".blue().bold());
+    for (name, entry) in database.list_records() {
+        let ips: Vec<String> = entry.ips.iter().map(|ip| ip.to_string()).collect();
+        println!("   {} -> {}", name.yellow(), ips.join(", ").green());
+    }
+    for (name, strings) in database.list_txt_records() {
+        println!("   {} -> {} {:?}", name.yellow(), "TXT".magenta(), strings);
+    }
+    for (alias, canonical) in database.list_cname_records() {
+        println!("   {} -> {} {}", alias.yellow(), "CNAME".magenta(), canonical.green());
+    }
+    for (name, services) in database.list_srv_records() {
+        for srv in services {
+            println!("   {} -> {} {} {} {} {}", name.yellow(), "SRV".magenta(),
+                     srv.priority, srv.weight, srv.port, srv.target.green());
+        }
+    }
+    for (name, rules) in database.list_naptr_records() {
+        for naptr in rules {
+            println!("   {} -> {} {} {} \"{}\" \"{}\" \"{}\" {}", name.yellow(), "NAPTR".magenta(),
+                     naptr.order, naptr.preference, naptr.flags, naptr.services, naptr.regexp,
+                     naptr.replacement.green());
+        }
+    }
+    for (name, entries) in database.list_caa_records() {
+        for caa in entries {
+            println!("   {} -> {} {} {} \"{}\"", name.yellow(), "CAA".magenta(),
+                     caa.flags, caa.tag, caa.value.green());
+        }
+    }
+    for (zone, nameservers) in database.list_ns_records() {
+        for nameserver in nameservers {
+            println!("   {} -> {} {}", zone.yellow(), "NS".magenta(), nameserver.green());
+        }
+    }
+    for (zone, soa) in database.list_soa_records() {
+        println!("   {} -> {} {} {} {}", zone.yellow(), "SOA".magenta(),
+                 soa.mname.green(), soa.rname, soa.serial);
+    }
+    println!();
 }
 
 #[tokio::main]
@@ -208,11 +1677,217 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("📋 Configuration:");
     println!("   • Adresse: {}", format!("{}:{}", args.address, args.port).cyan());
     println!("   • Mode verbeux: {}", if args.verbose { "ON".green() } else { "OFF".red() });
-    println!("   • Protocole: {}", "UDP".yellow());
+    if args.strict_names {
+        println!("   • Noms stricts: {}", "ON".green());
+    }
+    println!("   • Protocole: {}", if args.no_tcp { "UDP".yellow() } else { "UDP + TCP".yellow() });
+    println!("   • Cache: {}", if args.no_cache { "OFF".red() } else { "ON".green() });
+    if !args.no_cache {
+        println!("   • Cache négatif: {}", if args.negative_ttl == 0 {
+            "OFF".red()
+        } else {
+            format!("{}s", args.negative_ttl).green()
+        });
+    }
+    let rate_limit = (args.rate_limit > 0)
+        .then(|| (args.rate_limit, args.rate_limit_burst.unwrap_or(args.rate_limit)));
+    match rate_limit {
+        Some((qps, burst)) => println!("   • Limite de débit: {} req/s par IP (rafale: {})",
+                                       qps.to_string().cyan(), burst.to_string().cyan()),
+        None => println!("   • Limite de débit: {}", "OFF".red()),
+    }
+    if let Some(upstream) = &args.upstream {
+        println!("   • Serveur amont: {}", upstream.cyan());
+    }
+    if let Some(path) = &args.hosts_file {
+        println!("   • Fichier hosts: {}", path.display().to_string().cyan());
+    }
+    if let Some(path) = &args.zone_file {
+        println!("   • Fichier de zone: {}", path.display().to_string().cyan());
+    }
+    if let Some(path) = &args.database {
+        println!("   • Base SQLite: {}", path.display().to_string().cyan());
+    }
+    if let Some(path) = &args.acl_file {
+        println!("   • Fichier d'ACL: {}", path.display().to_string().cyan());
+    }
+    if let Some(path) = &args.query_log {
+        println!("   • Journal des requêtes: {}", path.display().to_string().cyan());
+    }
+    if args.rfc8482 {
+        println!("   • Requêtes ANY: {}", "HINFO (RFC 8482)".cyan());
+    }
+    for pair in args.secondary_for.chunks(2) {
+        println!("   • Zone secondaire: {} <- {}", pair[0].yellow(), pair[1].cyan());
+    }
+    if !args.allow_axfr.is_empty() {
+        let clients: Vec<String> = args.allow_axfr.iter().map(|cidr| cidr.to_string()).collect();
+        println!("   • Transferts AXFR autorisés: {}", clients.join(", ").cyan());
+    }
+    if args.threads > 1 {
+        println!("   • Threads: {} (SO_REUSEPORT)", args.threads.to_string().cyan());
+    }
     println!();
 
-    let addr = format!("{}:{}", args.address, args.port);
-    let server = DnsServer::new(&addr, args.verbose).await?;
+    let addr = match (args.address.as_str(), args.port).to_socket_addrs()?.next() {
+        Some(addr) => addr,
+        None => {
+            eprintln!("❌ {} Adresse invalide: {}", "FATAL".red().bold(), args.address);
+            std::process::exit(1);
+        }
+    };
+    let database = if let Some(path) = &args.hosts_file {
+        SimpleDnsDatabase::load_from_hosts_file(path)
+    } else if let Some(path) = &args.zone_file {
+        std::fs::read_to_string(path)
+            .and_then(|text| SimpleDnsDatabase::import_zone_file(&text))
+            .map_err(|e| std::io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))
+    } else {
+        Ok(SimpleDnsDatabase::new())
+    };
+    let zone_router = match &args.acl_file {
+        Some(path) => match ZoneRouter::load(path) {
+            Ok(router) => Some(Arc::new(router)),
+            Err(e) => {
+                eprintln!("❌ {} {}", "FATAL".red().bold(), e);
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+
+    let secondary = if args.secondary_for.is_empty() {
+        None
+    } else {
+        let mut primaries = HashMap::new();
+        for pair in args.secondary_for.chunks(2) {
+            let primary = pair[1].parse::<SocketAddr>()
+                .or_else(|_| pair[1].parse::<IpAddr>().map(|ip| SocketAddr::new(ip, 53)));
+            let Ok(primary) = primary else {
+                eprintln!("❌ {} Primaire invalide pour {}: {}", "FATAL".red().bold(), pair[0], pair[1]);
+                std::process::exit(1);
+            };
+            primaries.insert(pair[0].trim_end_matches('.').to_lowercase(), primary);
+        }
+        Some(Arc::new(SecondaryZones::new(primaries)))
+    };
+
+    // Les enregistrements persistés complètent (et remplacent) la base initiale
+    let database = match &args.database {
+        Some(path) => database.and_then(|database| database.with_store(path)),
+        None => database,
+    };
+
+    let database = match database {
+        Ok(database) => database,
+        Err(e) => {
+            eprintln!("❌ {} {}", "FATAL".red().bold(), e);
+            std::process::exit(1);
+        }
+    };
+
+    // Lier tous les sockets avant de démarrer pour signaler immédiatement une erreur
+    let reuse_port = args.threads > 1;
+    let mut sockets = Vec::new();
+    for _ in 0..args.threads {
+        sockets.push(bind_sockets(addr, !args.no_tcp, reuse_port)?);
+    }
+    println!("🚀 {} Serveur DNS démarré sur {}", "INFO".green().bold(), addr.to_string().cyan());
+    if !args.no_tcp {
+        println!("🚀 {} Écoute TCP sur {}", "INFO".green().bold(), addr.to_string().cyan());
+    }
+    print_database(&database);
+    for (zone, clients) in zone_router.iter().flat_map(|router| router.routes()) {
+        println!("🔀 {} Zone {} servie à part pour: {}", "ACL".blue().bold(), zone.yellow(), clients.cyan());
+    }
+
+    // Une instance par socket, chacune avec sa copie de la base et son cache
+    let stats = Arc::new(ServerStats::default());
+    let make_server = {
+        let (stats, upstream) = (stats.clone(), args.upstream.clone());
+        let (use_cache, negative_ttl, verbose) = (!args.no_cache, args.negative_ttl, args.verbose);
+        let (strict_names, server_version, rfc8482) = (args.strict_names, args.server_version.clone(), args.rfc8482);
+        // Un seul écrivain, partagé par toutes les instances
+        let query_log = args.query_log.clone().map(QueryLog::spawn);
+        let secondary = secondary.clone();
+        let axfr_clients = (!args.allow_axfr.is_empty())
+            .then(|| IpAcl::new("allow-axfr".to_string(), args.allow_axfr.clone()));
+        move |sockets| {
+            DnsServer::new(
+                sockets,
+                database.clone(),
+                upstream.clone(),
+                use_cache.then(|| DnsCache::new(CACHE_MAX_ENTRIES).with_negative_ttl(negative_ttl)),
+                rate_limit,
+                stats.clone(),
+                verbose,
+            ).map(|server| Arc::new(server
+                .with_strict_names(strict_names)
+                .with_server_version(server_version.clone())
+                .with_rfc8482(rfc8482)
+                .with_query_log(query_log.clone())
+                .with_zone_router(zone_router.clone())
+                .with_axfr_clients(axfr_clients.clone())
+                .with_secondary_zones(secondary.clone())))
+        }
+    };
+
+    // Avec --threads, chaque instance tourne dans son propre thread et son propre
+    // runtime tokio ; le runtime principal garde l'administration et l'arrêt
+    let mut servers = Vec::new();
+    let main_server = if sockets.len() == 1 {
+        let server = make_server(sockets.remove(0))?;
+        servers.push(server.clone());
+        Some(server)
+    } else {
+        let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+        for (worker, sockets) in sockets.into_iter().enumerate() {
+            let make_server = make_server.clone();
+            let ready_tx = ready_tx.clone();
+            std::thread::Builder::new()
+                .name(format!("dns-worker-{}", worker))
+                .spawn(move || {
+                    // Les sockets sont enregistrés auprès du runtime de ce thread
+                    let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+                        Ok(runtime) => runtime,
+                        Err(e) => return drop(ready_tx.send(Err(e))),
+                    };
+                    runtime.block_on(async move {
+                        let server = match make_server(sockets) {
+                            Ok(server) => server,
+                            Err(e) => return drop(ready_tx.send(Err(e))),
+                        };
+                        let _ = ready_tx.send(Ok(server.clone()));
+                        if let Err(e) = server.start().await {
+                            eprintln!("❌ {} Erreur serveur (thread {}): {}", "FATAL".red().bold(), worker, e);
+                        }
+                    });
+                })?;
+        }
+        for _ in 0..args.threads {
+            match ready_rx.recv() {
+                Ok(Ok(server)) => servers.push(server),
+                Ok(Err(e)) => {
+                    eprintln!("❌ {} Démarrage d'un thread impossible: {}", "FATAL".red().bold(), e);
+                    std::process::exit(1);
+                }
+                Err(_) => {
+                    eprintln!("❌ {} Un thread s'est arrêté au démarrage", "FATAL".red().bold());
+                    std::process::exit(1);
+                }
+            }
+        }
+        None
+    };
+    let servers: Arc<[Arc<DnsServer>]> = servers.into();
+
+    // Premier transfert des zones secondaires, les suivants sur NOTIFY
+    if let Some(secondary) = &secondary {
+        for zone in secondary.primaries.keys() {
+            let (secondary, zone) = (secondary.clone(), zone.clone());
+            tokio::spawn(async move { secondary.refresh(&zone).await });
+        }
+    }
 
     println!("💡 {} Pour tester le serveur:", "ASTUCE".yellow().bold());
     println!("   cargo run --bin dns_client -- google.com");
@@ -220,9 +1895,63 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("   dig @127.0.0.1 -p {} google.com", args.port);
     println!();
 
-    // Capturer Ctrl+C pour un arrêt propre
+    // Socket d'administration pour modifier la base sans redémarrer
+    if let (Some(path), Some(secret)) = (args.admin_socket, args.admin_secret) {
+        #[cfg(unix)]
+        {
+            let admin_servers = servers.clone();
+            tokio::spawn(async move {
+                if let Err(e) = DnsServer::run_admin_socket(admin_servers, path, secret).await {
+                    eprintln!("❌ {} Socket d'administration: {}", "ERROR".red().bold(), e);
+                }
+            });
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = (path, secret);
+            eprintln!("⚠️  {} Socket d'administration non disponible sur cette plateforme",
+                      "ADMIN".yellow().bold());
+        }
+    }
+
+    // Afficher les statistiques à la réception de SIGUSR1
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let stats = stats.clone();
+        let mut sigusr1 = signal(SignalKind::user_defined1())?;
+        tokio::spawn(async move {
+            while sigusr1.recv().await.is_some() {
+                stats.print();
+            }
+        });
+    }
+
+    // Affichage périodique des statistiques
+    if args.stats_interval > 0 {
+        let stats = stats.clone();
+        let period = Duration::from_secs(args.stats_interval);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+            loop {
+                interval.tick().await;
+                stats.print();
+            }
+        });
+    }
+
+    println!("👂 {} En attente de requêtes DNS...\n", "LISTENING".green().bold());
+
+    // Capturer Ctrl+C pour un arrêt propre ; les threads de --threads s'arrêtent avec le processus
+    let main_loop = async move {
+        match main_server {
+            Some(server) => server.start().await,
+            None => std::future::pending().await,
+        }
+    };
     tokio::select! {
-        result = server.start() => {
+        result = main_loop => {
             if let Err(e) = result {
                 eprintln!("❌ {} Erreur serveur: {}", "FATAL".red().bold(), e);
             }
@@ -232,6 +1961,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
+    stats.print();
+
     println!("👋 {} Serveur arrêté", "BYE".green().bold());
     Ok(())
 }
\ No newline at end of file
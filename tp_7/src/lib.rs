@@ -1,17 +1,38 @@
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use bytes::Bytes;
+use std::fmt;
 use std::io::{Cursor, Result as IoResult, Error, ErrorKind};
-use std::net::Ipv4Addr;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::{Path, PathBuf};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use hmac::{Hmac, Mac};
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{params, OptionalExtension, ToSql};
+use sha2::Sha256;
 
 // Types de requêtes DNS selon RFC 1035
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum DnsRecordType {
     A = 1,      // Adresse IPv4
     NS = 2,     // Name Server
     CNAME = 5,  // Canonical Name
+    SOA = 6,    // Start of Authority
     PTR = 12,   // Pointer
+    HINFO = 13, // Informations sur l'hôte (réponse minimale aux ANY, RFC 8482)
     MX = 15,    // Mail Exchange
+    TXT = 16,   // Texte
     AAAA = 28,  // Adresse IPv6
+    SRV = 33,   // Service
+    NAPTR = 35, // Réécriture de noms (RFC 3403, ENUM/SIP)
+    OPT = 41,   // Pseudo-enregistrement EDNS0 (RFC 6891)
+    TSIG = 250, // Signature de transaction (RFC 2845)
+    AXFR = 252, // Transfert de zone complet (RFC 5936, question TCP uniquement)
+    ANY = 255,  // Tous les types (question uniquement)
+    CAA = 257,  // Autorités de certification autorisées (RFC 8659)
 }
 
 impl DnsRecordType {
@@ -20,31 +41,125 @@ impl DnsRecordType {
             1 => Some(DnsRecordType::A),
             2 => Some(DnsRecordType::NS),
             5 => Some(DnsRecordType::CNAME),
+            6 => Some(DnsRecordType::SOA),
             12 => Some(DnsRecordType::PTR),
+            13 => Some(DnsRecordType::HINFO),
             15 => Some(DnsRecordType::MX),
+            16 => Some(DnsRecordType::TXT),
             28 => Some(DnsRecordType::AAAA),
+            33 => Some(DnsRecordType::SRV),
+            35 => Some(DnsRecordType::NAPTR),
+            41 => Some(DnsRecordType::OPT),
+            250 => Some(DnsRecordType::TSIG),
+            252 => Some(DnsRecordType::AXFR),
+            255 => Some(DnsRecordType::ANY),
+            257 => Some(DnsRecordType::CAA),
             _ => None,
         }
     }
+
+    // Inverse de l'affichage ("A", "TXT"...), insensible à la casse
+    pub fn from_name(name: &str) -> Option<Self> {
+        [1, 2, 5, 6, 12, 13, 15, 16, 28, 33, 35, 41, 250, 252, 255, 257]
+            .into_iter()
+            .filter_map(Self::from_u16)
+            .find(|rtype| rtype.to_string().eq_ignore_ascii_case(name))
+    }
+}
+
+impl fmt::Display for DnsRecordType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+// Nom de recherche inverse : 1.2.3.4 -> "4.3.2.1.in-addr.arpa"
+pub fn ipv4_to_arpa(addr: Ipv4Addr) -> String {
+    let [a, b, c, d] = addr.octets();
+    format!("{}.{}.{}.{}.in-addr.arpa", d, c, b, a)
 }
 
 // Classes DNS
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum DnsClass {
-    IN = 1,  // Internet
+    IN = 1,     // Internet
+    CH = 3,     // Chaos : identification du serveur (version.bind...)
+    ANY = 255,  // Toutes les classes (utilisée par TSIG)
 }
 
 impl DnsClass {
     pub fn from_u16(value: u16) -> Option<Self> {
         match value {
             1 => Some(DnsClass::IN),
+            3 => Some(DnsClass::CH),
+            255 => Some(DnsClass::ANY),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DnsClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+// Codes de réponse DNS (RCODE, RFC 1035 §4.1.1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DnsRcode {
+    NOERROR = 0,    // Pas d'erreur
+    FORMERR = 1,    // Requête mal formée
+    SERVFAIL = 2,   // Échec du serveur
+    NXDOMAIN = 3,   // Domaine inexistant
+    NOTIMP = 4,     // Non implémenté
+    REFUSED = 5,    // Refusé
+}
+
+impl DnsRcode {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(DnsRcode::NOERROR),
+            1 => Some(DnsRcode::FORMERR),
+            2 => Some(DnsRcode::SERVFAIL),
+            3 => Some(DnsRcode::NXDOMAIN),
+            4 => Some(DnsRcode::NOTIMP),
+            5 => Some(DnsRcode::REFUSED),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DnsRcode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+// Codes d'opération DNS (OPCODE, RFC 1035 §4.1.1, RFC 1996, RFC 2136)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DnsOpcode {
+    QUERY = 0,      // Requête standard
+    IQUERY = 1,     // Requête inverse (obsolète)
+    STATUS = 2,     // État du serveur
+    NOTIFY = 4,     // Notification de changement de zone (RFC 1996)
+    UPDATE = 5,     // Mise à jour dynamique (RFC 2136)
+}
+
+impl DnsOpcode {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(DnsOpcode::QUERY),
+            1 => Some(DnsOpcode::IQUERY),
+            2 => Some(DnsOpcode::STATUS),
+            4 => Some(DnsOpcode::NOTIFY),
+            5 => Some(DnsOpcode::UPDATE),
             _ => None,
         }
     }
 }
 
 // En-tête DNS (12 octets selon RFC 1035)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct DnsHeader {
     pub id: u16,            // Identifiant de transaction
     pub qr: bool,           // Query (0) ou Response (1)
@@ -61,6 +176,12 @@ pub struct DnsHeader {
     pub arcount: u16,       // Nombre d'enregistrements additionnels
 }
 
+impl Default for DnsHeader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl DnsHeader {
     pub fn new() -> Self {
         DnsHeader {
@@ -108,6 +229,60 @@ impl DnsHeader {
         bytes
     }
 
+    // Nom du code de réponse (RCODE)
+    pub fn rcode_name(&self) -> &'static str {
+        match self.rcode {
+            0 => "NOERROR",
+            1 => "FORMERR",
+            2 => "SERVFAIL",
+            3 => "NXDOMAIN",
+            4 => "NOTIMP",
+            5 => "REFUSED",
+            _ => "UNKNOWN",
+        }
+    }
+
+    // Nom du code d'opération (OPCODE)
+    pub fn opcode_name(&self) -> &'static str {
+        match self.opcode {
+            0 => "QUERY",
+            1 => "IQUERY",
+            2 => "STATUS",
+            4 => "NOTIFY",
+            5 => "UPDATE",
+            _ => "UNKNOWN",
+        }
+    }
+
+    // Vérifier les invariants de l'en-tête (RFC 1035 §4.1.1), toutes les violations sont rapportées
+    pub fn validate(&self) -> Result<(), Vec<DnsValidationError>> {
+        let mut errors = Vec::new();
+
+        if self.z != 0 {
+            errors.push(DnsValidationError::ReservedBitsSet(self.z));
+        }
+        if self.opcode > 5 {
+            errors.push(DnsValidationError::InvalidOpcode(self.opcode));
+        }
+        if !self.qr {
+            // Ces champs n'ont de sens que dans une réponse (un NOTIFY porte AA, RFC 1996 §3.7)
+            if self.aa && self.opcode != DnsOpcode::NOTIFY as u8 {
+                errors.push(DnsValidationError::AuthoritativeQuery);
+            }
+            if self.ra {
+                errors.push(DnsValidationError::RecursionAvailableInQuery);
+            }
+            if self.rcode != 0 {
+                errors.push(DnsValidationError::RcodeInQuery(self.rcode));
+            }
+            if self.qdcount == 0 {
+                errors.push(DnsValidationError::NoQuestion);
+            }
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
     // Désérialiser l'en-tête DNS depuis bytes
     pub fn from_bytes(data: &[u8]) -> IoResult<Self> {
         if data.len() < 12 {
@@ -141,8 +316,57 @@ impl DnsHeader {
     }
 }
 
+// Violations détectées par `DnsHeader::validate`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DnsValidationError {
+    ReservedBitsSet(u8),            // Le champ Z doit valoir 0
+    InvalidOpcode(u8),              // Opcode hors de 0-5
+    AuthoritativeQuery,             // AA positionné dans une requête
+    RecursionAvailableInQuery,      // RA positionné dans une requête
+    RcodeInQuery(u8),               // Code de réponse non nul dans une requête
+    NoQuestion,                     // Requête sans question
+}
+
+impl fmt::Display for DnsValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DnsValidationError::ReservedBitsSet(z) => write!(f, "bits réservés non nuls (z = {})", z),
+            DnsValidationError::InvalidOpcode(opcode) => write!(f, "opcode invalide ({})", opcode),
+            DnsValidationError::AuthoritativeQuery => write!(f, "bit AA positionné dans une requête"),
+            DnsValidationError::RecursionAvailableInQuery => write!(f, "bit RA positionné dans une requête"),
+            DnsValidationError::RcodeInQuery(rcode) => write!(f, "rcode {} dans une requête", rcode),
+            DnsValidationError::NoQuestion => write!(f, "requête sans question"),
+        }
+    }
+}
+
+// Affichage façon dig :
+// ;; ->>HEADER<<- opcode: QUERY, status: NOERROR, id: 1234
+// ;; flags: qr rd ra; QUERY: 1, ANSWER: 1, AUTHORITY: 0, ADDITIONAL: 0
+impl fmt::Display for DnsHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, ";; ->>HEADER<<- opcode: {}, status: {}, id: {}",
+                 self.opcode_name(), self.rcode_name(), self.id)?;
+
+        let flags: Vec<&str> = [
+            (self.qr, "qr"),
+            (self.aa, "aa"),
+            (self.tc, "tc"),
+            (self.rd, "rd"),
+            (self.ra, "ra"),
+        ].iter().filter(|(set, _)| *set).map(|(_, name)| *name).collect();
+
+        write!(f, ";; flags: {}; QUERY: {}, ANSWER: {}, AUTHORITY: {}, ADDITIONAL: {}",
+               flags.join(" "), self.qdcount, self.ancount, self.nscount, self.arcount)
+    }
+}
+
+// Longueurs maximales d'un label et d'un nom encodé (RFC 1035 §2.3.4)
+pub const MAX_LABEL_LENGTH: usize = 63;
+pub const MAX_NAME_LENGTH: usize = 255;
+
 // Question DNS
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct DnsQuestion {
     pub name: String,           // Nom de domaine (ex: "google.com")
     pub qtype: DnsRecordType,   // Type de requête
@@ -158,17 +382,35 @@ impl DnsQuestion {
         }
     }
 
+    // Vérifier qu'un nom est encodable : labels de 1 à 63 octets, 255 octets au total
+    // Un point final (nom absolu) est accepté
+    pub fn validate_name(name: &str) -> IoResult<()> {
+        let name = name.strip_suffix('.').unwrap_or(name);
+        if name.len() > MAX_NAME_LENGTH - 2 {
+            return Err(Error::new(ErrorKind::InvalidInput, "Nom de domaine trop long"));
+        }
+        if name.is_empty() {
+            return Ok(());
+        }
+        for label in name.split('.') {
+            if label.is_empty() || label.len() > MAX_LABEL_LENGTH {
+                return Err(Error::new(ErrorKind::InvalidInput, format!("Label invalide: '{}'", label)));
+            }
+        }
+        Ok(())
+    }
+
     // Encoder un nom de domaine au format DNS
     // "google.com" devient [6]google[3]com[0]
+    // Les labels vides sont ignorés et les labels trop longs tronqués à 63 octets :
+    // utiliser `validate_name` pour refuser ces noms en amont
     pub fn encode_name(name: &str) -> Vec<u8> {
         let mut encoded = Vec::new();
 
-        for part in name.split('.') {
-            if part.len() > 63 {
-                panic!("Label trop long: {}", part);
-            }
-            encoded.push(part.len() as u8);
-            encoded.extend_from_slice(part.as_bytes());
+        for part in name.split('.').filter(|part| !part.is_empty()) {
+            let bytes = &part.as_bytes()[..part.len().min(MAX_LABEL_LENGTH)];
+            encoded.push(bytes.len() as u8);
+            encoded.extend_from_slice(bytes);
         }
         encoded.push(0); // Terminateur
 
@@ -178,8 +420,43 @@ impl DnsQuestion {
     // Décoder un nom de domaine depuis le format DNS
     pub fn decode_name(data: &[u8], offset: &mut usize) -> IoResult<String> {
         let mut name_parts = Vec::new();
+        Self::walk_name(data, offset, |label| name_parts.push(String::from_utf8_lossy(label)))?;
+        Ok(name_parts.join("."))
+    }
+
+    // Comme `decode_name`, mais un label qui n'est pas de l'UTF-8 valide est une erreur
+    // au lieu d'être remplacé par U+FFFD (qui pourrait masquer un homographe IDN)
+    pub fn decode_name_strict(data: &[u8], offset: &mut usize) -> IoResult<String> {
+        let mut labels = Vec::new();
+        Self::walk_name(data, offset, |label| labels.push(label))?;
+
+        let name_parts = labels.into_iter()
+            .map(|label| std::str::from_utf8(label)
+                .map_err(|e| Error::new(ErrorKind::InvalidData, format!("Label UTF-8 invalide: {}", e))))
+            .collect::<IoResult<Vec<&str>>>()?;
+        Ok(name_parts.join("."))
+    }
+
+    // Décodage strict puis conversion en forme ASCII (Punycode, "xn--...") : les labels
+    // internationalisés deviennent comparables octet par octet
+    pub fn decode_name_punycode(data: &[u8], offset: &mut usize) -> IoResult<String> {
+        let name = Self::decode_name_strict(data, offset)?;
+        if name.is_empty() {
+            return Ok(name);
+        }
+        idna::domain_to_ascii(&name)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, format!("Nom IDN invalide '{}': {}", name, e)))
+    }
+
+    // Parcourir un nom encodé en appelant `on_label` pour chaque label, sans allouer
+    // `offset` est placé après le nom (après le premier pointeur de compression)
+    // Les données peuvent venir d'un client malveillant : chaque pointeur de compression
+    // doit viser un offset antérieur au précédent, ce qui interdit les boucles
+    fn walk_name<'a>(data: &'a [u8], offset: &mut usize, mut on_label: impl FnMut(&'a [u8])) -> IoResult<()> {
+        let mut name_length = 1;
         let mut jumped = false;
         let mut jump_offset = *offset;
+        let mut pointer_limit = *offset;
 
         loop {
             if *offset >= data.len() {
@@ -190,16 +467,28 @@ impl DnsQuestion {
 
             // Compression des pointeurs (RFC 1035)
             if (length & 0xC0) == 0xC0 {
+                let Some(&low) = data.get(*offset + 1) else {
+                    return Err(Error::new(ErrorKind::InvalidData, "Pointeur de compression tronqué"));
+                };
                 if !jumped {
                     jump_offset = *offset + 2;
                 }
 
-                let pointer = ((length as u16 & 0x3F) << 8) | (data[*offset + 1] as u16);
-                *offset = pointer as usize;
+                let pointer = (((length as u16 & 0x3F) << 8) | low as u16) as usize;
+                if pointer >= pointer_limit {
+                    return Err(Error::new(ErrorKind::InvalidData, "Pointeur de compression invalide"));
+                }
+                pointer_limit = pointer;
+                *offset = pointer;
                 jumped = true;
                 continue;
             }
 
+            // Types de labels 0x40 et 0x80 réservés (RFC 6891)
+            if length as usize > MAX_LABEL_LENGTH {
+                return Err(Error::new(ErrorKind::InvalidData, "Type de label non supporté"));
+            }
+
             *offset += 1;
 
             if length == 0 {
@@ -210,8 +499,12 @@ impl DnsQuestion {
                 return Err(Error::new(ErrorKind::InvalidData, "Label tronqué"));
             }
 
-            let label = String::from_utf8_lossy(&data[*offset..*offset + length as usize]);
-            name_parts.push(label.to_string());
+            name_length += length as usize + 1;
+            if name_length > MAX_NAME_LENGTH {
+                return Err(Error::new(ErrorKind::InvalidData, "Nom trop long"));
+            }
+
+            on_label(&data[*offset..*offset + length as usize]);
             *offset += length as usize;
         }
 
@@ -219,7 +512,7 @@ impl DnsQuestion {
             *offset = jump_offset;
         }
 
-        Ok(name_parts.join("."))
+        Ok(())
     }
 
     // Sérialiser la question DNS
@@ -239,14 +532,116 @@ impl DnsQuestion {
     }
 }
 
+impl fmt::Display for DnsQuestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, ";{}.\t\t{}\t{}", self.name, self.qclass, self.qtype)
+    }
+}
+
+// Données d'un enregistrement MX
+#[derive(Debug, Clone, PartialEq)]
+pub struct MxData {
+    pub priority: u16,          // Préférence (plus petit = prioritaire)
+    pub exchange: String,       // Serveur de messagerie
+}
+
+// Données d'un enregistrement SRV (RFC 2782)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SrvData {
+    pub priority: u16,          // Priorité (plus petit = prioritaire)
+    pub weight: u16,            // Poids entre serveurs de même priorité
+    pub port: u16,              // Port du service
+    pub target: String,         // Hôte fournissant le service
+}
+
+// Seul algorithme TSIG pris en charge
+pub const TSIG_ALGORITHM: &str = "hmac-sha256";
+
+// Écart d'horloge toléré par défaut entre signataire et vérificateur (secondes)
+pub const TSIG_FUDGE: u16 = 300;
+
+// Données d'un enregistrement TSIG (RFC 2845 §2.3)
+#[derive(Debug, Clone, PartialEq)]
+pub struct TsigData {
+    pub algorithm: String,      // Nom de l'algorithme, ex: "hmac-sha256"
+    pub time_signed: u64,       // Secondes depuis l'epoch, sur 48 bits
+    pub fudge: u16,             // Écart d'horloge toléré en secondes
+    pub mac: Vec<u8>,           // Code d'authentification du message
+    pub original_id: u16,       // ID du message au moment de la signature
+    pub error: u16,             // Code d'erreur étendu (0 = aucune)
+    pub other_data: Vec<u8>,    // Vide sauf erreur BADTIME
+}
+
+impl TsigData {
+    // RDATA : nom de l'algorithme non compressé, puis champs binaires
+    fn to_rdata(&self) -> Vec<u8> {
+        let mut data = DnsQuestion::encode_name(&self.algorithm);
+        data.write_u48::<BigEndian>(self.time_signed & 0xFFFF_FFFF_FFFF).unwrap();
+        data.write_u16::<BigEndian>(self.fudge).unwrap();
+        data.write_u16::<BigEndian>(self.mac.len() as u16).unwrap();
+        data.extend_from_slice(&self.mac);
+        data.write_u16::<BigEndian>(self.original_id).unwrap();
+        data.write_u16::<BigEndian>(self.error).unwrap();
+        data.write_u16::<BigEndian>(self.other_data.len() as u16).unwrap();
+        data.extend_from_slice(&self.other_data);
+        data
+    }
+
+    // Variables TSIG ajoutées au message pour le calcul du MAC (RFC 2845 §3.4.2)
+    fn digest_input(&self, message: &[u8], key_name: &str) -> Vec<u8> {
+        let mut input = message.to_vec();
+        input.extend_from_slice(&DnsQuestion::encode_name(&key_name.to_lowercase()));
+        input.write_u16::<BigEndian>(DnsClass::ANY as u16).unwrap();
+        input.write_u32::<BigEndian>(0).unwrap();
+        input.extend_from_slice(&DnsQuestion::encode_name(&self.algorithm.to_lowercase()));
+        input.write_u48::<BigEndian>(self.time_signed & 0xFFFF_FFFF_FFFF).unwrap();
+        input.write_u16::<BigEndian>(self.fudge).unwrap();
+        input.write_u16::<BigEndian>(self.error).unwrap();
+        input.write_u16::<BigEndian>(self.other_data.len() as u16).unwrap();
+        input.extend_from_slice(&self.other_data);
+        input
+    }
+}
+
+// Données d'un enregistrement NAPTR (RFC 3403)
+#[derive(Debug, Clone, PartialEq)]
+pub struct NaptrData {
+    pub order: u16,             // Ordre de traitement (plus petit d'abord)
+    pub preference: u16,        // Préférence entre règles de même ordre
+    pub flags: String,          // "u" (URI terminale), "s" (SRV), "a" (A/AAAA)...
+    pub services: String,       // Service et protocole : "E2U+sip", "SIP+D2U"
+    pub regexp: String,         // Expression de substitution, vide si `replacement` est utilisé
+    pub replacement: String,    // Prochain nom à interroger, vide pour la racine
+}
+
+// Données d'un enregistrement CAA (RFC 8659)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaaData {
+    pub flags: u8,              // Bit 7 : critique, l'AC doit comprendre le tag
+    pub tag: String,            // "issue", "issuewild" ou "iodef"
+    pub value: String,          // Domaine de l'AC, ";" pour n'en autoriser aucune
+}
+
+// Données d'un enregistrement SOA (RFC 1035 §3.3.13)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoaData {
+    pub mname: String,          // Serveur de noms primaire de la zone
+    pub rname: String,          // Adresse du responsable (admin.example.com)
+    pub serial: u32,            // Numéro de version de la zone
+    pub refresh: u32,           // Intervalle de rafraîchissement des secondaires
+    pub retry: u32,             // Délai avant nouvel essai après un échec
+    pub expire: u32,            // Durée de validité sans contact avec le primaire
+    pub minimum: u32,           // TTL des réponses négatives (RFC 2308)
+}
+
 // Réponse DNS (Resource Record)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct DnsRecord {
     pub name: String,           // Nom de domaine
     pub rtype: DnsRecordType,   // Type d'enregistrement
     pub class: DnsClass,        // Classe
     pub ttl: u32,               // Time To Live (secondes)
-    pub data: Vec<u8>,          // Données (ex: adresse IP)
+    pub data: Bytes,            // Données (ex: adresse IP), éventuellement partagées avec le paquet reçu
 }
 
 impl DnsRecord {
@@ -256,7 +651,181 @@ impl DnsRecord {
             rtype: DnsRecordType::A,
             class: DnsClass::IN,
             ttl,
-            data: ip.octets().to_vec(),
+            data: Bytes::copy_from_slice(&ip.octets()),
+        }
+    }
+
+    pub fn new_aaaa_record(name: String, addr: Ipv6Addr, ttl: u32) -> Self {
+        DnsRecord {
+            name,
+            rtype: DnsRecordType::AAAA,
+            class: DnsClass::IN,
+            ttl,
+            data: Bytes::copy_from_slice(&addr.octets()),
+        }
+    }
+
+    pub fn new_mx_record(name: String, priority: u16, exchange: String, ttl: u32) -> Self {
+        let mut data = Vec::new();
+        data.write_u16::<BigEndian>(priority).unwrap();
+        data.extend_from_slice(&DnsQuestion::encode_name(&exchange));
+
+        DnsRecord {
+            name,
+            rtype: DnsRecordType::MX,
+            class: DnsClass::IN,
+            ttl,
+            data: data.into(),
+        }
+    }
+
+    // Chaque chaîne est préfixée par sa longueur (max 255 octets)
+    // Les chaînes plus longues sont découpées automatiquement
+    pub fn new_txt_record(name: String, strings: &[&str], ttl: u32) -> Self {
+        let mut data = Vec::new();
+        for string in strings {
+            let bytes = string.as_bytes();
+            if bytes.is_empty() {
+                data.push(0);
+                continue;
+            }
+            for chunk in bytes.chunks(255) {
+                data.push(chunk.len() as u8);
+                data.extend_from_slice(chunk);
+            }
+        }
+
+        DnsRecord {
+            name,
+            rtype: DnsRecordType::TXT,
+            class: DnsClass::IN,
+            ttl,
+            data: data.into(),
+        }
+    }
+
+    // CPU et OS sous forme de chaînes de caractères DNS (au plus 255 octets chacune)
+    pub fn new_hinfo_record(name: String, cpu: &str, os: &str, ttl: u32) -> Self {
+        let mut data = Vec::new();
+        for field in [cpu, os] {
+            let bytes = &field.as_bytes()[..field.len().min(255)];
+            data.push(bytes.len() as u8);
+            data.extend_from_slice(bytes);
+        }
+
+        DnsRecord {
+            name,
+            rtype: DnsRecordType::HINFO,
+            class: DnsClass::IN,
+            ttl,
+            data: data.into(),
+        }
+    }
+
+    pub fn new_cname_record(alias: String, canonical: String, ttl: u32) -> Self {
+        DnsRecord {
+            name: alias,
+            rtype: DnsRecordType::CNAME,
+            class: DnsClass::IN,
+            ttl,
+            data: DnsQuestion::encode_name(&canonical).into(),
+        }
+    }
+
+    pub fn new_ns_record(zone: String, nameserver: String, ttl: u32) -> Self {
+        DnsRecord {
+            name: zone,
+            rtype: DnsRecordType::NS,
+            class: DnsClass::IN,
+            ttl,
+            data: DnsQuestion::encode_name(&nameserver).into(),
+        }
+    }
+
+    pub fn new_srv_record(
+        name: String,
+        priority: u16,
+        weight: u16,
+        port: u16,
+        target: String,
+        ttl: u32,
+    ) -> Self {
+        let mut data = Vec::new();
+        data.write_u16::<BigEndian>(priority).unwrap();
+        data.write_u16::<BigEndian>(weight).unwrap();
+        data.write_u16::<BigEndian>(port).unwrap();
+        data.extend_from_slice(&DnsQuestion::encode_name(&target));
+
+        DnsRecord {
+            name,
+            rtype: DnsRecordType::SRV,
+            class: DnsClass::IN,
+            ttl,
+            data: data.into(),
+        }
+    }
+
+    // Enregistrement TSIG : nom de la clé, classe ANY et TTL nul
+    pub fn new_tsig_record(key_name: &str, tsig: &TsigData) -> Self {
+        DnsRecord {
+            name: key_name.to_string(),
+            rtype: DnsRecordType::TSIG,
+            class: DnsClass::ANY,
+            ttl: 0,
+            data: tsig.to_rdata().into(),
+        }
+    }
+
+    pub fn new_naptr_record(name: String, naptr: &NaptrData, ttl: u32) -> Self {
+        let mut data = Vec::new();
+        data.write_u16::<BigEndian>(naptr.order).unwrap();
+        data.write_u16::<BigEndian>(naptr.preference).unwrap();
+        // Chaînes de caractères DNS : un octet de longueur puis au plus 255 octets
+        for text in [&naptr.flags, &naptr.services, &naptr.regexp] {
+            let bytes = &text.as_bytes()[..text.len().min(255)];
+            data.push(bytes.len() as u8);
+            data.extend_from_slice(bytes);
+        }
+        data.extend_from_slice(&DnsQuestion::encode_name(&naptr.replacement));
+
+        DnsRecord {
+            name,
+            rtype: DnsRecordType::NAPTR,
+            class: DnsClass::IN,
+            ttl,
+            data: data.into(),
+        }
+    }
+
+    pub fn new_caa_record(name: String, caa: &CaaData, ttl: u32) -> Self {
+        // La longueur du tag tient sur un octet
+        let tag = &caa.tag.as_bytes()[..caa.tag.len().min(255)];
+        let mut data = vec![caa.flags, tag.len() as u8];
+        data.extend_from_slice(tag);
+        data.extend_from_slice(caa.value.as_bytes());
+
+        DnsRecord {
+            name,
+            rtype: DnsRecordType::CAA,
+            class: DnsClass::IN,
+            ttl,
+            data: data.into(),
+        }
+    }
+
+    pub fn new_soa_record(zone: String, soa: &SoaData, ttl: u32) -> Self {
+        let mut data = DnsQuestion::encode_name(&soa.mname);
+        data.extend_from_slice(&DnsQuestion::encode_name(&soa.rname));
+        for value in [soa.serial, soa.refresh, soa.retry, soa.expire, soa.minimum] {
+            data.write_u32::<BigEndian>(value).unwrap();
+        }
+
+        DnsRecord {
+            name: zone,
+            rtype: DnsRecordType::SOA,
+            class: DnsClass::IN,
+            ttl,
+            data: data.into(),
         }
     }
 
@@ -298,89 +867,464 @@ impl DnsRecord {
             None
         }
     }
-}
-
-// Message DNS complet
-#[derive(Debug, Clone)]
-pub struct DnsMessage {
-    pub header: DnsHeader,
-    pub questions: Vec<DnsQuestion>,
-    pub answers: Vec<DnsRecord>,
-    pub authorities: Vec<DnsRecord>,
-    pub additionals: Vec<DnsRecord>,
-}
 
-impl DnsMessage {
-    pub fn new() -> Self {
-        DnsMessage {
-            header: DnsHeader::new(),
-            questions: Vec::new(),
-            answers: Vec::new(),
-            authorities: Vec::new(),
-            additionals: Vec::new(),
+    // Obtenir l'adresse IPv6 si c'est un enregistrement AAAA
+    pub fn get_aaaa(&self) -> Option<Ipv6Addr> {
+        if self.rtype == DnsRecordType::AAAA && self.data.len() == 16 {
+            let mut octets = [0u8; 16];
+            octets.copy_from_slice(&self.data);
+            Some(Ipv6Addr::from(octets))
+        } else {
+            None
         }
     }
 
-    // Créer une requête DNS
-    pub fn new_query(name: String, qtype: DnsRecordType) -> Self {
-        let mut message = Self::new();
-        message.header.qdcount = 1;
-        message.questions.push(DnsQuestion::new(name, qtype));
-        message
-    }
+    // Obtenir la priorité et le serveur si c'est un enregistrement MX
+    // `message` contient le message complet pour résoudre les pointeurs de compression
+    pub fn get_mx(&self, message: &[u8]) -> Option<MxData> {
+        if self.rtype != DnsRecordType::MX || self.data.len() < 3 {
+            return None;
+        }
 
-    // Créer une réponse DNS
-    pub fn new_response(query: &DnsMessage) -> Self {
-        let mut response = query.clone();
-        response.header.qr = true;  // C'est une réponse
-        response.header.ra = true;  // Récursion disponible
-        response.answers.clear();
-        response.authorities.clear();
-        response.additionals.clear();
-        response
+        let priority = u16::from_be_bytes([self.data[0], self.data[1]]);
+        let exchange = self.decode_rdata_name(2, message)?;
+
+        Some(MxData { priority, exchange })
     }
 
-    // Sérialiser le message DNS complet
-    pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = Vec::new();
+    // Obtenir le nom canonique si c'est un enregistrement CNAME
+    pub fn get_cname(&self, full_message: &[u8]) -> Option<String> {
+        if self.rtype != DnsRecordType::CNAME {
+            return None;
+        }
+        self.decode_rdata_name(0, full_message)
+    }
 
-        // Header
-        bytes.extend_from_slice(&self.header.to_bytes());
+    // Obtenir le serveur de noms si c'est un enregistrement NS
+    pub fn get_ns(&self, full_message: &[u8]) -> Option<String> {
+        if self.rtype != DnsRecordType::NS {
+            return None;
+        }
+        self.decode_rdata_name(0, full_message)
+    }
 
-        // Questions
-        for question in &self.questions {
-            bytes.extend_from_slice(&question.to_bytes());
+    // Obtenir le nom pointé si c'est un enregistrement PTR
+    pub fn get_ptr(&self, full_message: &[u8]) -> Option<String> {
+        if self.rtype != DnsRecordType::PTR {
+            return None;
         }
+        self.decode_rdata_name(0, full_message)
+    }
 
-        // Answers
-        for answer in &self.answers {
-            bytes.extend_from_slice(&answer.to_bytes());
+    // Obtenir les données de service si c'est un enregistrement SRV
+    // `full_message` contient le message complet pour résoudre les pointeurs de compression
+    pub fn get_srv(&self, full_message: &[u8]) -> Option<SrvData> {
+        if self.rtype != DnsRecordType::SRV || self.data.len() < 7 {
+            return None;
         }
 
-        // Authority records (pas implémenté pour la simplicité)
-        // Additional records (pas implémenté pour la simplicité)
+        Some(SrvData {
+            priority: u16::from_be_bytes([self.data[0], self.data[1]]),
+            weight: u16::from_be_bytes([self.data[2], self.data[3]]),
+            port: u16::from_be_bytes([self.data[4], self.data[5]]),
+            target: self.decode_rdata_name(6, full_message)?,
+        })
+    }
 
-        bytes
+    // Obtenir les données de zone si c'est un enregistrement SOA
+    // `full_message` contient le message complet pour résoudre les pointeurs de compression
+    pub fn get_soa(&self, full_message: &[u8]) -> Option<SoaData> {
+        if self.rtype != DnsRecordType::SOA {
+            return None;
+        }
+
+        let mut buffer = full_message.to_vec();
+        let mut offset = buffer.len();
+        buffer.extend_from_slice(&self.data);
+
+        let mname = DnsQuestion::decode_name(&buffer, &mut offset).ok()?;
+        let rname = DnsQuestion::decode_name(&buffer, &mut offset).ok()?;
+
+        let mut cursor = Cursor::new(buffer.get(offset..)?);
+        Some(SoaData {
+            mname,
+            rname,
+            serial: cursor.read_u32::<BigEndian>().ok()?,
+            refresh: cursor.read_u32::<BigEndian>().ok()?,
+            retry: cursor.read_u32::<BigEndian>().ok()?,
+            expire: cursor.read_u32::<BigEndian>().ok()?,
+            minimum: cursor.read_u32::<BigEndian>().ok()?,
+        })
     }
 
-    // Désérialiser un message DNS depuis bytes
-    pub fn from_bytes(data: &[u8]) -> IoResult<Self> {
-        if data.len() < 12 {
-            return Err(Error::new(ErrorKind::InvalidData, "Message trop court"));
+    // Décoder la RDATA TSIG (None si ce n'est pas un enregistrement TSIG)
+    // Le nom de l'algorithme n'est jamais compressé (RFC 2845 §2.3)
+    pub fn from_tsig(&self) -> Option<TsigData> {
+        if self.rtype != DnsRecordType::TSIG {
+            return None;
         }
 
-        let header = DnsHeader::from_bytes(data)?;
-        let mut offset = 12;
-        let mut questions = Vec::new();
-        let mut answers = Vec::new();
+        let mut offset = 0;
+        let algorithm = DnsQuestion::decode_name(&self.data, &mut offset).ok()?;
+        let mut cursor = Cursor::new(self.data.get(offset..)?);
+        let time_signed = cursor.read_u48::<BigEndian>().ok()?;
+        let fudge = cursor.read_u16::<BigEndian>().ok()?;
+        let mac_size = cursor.read_u16::<BigEndian>().ok()? as usize;
+        let mut mac = vec![0; mac_size];
+        std::io::Read::read_exact(&mut cursor, &mut mac).ok()?;
+        let original_id = cursor.read_u16::<BigEndian>().ok()?;
+        let error = cursor.read_u16::<BigEndian>().ok()?;
+        let other_len = cursor.read_u16::<BigEndian>().ok()? as usize;
+        let mut other_data = vec![0; other_len];
+        std::io::Read::read_exact(&mut cursor, &mut other_data).ok()?;
+
+        Some(TsigData { algorithm, time_signed, fudge, mac, original_id, error, other_data })
+    }
 
-        // Parser les questions
+    // Obtenir la règle de réécriture si c'est un enregistrement NAPTR
+    // `full_message` contient le message complet pour résoudre les pointeurs de compression
+    pub fn get_naptr(&self, full_message: &[u8]) -> Option<NaptrData> {
+        if self.rtype != DnsRecordType::NAPTR || self.data.len() < 4 {
+            return None;
+        }
+
+        let mut offset = 4;
+        let mut texts = Vec::with_capacity(3);
+        for _ in 0..3 {
+            let length = *self.data.get(offset)? as usize;
+            let text = self.data.get(offset + 1..offset + 1 + length)?;
+            texts.push(String::from_utf8_lossy(text).to_string());
+            offset += 1 + length;
+        }
+        let [flags, services, regexp] = <[String; 3]>::try_from(texts).ok()?;
+
+        Some(NaptrData {
+            order: u16::from_be_bytes([self.data[0], self.data[1]]),
+            preference: u16::from_be_bytes([self.data[2], self.data[3]]),
+            flags,
+            services,
+            regexp,
+            replacement: self.decode_rdata_name(offset, full_message)?,
+        })
+    }
+
+    // Obtenir la restriction d'AC si c'est un enregistrement CAA
+    pub fn get_caa(&self) -> Option<CaaData> {
+        if self.rtype != DnsRecordType::CAA || self.data.len() < 2 {
+            return None;
+        }
+
+        let tag_end = 2 + self.data[1] as usize;
+        let tag = self.data.get(2..tag_end)?;
+        Some(CaaData {
+            flags: self.data[0],
+            tag: String::from_utf8_lossy(tag).to_string(),
+            value: String::from_utf8_lossy(&self.data[tag_end..]).to_string(),
+        })
+    }
+
+    // Obtenir (CPU, OS) si c'est un enregistrement HINFO
+    pub fn get_hinfo(&self) -> Option<(String, String)> {
+        if self.rtype != DnsRecordType::HINFO {
+            return None;
+        }
+
+        let cpu_len = *self.data.first()? as usize;
+        let cpu = self.data.get(1..1 + cpu_len)?;
+        let os_len = *self.data.get(1 + cpu_len)? as usize;
+        let os = self.data.get(2 + cpu_len..2 + cpu_len + os_len)?;
+        Some((String::from_utf8_lossy(cpu).to_string(), String::from_utf8_lossy(os).to_string()))
+    }
+
+    // Obtenir les chaînes de caractères si c'est un enregistrement TXT
+    pub fn get_txt(&self) -> Option<Vec<String>> {
+        if self.rtype != DnsRecordType::TXT {
+            return None;
+        }
+
+        let mut strings = Vec::new();
+        let mut offset = 0;
+
+        while offset < self.data.len() {
+            let length = self.data[offset] as usize;
+            offset += 1;
+
+            if offset + length > self.data.len() {
+                return None;
+            }
+
+            strings.push(String::from_utf8_lossy(&self.data[offset..offset + length]).to_string());
+            offset += length;
+        }
+
+        Some(strings)
+    }
+
+    // Représentation textuelle de RDATA (format générique RFC 3597 si inconnu)
+    // `full_message` contient le message complet pour résoudre les pointeurs de compression
+    pub fn rdata_to_string(&self, full_message: &[u8]) -> String {
+        if let Some(ip) = self.get_ip() {
+            return ip.to_string();
+        }
+        if let Some(ip) = self.get_aaaa() {
+            return ip.to_string();
+        }
+        if let Some(mx) = self.get_mx(full_message) {
+            return format!("{} {}.", mx.priority, mx.exchange);
+        }
+        if let Some(canonical) = self.get_cname(full_message) {
+            return format!("{}.", canonical);
+        }
+        if let Some(target) = self.get_ptr(full_message) {
+            return format!("{}.", target);
+        }
+        if let Some(nameserver) = self.get_ns(full_message) {
+            return format!("{}.", nameserver);
+        }
+        if let Some(srv) = self.get_srv(full_message) {
+            return format!("{} {} {} {}.", srv.priority, srv.weight, srv.port, srv.target);
+        }
+        if let Some(soa) = self.get_soa(full_message) {
+            return format!("{}. {}. {} {} {} {} {}", soa.mname, soa.rname,
+                           soa.serial, soa.refresh, soa.retry, soa.expire, soa.minimum);
+        }
+        if let Some(tsig) = self.from_tsig() {
+            let mac: String = tsig.mac.iter().map(|byte| format!("{:02x}", byte)).collect();
+            return format!("{}. {} {} {} {} {} {} {}", tsig.algorithm, tsig.time_signed, tsig.fudge,
+                           tsig.mac.len(), mac, tsig.original_id, tsig.error, tsig.other_data.len());
+        }
+        if let Some(naptr) = self.get_naptr(full_message) {
+            return format!("{} {} \"{}\" \"{}\" \"{}\" {}.", naptr.order, naptr.preference,
+                           naptr.flags, naptr.services, naptr.regexp, naptr.replacement);
+        }
+        if let Some(caa) = self.get_caa() {
+            return format!("{} {} \"{}\"", caa.flags, caa.tag, caa.value);
+        }
+        if let Some(strings) = self.get_txt() {
+            let quoted: Vec<String> = strings.iter().map(|s| format!("\"{}\"", s)).collect();
+            return quoted.join(" ");
+        }
+
+        let hex: String = self.data.iter().map(|byte| format!("{:02x}", byte)).collect();
+        format!("\\# {} {}", self.data.len(), hex)
+    }
+
+    // Décoder un nom contenu dans RDATA à partir de `start`
+    // Les pointeurs de compression visent le message d'origine : on concatène
+    // le message et RDATA pour que leurs offsets restent valides
+    fn decode_rdata_name(&self, start: usize, message: &[u8]) -> Option<String> {
+        let mut buffer = message.to_vec();
+        let mut offset = buffer.len() + start;
+        buffer.extend_from_slice(&self.data);
+
+        DnsQuestion::decode_name(&buffer, &mut offset).ok()
+    }
+}
+
+impl DnsRecord {
+    // Ligne façon dig, en suivant les pointeurs de compression de `full_message`
+    pub fn to_dig_string(&self, full_message: &[u8]) -> String {
+        format!("{}.\t{}\t{}\t{}\t{}",
+                self.name, self.ttl, self.class, self.rtype, self.rdata_to_string(full_message))
+    }
+}
+
+// Sans le message d'origine : pour les enregistrements construits localement, sans compression
+impl fmt::Display for DnsRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_dig_string(&[]))
+    }
+}
+
+// Taille maximale d'un message UDP sans EDNS0 (RFC 1035)
+pub const UDP_MAX_SIZE: usize = 512;
+
+// Taille du tampon UDP annoncée dans les réponses EDNS0
+pub const EDNS_BUFFER_SIZE: u16 = 4096;
+
+// Message DNS complet
+#[derive(Debug, Clone, PartialEq)]
+pub struct DnsMessage {
+    pub header: DnsHeader,
+    pub questions: Vec<DnsQuestion>,
+    pub answers: Vec<DnsRecord>,
+    pub authorities: Vec<DnsRecord>,
+    pub additionals: Vec<DnsRecord>,
+    // Taille de tampon UDP annoncée par l'enregistrement OPT (EDNS0)
+    // L'OPT n'apparaît pas dans `additionals` mais est compté dans ARCOUNT
+    pub edns_payload_size: Option<u16>,
+}
+
+impl Default for DnsMessage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DnsMessage {
+    pub fn new() -> Self {
+        DnsMessage {
+            header: DnsHeader::new(),
+            questions: Vec::new(),
+            answers: Vec::new(),
+            authorities: Vec::new(),
+            additionals: Vec::new(),
+            edns_payload_size: None,
+        }
+    }
+
+    // Créer une requête DNS
+    pub fn new_query(name: String, qtype: DnsRecordType) -> Self {
+        DnsMessageBuilder::new()
+            .query(name, qtype)
+            .build()
+    }
+
+    // Créer une réponse DNS
+    pub fn new_response(query: &DnsMessage) -> Self {
+        let mut response = query.clone();
+        response.header.qr = true;  // C'est une réponse
+        response.header.ra = true;  // Récursion disponible
+        response.answers.clear();
+        response.authorities.clear();
+        response.additionals.clear();
+        response.header.ancount = 0;
+        response.header.nscount = 0;
+        response.header.arcount = 0;
+
+        // Répondre en EDNS0 uniquement si le client l'utilise
+        if query.edns_payload_size.is_some() {
+            response.edns_payload_size = Some(EDNS_BUFFER_SIZE);
+            response.header.arcount = 1;
+        }
+        response
+    }
+
+    // Créer une réponse d'erreur minimale : même ID et questions, aucun enregistrement
+    pub fn new_error_response(query: &DnsMessage, rcode: DnsRcode) -> Self {
+        let mut response = DnsMessage::new_response(query);
+        response.header.rcode = rcode as u8;
+        response.header.aa = false;
+        response.header.tc = false;
+        response.header.z = 0;
+        response
+    }
+
+    // Réponse AXFR (RFC 5936) : le SOA, chaque enregistrement de la zone, puis le SOA à
+    // nouveau, un enregistrement par message. L'appelant recopie l'ID et la question
+    pub fn new_axfr_response(records: &[DnsRecord], soa: &DnsRecord) -> Vec<DnsMessage> {
+        std::iter::once(soa)
+            .chain(records)
+            .chain(std::iter::once(soa))
+            .map(|record| {
+                let mut message = DnsMessageBuilder::new().answer(record.clone()).build();
+                message.header.qr = true;
+                message.header.aa = true;
+                message.header.rd = false;
+                message
+            })
+            .collect()
+    }
+
+    // Taille maximale de réponse UDP acceptée par l'émetteur de ce message
+    pub fn max_udp_size(&self) -> usize {
+        match self.edns_payload_size {
+            Some(size) => (size as usize).max(UDP_MAX_SIZE),
+            None => UDP_MAX_SIZE,
+        }
+    }
+
+    // Réduire le message à `max_bytes` octets une fois sérialisé : positionne TC et
+    // retire les réponses en partant de la fin, le client devant réessayer en TCP.
+    // Les sections d'autorité et additionnelle sont abandonnées si cela ne suffit pas.
+    pub fn truncate(&mut self, max_bytes: usize) {
+        let mut size = self.to_bytes().len();
+        if size <= max_bytes {
+            return;
+        }
+
+        self.header.tc = true;
+        while size > max_bytes {
+            match self.answers.pop() {
+                Some(record) => size -= record.to_bytes().len(),
+                None => break,
+            }
+        }
+        if size > max_bytes {
+            self.authorities.clear();
+            self.additionals.clear();
+        }
+
+        self.header.ancount = self.answers.len() as u16;
+        self.header.nscount = self.authorities.len() as u16;
+        self.header.arcount = self.additionals.len() as u16 + self.edns_payload_size.is_some() as u16;
+    }
+
+    // Sérialiser le message DNS complet
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        // Header
+        bytes.extend_from_slice(&self.header.to_bytes());
+
+        // Questions
+        for question in &self.questions {
+            bytes.extend_from_slice(&question.to_bytes());
+        }
+
+        // Answers, Authority records, Additional records
+        // Un TSIG doit rester le dernier enregistrement du message, après l'OPT
+        let (tsig, additionals): (Vec<&DnsRecord>, Vec<&DnsRecord>) = self.additionals.iter()
+            .partition(|record| record.rtype == DnsRecordType::TSIG);
+        for record in self.answers.iter().chain(&self.authorities).chain(additionals) {
+            bytes.extend_from_slice(&record.to_bytes());
+        }
+
+        // Pseudo-enregistrement OPT : nom racine, CLASS = taille du tampon UDP,
+        // TTL = RCODE étendu, version et flags (tous à 0), pas d'options
+        if let Some(payload_size) = self.edns_payload_size {
+            bytes.push(0);
+            bytes.write_u16::<BigEndian>(DnsRecordType::OPT as u16).unwrap();
+            bytes.write_u16::<BigEndian>(payload_size).unwrap();
+            bytes.write_u32::<BigEndian>(0).unwrap();
+            bytes.write_u16::<BigEndian>(0).unwrap();
+        }
+
+        for record in tsig {
+            bytes.extend_from_slice(&record.to_bytes());
+        }
+
+        bytes
+    }
+
+    // Désérialiser un message DNS depuis bytes
+    // Le paquet est copié une seule fois : les RDATA en sont des vues partagées
+    pub fn from_bytes(data: &[u8]) -> IoResult<Self> {
+        Self::from_shared_bytes(Bytes::copy_from_slice(data))
+    }
+
+    // Sans aucune copie de RDATA : le message garde `packet` en vie (compteur de références)
+    pub fn from_shared_bytes(packet: Bytes) -> IoResult<Self> {
+        Ok(Self::from_bytes_zero_copy(&packet)?.into_shared(&packet))
+    }
+
+    // Désérialiser sans copier les noms ni les données : le résultat emprunte `data`
+    pub fn from_bytes_zero_copy(data: &[u8]) -> IoResult<DnsMessageRef<'_>> {
+        if data.len() < 12 {
+            return Err(Error::new(ErrorKind::InvalidData, "Message trop court"));
+        }
+
+        let header = DnsHeader::from_bytes(data)?;
+        let mut offset = 12;
+        let mut questions = Vec::new();
+
+        // Parser les questions
         for _ in 0..header.qdcount {
             if offset >= data.len() {
                 return Err(Error::new(ErrorKind::InvalidData, "Question tronquée"));
             }
 
-            let name = DnsQuestion::decode_name(data, &mut offset)?;
+            let name = DnsNameRef::parse(data, &mut offset)?;
 
             if offset + 4 > data.len() {
                 return Err(Error::new(ErrorKind::InvalidData, "Question incomplète"));
@@ -396,42 +1340,73 @@ impl DnsMessage {
             let qclass = DnsClass::from_u16(qclass_num)
                 .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Classe de question invalide"))?;
 
-            questions.push(DnsQuestion {
+            questions.push(DnsQuestionRef {
                 name,
                 qtype,
                 qclass,
             });
         }
 
-        // Parser les réponses (version simplifiée)
-        for _ in 0..header.ancount {
-            if offset >= data.len() {
+        // Parser les sections de réponses, d'autorité et additionnelle
+        let mut edns_payload_size = None;
+        let answers = Self::parse_records(data, &mut offset, header.ancount, &mut edns_payload_size)?;
+        let authorities = Self::parse_records(data, &mut offset, header.nscount, &mut edns_payload_size)?;
+        let additionals = Self::parse_records(data, &mut offset, header.arcount, &mut edns_payload_size)?;
+
+        Ok(DnsMessageRef {
+            header,
+            questions,
+            answers,
+            authorities,
+            additionals,
+            edns_payload_size,
+        })
+    }
+
+    // Parser `count` enregistrements à partir de `offset` (version simplifiée)
+    // Les enregistrements de type ou classe inconnus sont ignorés
+    // Un enregistrement OPT est extrait dans `edns_payload_size` (sa CLASS est la taille du tampon)
+    fn parse_records<'a>(
+        data: &'a [u8],
+        offset: &mut usize,
+        count: u16,
+        edns_payload_size: &mut Option<u16>,
+    ) -> IoResult<Vec<DnsRecordRef<'a>>> {
+        let mut records = Vec::new();
+
+        for _ in 0..count {
+            if *offset >= data.len() {
                 break;
             }
 
-            let name = DnsQuestion::decode_name(data, &mut offset)?;
+            let name = DnsNameRef::parse(data, offset)?;
 
-            if offset + 10 > data.len() {
+            if *offset + 10 > data.len() {
                 break;
             }
 
-            let mut cursor = Cursor::new(&data[offset..]);
+            let mut cursor = Cursor::new(&data[*offset..]);
             let rtype_num = cursor.read_u16::<BigEndian>()?;
             let class_num = cursor.read_u16::<BigEndian>()?;
             let ttl = cursor.read_u32::<BigEndian>()?;
             let rdlength = cursor.read_u16::<BigEndian>()?;
-            offset += 10;
+            *offset += 10;
 
-            if offset + rdlength as usize > data.len() {
+            if *offset + rdlength as usize > data.len() {
                 break;
             }
 
-            let rdata = data[offset..offset + rdlength as usize].to_vec();
-            offset += rdlength as usize;
+            let rdata = &data[*offset..*offset + rdlength as usize];
+            *offset += rdlength as usize;
+
+            if rtype_num == DnsRecordType::OPT as u16 {
+                *edns_payload_size = Some(class_num);
+                continue;
+            }
 
             if let (Some(rtype), Some(class)) =
                 (DnsRecordType::from_u16(rtype_num), DnsClass::from_u16(class_num)) {
-                answers.push(DnsRecord {
+                records.push(DnsRecordRef {
                     name,
                     rtype,
                     class,
@@ -441,47 +1416,1950 @@ impl DnsMessage {
             }
         }
 
-        Ok(DnsMessage {
-            header,
-            questions,
-            answers,
-            authorities: Vec::new(),
-            additionals: Vec::new(),
-        })
+        Ok(records)
     }
 }
 
-// Base de données DNS simple pour le serveur
+// Nom de domaine emprunté au paquet d'origine, décodé à la demande
+// Le nom a été validé au parsing : le parcours des labels ne peut pas échouer
+#[derive(Debug, Clone, Copy)]
+pub struct DnsNameRef<'a> {
+    packet: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> DnsNameRef<'a> {
+    fn parse(packet: &'a [u8], offset: &mut usize) -> IoResult<Self> {
+        let name = DnsNameRef { packet, offset: *offset };
+        DnsQuestion::walk_name(packet, offset, |_| {})?;
+        Ok(name)
+    }
+
+    // Labels bruts du nom, pointeurs de compression suivis
+    pub fn labels(&self) -> DnsLabels<'a> {
+        DnsLabels { packet: self.packet, offset: Some(self.offset) }
+    }
+}
+
+// Même représentation que `DnsQuestion::decode_name` : labels séparés par des points
+impl fmt::Display for DnsNameRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, label) in self.labels().enumerate() {
+            if i > 0 {
+                f.write_str(".")?;
+            }
+            write!(f, "{}", String::from_utf8_lossy(label))?;
+        }
+        Ok(())
+    }
+}
+
+// Itérateur sur les labels d'un `DnsNameRef`
+pub struct DnsLabels<'a> {
+    packet: &'a [u8],
+    offset: Option<usize>,
+}
+
+impl<'a> Iterator for DnsLabels<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<&'a [u8]> {
+        loop {
+            let offset = self.offset?;
+            let length = *self.packet.get(offset)? as usize;
+
+            if length & 0xC0 == 0xC0 {
+                let low = *self.packet.get(offset + 1)? as usize;
+                self.offset = Some(((length & 0x3F) << 8) | low);
+                continue;
+            }
+            if length == 0 {
+                self.offset = None;
+                return None;
+            }
+
+            self.offset = Some(offset + 1 + length);
+            return self.packet.get(offset + 1..offset + 1 + length);
+        }
+    }
+}
+
+// Question empruntée au paquet d'origine
+#[derive(Debug, Clone, Copy)]
+pub struct DnsQuestionRef<'a> {
+    pub name: DnsNameRef<'a>,
+    pub qtype: DnsRecordType,
+    pub qclass: DnsClass,
+}
+
+impl DnsQuestionRef<'_> {
+    pub fn into_owned(self) -> DnsQuestion {
+        DnsQuestion {
+            name: self.name.to_string(),
+            qtype: self.qtype,
+            qclass: self.qclass,
+        }
+    }
+}
+
+// Enregistrement emprunté : RDATA pointe directement dans le paquet
+// Les noms compressés dans RDATA se décodent avec le paquet complet (voir `DnsRecord::get_cname`)
+#[derive(Debug, Clone, Copy)]
+pub struct DnsRecordRef<'a> {
+    pub name: DnsNameRef<'a>,
+    pub rtype: DnsRecordType,
+    pub class: DnsClass,
+    pub ttl: u32,
+    pub data: &'a [u8],
+}
+
+impl DnsRecordRef<'_> {
+    pub fn into_owned(self) -> DnsRecord {
+        self.into_record(Bytes::copy_from_slice(self.data))
+    }
+
+    // RDATA n'est pas copié : vue sur `packet`, qui doit être le tampon analysé
+    pub fn into_shared(self, packet: &Bytes) -> DnsRecord {
+        self.into_record(packet.slice_ref(self.data))
+    }
+
+    fn into_record(self, data: Bytes) -> DnsRecord {
+        DnsRecord {
+            name: self.name.to_string(),
+            rtype: self.rtype,
+            class: self.class,
+            ttl: self.ttl,
+            data,
+        }
+    }
+}
+
+// Message DNS emprunté, produit par `DnsMessage::from_bytes_zero_copy`
+// Seules les sections (Vec) sont allouées, ni les noms ni RDATA
 #[derive(Debug, Clone)]
-pub struct SimpleDnsDatabase {
-    records: HashMap<String, Ipv4Addr>,
+pub struct DnsMessageRef<'a> {
+    pub header: DnsHeader,
+    pub questions: Vec<DnsQuestionRef<'a>>,
+    pub answers: Vec<DnsRecordRef<'a>>,
+    pub authorities: Vec<DnsRecordRef<'a>>,
+    pub additionals: Vec<DnsRecordRef<'a>>,
+    pub edns_payload_size: Option<u16>,
 }
 
-impl SimpleDnsDatabase {
-    pub fn new() -> Self {
-        let mut db = SimpleDnsDatabase {
-            records: HashMap::new(),
-        };
+impl<'a> DnsMessageRef<'a> {
+    // Copier le message pour qu'il ne dépende plus du paquet
+    pub fn into_owned(self) -> DnsMessage {
+        self.map_records(DnsRecordRef::into_owned)
+    }
 
-        // Ajouter quelques enregistrements prédéfinis
-        db.add_record("localhost".to_string(), "127.0.0.1".parse().unwrap());
-        db.add_record("test.local".to_string(), "192.168.1.100".parse().unwrap());
-        db.add_record("server.local".to_string(), "192.168.1.1".parse().unwrap());
-        db.add_record("example.com".to_string(), "93.184.216.34".parse().unwrap());
-        db.add_record("google.com".to_string(), "8.8.8.8".parse().unwrap());
+    // Message indépendant de la durée de vie du paquet, RDATA partagés avec `packet`
+    // `packet` doit être le tampon passé à `DnsMessage::from_bytes_zero_copy`
+    pub fn into_shared(self, packet: &Bytes) -> DnsMessage {
+        self.map_records(|record| record.into_shared(packet))
+    }
 
-        db
+    fn map_records(self, convert: impl Fn(DnsRecordRef<'a>) -> DnsRecord) -> DnsMessage {
+        let convert_all = |records: Vec<DnsRecordRef<'a>>| records.into_iter().map(&convert).collect();
+        DnsMessage {
+            header: self.header,
+            questions: self.questions.into_iter().map(DnsQuestionRef::into_owned).collect(),
+            answers: convert_all(self.answers),
+            authorities: convert_all(self.authorities),
+            additionals: convert_all(self.additionals),
+            edns_payload_size: self.edns_payload_size,
+        }
     }
+}
 
-    pub fn add_record(&mut self, name: String, ip: Ipv4Addr) {
-        self.records.insert(name.to_lowercase(), ip);
+impl DnsMessage {
+    // Affichage façon dig d'un message reçu : `full_message` en est la forme brute,
+    // pour que les noms compressés de RDATA (CNAME, NS, MX...) soient lisibles
+    pub fn to_dig_string(&self, full_message: &[u8]) -> String {
+        let mut output = String::new();
+        // Écrire dans une String ne peut pas échouer
+        let _ = self.write_dig(&mut output, full_message);
+        output
     }
 
-    pub fn lookup(&self, name: &str) -> Option<Ipv4Addr> {
-        self.records.get(&name.to_lowercase()).copied()
+    fn write_dig(&self, f: &mut dyn fmt::Write, full_message: &[u8]) -> fmt::Result {
+        writeln!(f, "{}", self.header)?;
+
+        if !self.questions.is_empty() {
+            writeln!(f, "\n;; QUESTION SECTION:")?;
+            for question in &self.questions {
+                writeln!(f, "{}", question)?;
+            }
+        }
+
+        let sections = [
+            ("ANSWER", &self.answers),
+            ("AUTHORITY", &self.authorities),
+            ("ADDITIONAL", &self.additionals),
+        ];
+
+        for (title, records) in sections {
+            if records.is_empty() {
+                continue;
+            }
+            writeln!(f, "\n;; {} SECTION:", title)?;
+            for record in records {
+                writeln!(f, "{}", record.to_dig_string(full_message))?;
+            }
+        }
+
+        if let Some(payload_size) = self.edns_payload_size {
+            writeln!(f, "\n;; OPT PSEUDOSECTION:")?;
+            writeln!(f, "; EDNS: version: 0, flags:; udp: {}", payload_size)?;
+        }
+
+        Ok(())
     }
+}
 
-    pub fn list_records(&self) -> &HashMap<String, Ipv4Addr> {
-        &self.records
+impl fmt::Display for DnsMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_dig(f, &[])
+    }
+}
+
+// Différence entre deux messages sur un champ donné
+// Un côté vide signifie que l'élément n'existe que dans l'autre message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DnsDiff {
+    pub field: String,
+    pub left: String,
+    pub right: String,
+}
+
+impl DnsDiff {
+    fn new(field: impl Into<String>, left: impl ToString, right: impl ToString) -> Self {
+        DnsDiff {
+            field: field.into(),
+            left: left.to_string(),
+            right: right.to_string(),
+        }
+    }
+}
+
+// Authentification des messages par TSIG (RFC 2845), HMAC-SHA256 uniquement
+impl DnsMessage {
+    // Signer le message : un TSIG existant est remplacé, le nouveau est ajouté en dernier
+    pub fn sign_tsig(&mut self, key_name: &str, secret: &[u8]) -> IoResult<()> {
+        DnsQuestion::validate_name(key_name)?;
+        self.remove_tsig();
+
+        let time_signed = SystemTime::now().duration_since(UNIX_EPOCH)
+            .map_err(|e| Error::other(format!("Horloge système invalide: {}", e)))?
+            .as_secs();
+        let mut tsig = TsigData {
+            algorithm: TSIG_ALGORITHM.to_string(),
+            time_signed,
+            fudge: TSIG_FUDGE,
+            mac: Vec::new(),
+            original_id: self.header.id,
+            error: 0,
+            other_data: Vec::new(),
+        };
+        tsig.mac = Self::tsig_mac(secret, &tsig.digest_input(&self.to_bytes(), key_name));
+
+        self.additionals.push(DnsRecord::new_tsig_record(key_name, &tsig));
+        self.header.arcount += 1;
+        Ok(())
     }
-}
\ No newline at end of file
+
+    // Vérifier le TSIG final : nom de clé, algorithme, fenêtre de temps puis MAC
+    // Le message est re-sérialisé pour le calcul : la vérification suppose qu'il a été
+    // produit sans compression de noms, comme le fait `to_bytes`
+    pub fn verify_tsig(&self, key_name: &str, secret: &[u8]) -> bool {
+        let Some(record) = self.additionals.last() else {
+            return false;
+        };
+        let Some(tsig) = record.from_tsig() else {
+            return false;
+        };
+        if !record.name.eq_ignore_ascii_case(key_name) || !tsig.algorithm.eq_ignore_ascii_case(TSIG_ALGORITHM) {
+            return false;
+        }
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        if now.abs_diff(tsig.time_signed) > tsig.fudge as u64 {
+            return false;
+        }
+
+        // Message tel qu'il était avant la signature
+        let mut unsigned = self.clone();
+        unsigned.remove_tsig();
+        unsigned.header.id = tsig.original_id;
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepte toute taille de clé");
+        mac.update(&tsig.digest_input(&unsigned.to_bytes(), key_name));
+        mac.verify_slice(&tsig.mac).is_ok()
+    }
+
+    fn tsig_mac(secret: &[u8], input: &[u8]) -> Vec<u8> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepte toute taille de clé");
+        mac.update(input);
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    fn remove_tsig(&mut self) {
+        let before = self.additionals.len();
+        self.additionals.retain(|record| record.rtype != DnsRecordType::TSIG);
+        self.header.arcount = self.header.arcount.saturating_sub((before - self.additionals.len()) as u16);
+    }
+}
+
+impl DnsMessage {
+    // Comparer deux messages champ par champ : drapeaux de l'en-tête, questions,
+    // nombre de réponses, puis TTL et RDATA de chaque réponse
+    // L'ID de transaction est ignoré (il diffère toujours entre deux serveurs)
+    // Les réponses sont appariées par (nom, type, RDATA) : l'ordre n'a pas d'importance
+    // `a_raw` et `b_raw` sont les messages bruts, pour lire les noms compressés de RDATA
+    pub fn diff(a: &DnsMessage, a_raw: &[u8], b: &DnsMessage, b_raw: &[u8]) -> Vec<DnsDiff> {
+        let mut diffs = Vec::new();
+
+        let (left, right) = (&a.header, &b.header);
+        let flags = [
+            ("qr", left.qr, right.qr),
+            ("aa", left.aa, right.aa),
+            ("tc", left.tc, right.tc),
+            ("rd", left.rd, right.rd),
+            ("ra", left.ra, right.ra),
+        ];
+        for (name, left, right) in flags {
+            if left != right {
+                diffs.push(DnsDiff::new(format!("header.{}", name), left, right));
+            }
+        }
+        if left.opcode != right.opcode {
+            diffs.push(DnsDiff::new("header.opcode", left.opcode_name(), right.opcode_name()));
+        }
+        if left.rcode != right.rcode {
+            diffs.push(DnsDiff::new("header.rcode", left.rcode_name(), right.rcode_name()));
+        }
+
+        let question_count = a.questions.len().max(b.questions.len());
+        for i in 0..question_count {
+            let left = a.questions.get(i).map(|q| format!("{} {}", q.name, q.qtype)).unwrap_or_default();
+            let right = b.questions.get(i).map(|q| format!("{} {}", q.name, q.qtype)).unwrap_or_default();
+            if !left.eq_ignore_ascii_case(&right) {
+                diffs.push(DnsDiff::new(format!("question[{}]", i), left, right));
+            }
+        }
+
+        if a.answers.len() != b.answers.len() {
+            diffs.push(DnsDiff::new("header.ancount", a.answers.len(), b.answers.len()));
+        }
+
+        let key = |record: &DnsRecord, raw: &[u8]| {
+            (record.name.to_lowercase(), record.rtype, record.rdata_to_string(raw))
+        };
+        let mut unmatched: Vec<&DnsRecord> = b.answers.iter().collect();
+        for record in &a.answers {
+            let record_key = key(record, a_raw);
+            match unmatched.iter().position(|other| key(other, b_raw) == record_key) {
+                Some(index) => {
+                    let other = unmatched.remove(index);
+                    if record.ttl != other.ttl {
+                        let (name, rtype, rdata) = record_key;
+                        diffs.push(DnsDiff::new(format!("answer[{} {} {}].ttl", name, rtype, rdata),
+                                                record.ttl, other.ttl));
+                    }
+                }
+                None => diffs.push(DnsDiff::new("answer", record.to_dig_string(a_raw), "")),
+            }
+        }
+        for record in unmatched {
+            diffs.push(DnsDiff::new("answer", "", record.to_dig_string(b_raw)));
+        }
+
+        diffs
+    }
+}
+
+// Affichage façon diff unifié : une section @@ par champ, '-' pour `left`, '+' pour `right`
+pub fn display_diff(diffs: &[DnsDiff], left_label: &str, right_label: &str) -> String {
+    let mut output = format!("--- {}\n+++ {}\n", left_label, right_label);
+    for diff in diffs {
+        output.push_str(&format!("@@ {} @@\n", diff.field));
+        if !diff.left.is_empty() {
+            output.push_str(&format!("-{}\n", diff.left));
+        }
+        if !diff.right.is_empty() {
+            output.push_str(&format!("+{}\n", diff.right));
+        }
+    }
+    output
+}
+
+// Construction d'un message DNS avec calcul automatique des compteurs
+#[derive(Debug, Clone)]
+pub struct DnsMessageBuilder {
+    message: DnsMessage,
+}
+
+impl Default for DnsMessageBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DnsMessageBuilder {
+    pub fn new() -> Self {
+        DnsMessageBuilder {
+            message: DnsMessage::new(),
+        }
+    }
+
+    pub fn id(mut self, id: u16) -> Self {
+        self.message.header.id = id;
+        self
+    }
+
+    pub fn query(mut self, name: String, qtype: DnsRecordType) -> Self {
+        self.message.questions.push(DnsQuestion::new(name, qtype));
+        self
+    }
+
+    pub fn answer(mut self, record: DnsRecord) -> Self {
+        self.message.answers.push(record);
+        self
+    }
+
+    pub fn authority(mut self, record: DnsRecord) -> Self {
+        self.message.authorities.push(record);
+        self
+    }
+
+    pub fn additional(mut self, record: DnsRecord) -> Self {
+        self.message.additionals.push(record);
+        self
+    }
+
+    pub fn recursion_desired(mut self, rd: bool) -> Self {
+        self.message.header.rd = rd;
+        self
+    }
+
+    // Ajouter un enregistrement OPT annonçant la taille du tampon UDP
+    pub fn edns(mut self, payload_size: u16) -> Self {
+        self.message.edns_payload_size = Some(payload_size);
+        self
+    }
+
+    // Les compteurs de l'en-tête reflètent toujours le contenu des sections
+    pub fn build(mut self) -> DnsMessage {
+        let header = &mut self.message.header;
+        header.qdcount = self.message.questions.len() as u16;
+        header.ancount = self.message.answers.len() as u16;
+        header.nscount = self.message.authorities.len() as u16;
+        header.arcount = self.message.additionals.len() as u16
+            + self.message.edns_payload_size.is_some() as u16;
+        self.message
+    }
+}
+
+// Cache des réponses DNS avec expiration TTL et éviction LRU
+// Les compteurs utilisent des atomiques pour que `get` reste en lecture seule
+// Les réponses négatives sont mémorisées à part, avec leur propre TTL (RFC 2308 §5) :
+// NXDOMAIN par nom, quel que soit le type, et NODATA par couple (nom, type)
+#[derive(Debug)]
+pub struct DnsCache {
+    entries: HashMap<(String, DnsRecordType), CacheEntry>,
+    nxdomain: HashMap<String, NegativeEntry>,
+    nodata: HashMap<(String, DnsRecordType), NegativeEntry>,
+    negative_ttl: u32,
+    max_entries: usize,
+    clock: AtomicU64,   // Horloge logique pour l'ordre d'utilisation
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    records: Vec<DnsRecord>,
+    inserted_at: Instant,
+    ttl: u32,
+    last_used: AtomicU64,
+}
+
+// Réponse négative mémorisée jusqu'à `expires_at`
+#[derive(Debug)]
+struct NegativeEntry {
+    expires_at: Instant,
+}
+
+// Nature d'une réponse négative en cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NegativeAnswer {
+    NxDomain,   // Le nom n'existe pas
+    NoData,     // Le nom existe, mais sans enregistrement du type demandé
+}
+
+// TTL par défaut des réponses négatives (secondes)
+pub const DEFAULT_NEGATIVE_TTL: u32 = 30;
+
+// Statistiques d'utilisation du cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub size: usize,
+    pub negative_size: usize,
+}
+
+impl DnsCache {
+    pub fn new(max_entries: usize) -> Self {
+        DnsCache {
+            entries: HashMap::new(),
+            nxdomain: HashMap::new(),
+            nodata: HashMap::new(),
+            negative_ttl: DEFAULT_NEGATIVE_TTL,
+            max_entries,
+            clock: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    // Durée de mémorisation des réponses NXDOMAIN et NODATA (0 = pas de cache négatif)
+    pub fn with_negative_ttl(mut self, negative_ttl: u32) -> Self {
+        self.negative_ttl = negative_ttl;
+        self
+    }
+
+    // Réponse négative encore valide pour ce nom et ce type
+    // Un NXDOMAIN couvre tous les types, un NODATA seulement le type mémorisé
+    pub fn get_negative(&self, name: &str, rtype: DnsRecordType) -> Option<NegativeAnswer> {
+        let name = name.to_lowercase();
+        let now = Instant::now();
+        let answer = if self.nxdomain.get(&name).is_some_and(|entry| entry.expires_at > now) {
+            Some(NegativeAnswer::NxDomain)
+        } else if self.nodata.get(&(name, rtype)).is_some_and(|entry| entry.expires_at > now) {
+            Some(NegativeAnswer::NoData)
+        } else {
+            None
+        };
+        if answer.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        }
+        answer
+    }
+
+    // Mémoriser une réponse NXDOMAIN pour `negative_ttl` secondes
+    pub fn insert_nxdomain(&mut self, name: &str) {
+        let key = name.to_lowercase();
+        if self.nxdomain.contains_key(&key) || self.has_negative_room() {
+            let entry = self.negative_entry();
+            self.nxdomain.insert(key, entry);
+        }
+    }
+
+    // Mémoriser une réponse NODATA (nom existant, type absent) pour `negative_ttl` secondes
+    pub fn insert_nodata(&mut self, name: &str, rtype: DnsRecordType) {
+        let key = (name.to_lowercase(), rtype);
+        if self.nodata.contains_key(&key) || self.has_negative_room() {
+            let entry = self.negative_entry();
+            self.nodata.insert(key, entry);
+        }
+    }
+
+    // Place pour une nouvelle réponse négative, après purge des entrées expirées si besoin
+    fn has_negative_room(&mut self) -> bool {
+        if self.negative_ttl == 0 || self.max_entries == 0 {
+            return false;
+        }
+        if self.nxdomain.len() + self.nodata.len() >= self.max_entries {
+            self.evict_expired();
+        }
+        self.nxdomain.len() + self.nodata.len() < self.max_entries
+    }
+
+    fn negative_entry(&self) -> NegativeEntry {
+        NegativeEntry { expires_at: Instant::now() + Duration::from_secs(self.negative_ttl as u64) }
+    }
+
+    // Retourne les enregistrements encore valides avec leur TTL restant
+    pub fn get(&self, name: &str, rtype: DnsRecordType) -> Option<Vec<DnsRecord>> {
+        let entry = match self.entries.get(&(name.to_lowercase(), rtype)) {
+            Some(entry) if entry.inserted_at.elapsed().as_secs() < entry.ttl as u64 => entry,
+            _ => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
+        };
+
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        entry.last_used.store(self.tick(), Ordering::Relaxed);
+
+        let remaining = entry.ttl - entry.inserted_at.elapsed().as_secs() as u32;
+        Some(entry.records.iter().cloned().map(|mut record| {
+            record.ttl = remaining;
+            record
+        }).collect())
+    }
+
+    // Un TTL nul signifie "ne pas mettre en cache"
+    pub fn insert(&mut self, name: &str, rtype: DnsRecordType, records: Vec<DnsRecord>, ttl: u32) {
+        if ttl == 0 || self.max_entries == 0 {
+            return;
+        }
+
+        let key = (name.to_lowercase(), rtype);
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.max_entries {
+            // Libérer de la place : d'abord les entrées expirées, sinon la moins récemment utilisée
+            if self.evict_expired() == 0 {
+                self.evict_least_recently_used();
+            }
+        }
+
+        let entry = CacheEntry {
+            records,
+            inserted_at: Instant::now(),
+            ttl,
+            last_used: AtomicU64::new(self.tick()),
+        };
+        self.entries.insert(key, entry);
+    }
+
+    // Supprimer les entrées expirées, retourne le nombre d'entrées supprimées
+    pub fn evict_expired(&mut self) -> usize {
+        let before = self.entries.len() + self.nxdomain.len() + self.nodata.len();
+        self.entries.retain(|_, entry| {
+            entry.inserted_at.elapsed() < Duration::from_secs(entry.ttl as u64)
+        });
+        let now = Instant::now();
+        self.nxdomain.retain(|_, entry| entry.expires_at > now);
+        self.nodata.retain(|_, entry| entry.expires_at > now);
+        before - self.entries.len() - self.nxdomain.len() - self.nodata.len()
+    }
+
+    // Vider le cache (après une modification de la base)
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.nxdomain.clear();
+        self.nodata.clear();
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            size: self.entries.len(),
+            negative_size: self.nxdomain.len() + self.nodata.len(),
+        }
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        let oldest = self.entries.iter()
+            .min_by_key(|(_, entry)| entry.last_used.load(Ordering::Relaxed))
+            .map(|(key, _)| key.clone());
+
+        if let Some(key) = oldest {
+            self.entries.remove(&key);
+        }
+    }
+
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed)
+    }
+}
+
+// TTL des enregistrements statiques qui n'expirent jamais
+pub const PERMANENT_TTL: u32 = u32::MAX;
+
+// Entrée de la base avec suivi de l'expiration
+#[derive(Debug, Clone)]
+pub struct DnsEntry {
+    pub ips: Vec<Ipv4Addr>,     // Adresses associées au nom
+    pub ttl: u32,               // Durée de vie (secondes)
+    pub inserted_at: Instant,   // Date d'insertion
+    pub zone_ttl: Option<u32>,  // TTL annoncé aux clients pour une entrée de fichier de zone
+}
+
+impl DnsEntry {
+    pub fn new(ips: Vec<Ipv4Addr>, ttl: u32) -> Self {
+        DnsEntry {
+            ips,
+            ttl,
+            inserted_at: Instant::now(),
+            zone_ttl: None,
+        }
+    }
+
+    // TTL à placer dans une réponse : celui du fichier de zone s'il existe,
+    // sinon le TTL restant plafonné à `max_ttl`
+    pub fn response_ttl(&self, max_ttl: u32) -> u32 {
+        self.zone_ttl.unwrap_or_else(|| self.remaining_ttl().min(max_ttl))
+    }
+
+    // TTL restant en secondes (PERMANENT_TTL pour les entrées statiques)
+    pub fn remaining_ttl(&self) -> u32 {
+        if self.ttl == PERMANENT_TTL {
+            return PERMANENT_TTL;
+        }
+        let elapsed = self.inserted_at.elapsed().as_secs();
+        (self.ttl as u64).saturating_sub(elapsed) as u32
+    }
+}
+
+// Connexions SQLite ouvertes en parallèle par le pool
+const STORE_POOL_SIZE: u32 = 4;
+
+// Stockage SQLite des enregistrements A, pour qu'ils survivent au redémarrage
+// Le pool est partagé par les clones de la base (une copie par instance avec --threads)
+#[derive(Debug, Clone)]
+pub struct RecordStore {
+    pool: r2d2::Pool<SqliteConnectionManager>,
+    path: PathBuf,
+}
+
+fn store_error(e: impl fmt::Display) -> Error {
+    Error::other(format!("SQLite: {}", e))
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0)
+}
+
+impl RecordStore {
+    // Ouvrir ou créer le fichier ; `inserted_at` permet de reprendre le TTL
+    // là où il en était au lieu de le relancer à chaque redémarrage
+    pub fn open(path: &Path) -> IoResult<Self> {
+        let pool = r2d2::Pool::builder()
+            .max_size(STORE_POOL_SIZE)
+            .build(SqliteConnectionManager::file(path))
+            .map_err(|e| Error::other(format!("{}: {}", path.display(), e)))?;
+
+        pool.get().map_err(store_error)?.execute_batch(
+            "CREATE TABLE IF NOT EXISTS records (
+                 name TEXT NOT NULL,
+                 rtype INTEGER NOT NULL,
+                 rdata BLOB NOT NULL,
+                 ttl INTEGER NOT NULL,
+                 inserted_at INTEGER NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS records_name ON records (name, rtype);",
+        ).map_err(store_error)?;
+
+        Ok(RecordStore { pool, path: path.to_path_buf() })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // Remplacer l'enregistrement A d'un nom (un seul par nom, comme en mémoire)
+    pub fn put_a_record(&self, name: &str, ip: Ipv4Addr, ttl: u32) -> IoResult<()> {
+        let mut connection = self.pool.get().map_err(store_error)?;
+        let transaction = connection.transaction().map_err(store_error)?;
+        transaction.execute("DELETE FROM records WHERE name = ?1 AND rtype = ?2",
+                            params![name, DnsRecordType::A as u16])
+            .map_err(store_error)?;
+        transaction.execute("INSERT INTO records (name, rtype, rdata, ttl, inserted_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+                            params![name, DnsRecordType::A as u16, ip.octets().to_vec(), ttl, unix_now()])
+            .map_err(store_error)?;
+        transaction.commit().map_err(store_error)
+    }
+
+    // Retourne false si le nom n'avait pas d'enregistrement A
+    pub fn delete_a_record(&self, name: &str) -> IoResult<bool> {
+        let connection = self.pool.get().map_err(store_error)?;
+        let deleted = connection.execute("DELETE FROM records WHERE name = ?1 AND rtype = ?2",
+                                         params![name, DnsRecordType::A as u16])
+            .map_err(store_error)?;
+        Ok(deleted > 0)
+    }
+
+    // Premier nom de `candidates` (par ordre de priorité) ayant une adresse non expirée
+    pub fn lookup_a_record(&self, candidates: &[String]) -> IoResult<Option<Ipv4Addr>> {
+        if candidates.is_empty() {
+            return Ok(None);
+        }
+        // ?1..?n : les noms candidats, ?n+1 : l'heure courante
+        let count = candidates.len();
+        let placeholders: Vec<String> = (1..=count).map(|i| format!("?{}", i)).collect();
+        let priorities: Vec<String> = (1..=count).map(|i| format!("WHEN ?{} THEN {}", i, i)).collect();
+        let sql = format!(
+            "SELECT rdata FROM records WHERE rtype = {} AND (ttl = {} OR inserted_at + ttl > ?{}) AND name IN ({})
+             ORDER BY CASE name {} END LIMIT 1",
+            DnsRecordType::A as u16,
+            PERMANENT_TTL,
+            count + 1,
+            placeholders.join(", "),
+            priorities.join(" "),
+        );
+
+        let now = unix_now();
+        let mut values: Vec<&dyn ToSql> = candidates.iter().map(|name| name as &dyn ToSql).collect();
+        values.push(&now);
+
+        let connection = self.pool.get().map_err(store_error)?;
+        let mut statement = connection.prepare_cached(&sql).map_err(store_error)?;
+        let rdata: Option<Vec<u8>> = statement
+            .query_row(values.as_slice(), |row| row.get(0))
+            .optional()
+            .map_err(store_error)?;
+        Ok(rdata.and_then(|bytes| <[u8; 4]>::try_from(bytes.as_slice()).ok()).map(Ipv4Addr::from))
+    }
+
+    // Enregistrements A encore valides ; les expirés sont supprimés du fichier
+    pub fn load_a_records(&self) -> IoResult<Vec<(String, DnsEntry)>> {
+        let connection = self.pool.get().map_err(store_error)?;
+        let now = unix_now();
+        connection.execute("DELETE FROM records WHERE ttl != ?1 AND inserted_at + ttl <= ?2",
+                           params![PERMANENT_TTL, now])
+            .map_err(store_error)?;
+
+        let mut statement = connection
+            .prepare("SELECT name, rdata, ttl, inserted_at FROM records WHERE rtype = ?1")
+            .map_err(store_error)?;
+        let rows = statement
+            .query_map(params![DnsRecordType::A as u16], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?, row.get::<_, u32>(2)?, row.get::<_, i64>(3)?))
+            })
+            .map_err(store_error)?;
+
+        let mut records = Vec::new();
+        for row in rows {
+            let (name, rdata, ttl, inserted_at) = row.map_err(store_error)?;
+            let Ok(octets) = <[u8; 4]>::try_from(rdata.as_slice()) else {
+                continue;
+            };
+            let mut entry = DnsEntry::new(vec![Ipv4Addr::from(octets)], ttl);
+            // Âge déjà écoulé avant l'ouverture
+            let age = Duration::from_secs(now.saturating_sub(inserted_at).max(0) as u64);
+            entry.inserted_at = Instant::now().checked_sub(age).unwrap_or(entry.inserted_at);
+            records.push((name, entry));
+        }
+        Ok(records)
+    }
+}
+
+// Base de données DNS simple pour le serveur
+#[derive(Debug, Clone)]
+pub struct SimpleDnsDatabase {
+    records: HashMap<String, DnsEntry>,
+    txt_records: HashMap<String, Vec<String>>,
+    srv_records: HashMap<String, Vec<SrvData>>,
+    cname_records: HashMap<String, String>,
+    soa_records: HashMap<String, SoaData>,
+    ns_records: HashMap<String, Vec<String>>,
+    caa_records: HashMap<String, Vec<CaaData>>,
+    naptr_records: HashMap<String, Vec<NaptrData>>,
+    store: Option<RecordStore>,     // Base SQLite attachée (voir `open`)
+}
+
+impl Default for SimpleDnsDatabase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimpleDnsDatabase {
+    // Base vide, sans enregistrements prédéfinis
+    pub fn empty() -> Self {
+        SimpleDnsDatabase {
+            records: HashMap::new(),
+            txt_records: HashMap::new(),
+            srv_records: HashMap::new(),
+            cname_records: HashMap::new(),
+            soa_records: HashMap::new(),
+            ns_records: HashMap::new(),
+            caa_records: HashMap::new(),
+            naptr_records: HashMap::new(),
+            store: None,
+        }
+    }
+
+    // Ouvrir (ou créer) une base SQLite et charger ses enregistrements
+    pub fn open(path: &Path) -> IoResult<Self> {
+        Self::empty().with_store(path)
+    }
+
+    // Attacher une base SQLite : ses enregistrements remplacent ceux déjà
+    // chargés, les ajouts via add_record_persistent y sont écrits
+    pub fn with_store(mut self, path: &Path) -> IoResult<Self> {
+        let store = RecordStore::open(path)?;
+        self.records.extend(store.load_a_records()?);
+        self.store = Some(store);
+        Ok(self)
+    }
+
+    pub fn store(&self) -> Option<&RecordStore> {
+        self.store.as_ref()
+    }
+
+    pub fn new() -> Self {
+        let mut db = Self::empty();
+
+        // Ajouter quelques enregistrements prédéfinis
+        db.add_record("localhost".to_string(), "127.0.0.1".parse().unwrap());
+        db.add_record("test.local".to_string(), "192.168.1.100".parse().unwrap());
+        db.add_record("server.local".to_string(), "192.168.1.1".parse().unwrap());
+        db.add_record("example.com".to_string(), "93.184.216.34".parse().unwrap());
+        db.add_record("google.com".to_string(), "8.8.8.8".parse().unwrap());
+        db.add_cname_record("www.example.com".to_string(), "example.com".to_string());
+        db.add_txt_record("example.com".to_string(), vec!["v=spf1 -all".to_string()]);
+        db.add_srv_record("_http._tcp.example.com".to_string(), SrvData {
+            priority: 10,
+            weight: 5,
+            port: 80,
+            target: "example.com".to_string(),
+        });
+        db.add_caa_record("example.com".to_string(), CaaData {
+            flags: 0,
+            tag: "issue".to_string(),
+            value: "letsencrypt.org".to_string(),
+        });
+        db.add_caa_record("example.com".to_string(), CaaData {
+            flags: 0,
+            tag: "issuewild".to_string(),
+            value: ";".to_string(),
+        });
+        // Découverte SIP puis numéro ENUM +1-800-555-1234
+        db.add_naptr_record("example.com".to_string(), NaptrData {
+            order: 10,
+            preference: 0,
+            flags: "s".to_string(),
+            services: "SIP+D2U".to_string(),
+            regexp: String::new(),
+            replacement: "_sip._udp.example.com".to_string(),
+        });
+        db.add_naptr_record("4.3.2.1.5.5.5.0.0.8.1.e164.arpa".to_string(), NaptrData {
+            order: 100,
+            preference: 10,
+            flags: "u".to_string(),
+            services: "E2U+sip".to_string(),
+            regexp: "!^.*$!sip:info@example.com!".to_string(),
+            replacement: String::new(),
+        });
+        for zone in ["example.com", "local"] {
+            db.add_soa_record(zone.to_string(), SoaData {
+                mname: format!("ns1.{}", zone),
+                rname: format!("admin.{}", zone),
+                serial: 1,
+                refresh: 7200,
+                retry: 3600,
+                expire: 1_209_600,
+                minimum: 300,
+            });
+        }
+        db.add_ns_record("example.com".to_string(), "ns1.example.com".to_string());
+        db.add_ns_record("example.com".to_string(), "ns2.example.com".to_string());
+        db.add_ns_record("local".to_string(), "ns1.local".to_string());
+
+        db
+    }
+
+    // Charger les enregistrements depuis un fichier au format /etc/hosts
+    // Format: "IP nom [alias...]", les commentaires commencent par '#'
+    // Les entrées IPv6 sont ignorées pour l'instant
+    pub fn load_from_hosts_file(path: &Path) -> IoResult<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            Error::new(e.kind(), format!("Impossible de lire {}: {}", path.display(), e))
+        })?;
+
+        let mut db = Self::empty();
+
+        for (line_number, line) in content.lines().enumerate() {
+            // Retirer les commentaires
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let mut fields = line.split_whitespace();
+            let address = fields.next().unwrap_or("");
+
+            if address.contains(':') {
+                continue; // IPv6
+            }
+
+            let ip: Ipv4Addr = address.parse().map_err(|_| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("{}:{}: adresse invalide '{}'", path.display(), line_number + 1, address),
+                )
+            })?;
+
+            for name in fields {
+                db.add_record(name.to_string(), ip);
+            }
+        }
+
+        Ok(db)
+    }
+
+    // Exporter les enregistrements A au format "master file" (RFC 1035)
+    // Les entrées statiques sont écrites sans TTL : elles reprennent `default_ttl` à l'import
+    pub fn export_zone_file(&self, origin: &str, default_ttl: u32) -> String {
+        let origin = origin.trim_end_matches('.').to_lowercase();
+        let mut zone = format!("$ORIGIN {}.\n$TTL {}\n", origin, default_ttl);
+        if let Some(soa) = self.soa_records.get(&origin) {
+            zone.push_str(&format!("@\tIN\tSOA\t{}. {}. {} {} {} {} {}\n", soa.mname, soa.rname,
+                                   soa.serial, soa.refresh, soa.retry, soa.expire, soa.minimum));
+        }
+        for nameserver in self.ns_records.get(&origin).into_iter().flatten() {
+            zone.push_str(&format!("@\tIN\tNS\t{}.\n", nameserver));
+        }
+
+        let mut names: Vec<&String> = self.records.keys().collect();
+        names.sort();
+
+        for name in names {
+            let entry = &self.records[name];
+            if self.is_expired(entry) {
+                continue;
+            }
+
+            // Nom relatif à l'origine quand c'est possible
+            let owner = if *name == origin {
+                "@".to_string()
+            } else if let Some(relative) = name.strip_suffix(&format!(".{}", origin)) {
+                relative.to_string()
+            } else {
+                format!("{}.", name)
+            };
+
+            let ttl = match entry.zone_ttl {
+                Some(zone_ttl) => format!("{} ", zone_ttl),
+                None if entry.ttl == PERMANENT_TTL => String::new(),
+                None => format!("{} ", entry.remaining_ttl()),
+            };
+
+            for ip in &entry.ips {
+                zone.push_str(&format!("{}\t{}IN\tA\t{}\n", owner, ttl, ip));
+            }
+        }
+
+        zone
+    }
+
+    // Importer un fichier de zone (RFC 1035) : $ORIGIN, $TTL, enregistrements A, NS et SOA
+    // Les données de zone n'expirent jamais localement : le TTL explicite d'un A, sinon le
+    // $TTL courant, est seulement celui annoncé aux clients (`DnsEntry::zone_ttl`)
+    pub fn import_zone_file(text: &str) -> IoResult<Self> {
+        let mut db = Self::empty();
+        let mut origin = String::new();
+        let mut default_ttl: Option<u32> = None;
+        let mut last_owner: Option<String> = None;
+
+        let invalid = |line_number: usize, message: &str| {
+            Error::new(ErrorKind::InvalidData, format!("ligne {}: {}", line_number + 1, message))
+        };
+
+        for (line_number, raw_line) in text.lines().enumerate() {
+            // Retirer les commentaires
+            let line = raw_line.split(';').next().unwrap_or("");
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let mut fields: Vec<&str> = line.split_whitespace().collect();
+
+            // Directives
+            match fields[0].to_uppercase().as_str() {
+                "$ORIGIN" => {
+                    let value = fields.get(1).ok_or_else(|| invalid(line_number, "$ORIGIN sans valeur"))?;
+                    origin = value.trim_end_matches('.').to_lowercase();
+                    continue;
+                }
+                "$TTL" => {
+                    let ttl = fields.get(1)
+                        .and_then(|value| value.parse::<u32>().ok())
+                        .ok_or_else(|| invalid(line_number, "$TTL invalide"))?;
+                    default_ttl = Some(ttl);
+                    continue;
+                }
+                _ => {}
+            }
+
+            // Propriétaire : absent si la ligne commence par un blanc
+            let owner = if raw_line.starts_with(char::is_whitespace) {
+                last_owner.clone().ok_or_else(|| invalid(line_number, "propriétaire manquant"))?
+            } else {
+                let owner = fields.remove(0);
+                if owner == "@" {
+                    origin.clone()
+                } else if let Some(absolute) = owner.strip_suffix('.') {
+                    absolute.to_lowercase()
+                } else if origin.is_empty() {
+                    owner.to_lowercase()
+                } else {
+                    format!("{}.{}", owner.to_lowercase(), origin)
+                }
+            };
+            last_owner = Some(owner.clone());
+
+            // TTL et classe optionnels, dans n'importe quel ordre
+            let mut record_ttl = None;
+            while let Some(field) = fields.first() {
+                if let Ok(ttl) = field.parse::<u32>() {
+                    record_ttl = Some(ttl);
+                } else if !field.eq_ignore_ascii_case("IN") {
+                    break;
+                }
+                fields.remove(0);
+            }
+            let zone_ttl = record_ttl.or(default_ttl);
+
+            // Nom relatif à l'origine dans les données (NS, SOA)
+            let qualify = |name: &str| match name.strip_suffix('.') {
+                Some(absolute) => absolute.to_lowercase(),
+                None if origin.is_empty() => name.to_lowercase(),
+                None => format!("{}.{}", name.to_lowercase(), origin),
+            };
+
+            match fields.as_slice() {
+                [rtype, address] if rtype.eq_ignore_ascii_case("A") => {
+                    let ip: Ipv4Addr = address.parse()
+                        .map_err(|_| invalid(line_number, &format!("adresse invalide '{}'", address)))?;
+                    // Les adresses d'un même nom partagent le TTL du premier enregistrement
+                    match db.records.get_mut(&owner) {
+                        Some(entry) => entry.ips.push(ip),
+                        None => {
+                            let entry = DnsEntry { zone_ttl, ..DnsEntry::new(vec![ip], PERMANENT_TTL) };
+                            db.records.insert(owner, entry);
+                        }
+                    }
+                }
+                [rtype, nameserver] if rtype.eq_ignore_ascii_case("NS") => {
+                    db.add_ns_record(owner, qualify(nameserver));
+                }
+                [rtype, mname, rname, values @ ..] if rtype.eq_ignore_ascii_case("SOA") => {
+                    // Forme sur une ligne uniquement (sans parenthèses)
+                    let values: Vec<u32> = values.iter().map(|value| value.parse()).collect::<Result<_, _>>()
+                        .map_err(|_| invalid(line_number, "valeurs SOA invalides"))?;
+                    let [serial, refresh, retry, expire, minimum] = values[..] else {
+                        return Err(invalid(line_number, "SOA attend 5 valeurs numériques"));
+                    };
+                    db.add_soa_record(owner, SoaData {
+                        mname: qualify(mname),
+                        rname: qualify(rname),
+                        serial,
+                        refresh,
+                        retry,
+                        expire,
+                        minimum,
+                    });
+                }
+                [rtype, ..] => {
+                    return Err(invalid(line_number, &format!("type non supporté '{}'", rtype)));
+                }
+                [] => return Err(invalid(line_number, "enregistrement incomplet")),
+            }
+        }
+
+        Ok(db)
+    }
+
+    // Reconstruire une zone reçue par AXFR (messages bruts, dans l'ordre de réception)
+    // Les enregistrements hors de `zone` sont ignorés, le SOA de la zone est obligatoire
+    pub fn import_axfr(zone: &str, messages: &[Vec<u8>]) -> IoResult<Self> {
+        let zone = zone.trim_end_matches('.').to_lowercase();
+        let mut db = Self::empty();
+        let invalid = |record: &DnsRecord| {
+            Error::new(ErrorKind::InvalidData, format!("données {} invalides pour {}", record.rtype, record.name))
+        };
+
+        for raw in messages {
+            let message = DnsMessage::from_bytes(raw)?;
+            for record in &message.answers {
+                let name = record.name.trim_end_matches('.').to_lowercase();
+                if !in_zone(&name, &zone) {
+                    continue;
+                }
+                match record.rtype {
+                    DnsRecordType::A => {
+                        let ip = record.get_ip().ok_or_else(|| invalid(record))?;
+                        match db.records.get_mut(&name) {
+                            Some(entry) if !entry.ips.contains(&ip) => entry.ips.push(ip),
+                            Some(_) => {}
+                            None => db.add_record(name, ip),
+                        }
+                    }
+                    DnsRecordType::NS => db.add_ns_record(name, record.get_ns(raw).ok_or_else(|| invalid(record))?),
+                    DnsRecordType::SOA => db.add_soa_record(name, record.get_soa(raw).ok_or_else(|| invalid(record))?),
+                    DnsRecordType::CNAME => db.add_cname_record(name, record.get_cname(raw).ok_or_else(|| invalid(record))?),
+                    DnsRecordType::TXT => db.add_txt_record(name, record.get_txt().ok_or_else(|| invalid(record))?),
+                    DnsRecordType::SRV => db.add_srv_record(name, record.get_srv(raw).ok_or_else(|| invalid(record))?),
+                    DnsRecordType::NAPTR => db.add_naptr_record(name, record.get_naptr(raw).ok_or_else(|| invalid(record))?),
+                    DnsRecordType::CAA => db.add_caa_record(name, record.get_caa().ok_or_else(|| invalid(record))?),
+                    _ => {}
+                }
+            }
+        }
+
+        if db.lookup_soa(&zone).is_none() {
+            return Err(Error::new(ErrorKind::InvalidData, format!("transfert de {} sans SOA", zone)));
+        }
+        Ok(db)
+    }
+
+    // Ajouter un enregistrement statique (sans expiration)
+    // Un nom commençant par "*." (ex: "*.example.com") est un joker qui couvre
+    // tous les sous-domaines sans enregistrement exact
+    pub fn add_record(&mut self, name: String, ip: Ipv4Addr) {
+        self.add_record_with_ttl(name, ip, PERMANENT_TTL);
+    }
+
+    // Ajouter un enregistrement qui expire après `ttl` secondes
+    pub fn add_record_with_ttl(&mut self, name: String, ip: Ipv4Addr, ttl: u32) {
+        self.records.insert(name.to_lowercase(), DnsEntry::new(vec![ip], ttl));
+    }
+
+    // Supprimer l'enregistrement A d'un nom, retourne false s'il n'existait pas
+    pub fn remove_record(&mut self, name: &str) -> bool {
+        self.records.remove(&name.to_lowercase()).is_some()
+    }
+
+    // Ajouter un enregistrement en l'écrivant aussi dans la base SQLite attachée
+    // Sans base attachée, équivaut à add_record_with_ttl
+    pub fn add_record_persistent(&mut self, name: String, ip: Ipv4Addr, ttl: u32) -> IoResult<()> {
+        let name = name.to_lowercase();
+        if let Some(store) = &self.store {
+            store.put_a_record(&name, ip, ttl)?;
+        }
+        self.add_record_with_ttl(name, ip, ttl);
+        Ok(())
+    }
+
+    // Supprimer l'enregistrement A en mémoire et dans la base SQLite attachée
+    pub fn remove_record_persistent(&mut self, name: &str) -> IoResult<bool> {
+        let stored = match &self.store {
+            Some(store) => store.delete_a_record(&name.to_lowercase())?,
+            None => false,
+        };
+        Ok(self.remove_record(name) || stored)
+    }
+
+    // En mémoire d'abord, puis dans la base SQLite attachée, qu'un autre
+    // processus a pu compléter depuis l'ouverture
+    pub fn lookup(&self, name: &str) -> Option<Ipv4Addr> {
+        self.lookup_fresh(name)
+            .and_then(|entry| entry.ips.first().copied())
+            .or_else(|| {
+                let store = self.store.as_ref()?;
+                // Erreur SQLite traitée comme une absence, comme un nom inconnu
+                store.lookup_a_record(&Self::candidate_names(name)).ok().flatten()
+            })
+    }
+
+    pub fn is_expired(&self, entry: &DnsEntry) -> bool {
+        entry.ttl != PERMANENT_TTL
+            && entry.inserted_at.elapsed() >= Duration::from_secs(entry.ttl as u64)
+    }
+
+    // Retourne None si l'entrée est absente ou expirée
+    // Une correspondance exacte est toujours prioritaire sur un joker
+    pub fn lookup_fresh(&self, name: &str) -> Option<&DnsEntry> {
+        Self::candidate_names(name)
+            .iter()
+            .filter_map(|candidate| self.records.get(candidate))
+            .find(|entry| !self.is_expired(entry))
+    }
+
+    // Noms à essayer dans l'ordre : le nom exact puis les jokers
+    // "a.b.example.com" -> ["a.b.example.com", "*.b.example.com", "*.example.com", "*.com"]
+    fn candidate_names(name: &str) -> Vec<String> {
+        let name = name.to_lowercase();
+        let mut candidates = vec![name.clone()];
+
+        let mut remaining = name.as_str();
+        while let Some((_, parent)) = remaining.split_once('.') {
+            candidates.push(format!("*.{}", parent));
+            remaining = parent;
+        }
+
+        candidates
+    }
+
+    // Vrai si le nom possède au moins un enregistrement, quel qu'en soit le type :
+    // distingue NODATA (nom connu, type absent) de NXDOMAIN (RFC 2308 §2.1-2.2)
+    pub fn name_exists(&self, name: &str) -> bool {
+        self.lookup_fresh(name).is_some()
+            || self.lookup_cname(name).is_some()
+            || self.lookup_txt(name).is_some()
+            || self.lookup_srv(name).is_some()
+            || self.lookup_naptr(name).is_some()
+            || self.lookup_caa(name).is_some()
+            || self.lookup_ns(name).is_some()
+            || self.lookup_soa(name).is_some()
+    }
+
+    // Supprimer les entrées expirées
+    pub fn purge_expired(&mut self) -> usize {
+        let before = self.records.len();
+        let expired: Vec<String> = self.records
+            .iter()
+            .filter(|(_, entry)| self.is_expired(entry))
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        for name in expired {
+            self.records.remove(&name);
+        }
+
+        before - self.records.len()
+    }
+
+    pub fn list_records(&self) -> &HashMap<String, DnsEntry> {
+        &self.records
+    }
+
+    pub fn add_txt_record(&mut self, name: String, strings: Vec<String>) {
+        self.txt_records.insert(name.to_lowercase(), strings);
+    }
+
+    pub fn lookup_txt(&self, name: &str) -> Option<&Vec<String>> {
+        Self::candidate_names(name)
+            .iter()
+            .find_map(|candidate| self.txt_records.get(candidate))
+    }
+
+    pub fn list_txt_records(&self) -> &HashMap<String, Vec<String>> {
+        &self.txt_records
+    }
+
+    // Alias `alias` -> nom canonique `canonical`
+    pub fn add_cname_record(&mut self, alias: String, canonical: String) {
+        self.cname_records.insert(alias.to_lowercase(), canonical.to_lowercase());
+    }
+
+    pub fn lookup_cname(&self, alias: &str) -> Option<&String> {
+        self.cname_records.get(&alias.to_lowercase())
+    }
+
+    pub fn list_cname_records(&self) -> &HashMap<String, String> {
+        &self.cname_records
+    }
+
+    // Plusieurs enregistrements SRV peuvent coexister pour un même service
+    pub fn add_srv_record(&mut self, name: String, srv: SrvData) {
+        self.srv_records.entry(name.to_lowercase()).or_default().push(srv);
+    }
+
+    pub fn lookup_srv(&self, name: &str) -> Option<&Vec<SrvData>> {
+        self.srv_records.get(&name.to_lowercase())
+    }
+
+    pub fn list_srv_records(&self) -> &HashMap<String, Vec<SrvData>> {
+        &self.srv_records
+    }
+
+    // Les règles sont gardées dans l'ordre (order, preference)
+    pub fn add_naptr_record(&mut self, name: String, naptr: NaptrData) {
+        let rules = self.naptr_records.entry(name.to_lowercase()).or_default();
+        rules.push(naptr);
+        rules.sort_by_key(|rule| (rule.order, rule.preference));
+    }
+
+    pub fn lookup_naptr(&self, name: &str) -> Option<&Vec<NaptrData>> {
+        self.naptr_records.get(&name.to_lowercase())
+    }
+
+    pub fn list_naptr_records(&self) -> &HashMap<String, Vec<NaptrData>> {
+        &self.naptr_records
+    }
+
+    pub fn add_caa_record(&mut self, name: String, caa: CaaData) {
+        self.caa_records.entry(name.to_lowercase()).or_default().push(caa);
+    }
+
+    pub fn lookup_caa(&self, name: &str) -> Option<&Vec<CaaData>> {
+        self.caa_records.get(&name.to_lowercase())
+    }
+
+    pub fn list_caa_records(&self) -> &HashMap<String, Vec<CaaData>> {
+        &self.caa_records
+    }
+
+    // Un seul SOA par zone
+    pub fn add_soa_record(&mut self, zone: String, soa: SoaData) {
+        self.soa_records.insert(zone.to_lowercase(), soa);
+    }
+
+    pub fn lookup_soa(&self, zone: &str) -> Option<&SoaData> {
+        self.soa_records.get(&zone.to_lowercase())
+    }
+
+    // Zone la plus proche contenant `name` : "a.b.example.com" -> "example.com"
+    pub fn find_zone(&self, name: &str) -> Option<(&String, &SoaData)> {
+        Self::closest_zone(&self.soa_records, name)
+    }
+
+    pub fn list_soa_records(&self) -> &HashMap<String, SoaData> {
+        &self.soa_records
+    }
+
+    pub fn add_ns_record(&mut self, zone: String, nameserver: String) {
+        let nameservers = self.ns_records.entry(zone.to_lowercase()).or_default();
+        let nameserver = nameserver.trim_end_matches('.').to_lowercase();
+        if !nameservers.contains(&nameserver) {
+            nameservers.push(nameserver);
+        }
+    }
+
+    pub fn lookup_ns(&self, zone: &str) -> Option<&Vec<String>> {
+        self.ns_records.get(&zone.to_lowercase())
+    }
+
+    // Serveurs de noms de la zone la plus proche : le serveur fait autorité pour cette zone
+    pub fn find_authority(&self, name: &str) -> Option<(&String, &Vec<String>)> {
+        Self::closest_zone(&self.ns_records, name)
+    }
+
+    pub fn list_ns_records(&self) -> &HashMap<String, Vec<String>> {
+        &self.ns_records
+    }
+
+    // Enregistrements de `zone` et de ses sous-domaines pour un transfert de zone, sauf le SOA
+    // Triés par nom ; les TTL sont plafonnés à `max_ttl`, les A expirés sont omis
+    pub fn zone_records(&self, zone: &str, max_ttl: u32) -> Vec<DnsRecord> {
+        let zone = zone.trim_end_matches('.').to_lowercase();
+        let mut names: Vec<&String> = self.records.keys()
+            .chain(self.cname_records.keys())
+            .chain(self.txt_records.keys())
+            .chain(self.srv_records.keys())
+            .chain(self.naptr_records.keys())
+            .chain(self.caa_records.keys())
+            .chain(self.ns_records.keys())
+            .filter(|name| in_zone(name, &zone))
+            .collect();
+        names.sort();
+        names.dedup();
+
+        let mut records = Vec::new();
+        for name in names {
+            for nameserver in self.ns_records.get(name).into_iter().flatten() {
+                records.push(DnsRecord::new_ns_record(name.clone(), nameserver.clone(), max_ttl));
+            }
+            if let Some(entry) = self.records.get(name).filter(|entry| !self.is_expired(entry)) {
+                let ttl = entry.response_ttl(max_ttl);
+                records.extend(entry.ips.iter().map(|ip| DnsRecord::new_a_record(name.clone(), *ip, ttl)));
+            }
+            if let Some(canonical) = self.cname_records.get(name) {
+                records.push(DnsRecord::new_cname_record(name.clone(), canonical.clone(), max_ttl));
+            }
+            if let Some(strings) = self.txt_records.get(name) {
+                let parts: Vec<&str> = strings.iter().map(|s| s.as_str()).collect();
+                records.push(DnsRecord::new_txt_record(name.clone(), &parts, max_ttl));
+            }
+            for srv in self.srv_records.get(name).into_iter().flatten() {
+                records.push(DnsRecord::new_srv_record(
+                    name.clone(), srv.priority, srv.weight, srv.port, srv.target.clone(), max_ttl
+                ));
+            }
+            for naptr in self.naptr_records.get(name).into_iter().flatten() {
+                records.push(DnsRecord::new_naptr_record(name.clone(), naptr, max_ttl));
+            }
+            for caa in self.caa_records.get(name).into_iter().flatten() {
+                records.push(DnsRecord::new_caa_record(name.clone(), caa, max_ttl));
+            }
+        }
+        records
+    }
+
+    // Remonter les labels de `name` jusqu'à trouver une zone connue
+    fn closest_zone<'a, V>(zones: &'a HashMap<String, V>, name: &str) -> Option<(&'a String, &'a V)> {
+        let name = name.trim_end_matches('.').to_lowercase();
+        let mut candidate = name.as_str();
+        loop {
+            if let Some(found) = zones.get_key_value(candidate) {
+                return Some(found);
+            }
+            candidate = candidate.split_once('.')?.1;
+        }
+    }
+}
+
+// Réseau au format CIDR (192.168.0.0/16, 2001:db8::/32) ; une adresse seule vaut /32 ou /128
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cidr {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    pub fn parse(text: &str) -> IoResult<Self> {
+        let invalid = || Error::new(ErrorKind::InvalidData, format!("Réseau invalide: '{}'", text));
+        let (address, prefix) = match text.split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix)),
+            None => (text, None),
+        };
+        let network: IpAddr = address.parse().map_err(|_| invalid())?;
+        let max_prefix = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix.parse::<u8>().ok().filter(|prefix| *prefix <= max_prefix).ok_or_else(invalid)?,
+            None => max_prefix,
+        };
+        Ok(Cidr { network, prefix })
+    }
+
+    // Les adresses IPv4 sous forme IPv6 (::ffff:a.b.c.d) sont comparées en IPv4
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix)
+    }
+}
+
+// Liste de réseaux nommée, comme `acl "internal" { 192.168.0.0/16; };` dans BIND
+#[derive(Debug, Clone, PartialEq)]
+pub struct IpAcl {
+    pub name: String,
+    networks: Vec<Cidr>,
+}
+
+impl IpAcl {
+    pub fn new(name: String, networks: Vec<Cidr>) -> Self {
+        IpAcl { name, networks }
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        self.networks.iter().any(|network| network.contains(ip))
+    }
+}
+
+// Zone servie depuis sa propre base aux clients d'une ACL
+#[derive(Debug, Clone)]
+struct ZoneRoute {
+    zone: String,
+    clients: IpAcl,
+    database: SimpleDnsDatabase,
+}
+
+// Vues par client (split-horizon) : une même zone peut avoir une base par ACL
+// Les noms hors des zones routées, ou les clients d'aucune ACL, restent servis par la base principale
+#[derive(Debug, Clone, Default)]
+pub struct ZoneRouter {
+    routes: Vec<ZoneRoute>,
+}
+
+impl ZoneRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_route(&mut self, zone: &str, clients: IpAcl, database: SimpleDnsDatabase) {
+        self.routes.push(ZoneRoute {
+            zone: zone.trim_end_matches('.').to_lowercase(),
+            clients,
+            database,
+        });
+    }
+
+    // Charger un fichier d'ACL ; les chemins `file` sont relatifs à son répertoire
+    pub fn load(path: &Path) -> IoResult<Self> {
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            Error::new(e.kind(), format!("Impossible de lire {}: {}", path.display(), e))
+        })?;
+        Self::parse(&text, path.parent().unwrap_or(Path::new(".")))
+            .map_err(|e| Error::new(e.kind(), format!("{}: {}", path.display(), e)))
+    }
+
+    // Syntaxe inspirée de BIND, commentaires `#` et `//` :
+    //   acl "internal" { 192.168.0.0/16; 10.0.0.0/8; };
+    //   zone "example.com" { match-clients { internal; 172.16.0.1; }; file "internal.zone"; };
+    // `any` couvre tous les clients ; chaque `file` est un fichier de zone (RFC 1035)
+    pub fn parse(text: &str, base_dir: &Path) -> IoResult<Self> {
+        let mut tokens = AclTokens::new(text);
+        let mut acls: HashMap<String, IpAcl> = HashMap::new();
+        let mut router = ZoneRouter::new();
+
+        while let Some(keyword) = tokens.next_token() {
+            match keyword.as_str() {
+                "acl" => {
+                    let name = tokens.expect_word("nom d'ACL")?;
+                    let mut networks = Vec::new();
+                    for element in tokens.block()? {
+                        networks.push(Cidr::parse(&element).map_err(|e| tokens.error(&e.to_string()))?);
+                    }
+                    acls.insert(name.clone(), IpAcl::new(name, networks));
+                }
+                "zone" => {
+                    let zone = tokens.expect_word("nom de zone")?;
+                    tokens.expect("{")?;
+                    let (mut clients, mut file) = (None, None);
+                    loop {
+                        match tokens.expect_word("option de zone")?.as_str() {
+                            "}" => break,
+                            "match-clients" => {
+                                let mut networks = Vec::new();
+                                let mut names = Vec::new();
+                                for element in tokens.block()? {
+                                    if element == "any" {
+                                        networks.extend([Cidr::parse("0.0.0.0/0")?, Cidr::parse("::/0")?]);
+                                    } else if let Some(acl) = acls.get(&element) {
+                                        networks.extend(acl.networks.iter().copied());
+                                    } else {
+                                        networks.push(Cidr::parse(&element)
+                                            .map_err(|_| tokens.error(&format!("ACL inconnue ou réseau invalide '{}'", element)))?);
+                                    }
+                                    names.push(element);
+                                }
+                                clients = Some(IpAcl::new(names.join(","), networks));
+                            }
+                            "file" => {
+                                file = Some(tokens.expect_word("fichier de zone")?);
+                                tokens.expect(";")?;
+                            }
+                            other => return Err(tokens.error(&format!("option de zone inconnue '{}'", other))),
+                        }
+                    }
+                    tokens.skip(";");
+
+                    let clients = clients.ok_or_else(|| tokens.error(&format!("zone \"{}\" sans match-clients", zone)))?;
+                    let file = file.ok_or_else(|| tokens.error(&format!("zone \"{}\" sans file", zone)))?;
+                    let path = base_dir.join(&file);
+                    let database = std::fs::read_to_string(&path)
+                        .and_then(|text| SimpleDnsDatabase::import_zone_file(&text))
+                        .map_err(|e| Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+                    router.add_route(&zone, clients, database);
+                }
+                other => return Err(tokens.error(&format!("instruction inconnue '{}' (attendu: acl, zone)", other))),
+            }
+        }
+
+        Ok(router)
+    }
+
+    // Base à utiliser pour ce client : zone la plus précise contenant le nom et dont
+    // l'ACL couvre le client (la première du fichier à précision égale)
+    pub fn route(&self, domain: &str, client_ip: IpAddr) -> Option<&SimpleDnsDatabase> {
+        let domain = domain.trim_end_matches('.').to_lowercase();
+        let mut best: Option<&ZoneRoute> = None;
+        for route in &self.routes {
+            if in_zone(&domain, &route.zone)
+                && route.clients.contains(client_ip)
+                && best.is_none_or(|best| route.zone.len() > best.zone.len())
+            {
+                best = Some(route);
+            }
+        }
+        best.map(|route| &route.database)
+    }
+
+    // Le nom appartient-il à une zone routée ? Sa réponse dépend alors du client
+    pub fn covers(&self, domain: &str) -> bool {
+        let domain = domain.trim_end_matches('.').to_lowercase();
+        self.routes.iter().any(|route| in_zone(&domain, &route.zone))
+    }
+
+    // (zone, ACL) dans l'ordre du fichier
+    pub fn routes(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.routes.iter().map(|route| (route.zone.as_str(), route.clients.name.as_str()))
+    }
+
+}
+
+// `name` est-il `zone` ou l'un de ses sous-domaines ? (noms en minuscules, sans point final)
+fn in_zone(name: &str, zone: &str) -> bool {
+    name == zone || name.strip_suffix(zone).is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+// Découpage d'un fichier d'ACL en mots, chaînes entre guillemets et `{`, `}`, `;`
+struct AclTokens {
+    tokens: Vec<(String, usize)>,
+    position: usize,
+}
+
+impl AclTokens {
+    fn new(text: &str) -> Self {
+        let mut tokens = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("");
+            let line = line.split("//").next().unwrap_or("");
+            let mut chars = line.chars().peekable();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    chars.next();
+                } else if matches!(c, '{' | '}' | ';') {
+                    tokens.push((c.to_string(), number + 1));
+                    chars.next();
+                } else if c == '"' {
+                    chars.next();
+                    let word: String = chars.by_ref().take_while(|c| *c != '"').collect();
+                    tokens.push((word, number + 1));
+                } else {
+                    let mut word = String::new();
+                    while let Some(&c) = chars.peek() {
+                        if c.is_whitespace() || matches!(c, '{' | '}' | ';' | '"') {
+                            break;
+                        }
+                        word.push(c);
+                        chars.next();
+                    }
+                    tokens.push((word, number + 1));
+                }
+            }
+        }
+        AclTokens { tokens, position: 0 }
+    }
+
+    fn next_token(&mut self) -> Option<String> {
+        let token = self.tokens.get(self.position).map(|(token, _)| token.clone());
+        self.position += 1;
+        token
+    }
+
+    fn error(&self, message: &str) -> Error {
+        let line = self.tokens.get(self.position.saturating_sub(1)).or(self.tokens.last()).map_or(0, |(_, line)| *line);
+        Error::new(ErrorKind::InvalidData, format!("ligne {}: {}", line, message))
+    }
+
+    fn expect_word(&mut self, what: &str) -> IoResult<String> {
+        self.next_token().ok_or_else(|| self.error(&format!("{} attendu en fin de fichier", what)))
+    }
+
+    fn expect(&mut self, expected: &str) -> IoResult<()> {
+        match self.next_token() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(self.error(&format!("'{}' attendu, trouvé '{}'", expected, token))),
+            None => Err(self.error(&format!("'{}' attendu en fin de fichier", expected))),
+        }
+    }
+
+    // Jeton facultatif (le `;` après une accolade fermante)
+    fn skip(&mut self, optional: &str) {
+        if self.tokens.get(self.position).is_some_and(|(token, _)| token == optional) {
+            self.position += 1;
+        }
+    }
+
+    // `{ a; b; };` -> ["a", "b"]
+    fn block(&mut self) -> IoResult<Vec<String>> {
+        self.expect("{")?;
+        let mut elements = Vec::new();
+        loop {
+            let element = self.expect_word("élément")?;
+            if element == "}" {
+                break;
+            }
+            elements.push(element);
+            self.expect(";")?;
+        }
+        self.skip(";");
+        Ok(elements)
+    }
+}
+
+// Journal des requêtes du serveur (--query-log), une ligne par requête :
+// `<horodatage> <ip_client> <domaine> <type> <rcode> <latence_us>`
+// Horodatage RFC 3339 en UTC à la milliseconde, rcode sous forme de mnémonique
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryLogEntry {
+    pub timestamp_ms: u64,      // Millisecondes depuis l'époque Unix
+    pub client_ip: IpAddr,
+    pub domain: String,
+    pub qtype: DnsRecordType,
+    pub rcode: u8,
+    pub latency_us: u64,
+}
+
+// Noms des codes de réponse (RFC 1035 §4.1.1)
+const RCODE_NAMES: [&str; 6] = ["NOERROR", "FORMERR", "SERVFAIL", "NXDOMAIN", "NOTIMP", "REFUSED"];
+
+impl QueryLogEntry {
+    // Entrée horodatée à l'instant présent
+    pub fn new(client_ip: IpAddr, domain: String, qtype: DnsRecordType, rcode: u8, latency: Duration) -> Self {
+        QueryLogEntry {
+            timestamp_ms: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0),
+            client_ip,
+            domain,
+            qtype,
+            rcode,
+            latency_us: latency.as_micros() as u64,
+        }
+    }
+
+    // Date UTC (AAAA-MM-JJ) de la requête, utilisée pour la rotation quotidienne
+    pub fn date(&self) -> String {
+        utc_date(self.timestamp_ms)
+    }
+
+    // Relire une ligne produite par `Display`
+    pub fn parse(line: &str) -> IoResult<Self> {
+        let invalid = |what: &str| Error::new(ErrorKind::InvalidData, format!("{} invalide: '{}'", what, line));
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [timestamp, client_ip, domain, qtype, rcode, latency_us] = fields[..] else {
+            return Err(invalid("Ligne de journal"));
+        };
+
+        Ok(QueryLogEntry {
+            timestamp_ms: parse_rfc3339_ms(timestamp).ok_or_else(|| invalid("Horodatage"))?,
+            client_ip: client_ip.parse().map_err(|_| invalid("Adresse client"))?,
+            domain: unescape_log_field(domain),
+            qtype: DnsRecordType::from_name(qtype).ok_or_else(|| invalid("Type"))?,
+            rcode: match RCODE_NAMES.iter().position(|name| *name == rcode) {
+                Some(code) => code as u8,
+                None => rcode.strip_prefix("RCODE").and_then(|code| code.parse().ok()).ok_or_else(|| invalid("Rcode"))?,
+            },
+            latency_us: latency_us.parse().map_err(|_| invalid("Latence"))?,
+        })
+    }
+}
+
+impl fmt::Display for QueryLogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rcode = match RCODE_NAMES.get(self.rcode as usize) {
+            Some(name) => name.to_string(),
+            None => format!("RCODE{}", self.rcode),
+        };
+        // La racine s'écrit "." pour garder six champs
+        let domain = if self.domain.is_empty() { ".".to_string() } else { escape_log_field(&self.domain) };
+        write!(f, "{} {} {} {} {} {}",
+               format_rfc3339_ms(self.timestamp_ms), self.client_ip, domain, self.qtype, rcode, self.latency_us)
+    }
+}
+
+// Espaces et caractères de contrôle d'un nom écrits en \DDD, comme dans un fichier de zone
+fn escape_log_field(field: &str) -> String {
+    let mut escaped = String::new();
+    for c in field.chars() {
+        if c == '\\' || c.is_whitespace() || c.is_control() {
+            for byte in c.to_string().bytes() {
+                escaped.push_str(&format!("\\{:03}", byte));
+            }
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+fn unescape_log_field(field: &str) -> String {
+    if field == "." {
+        return String::new();
+    }
+    let bytes = field.as_bytes();
+    let mut unescaped = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let code = bytes.get(i + 1..i + 4)
+            .filter(|_| bytes[i] == b'\\')
+            .and_then(|digits| std::str::from_utf8(digits).ok()?.parse::<u8>().ok());
+        match code {
+            Some(byte) => {
+                unescaped.push(byte);
+                i += 4;
+            }
+            None => {
+                unescaped.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&unescaped).to_string()
+}
+
+fn utc_datetime(timestamp_ms: u64) -> DateTime<Utc> {
+    i64::try_from(timestamp_ms).ok()
+        .and_then(DateTime::from_timestamp_millis)
+        .unwrap_or_default()
+}
+
+// Date UTC (AAAA-MM-JJ) d'un horodatage en millisecondes
+pub fn utc_date(timestamp_ms: u64) -> String {
+    utc_datetime(timestamp_ms).format("%Y-%m-%d").to_string()
+}
+
+// Ex: 2024-05-01T12:00:00.123Z
+fn format_rfc3339_ms(timestamp_ms: u64) -> String {
+    utc_datetime(timestamp_ms).to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+// Seul le format écrit par `format_rfc3339_ms` est accepté : UTC, millisecondes
+fn parse_rfc3339_ms(text: &str) -> Option<u64> {
+    let timestamp_ms = DateTime::parse_from_rfc3339(text).ok()?.timestamp_millis();
+    u64::try_from(timestamp_ms).ok()
+        .filter(|timestamp_ms| format_rfc3339_ms(*timestamp_ms) == text)
+}
+
+// Faux serveur DNS pour les tests : écoute sur un port choisi par le système,
+// reçoit un nombre fixé de paquets UDP et renvoie à chacun la réponse configurée.
+// L'ID de transaction et les questions de la requête sont recopiés dans la réponse.
+#[cfg(feature = "testing")]
+pub struct MockDnsServer {
+    addr: std::net::SocketAddr,
+    handle: std::thread::JoinHandle<IoResult<Vec<DnsMessage>>>,
+}
+
+#[cfg(feature = "testing")]
+impl MockDnsServer {
+    // `response` à None : la requête est reçue mais jamais servie (test de timeout)
+    pub fn start(response: Option<DnsMessage>) -> IoResult<Self> {
+        Self::start_many(response, 1)
+    }
+
+    // Servir `count` requêtes avant de s'arrêter
+    pub fn start_many(response: Option<DnsMessage>, count: usize) -> IoResult<Self> {
+        Self::start_at(([127, 0, 0, 1], 0).into(), response, count)
+    }
+
+    // Écouter sur une adresse donnée, par exemple 127.0.0.2 pour simuler un autre serveur
+    pub fn start_at(addr: std::net::SocketAddr, response: Option<DnsMessage>, count: usize) -> IoResult<Self> {
+        let socket = std::net::UdpSocket::bind(addr)?;
+        let addr = socket.local_addr()?;
+
+        let handle = std::thread::spawn(move || {
+            let mut queries = Vec::with_capacity(count);
+            let mut buffer = [0u8; UDP_MAX_SIZE];
+            while queries.len() < count {
+                let (size, client) = socket.recv_from(&mut buffer)?;
+                let query = DnsMessage::from_bytes(&buffer[..size])?;
+
+                if let Some(mut response) = response.clone() {
+                    response.header.id = query.header.id;
+                    response.header.qr = true;
+                    response.header.qdcount = query.questions.len() as u16;
+                    response.questions = query.questions.clone();
+                    socket.send_to(&response.to_bytes(), client)?;
+                }
+
+                queries.push(query);
+            }
+
+            Ok(queries)
+        });
+
+        Ok(MockDnsServer { addr, handle })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.addr.port()
+    }
+
+    pub fn addr(&self) -> std::net::SocketAddr {
+        self.addr
+    }
+
+    // Attendre la fin du serveur et retourner la requête reçue
+    pub fn received_query(self) -> IoResult<DnsMessage> {
+        self.received_queries()?
+            .pop()
+            .ok_or_else(|| Error::other("aucune requête reçue"))
+    }
+
+    // Attendre la fin du serveur et retourner les requêtes dans l'ordre de réception
+    pub fn received_queries(self) -> IoResult<Vec<DnsMessage>> {
+        self.handle
+            .join()
+            .map_err(|_| Error::other("le serveur de test a paniqué"))?
+    }
+}
@@ -124,6 +124,46 @@ else
 fi
 echo ""
 
+# Test DNS over TCP (message préfixé par sa longueur sur 2 octets)
+print_step "=== TEST DNS SUR TCP ==="
+echo ""
+
+print_step "Test: Requête A via TCP"
+if command -v dig >/dev/null 2>&1; then
+    if timeout 10 dig +tcp +short @127.0.0.1 -p 5353 google.com > /tmp/dns_tcp_test.txt /tmp/dns_json_test.txt 2>&1 \
+        && grep -q "8.8.8.8" /tmp/dns_tcp_test.txt; then
+        print_success "✓ Réponse TCP valide"
+    else
+        print_error "Échec de la requête TCP"
+        cat /tmp/dns_tcp_test.txt
+    fi
+else
+    print_warning "dig non disponible, test TCP ignoré"
+fi
+echo ""
+
+# Test de la sortie JSON
+print_step "=== TEST SORTIE JSON ==="
+echo ""
+
+print_step "Test: --output-format json"
+if timeout 10 cargo run -q --bin dns_client -- "google.com" --server "127.0.0.1:5353" --output-format json > /tmp/dns_json_test.txt 2>&1 \
+    && python3 -c '
+import json, sys
+r = json.load(open("/tmp/dns_json_test.txt"))
+assert isinstance(r["domain"], str) and isinstance(r["query_type"], str) and isinstance(r["rcode"], str)
+assert isinstance(r["response_time_ms"], float)
+a = r["answers"][0]
+assert isinstance(a["name"], str) and isinstance(a["type"], str) and isinstance(a["ttl"], int)
+assert a["address"] == "8.8.8.8"
+' 2>/dev/null; then
+    print_success "✓ JSON valide avec les types attendus"
+else
+    print_error "Sortie JSON invalide"
+    cat /tmp/dns_json_test.txt
+fi
+echo ""
+
 # Test de performance
 print_step "=== TEST DE PERFORMANCE ==="
 echo ""
@@ -213,7 +253,7 @@ echo ""
 
 # Nettoyage
 print_step "Nettoyage des fichiers temporaires..."
-rm -f /tmp/dns_test_output.txt /tmp/dns_ns_test.txt /tmp/perf_test_*.txt /tmp/concurrent_*.txt /tmp/public_comparison.txt
+rm -f /tmp/dns_test_output.txt /tmp/dns_ns_test.txt /tmp/dns_tcp_test.txt /tmp/dns_json_test.txt /tmp/perf_test_*.txt /tmp/concurrent_*.txt /tmp/public_comparison.txt
 print_success "Nettoyage terminé"
 
 echo ""
@@ -223,6 +263,8 @@ print_success "✅ Compilation et démarrage du serveur"
 print_success "✅ Résolution de domaines locaux"
 print_success "✅ Gestion des domaines inexistants (NXDOMAIN)"
 print_success "✅ Rejet des types non supportés (NOTIMP)"
+print_success "✅ Requêtes DNS sur TCP"
+print_success "✅ Sortie JSON"
 print_success "✅ Test de performance"
 print_success "✅ Test de concurrence"
 print_success "✅ Comparaison avec DNS publics"
@@ -0,0 +1,139 @@
+// Tests du cache de réponses : expiration TTL, éviction LRU et compteurs
+use std::net::Ipv4Addr;
+use std::thread;
+use std::time::Duration;
+
+use dns_client_server::{CacheStats, DnsCache, DnsRecord, DnsRecordType, NegativeAnswer};
+
+fn a_record(name: &str, last_octet: u8, ttl: u32) -> Vec<DnsRecord> {
+    vec![DnsRecord::new_a_record(name.to_string(), Ipv4Addr::new(10, 0, 0, last_octet), ttl)]
+}
+
+#[test]
+fn get_returns_inserted_records_case_insensitively() {
+    let mut cache = DnsCache::new(10);
+    cache.insert("Example.COM", DnsRecordType::A, a_record("example.com", 1, 300), 300);
+
+    let records = cache.get("example.com", DnsRecordType::A).unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].get_ip(), Some(Ipv4Addr::new(10, 0, 0, 1)));
+    assert!(records[0].ttl <= 300);
+
+    // Le type fait partie de la clé
+    assert!(cache.get("example.com", DnsRecordType::AAAA).is_none());
+}
+
+#[test]
+fn entries_expire_after_their_ttl() {
+    let mut cache = DnsCache::new(10);
+    cache.insert("short.local", DnsRecordType::A, a_record("short.local", 1, 1), 1);
+    cache.insert("long.local", DnsRecordType::A, a_record("long.local", 2, 60), 60);
+    assert!(cache.get("short.local", DnsRecordType::A).is_some());
+
+    thread::sleep(Duration::from_millis(1100));
+
+    assert!(cache.get("short.local", DnsRecordType::A).is_none());
+    // Le TTL restant est décompté sur les réponses servies depuis le cache
+    let remaining = cache.get("long.local", DnsRecordType::A).unwrap()[0].ttl;
+    assert!((58..60).contains(&remaining), "TTL restant {}", remaining);
+
+    assert_eq!(cache.evict_expired(), 1);
+    assert_eq!(cache.stats().size, 1);
+}
+
+#[test]
+fn zero_ttl_or_capacity_is_not_cached() {
+    let mut cache = DnsCache::new(10);
+    cache.insert("nocache.local", DnsRecordType::A, a_record("nocache.local", 1, 0), 0);
+    assert_eq!(cache.stats().size, 0);
+
+    let mut disabled = DnsCache::new(0);
+    disabled.insert("a.local", DnsRecordType::A, a_record("a.local", 1, 60), 60);
+    assert!(disabled.get("a.local", DnsRecordType::A).is_none());
+}
+
+#[test]
+fn least_recently_used_entry_is_evicted_at_capacity() {
+    let mut cache = DnsCache::new(3);
+    for (octet, name) in ["a.local", "b.local", "c.local"].iter().enumerate() {
+        cache.insert(name, DnsRecordType::A, a_record(name, octet as u8, 60), 60);
+    }
+
+    // "a" est relu : "b" devient la moins récemment utilisée
+    assert!(cache.get("a.local", DnsRecordType::A).is_some());
+    cache.insert("d.local", DnsRecordType::A, a_record("d.local", 4, 60), 60);
+
+    assert_eq!(cache.stats().size, 3);
+    assert!(cache.get("b.local", DnsRecordType::A).is_none());
+    for name in ["a.local", "c.local", "d.local"] {
+        assert!(cache.get(name, DnsRecordType::A).is_some(), "{} évincé à tort", name);
+    }
+
+    // Remplacer une clé existante ne fait rien sortir
+    cache.insert("c.local", DnsRecordType::A, a_record("c.local", 9, 60), 60);
+    assert_eq!(cache.stats().size, 3);
+    assert_eq!(cache.get("c.local", DnsRecordType::A).unwrap()[0].get_ip(), Some(Ipv4Addr::new(10, 0, 0, 9)));
+}
+
+#[test]
+fn expired_entries_are_evicted_before_lru() {
+    let mut cache = DnsCache::new(2);
+    cache.insert("old.local", DnsRecordType::A, a_record("old.local", 1, 1), 1);
+    cache.insert("kept.local", DnsRecordType::A, a_record("kept.local", 2, 60), 60);
+    thread::sleep(Duration::from_millis(1100));
+
+    // "kept" est la moins récemment utilisée, mais "old" a expiré
+    cache.insert("new.local", DnsRecordType::A, a_record("new.local", 3, 60), 60);
+    assert!(cache.get("kept.local", DnsRecordType::A).is_some());
+    assert!(cache.get("new.local", DnsRecordType::A).is_some());
+}
+
+#[test]
+fn hits_and_misses_are_counted() {
+    let mut cache = DnsCache::new(10);
+    assert_eq!(cache.stats(), CacheStats { hits: 0, misses: 0, size: 0, negative_size: 0 });
+
+    assert!(cache.get("example.com", DnsRecordType::A).is_none());
+    cache.insert("example.com", DnsRecordType::A, a_record("example.com", 1, 60), 60);
+    for _ in 0..3 {
+        assert!(cache.get("example.com", DnsRecordType::A).is_some());
+    }
+    assert!(cache.get("missing.com", DnsRecordType::A).is_none());
+
+    assert_eq!(cache.stats(), CacheStats { hits: 3, misses: 2, size: 1, negative_size: 0 });
+
+    // Vider le cache garde les compteurs
+    cache.clear();
+    assert_eq!(cache.stats(), CacheStats { hits: 3, misses: 2, size: 0, negative_size: 0 });
+}
+
+#[test]
+fn negative_entries_expire_after_negative_ttl() {
+    let mut cache = DnsCache::new(10).with_negative_ttl(1);
+    cache.insert_nxdomain("Missing.local");
+    cache.insert_nodata("Known.local", DnsRecordType::TXT);
+
+    // NXDOMAIN vaut pour tous les types, NODATA seulement pour le type mémorisé
+    assert_eq!(cache.get_negative("missing.local", DnsRecordType::A), Some(NegativeAnswer::NxDomain));
+    assert_eq!(cache.get_negative("missing.local", DnsRecordType::AAAA), Some(NegativeAnswer::NxDomain));
+    assert_eq!(cache.get_negative("known.local", DnsRecordType::TXT), Some(NegativeAnswer::NoData));
+    assert_eq!(cache.get_negative("known.local", DnsRecordType::A), None);
+    assert_eq!(cache.stats().negative_size, 2);
+
+    thread::sleep(Duration::from_millis(1100));
+
+    assert_eq!(cache.get_negative("missing.local", DnsRecordType::A), None);
+    assert_eq!(cache.get_negative("known.local", DnsRecordType::TXT), None);
+    assert_eq!(cache.evict_expired(), 2);
+    assert_eq!(cache.stats().negative_size, 0);
+}
+
+#[test]
+fn negative_ttl_zero_disables_negative_caching() {
+    let mut cache = DnsCache::new(10).with_negative_ttl(0);
+    cache.insert_nxdomain("missing.local");
+    cache.insert_nodata("known.local", DnsRecordType::TXT);
+    assert_eq!(cache.get_negative("missing.local", DnsRecordType::A), None);
+    assert_eq!(cache.get_negative("known.local", DnsRecordType::TXT), None);
+    assert_eq!(cache.stats().negative_size, 0);
+}
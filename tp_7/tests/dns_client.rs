@@ -0,0 +1,300 @@
+// Tests du client DNS contre `MockDnsServer`, sans accès au réseau
+use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+use std::process::{Command, Output};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use dns_client_server::{DnsMessage, DnsMessageBuilder, DnsRecord, DnsRecordType, MockDnsServer};
+use serde_json::Value;
+
+// Lancer dns_client en JSON contre le faux serveur, sans nouvelle tentative
+fn run_client(server: &MockDnsServer, domain: &str, extra_args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_dns_client"))
+        .arg(domain)
+        .args(["--server", &server.addr().to_string()])
+        .args(["--output-format", "json", "--retries", "0"])
+        .args(extra_args)
+        .output()
+        .expect("impossible de lancer dns_client")
+}
+
+fn json_output(output: &Output) -> Value {
+    assert!(output.status.success(), "dns_client a échoué: {}", String::from_utf8_lossy(&output.stderr));
+    serde_json::from_slice(&output.stdout).expect("sortie JSON invalide")
+}
+
+#[test]
+fn parses_a_record_response() {
+    let mut response = DnsMessage::new();
+    response.answers.push(DnsRecord::new_a_record("example.com".to_string(), Ipv4Addr::new(93, 184, 216, 34), 300));
+    response.header.ancount = 1;
+    let server = MockDnsServer::start(Some(response)).unwrap();
+
+    let result = json_output(&run_client(&server, "example.com", &[]));
+
+    assert_eq!(result["rcode"], "NOERROR");
+    assert_eq!(result["answers"][0]["address"], "93.184.216.34");
+    assert_eq!(result["answers"][0]["ttl"], 300);
+
+    let query = server.received_query().unwrap();
+    assert_eq!(query.questions[0].name, "example.com");
+    assert_eq!(query.questions[0].qtype, DnsRecordType::A);
+}
+
+#[test]
+fn reports_nxdomain() {
+    let mut response = DnsMessage::new();
+    response.header.rcode = 3;
+    let server = MockDnsServer::start(Some(response)).unwrap();
+
+    let result = json_output(&run_client(&server, "inexistant.test", &[]));
+
+    assert_eq!(result["rcode"], "NXDOMAIN");
+    assert_eq!(result["answers"].as_array().unwrap().len(), 0);
+}
+
+#[test]
+fn parses_cname_chain() {
+    let mut response = DnsMessage::new();
+    response.answers.push(DnsRecord::new_cname_record("www.example.com".to_string(), "example.com".to_string(), 300));
+    response.answers.push(DnsRecord::new_a_record("example.com".to_string(), Ipv4Addr::new(10, 0, 0, 1), 300));
+    response.header.ancount = 2;
+    let server = MockDnsServer::start(Some(response)).unwrap();
+
+    let result = json_output(&run_client(&server, "www.example.com", &[]));
+
+    assert_eq!(result["answers"][0]["type"], "CNAME");
+    assert_eq!(result["answers"][0]["address"], "CNAME example.com");
+    assert_eq!(result["answers"][1]["name"], "example.com");
+    assert_eq!(result["answers"][1]["address"], "10.0.0.1");
+}
+
+#[test]
+fn times_out_without_response() {
+    let server = MockDnsServer::start(None).unwrap();
+
+    let output = run_client(&server, "example.com", &["--timeout", "200"]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Timeout après 200ms"));
+    server.received_query().unwrap();
+}
+
+// Faux serveur qui ignore les `dropped` premiers paquets puis répond au suivant
+fn start_lossy_server(dropped: usize, answer: Ipv4Addr) -> (SocketAddr, JoinHandle<Vec<DnsMessage>>) {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let addr = socket.local_addr().unwrap();
+    let handle = thread::spawn(move || {
+        let mut queries = Vec::new();
+        let mut buffer = [0u8; 512];
+        loop {
+            let (size, client) = socket.recv_from(&mut buffer).unwrap();
+            let query = DnsMessage::from_bytes(&buffer[..size]).unwrap();
+            queries.push(query.clone());
+            if queries.len() > dropped {
+                let mut response = DnsMessage::new_response(&query);
+                response.answers.push(DnsRecord::new_a_record(query.questions[0].name.clone(), answer, 300));
+                response.header.ancount = 1;
+                socket.send_to(&response.to_bytes(), client).unwrap();
+                return queries;
+            }
+        }
+    });
+    (addr, handle)
+}
+
+// Faux serveur muet : compte les requêtes reçues jusqu'à une seconde de silence
+fn start_silent_server() -> (SocketAddr, JoinHandle<usize>) {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket.set_read_timeout(Some(Duration::from_secs(1))).unwrap();
+    let addr = socket.local_addr().unwrap();
+    let handle = thread::spawn(move || {
+        let mut buffer = [0u8; 512];
+        let mut received = 0;
+        while socket.recv(&mut buffer).is_ok() {
+            received += 1;
+        }
+        received
+    });
+    (addr, handle)
+}
+
+#[test]
+fn retries_after_dropped_packets() {
+    let (addr, handle) = start_lossy_server(2, Ipv4Addr::new(10, 0, 0, 3));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_dns_client"))
+        .arg("example.com")
+        .args(["--server", &addr.to_string()])
+        .args(["--output-format", "json", "--timeout", "200", "--retries", "2", "--backoff-ms", "50"])
+        .output()
+        .expect("impossible de lancer dns_client");
+
+    let result = json_output(&output);
+    assert_eq!(result["answers"][0]["address"], "10.0.0.3");
+
+    // Trois envois, chacun avec un nouvel ID de transaction (RFC 5452)
+    let queries = handle.join().unwrap();
+    assert_eq!(queries.len(), 3);
+    assert!(queries.iter().all(|query| query.questions[0].name == "example.com"));
+    assert_ne!(queries[0].header.id, queries[1].header.id);
+    assert_ne!(queries[1].header.id, queries[2].header.id);
+}
+
+#[test]
+fn gives_up_when_retries_are_exhausted() {
+    let (addr, handle) = start_silent_server();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_dns_client"))
+        .arg("example.com")
+        .args(["--server", &addr.to_string()])
+        .args(["--timeout", "100", "--retries", "1", "--backoff-ms", "10"])
+        .output()
+        .expect("impossible de lancer dns_client");
+
+    assert!(!output.status.success());
+    assert_eq!(handle.join().unwrap(), 2);
+}
+
+// Fichier de lot temporaire, supprimé à la fin du test
+struct BatchFile(std::path::PathBuf);
+
+impl BatchFile {
+    fn new(name: &str, content: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("dns_batch_{}_{}.txt", name, std::process::id()));
+        std::fs::write(&path, content).unwrap();
+        BatchFile(path)
+    }
+}
+
+impl Drop for BatchFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+fn run_batch(server: &MockDnsServer, file: &BatchFile, extra_args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_dns_client"))
+        .args(["--batch-file", file.0.to_str().unwrap()])
+        .args(["--server", &server.addr().to_string()])
+        .args(["--output-format", "json", "--retries", "0"])
+        .args(extra_args)
+        .output()
+        .expect("impossible de lancer dns_client")
+}
+
+#[test]
+fn batch_queries_fifty_domains() {
+    let mut response = DnsMessage::new();
+    response.answers.push(DnsRecord::new_a_record("example.com".to_string(), Ipv4Addr::new(10, 0, 0, 1), 60));
+    response.header.ancount = 1;
+    let server = MockDnsServer::start_many(Some(response), 50).unwrap();
+
+    let mut content = String::from("# audit\n\n");
+    for i in 0..50 {
+        let qtype = if i % 5 == 0 { " TXT" } else { "" };
+        content.push_str(&format!("host{}.example.com{}\n", i, qtype));
+    }
+    let file = BatchFile::new("fifty", &content);
+
+    let results = json_output(&run_batch(&server, &file, &["--concurrent", "8"]));
+
+    // Tableau dans l'ordre du fichier, quel que soit l'ordre des réponses
+    let results = results.as_array().expect("tableau JSON attendu");
+    assert_eq!(results.len(), 50);
+    for (i, result) in results.iter().enumerate() {
+        assert_eq!(result["domain"], format!("host{}.example.com", i));
+        assert_eq!(result["query_type"], if i % 5 == 0 { "TXT" } else { "A" });
+        assert_eq!(result["rcode"], "NOERROR");
+        assert_eq!(result["answers"][0]["address"], "10.0.0.1");
+        assert!(result.get("error").is_none());
+    }
+
+    let mut queried: Vec<String> = server.received_queries().unwrap().iter()
+        .map(|query| query.questions[0].name.clone())
+        .collect();
+    queried.sort();
+    let mut expected: Vec<String> = (0..50).map(|i| format!("host{}.example.com", i)).collect();
+    expected.sort();
+    assert_eq!(queried, expected);
+}
+
+#[test]
+fn batch_rejects_invalid_line_before_querying() {
+    let server = MockDnsServer::start(None).unwrap();
+    let file = BatchFile::new("invalid", "example.com\nexample.com BOGUS\n");
+
+    let output = run_batch(&server, &file, &[]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains(":2: Type de requête non supporté: BOGUS"));
+}
+
+// Référence vers `zone`, servie par `nameserver` dont l'adresse est donnée en glue
+fn referral(zone: &str, nameserver: &str, glue: Ipv4Addr) -> DnsMessage {
+    DnsMessageBuilder::new()
+        .authority(DnsRecord::new_ns_record(zone.to_string(), nameserver.to_string(), 172_800))
+        .additional(DnsRecord::new_a_record(nameserver.to_string(), glue, 172_800))
+        .build()
+}
+
+#[test]
+fn trace_follows_two_delegation_levels() {
+    // Racine sur 127.0.0.1, TLD sur 127.0.0.2, serveur faisant autorité sur 127.0.0.3, même port
+    let root = MockDnsServer::start_many(Some(referral("com", "a.gtld.test", Ipv4Addr::new(127, 0, 0, 2))), 1).unwrap();
+    let port = root.port();
+    let tld = MockDnsServer::start_at(
+        (Ipv4Addr::new(127, 0, 0, 2), port).into(),
+        Some(referral("example.com", "ns1.example.com", Ipv4Addr::new(127, 0, 0, 3))),
+        1,
+    ).unwrap();
+    let answer = DnsMessageBuilder::new()
+        .answer(DnsRecord::new_a_record("www.example.com".to_string(), Ipv4Addr::new(93, 184, 216, 34), 300))
+        .build();
+    let authoritative = MockDnsServer::start_at((Ipv4Addr::new(127, 0, 0, 3), port).into(), Some(answer), 1).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_dns_client"))
+        .args(["www.example.com", "--trace", "--root-server", &root.addr().to_string()])
+        .args(["--output-format", "json", "--retries", "0", "--timeout", "2000"])
+        .output()
+        .expect("impossible de lancer dns_client");
+    let steps = json_output(&output);
+
+    let steps = steps.as_array().expect("tableau JSON attendu");
+    assert_eq!(steps.len(), 3);
+    assert_eq!(steps[0]["zone"], ".");
+    assert_eq!(steps[0]["referral"], "com");
+    assert_eq!(steps[0]["records"], serde_json::json!(["com 172800 NS a.gtld.test", "a.gtld.test 172800 A 127.0.0.2"]));
+    assert_eq!(steps[1]["zone"], "com");
+    assert_eq!(steps[1]["server"], "a.gtld.test");
+    assert_eq!(steps[1]["address"], format!("127.0.0.2:{}", port));
+    assert_eq!(steps[1]["referral"], "example.com");
+    assert_eq!(steps[2]["zone"], "example.com");
+    assert_eq!(steps[2]["server"], "ns1.example.com");
+    assert_eq!(steps[2]["rcode"], "NOERROR");
+    assert!(steps[2]["referral"].is_null());
+    assert_eq!(steps[2]["records"], serde_json::json!(["www.example.com 300 A 93.184.216.34"]));
+
+    // Requêtes itératives : récursion non demandée, même question à chaque niveau
+    for server in [root, tld, authoritative] {
+        let query = server.received_query().unwrap();
+        assert!(!query.header.rd);
+        assert_eq!(query.questions[0].name, "www.example.com");
+    }
+}
+
+#[test]
+fn trace_fails_when_root_does_not_answer() {
+    let root = MockDnsServer::start(None).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_dns_client"))
+        .args(["example.com", "--trace", "--root-server", &root.addr().to_string()])
+        .args(["--output-format", "json", "--retries", "0", "--timeout", "200"])
+        .output()
+        .expect("impossible de lancer dns_client");
+
+    assert!(!output.status.success());
+    let steps: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(steps[0]["zone"], ".");
+    assert!(steps[0]["error"].as_str().unwrap().contains("Timeout après 200ms"));
+}
@@ -0,0 +1,787 @@
+// Tests du serveur DNS lancé en sous-processus avec sa base par défaut
+use std::collections::HashSet;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream, UdpSocket};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use dns_client_server::{
+    DnsClass, DnsMessage, DnsMessageBuilder, DnsOpcode, DnsRecord, DnsRecordType, MockDnsServer, QueryLogEntry,
+    SimpleDnsDatabase, SoaData, PERMANENT_TTL,
+};
+
+// Port distinct par test, les tests tournent en parallèle
+const BASE_PORT: u16 = 15370;
+
+// Serveur arrêté à la fin du test, même en cas d'échec
+struct ServerProcess(Child);
+
+impl ServerProcess {
+    fn start(port: u16) -> Self {
+        Self::start_with_args(port, &[])
+    }
+
+    fn start_with_args(port: u16, extra_args: &[&str]) -> Self {
+        Self::start_rate_limited(port, &[&["--rate-limit", "0"], extra_args].concat())
+    }
+
+    // Sans désactiver la limite de débit par défaut (100 requêtes/s par IP)
+    fn start_rate_limited(port: u16, extra_args: &[&str]) -> Self {
+        let child = Command::new(env!("CARGO_BIN_EXE_dns_server"))
+            .args(["--port", &port.to_string()])
+            .args(["--no-tcp", "--stats-interval", "0"])
+            .args(extra_args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("impossible de lancer dns_server");
+        ServerProcess(child)
+    }
+}
+
+impl Drop for ServerProcess {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+// Envoyer la requête jusqu'à ce que le serveur soit prêt, renvoyer la réponse brute
+fn query(port: u16, domain: &str, qtype: DnsRecordType) -> (DnsMessage, Vec<u8>) {
+    query_from("127.0.0.1", port, domain, qtype)
+}
+
+// Même chose depuis une adresse source donnée (127.0.0.x, pour les ACL)
+fn query_from(source: &str, port: u16, domain: &str, qtype: DnsRecordType) -> (DnsMessage, Vec<u8>) {
+    let socket = UdpSocket::bind((source, 0)).unwrap();
+    socket.set_read_timeout(Some(Duration::from_millis(200))).unwrap();
+    socket.connect(("127.0.0.1", port)).unwrap();
+
+    let request = DnsMessage::new_query(domain.to_string(), qtype).to_bytes();
+    let mut buffer = [0u8; 512];
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while Instant::now() < deadline {
+        socket.send(&request).unwrap();
+        if let Ok(len) = socket.recv(&mut buffer) {
+            let raw = buffer[..len].to_vec();
+            return (DnsMessage::from_bytes(&raw).expect("réponse invalide"), raw);
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    panic!("dns_server ne répond pas sur le port {}", port);
+}
+
+// Envoyer un paquet tel quel, une fois le serveur prêt
+fn send_raw(port: u16, packet: &[u8]) -> DnsMessage {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+    socket.send_to(packet, ("127.0.0.1", port)).unwrap();
+    let mut buffer = [0u8; 512];
+    let len = socket.recv(&mut buffer).expect("pas de réponse");
+    DnsMessage::from_bytes(&buffer[..len]).unwrap()
+}
+
+#[test]
+fn answers_ns_query_with_configured_nameservers() {
+    let port = BASE_PORT;
+    let _server = ServerProcess::start(port);
+
+    let (response, raw) = query(port, "example.com", DnsRecordType::NS);
+
+    assert_eq!(response.header.rcode, 0);
+    assert!(response.header.aa);
+    let mut nameservers: Vec<String> = response.answers.iter().filter_map(|record| record.get_ns(&raw)).collect();
+    nameservers.sort();
+    assert_eq!(nameservers, ["ns1.example.com", "ns2.example.com"]);
+    // Déjà dans la réponse : pas de doublon dans l'autorité
+    assert!(response.authorities.is_empty());
+}
+
+#[test]
+fn adds_zone_nameservers_to_authority_section() {
+    let port = BASE_PORT + 1;
+    let _server = ServerProcess::start(port);
+
+    let (response, raw) = query(port, "example.com", DnsRecordType::A);
+
+    assert_eq!(response.header.rcode, 0);
+    assert_eq!(response.header.nscount, 2);
+    for record in &response.authorities {
+        assert_eq!(record.name, "example.com");
+        assert!(record.get_ns(&raw).is_some());
+    }
+}
+
+#[test]
+fn nxdomain_carries_zone_soa() {
+    let port = BASE_PORT + 2;
+    let _server = ServerProcess::start(port);
+
+    let (response, raw) = query(port, "inexistant.example.com", DnsRecordType::A);
+
+    assert_eq!(response.header.rcode, 3);
+    assert_eq!(response.authorities.len(), 1);
+    let soa = response.authorities[0].get_soa(&raw).expect("SOA attendu dans l'autorité");
+    assert_eq!(soa.mname, "ns1.example.com");
+}
+
+#[test]
+fn existing_name_without_the_type_is_nodata() {
+    let port = BASE_PORT + 19;
+    let _server = ServerProcess::start(port);
+
+    // Le nom n'a qu'un enregistrement SRV : pas d'adresse, mais il existe
+    let (response, raw) = query(port, "_http._tcp.example.com", DnsRecordType::A);
+
+    assert_eq!(response.header.rcode, 0);
+    assert!(response.answers.is_empty());
+    assert_eq!(response.authorities.len(), 1);
+    let soa = response.authorities[0].get_soa(&raw).expect("SOA attendu dans l'autorité");
+    assert_eq!(soa.mname, "ns1.example.com");
+
+    // Même règle pour les autres types : test.local n'a qu'une adresse
+    let (response, _) = query(port, "test.local", DnsRecordType::TXT);
+    assert_eq!(response.header.rcode, 0);
+    assert!(response.answers.is_empty());
+
+    let (response, _) = query(port, "absent.local", DnsRecordType::TXT);
+    assert_eq!(response.header.rcode, 3);
+}
+
+#[test]
+fn answers_caa_query() {
+    let port = BASE_PORT + 3;
+    let _server = ServerProcess::start(port);
+
+    let (response, _) = query(port, "example.com", DnsRecordType::CAA);
+
+    assert_eq!(response.header.rcode, 0);
+    let entries: Vec<(String, String)> = response.answers.iter()
+        .filter_map(|record| record.get_caa())
+        .map(|caa| (caa.tag, caa.value))
+        .collect();
+    assert_eq!(entries, [
+        ("issue".to_string(), "letsencrypt.org".to_string()),
+        ("issuewild".to_string(), ";".to_string()),
+    ]);
+}
+
+#[test]
+fn invalid_header_gets_formerr() {
+    let port = BASE_PORT + 4;
+    let _server = ServerProcess::start(port);
+    // Attendre que le serveur réponde avant d'envoyer la requête invalide
+    query(port, "example.com", DnsRecordType::A);
+
+    let mut request = DnsMessage::new_query("example.com".to_string(), DnsRecordType::A);
+    request.header.z = 1;
+    let response = send_raw(port, &request.to_bytes());
+    assert_eq!(response.header.rcode, 1);
+    assert_eq!(response.header.id, request.header.id);
+    assert!(response.answers.is_empty());
+}
+
+#[test]
+fn strict_names_rejects_invalid_utf8() {
+    let port = BASE_PORT + 5;
+    let _server = ServerProcess::start_with_args(port, &["--strict-names"]);
+    query(port, "example.com", DnsRecordType::A);
+
+    // "ex\xFFmple.com" : octet invalide en UTF-8 dans le premier label
+    let mut packet = DnsMessage::new_query("example.com".to_string(), DnsRecordType::A).to_bytes();
+    packet[15] = 0xFF;
+
+    let response = send_raw(port, &packet);
+    assert_eq!(response.header.rcode, 1);
+}
+
+// Requête CHAOS TXT, comme `dig @serveur version.bind CH TXT`
+fn chaos_query(port: u16, name: &str) -> DnsMessage {
+    let mut request = DnsMessage::new_query(name.to_string(), DnsRecordType::TXT);
+    request.questions[0].qclass = DnsClass::CH;
+    send_raw(port, &request.to_bytes())
+}
+
+#[test]
+fn chaos_version_bind_returns_configured_version() {
+    let port = BASE_PORT + 6;
+    let _server = ServerProcess::start_with_args(port, &["--server-version", "test-dns 1.2.3"]);
+    query(port, "example.com", DnsRecordType::A);
+
+    let response = chaos_query(port, "version.bind");
+    assert_eq!(response.header.rcode, 0);
+    assert_eq!(response.answers.len(), 1);
+    assert_eq!(response.answers[0].class, DnsClass::CH);
+    assert_eq!(response.answers[0].get_txt(), Some(vec!["test-dns 1.2.3".to_string()]));
+
+    for name in ["hostname.bind", "id.server"] {
+        let response = chaos_query(port, name);
+        assert_eq!(response.header.rcode, 0);
+        assert!(response.answers[0].get_txt().is_some());
+    }
+
+    // Noms CHAOS inconnus refusés
+    assert_eq!(chaos_query(port, "example.com").header.rcode, 5);
+}
+
+#[test]
+fn any_query_returns_records_of_every_type() {
+    let port = BASE_PORT + 7;
+    let _server = ServerProcess::start(port);
+
+    let (response, _) = query(port, "example.com", DnsRecordType::ANY);
+
+    assert_eq!(response.header.rcode, 0);
+    let ips: Vec<_> = response.answers.iter().filter_map(|record| record.get_ip()).collect();
+    assert_eq!(ips, ["93.184.216.34".parse::<std::net::Ipv4Addr>().unwrap()]);
+    let txt: Vec<_> = response.answers.iter().filter_map(|record| record.get_txt()).collect();
+    assert_eq!(txt, [vec!["v=spf1 -all".to_string()]]);
+    assert!(response.answers.iter().any(|record| record.rtype == DnsRecordType::CAA));
+
+    let (response, _) = query(port, "inexistant.example.com", DnsRecordType::ANY);
+    assert_eq!(response.header.rcode, 3);
+}
+
+#[test]
+fn any_query_with_rfc8482_returns_single_hinfo() {
+    let port = BASE_PORT + 8;
+    let _server = ServerProcess::start_with_args(port, &["--rfc8482"]);
+
+    let (response, _) = query(port, "example.com", DnsRecordType::ANY);
+
+    assert_eq!(response.header.rcode, 0);
+    assert_eq!(response.answers.len(), 1);
+    assert_eq!(response.answers[0].get_hinfo(), Some(("RFC8482".to_string(), String::new())));
+}
+
+#[test]
+fn query_log_lines_parse_back() {
+    let port = BASE_PORT + 9;
+    let log_path = std::env::temp_dir().join(format!("dns_query_log_{}.log", std::process::id()));
+    let _ = std::fs::remove_file(&log_path);
+    let _server = ServerProcess::start_with_args(port, &["--query-log", log_path.to_str().unwrap()]);
+
+    let queries = [
+        ("example.com", DnsRecordType::A),
+        ("example.com", DnsRecordType::TXT),
+        ("www.example.com", DnsRecordType::CNAME),
+        ("example.com", DnsRecordType::NS),
+        ("inexistant.example.com", DnsRecordType::A),
+    ];
+    for (domain, qtype) in queries {
+        query(port, domain, qtype);
+    }
+
+    // Les lignes sont écrites par une tâche de fond
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let lines = loop {
+        let content = std::fs::read_to_string(&log_path).unwrap_or_default();
+        let lines: Vec<String> = content.lines().map(str::to_string).collect();
+        if lines.len() >= queries.len() || Instant::now() > deadline {
+            break lines;
+        }
+        thread::sleep(Duration::from_millis(50));
+    };
+    let _ = std::fs::remove_file(&log_path);
+
+    let entries: Vec<QueryLogEntry> = lines.iter()
+        .map(|line| QueryLogEntry::parse(line).unwrap_or_else(|e| panic!("{}", e)))
+        .collect();
+    assert_eq!(entries.len(), queries.len());
+    for (entry, (domain, qtype)) in entries.iter().zip(queries) {
+        assert_eq!(entry.client_ip.to_string(), "127.0.0.1");
+        assert_eq!(entry.domain, domain);
+        assert_eq!(entry.qtype, qtype);
+        assert!(entry.latency_us < 1_000_000);
+    }
+    let rcodes: Vec<u8> = entries.iter().map(|entry| entry.rcode).collect();
+    assert_eq!(rcodes, [0, 0, 0, 0, 3]);
+    assert!(lines[4].contains(" NXDOMAIN "));
+    assert!(entries.windows(2).all(|pair| pair[0].timestamp_ms <= pair[1].timestamp_ms));
+}
+
+#[test]
+fn acl_file_gives_internal_clients_their_own_zone() {
+    let port = BASE_PORT + 10;
+    let dir = std::env::temp_dir().join(format!("dns_acl_server_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("internal.zone"), "$ORIGIN example.com.\n@ IN A 10.0.0.1\n").unwrap();
+    std::fs::write(dir.join("named.acl"), r#"
+        acl "internal" { 127.0.0.2; };
+        zone "example.com" { match-clients { internal; }; file "internal.zone"; };
+    "#).unwrap();
+    let acl_file = dir.join("named.acl");
+    let _server = ServerProcess::start_with_args(port, &["--acl-file", acl_file.to_str().unwrap()]);
+
+    // Requêtes répétées : le cache ne doit pas mélanger les deux vues
+    for _ in 0..2 {
+        let (external, _) = query_from("127.0.0.1", port, "example.com", DnsRecordType::A);
+        assert_eq!(external.answers[0].get_ip(), Some("93.184.216.34".parse().unwrap()));
+
+        let (internal, _) = query_from("127.0.0.2", port, "example.com", DnsRecordType::A);
+        assert_eq!(internal.answers[0].get_ip(), Some("10.0.0.1".parse().unwrap()));
+    }
+
+    // Noms hors des zones routées : base principale pour tous
+    let (internal, _) = query_from("127.0.0.2", port, "google.com", DnsRecordType::A);
+    assert_eq!(internal.answers[0].get_ip(), Some("8.8.8.8".parse().unwrap()));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// Secondaire simulé : demander la zone en AXFR sur TCP et lire les messages jusqu'au SOA final
+fn transfer_zone(port: u16, zone: &str) -> Vec<DnsMessage> {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    stream.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+    let request = DnsMessage::new_query(zone.to_string(), DnsRecordType::AXFR);
+    let bytes = request.to_bytes();
+    stream.write_all(&(bytes.len() as u16).to_be_bytes()).unwrap();
+    stream.write_all(&bytes).unwrap();
+
+    let mut messages = Vec::new();
+    loop {
+        let mut length = [0u8; 2];
+        stream.read_exact(&mut length).unwrap();
+        let mut data = vec![0u8; u16::from_be_bytes(length) as usize];
+        stream.read_exact(&mut data).unwrap();
+        let message = DnsMessage::from_bytes(&data).unwrap();
+        assert_eq!(message.header.id, request.header.id);
+
+        let done = message.header.rcode != 0
+            || (!messages.is_empty() && message.answers.first().is_some_and(|record| record.rtype == DnsRecordType::SOA));
+        messages.push(message);
+        if done {
+            return messages;
+        }
+    }
+}
+
+#[test]
+fn axfr_sends_the_whole_zone_to_allowed_secondary() {
+    let port = BASE_PORT + 11;
+    let _server = ServerProcess::start_with_args(port, &["--tcp", "--allow-axfr", "127.0.0.0/8"]);
+    query(port, "example.com", DnsRecordType::A);
+
+    let messages = transfer_zone(port, "example.com");
+
+    assert_eq!(messages[0].questions[0].qtype, DnsRecordType::AXFR);
+    assert_eq!(messages[0].answers[0].rtype, DnsRecordType::SOA);
+    let records: Vec<_> = messages[1..messages.len() - 1].iter().flat_map(|message| message.answers.clone()).collect();
+    assert_eq!(records, SimpleDnsDatabase::new().zone_records("example.com", 300));
+    assert!(records.iter().any(|record| record.rtype == DnsRecordType::CAA));
+
+    // AXFR uniquement en TCP
+    assert_eq!(query(port, "example.com", DnsRecordType::AXFR).0.header.rcode, 5);
+}
+
+#[test]
+fn axfr_refused_to_other_clients() {
+    let port = BASE_PORT + 12;
+    let _server = ServerProcess::start_with_args(port, &["--tcp", "--allow-axfr", "10.0.0.2"]);
+    query(port, "example.com", DnsRecordType::A);
+
+    let messages = transfer_zone(port, "example.com");
+
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].header.rcode, 5);
+    assert!(messages[0].answers.is_empty());
+}
+
+// Primaire simulé : la n-ième connexion AXFR reçoit la zone au serial n, où www pointe vers `versions[n - 1]`
+fn start_primary(versions: Vec<Ipv4Addr>) -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    thread::spawn(move || {
+        for (serial, ip) in (1..).zip(versions) {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut length = [0u8; 2];
+            stream.read_exact(&mut length).unwrap();
+            let mut data = vec![0u8; u16::from_be_bytes(length) as usize];
+            stream.read_exact(&mut data).unwrap();
+            let query = DnsMessage::from_bytes(&data).unwrap();
+
+            let soa = SoaData {
+                mname: "ns1.example.com".to_string(),
+                rname: "hostmaster.example.com".to_string(),
+                serial,
+                refresh: 3600,
+                retry: 600,
+                expire: 86400,
+                minimum: 300,
+            };
+            let soa = DnsRecord::new_soa_record("example.com".to_string(), &soa, 300);
+            let records = [DnsRecord::new_a_record("www.example.com".to_string(), ip, 300)];
+            for mut message in DnsMessage::new_axfr_response(&records, &soa) {
+                message.header.id = query.header.id;
+                let bytes = message.to_bytes();
+                stream.write_all(&(bytes.len() as u16).to_be_bytes()).unwrap();
+                stream.write_all(&bytes).unwrap();
+            }
+        }
+    });
+    port
+}
+
+// Attendre que www.example.com pointe vers `expected` (le transfert est asynchrone)
+fn wait_for_address(port: u16, expected: Ipv4Addr) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        let (response, _) = query(port, "www.example.com", DnsRecordType::A);
+        if response.answers.first().and_then(|record| record.get_ip()) == Some(expected) {
+            return;
+        }
+        assert!(Instant::now() < deadline, "www.example.com ne pointe pas vers {}", expected);
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+// NOTIFY pour example.com envoyé depuis `source`
+fn send_notify(source: &str, port: u16) -> (DnsMessage, DnsMessage) {
+    let mut notify = DnsMessage::new_query("example.com".to_string(), DnsRecordType::SOA);
+    notify.header.opcode = DnsOpcode::NOTIFY as u8;
+    notify.header.aa = true;
+    notify.header.rd = false;
+
+    let socket = UdpSocket::bind((source, 0)).unwrap();
+    socket.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+    socket.send_to(&notify.to_bytes(), ("127.0.0.1", port)).unwrap();
+    let mut buffer = [0u8; 512];
+    let len = socket.recv(&mut buffer).expect("pas de réponse au NOTIFY");
+    (notify, DnsMessage::from_bytes(&buffer[..len]).unwrap())
+}
+
+#[test]
+fn notify_from_primary_triggers_zone_transfer() {
+    let port = BASE_PORT + 13;
+    let primary_port = start_primary(vec![Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2)]);
+    let primary = format!("127.0.0.1:{}", primary_port);
+    let _server = ServerProcess::start_with_args(port, &["--secondary-for", "example.com", &primary]);
+
+    // Transfert initial au démarrage
+    wait_for_address(port, Ipv4Addr::new(10, 0, 0, 1));
+
+    // Un autre serveur que le primaire ne peut pas déclencher de transfert
+    let (_, refused) = send_notify("127.0.0.2", port);
+    assert_eq!(refused.header.rcode, 5);
+
+    let (notify, response) = send_notify("127.0.0.1", port);
+    assert_eq!(response.header.id, notify.header.id);
+    assert!(response.header.qr);
+    assert_eq!(response.header.opcode, DnsOpcode::NOTIFY as u8);
+    assert_eq!(response.header.rcode, 0);
+    assert_eq!(response.questions, notify.questions);
+
+    // Le NOTIFY a provoqué un nouveau transfert (serial 2)
+    wait_for_address(port, Ipv4Addr::new(10, 0, 0, 2));
+}
+
+#[test]
+fn unknown_names_are_relayed_to_upstream() {
+    let port = BASE_PORT + 14;
+    let mut upstream_answer = DnsMessage::new();
+    upstream_answer.answers.push(DnsRecord::new_a_record("relayed.test".to_string(), Ipv4Addr::new(203, 0, 113, 7), 120));
+    upstream_answer.header.ancount = 1;
+    let upstream = MockDnsServer::start(Some(upstream_answer)).unwrap();
+    let _server = ServerProcess::start_with_args(port, &["--upstream", &upstream.addr().to_string()]);
+    // Nom local : répondu sans passer par l'amont
+    query(port, "example.com", DnsRecordType::A);
+
+    let request = DnsMessage::new_query("relayed.test".to_string(), DnsRecordType::A);
+    let response = send_raw(port, &request.to_bytes());
+
+    assert_eq!(response.header.id, request.header.id);
+    assert_eq!(response.header.rcode, 0);
+    assert_eq!(response.answers[0].get_ip(), Some(Ipv4Addr::new(203, 0, 113, 7)));
+    assert_eq!(response.answers[0].ttl, 120);
+
+    // La requête du client est transmise telle quelle
+    let forwarded = upstream.received_query().unwrap();
+    assert_eq!(forwarded.header.id, request.header.id);
+    assert_eq!(forwarded.questions, request.questions);
+}
+
+#[test]
+fn rate_limit_answers_only_a_hundred_of_two_hundred_queries() {
+    let port = BASE_PORT + 15;
+    let _server = ServerProcess::start_rate_limited(port, &["--rate-limit", "100"]);
+    // Attente du démarrage depuis une autre IP : le quota de 127.0.0.1 reste plein
+    query_from("127.0.0.2", port, "example.com", DnsRecordType::A);
+
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket.connect(("127.0.0.1", port)).unwrap();
+    socket.set_read_timeout(Some(Duration::from_millis(500))).unwrap();
+
+    let started = Instant::now();
+    for id in 0..200 {
+        let mut request = DnsMessage::new_query("example.com".to_string(), DnsRecordType::A);
+        request.header.id = id;
+        socket.send(&request.to_bytes()).unwrap();
+    }
+
+    let mut answered = HashSet::new();
+    let mut last_answer = Duration::ZERO;
+    let mut buffer = [0u8; 512];
+    while let Ok(len) = socket.recv(&mut buffer) {
+        answered.insert(DnsMessage::from_bytes(&buffer[..len]).unwrap().header.id);
+        last_answer = started.elapsed();
+    }
+
+    // Rafale de 100 jetons, plus ceux regagnés pendant le traitement (un toutes les 10 ms) ;
+    // chaque paquet est traité dans sa propre tâche, les jetons ne suivent donc pas l'ordre d'envoi
+    let refilled = last_answer.as_millis() as usize / 10 + 1;
+    assert!((100..=100 + refilled).contains(&answered.len()), "{} réponses en {:?}", answered.len(), last_answer);
+}
+
+// Session sur le socket d'administration, secret déjà envoyé
+struct AdminSession {
+    stream: UnixStream,
+    reader: BufReader<UnixStream>,
+}
+
+impl AdminSession {
+    fn open(path: &Path, secret: &str) -> Self {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let stream = loop {
+            match UnixStream::connect(path) {
+                Ok(stream) => break stream,
+                Err(_) if Instant::now() < deadline => thread::sleep(Duration::from_millis(50)),
+                Err(e) => panic!("socket d'administration injoignable: {}", e),
+            }
+        };
+        stream.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+        let mut session = AdminSession { reader: BufReader::new(stream.try_clone().unwrap()), stream };
+        session.send(secret);
+        session
+    }
+
+    fn send(&mut self, line: &str) {
+        writeln!(self.stream, "{}", line).unwrap();
+    }
+
+    fn read_line(&mut self) -> String {
+        let mut line = String::new();
+        self.reader.read_line(&mut line).unwrap();
+        line.trim_end().to_string()
+    }
+
+    fn command(&mut self, line: &str) -> String {
+        self.send(line);
+        self.read_line()
+    }
+}
+
+#[test]
+fn admin_socket_add_then_resolve() {
+    let port = BASE_PORT + 16;
+    let socket_path = std::env::temp_dir().join(format!("dns_admin_{}.sock", std::process::id()));
+    let _server = ServerProcess::start_with_args(port, &[
+        "--admin-socket", socket_path.to_str().unwrap(),
+        "--admin-secret", "s3cret",
+    ]);
+
+    // NXDOMAIN d'abord, mémorisé dans le cache négatif
+    let (response, _) = query(port, "new.example.com", DnsRecordType::A);
+    assert_eq!(response.header.rcode, 3);
+
+    let mut admin = AdminSession::open(&socket_path, "s3cret");
+    assert_eq!(admin.read_line(), "OK");
+    assert_eq!(admin.command("ADD new.example.com 10.9.8.7"), "OK new.example.com -> 10.9.8.7");
+
+    let (response, _) = query(port, "new.example.com", DnsRecordType::A);
+    assert_eq!(response.header.rcode, 0);
+    assert_eq!(response.answers[0].get_ip(), Some(Ipv4Addr::new(10, 9, 8, 7)));
+
+    assert_eq!(admin.command("DEL new.example.com"), "OK new.example.com supprimé");
+    assert_eq!(query(port, "new.example.com", DnsRecordType::A).0.header.rcode, 3);
+    assert!(admin.command("ADD bad.example.com 10.0.0.999").starts_with("ERR"));
+
+    // Mauvais secret : refus puis fermeture
+    let mut intruder = AdminSession::open(&socket_path, "faux");
+    assert!(intruder.read_line().starts_with("ERR"));
+    assert_eq!(intruder.read_line(), "");
+}
+
+// Valeur d'un compteur de la réponse STATS (ex: "cache_hits")
+fn admin_stat(admin: &mut AdminSession, key: &str) -> u64 {
+    let summary = admin.command("STATS");
+    assert_eq!(admin.read_line(), "OK");
+    summary
+        .split_whitespace()
+        .find_map(|field| field.strip_prefix(&format!("{}=", key)))
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| panic!("{} absent de '{}'", key, summary))
+}
+
+#[test]
+fn record_is_returned_once_negative_ttl_expires() {
+    let port = BASE_PORT + 17;
+    let socket_path = std::env::temp_dir().join(format!("dns_negative_{}.sock", std::process::id()));
+    let _server = ServerProcess::start_with_args(port, &[
+        "--negative-ttl", "1",
+        "--admin-socket", socket_path.to_str().unwrap(),
+        "--admin-secret", "s3cret",
+    ]);
+
+    assert_eq!(query(port, "late.example.com", DnsRecordType::A).0.header.rcode, 3);
+    let mut admin = AdminSession::open(&socket_path, "s3cret");
+    assert_eq!(admin.read_line(), "OK");
+    assert_eq!(admin_stat(&mut admin, "cache_negatif"), 1);
+    let hits = admin_stat(&mut admin, "cache_hits");
+
+    // Servi depuis le cache négatif, sans consulter la base
+    assert_eq!(query(port, "late.example.com", DnsRecordType::A).0.header.rcode, 3);
+    assert_eq!(admin_stat(&mut admin, "cache_hits"), hits + 1);
+
+    // Après le TTL négatif, la base est consultée de nouveau
+    thread::sleep(Duration::from_millis(1100));
+    let misses = admin_stat(&mut admin, "cache_misses");
+    assert_eq!(query(port, "late.example.com", DnsRecordType::A).0.header.rcode, 3);
+    assert_eq!(admin_stat(&mut admin, "cache_hits"), hits + 1);
+    assert_eq!(admin_stat(&mut admin, "cache_misses"), misses + 1);
+
+    // Un enregistrement ajouté ensuite est bien renvoyé
+    assert_eq!(admin.command("ADD late.example.com 10.1.2.3"), "OK late.example.com -> 10.1.2.3");
+    let (response, _) = query(port, "late.example.com", DnsRecordType::A);
+    assert_eq!(response.header.rcode, 0);
+    assert_eq!(response.answers[0].get_ip(), Some(Ipv4Addr::new(10, 1, 2, 3)));
+}
+
+#[test]
+fn nodata_is_cached_per_type_and_nxdomain_per_name() {
+    let port = BASE_PORT + 20;
+    let socket_path = std::env::temp_dir().join(format!("dns_nodata_{}.sock", std::process::id()));
+    let _server = ServerProcess::start_with_args(port, &[
+        "--admin-socket", socket_path.to_str().unwrap(),
+        "--admin-secret", "s3cret",
+    ]);
+
+    // test.local n'a qu'une adresse : TXT est un NODATA, jamais un NXDOMAIN
+    assert_eq!(query(port, "test.local", DnsRecordType::TXT).0.header.rcode, 0);
+    let mut admin = AdminSession::open(&socket_path, "s3cret");
+    assert_eq!(admin.read_line(), "OK");
+    assert_eq!(admin_stat(&mut admin, "cache_negatif"), 1);
+    let hits = admin_stat(&mut admin, "cache_hits");
+
+    // Servi depuis le cache, toujours en NOERROR sans réponse
+    let (response, _) = query(port, "test.local", DnsRecordType::TXT);
+    assert_eq!(response.header.rcode, 0);
+    assert!(response.answers.is_empty());
+    assert_eq!(admin_stat(&mut admin, "cache_hits"), hits + 1);
+
+    // Le NODATA TXT ne masque pas l'adresse du même nom
+    let (response, _) = query(port, "test.local", DnsRecordType::A);
+    assert_eq!(response.header.rcode, 0);
+    assert_eq!(response.answers.len(), 1);
+
+    // Un NXDOMAIN couvre le nom pour tous les types
+    assert_eq!(query(port, "absent.local", DnsRecordType::A).0.header.rcode, 3);
+    let hits = admin_stat(&mut admin, "cache_hits");
+    assert_eq!(query(port, "absent.local", DnsRecordType::TXT).0.header.rcode, 3);
+    assert_eq!(admin_stat(&mut admin, "cache_hits"), hits + 1);
+}
+
+#[test]
+fn records_written_to_the_sqlite_store_are_served() {
+    let port = BASE_PORT + 22;
+    let path = std::env::temp_dir().join(format!("dns_server_store_{}.sqlite", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let _server = ServerProcess::start_with_args(port, &["--database", path.to_str().unwrap()]);
+    query(port, "example.com", DnsRecordType::A);
+
+    // Écrit par un autre processus après le démarrage : absent de la mémoire du serveur
+    let mut writer = SimpleDnsDatabase::open(&path).unwrap();
+    writer.add_record_persistent("late.local".to_string(), Ipv4Addr::new(10, 0, 0, 9), PERMANENT_TTL).unwrap();
+
+    let (response, _) = query(port, "late.local", DnsRecordType::A);
+    assert_eq!(response.header.rcode, 0);
+    assert_eq!(response.answers[0].get_ip(), Some(Ipv4Addr::new(10, 0, 0, 9)));
+    let _ = std::fs::remove_file(&path);
+}
+
+// Requête sur TCP, préfixée par sa longueur
+fn tcp_query(port: u16, request: &DnsMessage) -> DnsMessage {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    stream.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+    let bytes = request.to_bytes();
+    stream.write_all(&(bytes.len() as u16).to_be_bytes()).unwrap();
+    stream.write_all(&bytes).unwrap();
+
+    let mut length = [0u8; 2];
+    stream.read_exact(&mut length).unwrap();
+    let mut data = vec![0u8; u16::from_be_bytes(length) as usize];
+    stream.read_exact(&mut data).unwrap();
+    DnsMessage::from_bytes(&data).unwrap()
+}
+
+// Requête UDP avec un tampon de réception de `buffer_size` octets, réponse brute
+fn udp_query_raw(port: u16, request: &DnsMessage, buffer_size: usize) -> Vec<u8> {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+    socket.send_to(&request.to_bytes(), ("127.0.0.1", port)).unwrap();
+    let mut buffer = vec![0u8; buffer_size];
+    let len = socket.recv(&mut buffer).expect("pas de réponse");
+    buffer.truncate(len);
+    buffer
+}
+
+// Requête à 16 questions : les 16 réponses A dépassent les 512 octets d'UDP
+fn many_questions(edns: Option<u16>) -> DnsMessage {
+    let mut builder = DnsMessageBuilder::new();
+    for _ in 0..16 {
+        builder = builder.query("example.com".to_string(), DnsRecordType::A);
+    }
+    match edns {
+        Some(size) => builder.edns(size).build(),
+        None => builder.build(),
+    }
+}
+
+#[test]
+fn oversized_udp_response_is_truncated_with_tc() {
+    let port = BASE_PORT + 18;
+    let _server = ServerProcess::start_with_args(port, &["--tcp"]);
+    // Attente du démarrage
+    query(port, "example.com", DnsRecordType::A);
+
+    let request = many_questions(None);
+    let raw = udp_query_raw(port, &request, 512);
+    let response = DnsMessage::from_bytes(&raw).unwrap();
+    assert!(raw.len() <= 512, "{} octets", raw.len());
+    assert!(response.header.tc);
+    assert!(!response.answers.is_empty() && response.answers.len() < 16, "{} réponses", response.answers.len());
+    assert_eq!(response.header.ancount as usize, response.answers.len());
+
+    // Le client qui voit TC réessaie en TCP et obtient tout
+    let full = tcp_query(port, &request);
+    assert!(!full.header.tc);
+    assert_eq!(full.answers.len(), 16);
+
+    // Un tampon EDNS0 assez grand évite aussi la troncature
+    let response = DnsMessage::from_bytes(&udp_query_raw(port, &many_questions(Some(4096)), 4096)).unwrap();
+    assert!(!response.header.tc);
+    assert_eq!(response.answers.len(), 16);
+}
+
+#[test]
+fn silent_tcp_clients_are_disconnected_after_the_idle_timeout() {
+    let port = BASE_PORT + 21;
+    let _server = ServerProcess::start_with_args(port, &["--tcp"]);
+    query(port, "example.com", DnsRecordType::A);
+
+    // Un client muet et un client qui n'envoie que la longueur du message
+    let started = Instant::now();
+    let mut silent = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    let mut partial = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    partial.write_all(&64u16.to_be_bytes()).unwrap();
+
+    for stream in [&mut silent, &mut partial] {
+        stream.set_read_timeout(Some(Duration::from_secs(15))).unwrap();
+        let mut buffer = [0u8; 1];
+        assert_eq!(stream.read(&mut buffer).unwrap(), 0, "connexion fermée attendue");
+    }
+    let elapsed = started.elapsed();
+    assert!(elapsed >= Duration::from_secs(9), "fermée trop tôt: {:?}", elapsed);
+}
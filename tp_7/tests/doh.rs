@@ -0,0 +1,84 @@
+// Tests du mode DNS-over-HTTPS du client contre un faux serveur HTTP (wiremock)
+use std::net::Ipv4Addr;
+use std::process::Output;
+
+use dns_client_server::{DnsMessage, DnsMessageBuilder, DnsRecord, DnsRecordType};
+use serde_json::Value;
+use tokio::process::Command;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+// Lancer dns_client en JSON contre l'URL DoH du faux serveur, sans nouvelle tentative
+async fn run_client(server: &MockServer, domain: &str) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_dns_client"))
+        .arg(domain)
+        .args(["--doh-url", &format!("{}/dns-query", server.uri())])
+        .args(["--output-format", "json", "--retries", "0", "--timeout", "2000"])
+        .output()
+        .await
+        .expect("impossible de lancer dns_client")
+}
+
+#[tokio::test]
+async fn posts_query_and_parses_response_body() {
+    let response = DnsMessageBuilder::new()
+        .answer(DnsRecord::new_a_record("example.com".to_string(), Ipv4Addr::new(93, 184, 216, 34), 300))
+        .build();
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/dns-query"))
+        .and(header("content-type", "application/dns-message"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(response.to_bytes(), "application/dns-message"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let output = run_client(&server, "example.com").await;
+    assert!(output.status.success(), "dns_client a échoué: {}", String::from_utf8_lossy(&output.stderr));
+    let result: Value = serde_json::from_slice(&output.stdout).expect("sortie JSON invalide");
+    assert_eq!(result["rcode"], "NOERROR");
+    assert_eq!(result["answers"][0]["address"], "93.184.216.34");
+
+    // Le corps du POST est la requête DNS en format binaire
+    let requests = server.received_requests().await.unwrap();
+    let query = DnsMessage::from_bytes(&requests[0].body).unwrap();
+    assert_eq!(query.questions[0].name, "example.com");
+    assert_eq!(query.questions[0].qtype, DnsRecordType::A);
+}
+
+#[tokio::test]
+async fn reports_http_errors() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(503).set_body_string("maintenance en cours"))
+        .mount(&server)
+        .await;
+
+    let output = run_client(&server, "example.com").await;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("HTTP 503"), "{}", stderr);
+    assert!(stderr.contains("(erreur interne du serveur DoH): maintenance en cours"), "{}", stderr);
+
+    server.reset().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(415))
+        .mount(&server)
+        .await;
+
+    let stderr = String::from_utf8_lossy(&run_client(&server, "example.com").await.stderr).to_string();
+    assert!(stderr.contains("HTTP 415 Unsupported Media Type (requête rejetée par le serveur DoH)"), "{}", stderr);
+}
+
+#[tokio::test]
+async fn rejects_non_dns_content_type() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw("<html></html>", "text/html"))
+        .mount(&server)
+        .await;
+
+    let output = run_client(&server, "example.com").await;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Content-Type 'text/html'"));
+}
@@ -0,0 +1,95 @@
+// Tests de DnsHeader::validate : une violation par test, plus les en-têtes valides
+use dns_client_server::{DnsHeader, DnsMessage, DnsRcode, DnsRecordType, DnsValidationError};
+
+// Requête standard valide : une question, aucun bit de réponse
+fn query_header() -> DnsHeader {
+    DnsHeader { qdcount: 1, ..DnsHeader::new() }
+}
+
+fn single_error(header: DnsHeader) -> DnsValidationError {
+    let errors = header.validate().expect_err("en-tête invalide accepté");
+    assert_eq!(errors.len(), 1, "erreurs inattendues: {:?}", errors);
+    errors[0]
+}
+
+#[test]
+fn accepts_standard_query() {
+    let query = DnsMessage::new_query("example.com".to_string(), DnsRecordType::A);
+    assert_eq!(query.header.validate(), Ok(()));
+}
+
+#[test]
+fn accepts_response_flags() {
+    let query = DnsMessage::new_query("example.com".to_string(), DnsRecordType::A);
+    let mut response = DnsMessage::new_response(&query);
+    response.header.aa = true;
+    response.header.rcode = 3;
+    assert_eq!(response.header.validate(), Ok(()));
+}
+
+#[test]
+fn error_response_matches_query() {
+    let query = DnsMessage::new_query("example.com".to_string(), DnsRecordType::A);
+    let response = DnsMessage::new_error_response(&query, DnsRcode::SERVFAIL);
+    assert_eq!(response.header.id, query.header.id);
+    assert_eq!(response.questions, query.questions);
+    assert!(response.header.qr && response.header.ra);
+    assert_eq!(response.header.rcode, DnsRcode::SERVFAIL as u8);
+    assert!(response.answers.is_empty() && response.authorities.is_empty());
+    assert_eq!(response.header.validate(), Ok(()));
+
+    // L'ID survit à l'aller-retour en octets
+    let parsed = DnsMessage::from_bytes(&response.to_bytes()).unwrap();
+    assert_eq!(parsed.header.id, query.header.id);
+    assert_eq!(DnsRcode::from_u8(parsed.header.rcode), Some(DnsRcode::SERVFAIL));
+}
+
+#[test]
+fn rejects_reserved_bits() {
+    let header = DnsHeader { z: 2, ..query_header() };
+    assert_eq!(single_error(header), DnsValidationError::ReservedBitsSet(2));
+}
+
+#[test]
+fn rejects_unknown_opcode() {
+    let header = DnsHeader { opcode: 6, ..query_header() };
+    assert_eq!(single_error(header), DnsValidationError::InvalidOpcode(6));
+}
+
+#[test]
+fn rejects_authoritative_query() {
+    let header = DnsHeader { aa: true, ..query_header() };
+    assert_eq!(single_error(header), DnsValidationError::AuthoritativeQuery);
+}
+
+#[test]
+fn rejects_recursion_available_in_query() {
+    let header = DnsHeader { ra: true, ..query_header() };
+    assert_eq!(single_error(header), DnsValidationError::RecursionAvailableInQuery);
+}
+
+#[test]
+fn rejects_rcode_in_query() {
+    let header = DnsHeader { rcode: 2, ..query_header() };
+    assert_eq!(single_error(header), DnsValidationError::RcodeInQuery(2));
+}
+
+#[test]
+fn rejects_query_without_question() {
+    let header = DnsHeader { qdcount: 0, ..query_header() };
+    assert_eq!(single_error(header), DnsValidationError::NoQuestion);
+}
+
+#[test]
+fn reports_every_violation() {
+    let header = DnsHeader { z: 1, opcode: 15, aa: true, ra: true, rcode: 5, qdcount: 0, ..DnsHeader::new() };
+    let errors = header.validate().unwrap_err();
+    assert_eq!(errors, [
+        DnsValidationError::ReservedBitsSet(1),
+        DnsValidationError::InvalidOpcode(15),
+        DnsValidationError::AuthoritativeQuery,
+        DnsValidationError::RecursionAvailableInQuery,
+        DnsValidationError::RcodeInQuery(5),
+        DnsValidationError::NoQuestion,
+    ]);
+}
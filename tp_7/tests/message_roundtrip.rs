@@ -0,0 +1,466 @@
+// Tests de propriété : to_bytes puis from_bytes doit redonner le même message
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use dns_client_server::{
+    CaaData, DnsHeader, DnsMessage, DnsMessageBuilder, DnsQuestion, DnsRecord, DnsRecordType, MxData, NaptrData,
+    SoaData,
+};
+use proptest::prelude::*;
+
+// Nom de domaine valide : 1 à 4 labels de 1 à 63 caractères
+fn domain_name() -> impl Strategy<Value = String> {
+    let label = "[a-z0-9]([a-z0-9-]{0,61}[a-z0-9])?";
+    prop::collection::vec(label, 1..=4).prop_map(|labels| labels.join("."))
+}
+
+fn record_type() -> impl Strategy<Value = DnsRecordType> {
+    prop_oneof![
+        Just(DnsRecordType::A),
+        Just(DnsRecordType::NS),
+        Just(DnsRecordType::CNAME),
+        Just(DnsRecordType::SOA),
+        Just(DnsRecordType::PTR),
+        Just(DnsRecordType::MX),
+        Just(DnsRecordType::TXT),
+        Just(DnsRecordType::AAAA),
+        Just(DnsRecordType::SRV),
+        Just(DnsRecordType::NAPTR),
+        Just(DnsRecordType::CAA),
+        Just(DnsRecordType::HINFO),
+        Just(DnsRecordType::ANY),
+    ]
+}
+
+fn question() -> impl Strategy<Value = DnsQuestion> {
+    (domain_name(), record_type()).prop_map(|(name, qtype)| DnsQuestion::new(name, qtype))
+}
+
+fn soa_data() -> impl Strategy<Value = SoaData> {
+    (domain_name(), domain_name(), any::<[u32; 5]>()).prop_map(
+        |(mname, rname, [serial, refresh, retry, expire, minimum])| SoaData {
+            mname,
+            rname,
+            serial,
+            refresh,
+            retry,
+            expire,
+            minimum,
+        },
+    )
+}
+
+fn naptr_data() -> impl Strategy<Value = NaptrData> {
+    (any::<(u16, u16)>(), "[a-z]{0,1}", "[A-Za-z0-9+:]{0,32}", "[ -~]{0,64}", prop_oneof![Just(String::new()), domain_name()])
+        .prop_map(|((order, preference), flags, services, regexp, replacement)| NaptrData {
+            order,
+            preference,
+            flags,
+            services,
+            regexp,
+            replacement,
+        })
+}
+
+fn record() -> impl Strategy<Value = DnsRecord> {
+    prop_oneof![
+        (domain_name(), any::<[u8; 4]>(), any::<u32>())
+            .prop_map(|(name, ip, ttl)| DnsRecord::new_a_record(name, Ipv4Addr::from(ip), ttl)),
+        (domain_name(), any::<[u8; 16]>(), any::<u32>())
+            .prop_map(|(name, ip, ttl)| DnsRecord::new_aaaa_record(name, Ipv6Addr::from(ip), ttl)),
+        (domain_name(), any::<u16>(), domain_name(), any::<u32>())
+            .prop_map(|(name, priority, exchange, ttl)| DnsRecord::new_mx_record(name, priority, exchange, ttl)),
+        (domain_name(), prop::collection::vec("[ -~]{0,300}", 1..3), any::<u32>())
+            .prop_map(|(name, strings, ttl)| {
+                let strings: Vec<&str> = strings.iter().map(String::as_str).collect();
+                DnsRecord::new_txt_record(name, &strings, ttl)
+            }),
+        (domain_name(), domain_name(), any::<u32>())
+            .prop_map(|(alias, canonical, ttl)| DnsRecord::new_cname_record(alias, canonical, ttl)),
+        (domain_name(), domain_name(), any::<u32>())
+            .prop_map(|(zone, nameserver, ttl)| DnsRecord::new_ns_record(zone, nameserver, ttl)),
+        (domain_name(), any::<(u16, u16, u16)>(), domain_name(), any::<u32>())
+            .prop_map(|(name, (priority, weight, port), target, ttl)| {
+                DnsRecord::new_srv_record(name, priority, weight, port, target, ttl)
+            }),
+        (domain_name(), any::<u8>(), "[a-z0-9]{1,15}", "[ -~]{0,100}", any::<u32>())
+            .prop_map(|(name, flags, tag, value, ttl)| {
+                DnsRecord::new_caa_record(name, &CaaData { flags, tag, value }, ttl)
+            }),
+        (domain_name(), naptr_data(), any::<u32>())
+            .prop_map(|(name, naptr, ttl)| DnsRecord::new_naptr_record(name, &naptr, ttl)),
+        (domain_name(), soa_data(), any::<u32>())
+            .prop_map(|(zone, soa, ttl)| DnsRecord::new_soa_record(zone, &soa, ttl)),
+    ]
+}
+
+// Chaîne de CNAME terminée par un enregistrement A, comme la produit le serveur
+fn cname_chain() -> impl Strategy<Value = Vec<DnsRecord>> {
+    (prop::collection::vec(domain_name(), 2..=8), any::<[u8; 4]>(), any::<u32>()).prop_map(|(names, ip, ttl)| {
+        let mut records: Vec<DnsRecord> = names
+            .windows(2)
+            .map(|pair| DnsRecord::new_cname_record(pair[0].clone(), pair[1].clone(), ttl))
+            .collect();
+        records.push(DnsRecord::new_a_record(names[names.len() - 1].clone(), Ipv4Addr::from(ip), ttl));
+        records
+    })
+}
+
+fn header() -> impl Strategy<Value = DnsHeader> {
+    (any::<u16>(), any::<[bool; 5]>(), 0u8..16, 0u8..8, 0u8..16).prop_map(
+        |(id, [qr, aa, tc, rd, ra], opcode, z, rcode)| DnsHeader {
+            id,
+            qr,
+            opcode,
+            aa,
+            tc,
+            rd,
+            ra,
+            z,
+            rcode,
+            ..DnsHeader::new()
+        },
+    )
+}
+
+fn message() -> impl Strategy<Value = DnsMessage> {
+    (
+        header(),
+        prop::collection::vec(question(), 0..4),
+        prop_oneof![prop::collection::vec(record(), 0..8), cname_chain()],
+        prop::collection::vec(record(), 0..4),
+        prop::collection::vec(record(), 0..4),
+        any::<Option<u16>>(),
+    )
+        .prop_map(|(header, questions, answers, authorities, additionals, edns_payload_size)| {
+            let mut message = DnsMessage {
+                header,
+                questions,
+                answers,
+                authorities,
+                additionals,
+                edns_payload_size,
+            };
+            // Les compteurs de l'en-tête doivent refléter le contenu des sections
+            message.header.qdcount = message.questions.len() as u16;
+            message.header.ancount = message.answers.len() as u16;
+            message.header.nscount = message.authorities.len() as u16;
+            message.header.arcount =
+                message.additionals.len() as u16 + message.edns_payload_size.is_some() as u16;
+            message
+        })
+}
+
+proptest! {
+    #[test]
+    fn roundtrip_preserves_message(message in message()) {
+        let bytes = message.to_bytes();
+        let parsed = DnsMessage::from_bytes(&bytes).expect("message valide rejeté");
+        prop_assert_eq!(&parsed, &message);
+        prop_assert_eq!(parsed.to_bytes(), bytes);
+    }
+
+    // Le parseur sans copie décode exactement les mêmes noms et données
+    #[test]
+    fn zero_copy_matches_owned(message in message()) {
+        let bytes = message.to_bytes();
+        let borrowed = DnsMessage::from_bytes_zero_copy(&bytes).expect("message valide rejeté");
+        for (question, expected) in borrowed.questions.iter().zip(&message.questions) {
+            prop_assert_eq!(question.name.to_string(), expected.name.clone());
+        }
+        for (record, expected) in borrowed.answers.iter().zip(&message.answers) {
+            prop_assert_eq!(record.data, &expected.data[..]);
+        }
+        prop_assert_eq!(borrowed.into_owned(), message);
+    }
+
+    // Des compteurs au maximum sans contenu ne doivent jamais faire paniquer le parseur
+    #[test]
+    fn max_counts_do_not_panic(id in any::<u16>(), counts in prop::array::uniform4(prop_oneof![Just(0u16), Just(u16::MAX)])) {
+        let header = DnsHeader {
+            id,
+            qdcount: counts[0],
+            ancount: counts[1],
+            nscount: counts[2],
+            arcount: counts[3],
+            ..DnsHeader::new()
+        };
+        let result = DnsMessage::from_bytes(&header.to_bytes());
+        if counts[0] > 0 {
+            prop_assert!(result.is_err());
+        }
+    }
+}
+
+// from_shared_bytes ne copie pas RDATA : chaque enregistrement est une vue sur le paquet
+#[test]
+fn shared_parsing_keeps_rdata_in_the_packet() {
+    let mut message = DnsMessage::new_query("example.com".to_string(), DnsRecordType::A);
+    for last_octet in 1..=3 {
+        message.answers.push(DnsRecord::new_a_record("example.com".to_string(), Ipv4Addr::new(10, 0, 0, last_octet), 300));
+    }
+    message.header.ancount = 3;
+
+    let packet = bytes::Bytes::from(message.to_bytes());
+    let parsed = DnsMessage::from_shared_bytes(packet.clone()).unwrap();
+    assert_eq!(parsed, message);
+    let packet_range = packet.as_ptr_range();
+    for record in &parsed.answers {
+        assert!(packet_range.contains(&record.data.as_ptr()));
+    }
+}
+
+#[test]
+fn roundtrip_empty_message() {
+    let message = DnsMessage::new();
+    assert_eq!(DnsMessage::from_bytes(&message.to_bytes()).unwrap(), message);
+}
+
+// Le SOA doit se relire à l'identique depuis la section d'autorité d'un NXDOMAIN
+#[test]
+fn roundtrip_soa_record() {
+    let soa = SoaData {
+        mname: "ns1.example.com".to_string(),
+        rname: "admin.example.com".to_string(),
+        serial: 2024010101,
+        refresh: 7200,
+        retry: 3600,
+        expire: 1_209_600,
+        minimum: 300,
+    };
+    let mut message = DnsMessage::new();
+    message.header.rcode = 3;
+    message.authorities.push(DnsRecord::new_soa_record("example.com".to_string(), &soa, 300));
+    message.header.nscount = 1;
+
+    let bytes = message.to_bytes();
+    let parsed = DnsMessage::from_bytes(&bytes).unwrap();
+    assert_eq!(parsed, message);
+    assert_eq!(parsed.authorities[0].rtype, DnsRecordType::SOA);
+    assert_eq!(parsed.authorities[0].get_soa(&bytes), Some(soa));
+}
+
+#[test]
+fn roundtrip_caa_records() {
+    let entries = [
+        CaaData { flags: 0, tag: "issue".to_string(), value: "letsencrypt.org".to_string() },
+        CaaData { flags: 0, tag: "issuewild".to_string(), value: ";".to_string() },
+    ];
+    let mut message = DnsMessage::new();
+    for caa in &entries {
+        message.answers.push(DnsRecord::new_caa_record("example.com".to_string(), caa, 300));
+    }
+    message.header.ancount = entries.len() as u16;
+
+    let parsed = DnsMessage::from_bytes(&message.to_bytes()).unwrap();
+    assert_eq!(parsed, message);
+    let decoded: Vec<CaaData> = parsed.answers.iter().filter_map(DnsRecord::get_caa).collect();
+    assert_eq!(decoded, entries);
+    assert_eq!(parsed.answers[0].rdata_to_string(&[]), "0 issue \"letsencrypt.org\"");
+}
+
+fn sip_naptr() -> NaptrData {
+    NaptrData {
+        order: 10,
+        preference: 0,
+        flags: "s".to_string(),
+        services: "SIP+D2U".to_string(),
+        regexp: String::new(),
+        replacement: "_sip._udp.example.com".to_string(),
+    }
+}
+
+#[test]
+fn roundtrip_naptr_record() {
+    let mut message = DnsMessage::new_query("example.com".to_string(), DnsRecordType::NAPTR);
+    message.answers.push(DnsRecord::new_naptr_record("example.com".to_string(), &sip_naptr(), 300));
+    message.header.ancount = 1;
+
+    let bytes = message.to_bytes();
+    let parsed = DnsMessage::from_bytes(&bytes).unwrap();
+    assert_eq!(parsed, message);
+    assert_eq!(parsed.answers[0].get_naptr(&bytes), Some(sip_naptr()));
+}
+
+// Le nom de remplacement peut pointer vers un nom déjà présent dans le message
+#[test]
+fn naptr_compressed_replacement() {
+    let mut message = DnsMessage::new_query("example.com".to_string(), DnsRecordType::NAPTR);
+    let mut record = DnsRecord::new_naptr_record("example.com".to_string(), &sip_naptr(), 300);
+    let name_start = record.data.len() - DnsQuestion::encode_name("_sip._udp.example.com").len();
+    // "_sip._udp" puis pointeur vers "example.com" dans la question (offset 12)
+    record.data = [&record.data[..name_start], b"\x04_sip\x04_udp\xC0\x0C"].concat().into();
+    message.answers.push(record);
+    message.header.ancount = 1;
+
+    let bytes = message.to_bytes();
+    let parsed = DnsMessage::from_bytes(&bytes).unwrap();
+    assert_eq!(parsed.answers[0].get_naptr(&bytes), Some(sip_naptr()));
+}
+
+#[test]
+fn roundtrip_query_without_additionals() {
+    let message = DnsMessage::new_query("example.com".to_string(), DnsRecordType::A);
+    let parsed = DnsMessage::from_bytes(&message.to_bytes()).unwrap();
+    assert!(parsed.additionals.is_empty());
+    assert_eq!(parsed, message);
+}
+
+#[test]
+fn roundtrip_mx_records() {
+    let mut message = DnsMessage::new_query("example.com".to_string(), DnsRecordType::MX);
+    message.answers.push(DnsRecord::new_mx_record("example.com".to_string(), 10, "mail.example.com".to_string(), 300));
+    message.answers.push(DnsRecord::new_mx_record("example.com".to_string(), 20, "backup.example.org".to_string(), 300));
+    message.header.ancount = 2;
+
+    let bytes = message.to_bytes();
+    let parsed = DnsMessage::from_bytes(&bytes).unwrap();
+    assert_eq!(parsed, message);
+    let exchanges: Vec<MxData> = parsed.answers.iter().filter_map(|record| record.get_mx(&bytes)).collect();
+    assert_eq!(exchanges, [
+        MxData { priority: 10, exchange: "mail.example.com".to_string() },
+        MxData { priority: 20, exchange: "backup.example.org".to_string() },
+    ]);
+
+    // Pas de MX dans un enregistrement d'un autre type
+    let a = DnsRecord::new_a_record("example.com".to_string(), Ipv4Addr::new(10, 0, 0, 1), 300);
+    assert_eq!(a.get_mx(&bytes), None);
+}
+
+// L'échangeur peut pointer vers un nom déjà présent dans le message
+#[test]
+fn mx_compressed_exchange() {
+    let mut message = DnsMessage::new_query("example.com".to_string(), DnsRecordType::MX);
+    let mut record = DnsRecord::new_mx_record("example.com".to_string(), 5, "mail.example.com".to_string(), 300);
+    // Préférence, "mail" puis pointeur vers "example.com" dans la question (offset 12)
+    record.data = b"\x00\x05\x04mail\xC0\x0C".to_vec().into();
+    message.answers.push(record);
+    message.header.ancount = 1;
+
+    let bytes = message.to_bytes();
+    let parsed = DnsMessage::from_bytes(&bytes).unwrap();
+    assert_eq!(parsed.answers[0].get_mx(&bytes), Some(MxData { priority: 5, exchange: "mail.example.com".to_string() }));
+}
+
+// L'affichage façon dig suit les pointeurs de compression du message brut
+#[test]
+fn dig_output_follows_compressed_names() {
+    let mut message = DnsMessage::new_query("www.example.com".to_string(), DnsRecordType::CNAME);
+    let mut record = DnsRecord::new_cname_record("www.example.com".to_string(), "example.com".to_string(), 300);
+    // Pointeur vers "example.com" dans la question : "www" (4 octets) après l'en-tête
+    record.data = b"\xC0\x10".to_vec().into();
+    message.answers.push(record);
+    message.header.ancount = 1;
+
+    let bytes = message.to_bytes();
+    let parsed = DnsMessage::from_bytes(&bytes).unwrap();
+    assert_eq!(parsed.answers[0].rdata_to_string(&bytes), "example.com.");
+    assert!(parsed.to_dig_string(&bytes).contains("www.example.com.\t300\tIN\tCNAME\texample.com."));
+}
+
+#[test]
+fn builder_counts_match_sections() {
+    let a = |last_octet| DnsRecord::new_a_record("example.com".to_string(), Ipv4Addr::new(10, 0, 0, last_octet), 300);
+    let mx = DnsRecord::new_mx_record("example.com".to_string(), 10, "mail.example.com".to_string(), 300);
+
+    let message = DnsMessageBuilder::new()
+        .id(0x1234)
+        .query("example.com".to_string(), DnsRecordType::A)
+        .answer(a(1))
+        .answer(a(2))
+        .answer(a(3))
+        .authority(mx)
+        .additional(a(4))
+        .recursion_desired(false)
+        .build();
+
+    assert_eq!(message.header.id, 0x1234);
+    assert!(!message.header.rd);
+    assert_eq!(
+        (message.header.qdcount, message.header.ancount, message.header.nscount, message.header.arcount),
+        (1, 3, 1, 1)
+    );
+
+    // Les compteurs survivent à la sérialisation
+    let parsed = DnsMessage::from_bytes(&message.to_bytes()).unwrap();
+    assert_eq!(parsed, message);
+
+    // L'enregistrement OPT compte dans la section additionnelle
+    let with_edns = DnsMessageBuilder::new().query("example.com".to_string(), DnsRecordType::A).edns(4096).build();
+    assert_eq!(with_edns.header.arcount, 1);
+    assert!(with_edns.additionals.is_empty());
+
+    let empty = DnsMessageBuilder::new().build();
+    assert_eq!((empty.header.qdcount, empty.header.ancount, empty.header.nscount, empty.header.arcount), (0, 0, 0, 0));
+}
+
+proptest! {
+    #[test]
+    fn builder_counts_are_always_consistent(
+        questions in prop::collection::vec(question(), 0..4),
+        answers in prop::collection::vec(record(), 0..6),
+        authorities in prop::collection::vec(record(), 0..4),
+        additionals in prop::collection::vec(record(), 0..4),
+    ) {
+        let mut builder = DnsMessageBuilder::new();
+        for question in &questions {
+            builder = builder.query(question.name.clone(), question.qtype);
+        }
+        for record in &answers {
+            builder = builder.answer(record.clone());
+        }
+        for record in &authorities {
+            builder = builder.authority(record.clone());
+        }
+        for record in &additionals {
+            builder = builder.additional(record.clone());
+        }
+        let message = builder.build();
+
+        prop_assert_eq!(message.header.qdcount as usize, questions.len());
+        prop_assert_eq!(message.header.ancount as usize, answers.len());
+        prop_assert_eq!(message.header.nscount as usize, authorities.len());
+        prop_assert_eq!(message.header.arcount as usize, additionals.len());
+    }
+}
+
+// 40 réponses TXT d'environ 60 octets : bien au-delà des 512 octets d'UDP
+fn large_response() -> DnsMessage {
+    let mut builder = DnsMessageBuilder::new().query("example.com".to_string(), DnsRecordType::TXT);
+    for index in 0..40 {
+        let text = format!("entrée {:02} {}", index, "x".repeat(30));
+        builder = builder.answer(DnsRecord::new_txt_record("example.com".to_string(), &[&text], 300));
+    }
+    builder.build()
+}
+
+#[test]
+fn truncate_sets_tc_and_trims_answers() {
+    let mut message = large_response();
+    let original = message.answers.clone();
+    assert!(message.to_bytes().len() > 512);
+
+    message.truncate(512);
+
+    let bytes = message.to_bytes();
+    assert!(bytes.len() <= 512, "{} octets", bytes.len());
+    assert!(message.header.tc);
+    assert!(!message.answers.is_empty());
+    assert_eq!(message.header.ancount as usize, message.answers.len());
+    // Les réponses gardées sont les premières, dans l'ordre
+    assert_eq!(message.answers[..], original[..message.answers.len()]);
+
+    let parsed = DnsMessage::from_bytes(&bytes).unwrap();
+    assert!(parsed.header.tc);
+    assert_eq!(parsed.answers, message.answers);
+}
+
+#[test]
+fn truncate_leaves_small_messages_untouched() {
+    let mut message = large_response();
+    let size = message.to_bytes().len();
+    let before = message.clone();
+
+    message.truncate(size);
+    assert_eq!(message, before);
+    assert!(!message.header.tc);
+}
@@ -0,0 +1,61 @@
+// Décodage des noms : mode permissif, strict (UTF-8 valide) et Punycode
+use dns_client_server::DnsQuestion;
+
+// Encoder des labels bruts, sans passer par encode_name qui n'accepte que des &str
+fn raw_name(labels: &[&[u8]]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for label in labels {
+        bytes.push(label.len() as u8);
+        bytes.extend_from_slice(label);
+    }
+    bytes.push(0);
+    bytes
+}
+
+#[test]
+fn lossy_decoder_replaces_invalid_utf8() {
+    let data = raw_name(&[b"ex\xFFmple", b"com"]);
+    let name = DnsQuestion::decode_name(&data, &mut 0).unwrap();
+    assert_eq!(name, "ex\u{FFFD}mple.com");
+}
+
+#[test]
+fn strict_decoder_rejects_invalid_utf8() {
+    let data = raw_name(&[b"ex\xFFmple", b"com"]);
+    assert!(DnsQuestion::decode_name_strict(&data, &mut 0).is_err());
+}
+
+#[test]
+fn strict_decoder_accepts_valid_names() {
+    let data = raw_name(&[b"www", "bücher".as_bytes(), b"de"]);
+    let mut offset = 0;
+    assert_eq!(DnsQuestion::decode_name_strict(&data, &mut offset).unwrap(), "www.bücher.de");
+    assert_eq!(offset, data.len());
+}
+
+#[test]
+fn strict_decoder_follows_compression() {
+    // "mail" puis pointeur vers "example.com" au début du paquet
+    let mut data = raw_name(&[b"example", b"com"]);
+    let offset = data.len();
+    data.extend_from_slice(&[4, b'm', b'a', b'i', b'l', 0xC0, 0]);
+    assert_eq!(DnsQuestion::decode_name_strict(&data, &mut offset.clone()).unwrap(), "mail.example.com");
+}
+
+#[test]
+fn punycode_decoder_converts_idn_labels() {
+    let data = raw_name(&["bücher".as_bytes(), b"de"]);
+    assert_eq!(DnsQuestion::decode_name_punycode(&data, &mut 0).unwrap(), "xn--bcher-kva.de");
+}
+
+#[test]
+fn punycode_decoder_keeps_ascii_names() {
+    let data = DnsQuestion::encode_name("xn--bcher-kva.example.com");
+    assert_eq!(DnsQuestion::decode_name_punycode(&data, &mut 0).unwrap(), "xn--bcher-kva.example.com");
+}
+
+#[test]
+fn punycode_decoder_rejects_invalid_utf8() {
+    let data = raw_name(&[b"\xC3\x28", b"com"]);
+    assert!(DnsQuestion::decode_name_punycode(&data, &mut 0).is_err());
+}
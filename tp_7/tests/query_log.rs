@@ -0,0 +1,57 @@
+// Format des lignes du journal des requêtes (--query-log)
+use std::net::IpAddr;
+
+use dns_client_server::{utc_date, DnsRecordType, QueryLogEntry};
+
+fn entry(timestamp_ms: u64, domain: &str, rcode: u8) -> QueryLogEntry {
+    QueryLogEntry {
+        timestamp_ms,
+        client_ip: "192.0.2.7".parse::<IpAddr>().unwrap(),
+        domain: domain.to_string(),
+        qtype: DnsRecordType::AAAA,
+        rcode,
+        latency_us: 842,
+    }
+}
+
+#[test]
+fn formats_one_line_per_query() {
+    // 2024-02-29T23:59:59.999Z
+    let line = entry(1_709_251_199_999, "www.example.com", 3).to_string();
+    assert_eq!(line, "2024-02-29T23:59:59.999Z 192.0.2.7 www.example.com AAAA NXDOMAIN 842");
+}
+
+#[test]
+fn parses_formatted_lines_back() {
+    for original in [
+        entry(0, "example.com", 0),
+        entry(1_709_251_199_999, "", 2),
+        entry(4_102_444_800_000, "a b\\c.example", 9),
+        entry(1_700_000_000_123, "café.example", 5),
+    ] {
+        let line = original.to_string();
+        assert_eq!(line.split(' ').count(), 6, "{}", line);
+        assert_eq!(QueryLogEntry::parse(&line).unwrap(), original);
+    }
+}
+
+#[test]
+fn rejects_malformed_lines() {
+    for line in [
+        "",
+        "2024-02-29T23:59:59.999Z 192.0.2.7 example.com A NOERROR",
+        "2024-13-01T00:00:00.000Z 192.0.2.7 example.com A NOERROR 5",
+        "2024-02-29T23:59:59Z 192.0.2.7 example.com A NOERROR 5",
+        "2024-02-29T23:59:59.999Z host example.com A NOERROR 5",
+        "2024-02-29T23:59:59.999Z 192.0.2.7 example.com BOGUS NOERROR 5",
+    ] {
+        assert!(QueryLogEntry::parse(line).is_err(), "{}", line);
+    }
+}
+
+#[test]
+fn rotation_date_changes_at_utc_midnight() {
+    assert_eq!(utc_date(1_709_251_199_999), "2024-02-29");
+    assert_eq!(utc_date(1_709_251_200_000), "2024-03-01");
+    assert_eq!(entry(0, "example.com", 0).date(), "1970-01-01");
+}
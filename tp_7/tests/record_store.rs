@@ -0,0 +1,108 @@
+// Tests de la persistance SQLite de SimpleDnsDatabase
+use std::net::Ipv4Addr;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use dns_client_server::{SimpleDnsDatabase, PERMANENT_TTL};
+
+// Nombre de recherches pour mesurer la latence moyenne
+const LOOKUPS: u32 = 1000;
+
+// Fichier supprimé à la fin du test, même en cas d'échec
+struct TempDatabase(PathBuf);
+
+impl TempDatabase {
+    fn new(test: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("dns_records_{}_{}.sqlite", test, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        TempDatabase(path)
+    }
+}
+
+impl Drop for TempDatabase {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+#[test]
+fn records_survive_reopen() {
+    let file = TempDatabase::new("reopen");
+    let ip = Ipv4Addr::new(10, 0, 0, 7);
+
+    let mut database = SimpleDnsDatabase::open(&file.0).unwrap();
+    database.add_record_persistent("App.Local".to_string(), ip, PERMANENT_TTL).unwrap();
+    database.add_record_persistent("*.dev.local".to_string(), Ipv4Addr::new(10, 0, 0, 8), PERMANENT_TTL).unwrap();
+    drop(database);
+
+    let database = SimpleDnsDatabase::open(&file.0).unwrap();
+    assert_eq!(database.lookup("app.local"), Some(ip));
+    assert_eq!(database.lookup("api.dev.local"), Some(Ipv4Addr::new(10, 0, 0, 8)));
+    assert_eq!(database.list_records().len(), 2);
+}
+
+#[test]
+fn add_replaces_and_remove_persists() {
+    let file = TempDatabase::new("remove");
+
+    let mut database = SimpleDnsDatabase::open(&file.0).unwrap();
+    database.add_record_persistent("app.local".to_string(), Ipv4Addr::new(10, 0, 0, 1), PERMANENT_TTL).unwrap();
+    database.add_record_persistent("app.local".to_string(), Ipv4Addr::new(10, 0, 0, 2), PERMANENT_TTL).unwrap();
+    database.add_record_persistent("old.local".to_string(), Ipv4Addr::new(10, 0, 0, 3), PERMANENT_TTL).unwrap();
+    assert!(database.remove_record_persistent("old.local").unwrap());
+    assert!(!database.remove_record_persistent("old.local").unwrap());
+    drop(database);
+
+    let database = SimpleDnsDatabase::open(&file.0).unwrap();
+    assert_eq!(database.lookup("app.local"), Some(Ipv4Addr::new(10, 0, 0, 2)));
+    assert_eq!(database.lookup("old.local"), None);
+}
+
+#[test]
+fn expired_records_are_not_reloaded() {
+    let file = TempDatabase::new("expired");
+
+    let mut database = SimpleDnsDatabase::open(&file.0).unwrap();
+    database.add_record_persistent("short.local".to_string(), Ipv4Addr::new(10, 0, 0, 1), 0).unwrap();
+    database.add_record_persistent("long.local".to_string(), Ipv4Addr::new(10, 0, 0, 2), 3600).unwrap();
+    drop(database);
+
+    let database = SimpleDnsDatabase::open(&file.0).unwrap();
+    assert_eq!(database.lookup("short.local"), None);
+    let entry = database.lookup_fresh("long.local").expect("enregistrement encore valide");
+    assert!(entry.remaining_ttl() <= 3600 && entry.remaining_ttl() > 3500);
+}
+
+#[test]
+fn lookup_sees_records_written_by_another_handle() {
+    let file = TempDatabase::new("shared");
+    let reader = SimpleDnsDatabase::open(&file.0).unwrap();
+
+    let mut writer = SimpleDnsDatabase::open(&file.0).unwrap();
+    writer.add_record_persistent("late.local".to_string(), Ipv4Addr::new(10, 0, 0, 9), PERMANENT_TTL).unwrap();
+
+    // Absent de la mémoire du lecteur : trouvé dans SQLite
+    assert!(reader.lookup_fresh("late.local").is_none());
+    assert_eq!(reader.lookup("late.local"), Some(Ipv4Addr::new(10, 0, 0, 9)));
+    assert_eq!(reader.lookup("missing.local"), None);
+}
+
+#[test]
+fn sqlite_lookup_stays_under_one_millisecond() {
+    let file = TempDatabase::new("latency");
+    let reader = SimpleDnsDatabase::open(&file.0).unwrap();
+
+    let mut writer = SimpleDnsDatabase::open(&file.0).unwrap();
+    for i in 0..500u32 {
+        let ip = Ipv4Addr::from(0x0A00_0000 + i);
+        writer.add_record_persistent(format!("host{}.local", i), ip, PERMANENT_TTL).unwrap();
+    }
+
+    // Chaque recherche passe par SQLite : le lecteur n'a rien en mémoire
+    let start = Instant::now();
+    for i in 0..LOOKUPS {
+        assert!(reader.lookup(&format!("host{}.local", i % 500)).is_some());
+    }
+    let average = start.elapsed() / LOOKUPS;
+    assert!(average < Duration::from_millis(1), "recherche SQLite trop lente: {:?}", average);
+}
@@ -0,0 +1,117 @@
+// Tests de SimpleDnsDatabase : expiration TTL, fichier hosts et jokers
+use std::io::ErrorKind;
+use std::net::Ipv4Addr;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use dns_client_server::SimpleDnsDatabase;
+
+// Fichier supprimé à la fin du test, même en cas d'échec
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(test: &str, content: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("dns_hosts_{}_{}", test, std::process::id()));
+        std::fs::write(&path, content).unwrap();
+        TempFile(path)
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+#[test]
+fn entry_with_ttl_one_is_gone_after_two_seconds() {
+    let mut db = SimpleDnsDatabase::empty();
+    db.add_record_with_ttl("short.local".to_string(), Ipv4Addr::new(10, 0, 0, 1), 1);
+    db.add_record_with_ttl("long.local".to_string(), Ipv4Addr::new(10, 0, 0, 2), 60);
+    db.add_record("static.local".to_string(), Ipv4Addr::new(10, 0, 0, 3));
+    assert_eq!(db.lookup("short.local"), Some(Ipv4Addr::new(10, 0, 0, 1)));
+
+    thread::sleep(Duration::from_secs(2));
+
+    assert!(db.lookup_fresh("short.local").is_none());
+    assert_eq!(db.lookup("short.local"), None);
+    assert_eq!(db.lookup("long.local"), Some(Ipv4Addr::new(10, 0, 0, 2)));
+    assert_eq!(db.lookup("static.local"), Some(Ipv4Addr::new(10, 0, 0, 3)));
+
+    // L'entrée expirée reste en mémoire jusqu'au nettoyage
+    assert!(db.list_records().contains_key("short.local"));
+    assert_eq!(db.purge_expired(), 1);
+    assert!(!db.list_records().contains_key("short.local"));
+    assert_eq!(db.list_records().len(), 2);
+}
+
+#[test]
+fn hosts_file_with_twenty_entries_resolves_every_name() {
+    let mut content = String::from("# Fichier hosts de test\n\n");
+    for index in 1..=20 {
+        content.push_str(&format!("10.0.1.{}\thost{:02}.test\t# machine {}\n", index, index, index));
+    }
+    // Alias, IPv6 ignorée et commentaire en fin de fichier
+    content.push_str("192.168.0.1 router.test gateway.test\n::1 ip6-localhost\n# fin\n");
+    let file = TempFile::new("twenty", &content);
+
+    let db = SimpleDnsDatabase::load_from_hosts_file(&file.0).unwrap();
+
+    for index in 1..=20 {
+        assert_eq!(db.lookup(&format!("host{:02}.test", index)), Some(Ipv4Addr::new(10, 0, 1, index)));
+    }
+    assert_eq!(db.lookup("HOST07.test"), Some(Ipv4Addr::new(10, 0, 1, 7)));
+    assert_eq!(db.lookup("gateway.test"), Some(Ipv4Addr::new(192, 168, 0, 1)));
+    assert_eq!(db.lookup("ip6-localhost"), None);
+    assert_eq!(db.list_records().len(), 22);
+}
+
+#[test]
+fn hosts_file_errors_are_reported() {
+    let missing = std::env::temp_dir().join(format!("dns_hosts_missing_{}", std::process::id()));
+    let error = SimpleDnsDatabase::load_from_hosts_file(&missing).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::NotFound);
+    assert!(error.to_string().contains("Impossible de lire"));
+
+    let file = TempFile::new("invalid", "10.0.0.1 ok.test\n10.0.0.999 ko.test\n");
+    let error = SimpleDnsDatabase::load_from_hosts_file(&file.0).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+    assert!(error.to_string().contains(":2: adresse invalide '10.0.0.999'"), "{}", error);
+}
+
+#[test]
+fn wildcard_covers_one_and_two_levels() {
+    let mut db = SimpleDnsDatabase::empty();
+    db.add_record("*.example.com".to_string(), Ipv4Addr::new(10, 0, 0, 1));
+
+    for name in ["foo.example.com", "bar.example.com", "a.b.example.com"] {
+        assert_eq!(db.lookup(name), Some(Ipv4Addr::new(10, 0, 0, 1)), "{}", name);
+    }
+    // Le joker ne couvre pas le nom de la zone lui-même
+    assert_eq!(db.lookup("example.com"), None);
+    assert_eq!(db.lookup("foo.example.org"), None);
+}
+
+#[test]
+fn exact_and_closer_wildcard_beat_wider_wildcard() {
+    let mut db = SimpleDnsDatabase::empty();
+    db.add_record("*.example.com".to_string(), Ipv4Addr::new(10, 0, 0, 1));
+    db.add_record("*.b.example.com".to_string(), Ipv4Addr::new(10, 0, 0, 2));
+    db.add_record("www.example.com".to_string(), Ipv4Addr::new(10, 0, 0, 3));
+
+    assert_eq!(db.lookup("www.example.com"), Some(Ipv4Addr::new(10, 0, 0, 3)));
+    assert_eq!(db.lookup("a.b.example.com"), Some(Ipv4Addr::new(10, 0, 0, 2)));
+    assert_eq!(db.lookup("a.c.example.com"), Some(Ipv4Addr::new(10, 0, 0, 1)));
+}
+
+#[test]
+fn expired_exact_entry_falls_back_to_wildcard() {
+    let mut db = SimpleDnsDatabase::empty();
+    db.add_record("*.example.com".to_string(), Ipv4Addr::new(10, 0, 0, 1));
+    db.add_record_with_ttl("tmp.example.com".to_string(), Ipv4Addr::new(10, 0, 0, 9), 1);
+    assert_eq!(db.lookup("tmp.example.com"), Some(Ipv4Addr::new(10, 0, 0, 9)));
+
+    thread::sleep(Duration::from_millis(1100));
+    assert_eq!(db.lookup("tmp.example.com"), Some(Ipv4Addr::new(10, 0, 0, 1)));
+}
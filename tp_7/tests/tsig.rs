@@ -0,0 +1,94 @@
+// Signature et vérification TSIG (RFC 2845) d'un message DNS
+use std::net::Ipv4Addr;
+
+use dns_client_server::{DnsClass, DnsMessage, DnsRecord, DnsRecordType, TsigData, TSIG_ALGORITHM};
+
+const KEY_NAME: &str = "transfer.example.com";
+const SECRET: &[u8] = b"secret-partage-de-test";
+
+fn signed_response() -> DnsMessage {
+    let query = DnsMessage::new_query("example.com".to_string(), DnsRecordType::A);
+    let mut response = DnsMessage::new_response(&query);
+    response.answers.push(DnsRecord::new_a_record("example.com".to_string(), Ipv4Addr::new(93, 184, 216, 34), 300));
+    response.header.ancount = 1;
+    response.sign_tsig(KEY_NAME, SECRET).unwrap();
+    response
+}
+
+#[test]
+fn tsig_record_roundtrip() {
+    let tsig = TsigData {
+        algorithm: TSIG_ALGORITHM.to_string(),
+        time_signed: 1_700_000_000,
+        fudge: 300,
+        mac: vec![0xAB; 32],
+        original_id: 0x1234,
+        error: 0,
+        other_data: vec![1, 2, 3],
+    };
+    let record = DnsRecord::new_tsig_record(KEY_NAME, &tsig);
+    assert_eq!(record.class, DnsClass::ANY);
+    assert_eq!(record.ttl, 0);
+
+    let mut message = DnsMessage::new();
+    message.additionals.push(record);
+    message.header.arcount = 1;
+    let parsed = DnsMessage::from_bytes(&message.to_bytes()).unwrap();
+    assert_eq!(parsed, message);
+    assert_eq!(parsed.additionals[0].from_tsig(), Some(tsig));
+}
+
+#[test]
+fn signed_message_verifies() {
+    let response = signed_response();
+    assert_eq!(response.header.arcount, 1);
+    assert!(response.verify_tsig(KEY_NAME, SECRET));
+
+    // Après un aller-retour sur le réseau
+    let parsed = DnsMessage::from_bytes(&response.to_bytes()).unwrap();
+    assert!(parsed.verify_tsig(KEY_NAME, SECRET));
+}
+
+#[test]
+fn signing_twice_replaces_the_signature() {
+    let mut response = signed_response();
+    response.sign_tsig(KEY_NAME, SECRET).unwrap();
+    assert_eq!(response.additionals.len(), 1);
+    assert_eq!(response.header.arcount, 1);
+    assert!(response.verify_tsig(KEY_NAME, SECRET));
+}
+
+#[test]
+fn tsig_stays_last_with_edns() {
+    let query = DnsMessage::new_query("example.com".to_string(), DnsRecordType::A);
+    let mut message = DnsMessage::new_response(&query);
+    message.edns_payload_size = Some(4096);
+    message.header.arcount = 1;
+    message.sign_tsig(KEY_NAME, SECRET).unwrap();
+
+    let bytes = message.to_bytes();
+    assert!(bytes.ends_with(&message.additionals[0].to_bytes()));
+    let parsed = DnsMessage::from_bytes(&bytes).unwrap();
+    assert_eq!(parsed.edns_payload_size, Some(4096));
+    assert!(parsed.verify_tsig(KEY_NAME, SECRET));
+}
+
+#[test]
+fn tampered_message_is_rejected() {
+    let mut response = signed_response();
+    response.answers[0] = DnsRecord::new_a_record("example.com".to_string(), Ipv4Addr::new(10, 0, 0, 1), 300);
+    assert!(!response.verify_tsig(KEY_NAME, SECRET));
+}
+
+#[test]
+fn wrong_key_is_rejected() {
+    let response = signed_response();
+    assert!(!response.verify_tsig(KEY_NAME, b"autre-secret"));
+    assert!(!response.verify_tsig("other.example.com", SECRET));
+}
+
+#[test]
+fn unsigned_message_is_rejected() {
+    let message = DnsMessage::new_query("example.com".to_string(), DnsRecordType::A);
+    assert!(!message.verify_tsig(KEY_NAME, SECRET));
+}
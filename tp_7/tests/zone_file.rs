@@ -0,0 +1,84 @@
+// Tests de l'export / import au format fichier de zone (RFC 1035)
+use std::net::Ipv4Addr;
+
+use dns_client_server::{SimpleDnsDatabase, SoaData, PERMANENT_TTL};
+
+fn soa() -> SoaData {
+    SoaData {
+        mname: "ns1.example.com".to_string(),
+        rname: "hostmaster.example.com".to_string(),
+        serial: 2024010101,
+        refresh: 3600,
+        retry: 600,
+        expire: 86400,
+        minimum: 300,
+    }
+}
+
+#[test]
+fn export_then_import_keeps_records_and_ttls() {
+    let mut database = SimpleDnsDatabase::empty();
+    database.add_soa_record("example.com".to_string(), soa());
+    database.add_ns_record("example.com".to_string(), "ns1.example.com".to_string());
+    database.add_record_with_ttl("www.example.com".to_string(), Ipv4Addr::new(10, 0, 0, 80), 600);
+    database.add_record_with_ttl("mail.example.com".to_string(), Ipv4Addr::new(10, 0, 0, 25), 3600);
+    database.add_record("example.com".to_string(), Ipv4Addr::new(10, 0, 0, 1));
+    database.add_record_with_ttl("other.org".to_string(), Ipv4Addr::new(192, 0, 2, 1), 120);
+
+    let zone = database.export_zone_file("example.com", 300);
+    let imported = SimpleDnsDatabase::import_zone_file(&zone).unwrap();
+
+    assert_eq!(imported.list_records().len(), 4);
+    for (name, entry) in database.list_records() {
+        let copy = imported.lookup_fresh(name).unwrap_or_else(|| panic!("{} perdu à l'import", name));
+        assert_eq!(copy.ips, entry.ips);
+        // Les données de zone n'expirent pas ; le TTL explicite (à la seconde écoulée près)
+        // ou le $TTL est celui annoncé aux clients
+        assert_eq!(copy.ttl, PERMANENT_TTL);
+        let expected = if entry.ttl == PERMANENT_TTL { 300 } else { entry.ttl };
+        let announced = copy.zone_ttl.unwrap();
+        assert!(announced <= expected && announced + 1 >= expected, "{}: TTL {} au lieu de {}", name, announced, expected);
+        assert_eq!(copy.response_ttl(60), announced);
+    }
+    assert_eq!(imported.lookup_soa("example.com"), Some(&soa()));
+    assert_eq!(imported.lookup_ns("example.com"), Some(&vec!["ns1.example.com".to_string()]));
+
+    // Un second export garde les TTL annoncés
+    assert_eq!(SimpleDnsDatabase::import_zone_file(&imported.export_zone_file("example.com", 60)).unwrap()
+        .lookup_fresh("example.com").unwrap().zone_ttl, Some(300));
+}
+
+#[test]
+fn record_ttl_overrides_default_ttl() {
+    let zone = "$ORIGIN example.com.
+static  IN A 10.0.0.1
+$TTL 900
+www     IN A 10.0.0.80
+api 60  IN A 10.0.0.81
+mail IN 7200 A 10.0.0.25
+";
+    let database = SimpleDnsDatabase::import_zone_file(zone).unwrap();
+    let zone_ttl = |name: &str| database.lookup_fresh(name).unwrap().zone_ttl;
+
+    assert_eq!(zone_ttl("static.example.com"), None);
+    assert_eq!(zone_ttl("www.example.com"), Some(900));
+    assert_eq!(zone_ttl("api.example.com"), Some(60));
+    assert_eq!(zone_ttl("mail.example.com"), Some(7200));
+    assert!(database.list_records().values().all(|entry| entry.ttl == PERMANENT_TTL));
+}
+
+#[test]
+fn imported_zone_survives_the_expiry_purge() {
+    // Même un $TTL nul ne fait pas expirer la zone : il indique seulement aux clients de ne pas cacher
+    let zone = "$ORIGIN example.com.
+$TTL 0
+@   IN SOA ns1 hostmaster 2024010101 3600 600 86400 300
+www IN A 10.0.0.80
+";
+    let mut database = SimpleDnsDatabase::import_zone_file(zone).unwrap();
+    assert_eq!(database.purge_expired(), 0);
+
+    let entry = database.lookup_fresh("www.example.com").unwrap();
+    assert_eq!(entry.ips, vec![Ipv4Addr::new(10, 0, 0, 80)]);
+    assert_eq!(entry.response_ttl(300), 0);
+}
@@ -0,0 +1,117 @@
+// Tests des ACL et du routage des zones par client (--acl-file)
+use std::net::IpAddr;
+use std::path::PathBuf;
+
+use dns_client_server::{Cidr, IpAcl, SimpleDnsDatabase, ZoneRouter};
+
+fn ip(text: &str) -> IpAddr {
+    text.parse().unwrap()
+}
+
+// Répertoire temporaire supprimé à la fin du test
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("dns_acl_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+
+    fn write(&self, name: &str, content: &str) {
+        std::fs::write(self.0.join(name), content).unwrap();
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn cidr_matches_prefixes() {
+    let network = Cidr::parse("192.168.0.0/16").unwrap();
+    assert!(network.contains(ip("192.168.42.7")));
+    assert!(!network.contains(ip("192.169.0.1")));
+    assert!(!network.contains(ip("2001:db8::1")));
+    // Adresse IPv4 vue par une socket IPv6
+    assert!(network.contains(ip("::ffff:192.168.1.1")));
+
+    assert!(Cidr::parse("10.0.0.1").unwrap().contains(ip("10.0.0.1")));
+    assert!(!Cidr::parse("10.0.0.1").unwrap().contains(ip("10.0.0.2")));
+    assert!(Cidr::parse("0.0.0.0/0").unwrap().contains(ip("203.0.113.9")));
+    assert!(Cidr::parse("2001:db8::/32").unwrap().contains(ip("2001:db8:ffff::1")));
+    assert!(!Cidr::parse("2001:db8::/32").unwrap().contains(ip("2001:db9::1")));
+
+    for invalid in ["192.168.0.0/33", "10.0.0.0/x", "interne", "::/129"] {
+        assert!(Cidr::parse(invalid).is_err(), "{}", invalid);
+    }
+}
+
+#[test]
+fn routes_by_zone_and_client() {
+    let dir = TempDir::new("route");
+    dir.write("internal.zone", "$ORIGIN example.com.\n@ IN A 10.0.0.1\nintranet IN A 10.0.0.2\n");
+    dir.write("lab.zone", "$ORIGIN lab.example.com.\n@ IN A 10.9.0.1\n");
+    dir.write("named.acl", r#"
+        # Réseaux de l'entreprise
+        acl "internal" { 192.168.0.0/16; 10.0.0.0/8; };
+        acl lab { 10.9.0.0/16; };
+
+        zone "example.com" {
+            match-clients { internal; };   // vue interne
+            file "internal.zone";
+        };
+        zone "lab.example.com" { match-clients { lab; 172.16.0.1; }; file "lab.zone"; };
+    "#);
+
+    let router = ZoneRouter::load(&dir.0.join("named.acl")).unwrap();
+    assert_eq!(router.routes().collect::<Vec<_>>(), [("example.com", "internal"), ("lab.example.com", "lab,172.16.0.1")]);
+
+    let internal = router.route("intranet.example.com", ip("192.168.1.10")).expect("vue interne");
+    assert_eq!(internal.lookup("intranet.example.com"), Some("10.0.0.2".parse().unwrap()));
+    // Client externe, ou nom hors des zones routées : base principale
+    assert!(router.route("example.com", ip("203.0.113.5")).is_none());
+    assert!(router.route("google.com", ip("192.168.1.10")).is_none());
+
+    // La zone la plus précise l'emporte quand le client est dans les deux ACL
+    let lab = router.route("lab.example.com", ip("10.9.3.3")).unwrap();
+    assert_eq!(lab.lookup("lab.example.com"), Some("10.9.0.1".parse().unwrap()));
+    let lab = router.route("LAB.example.com.", ip("172.16.0.1")).unwrap();
+    assert_eq!(lab.lookup("lab.example.com"), Some("10.9.0.1".parse().unwrap()));
+
+    assert!(router.covers("www.example.com"));
+    assert!(!router.covers("notexample.com"));
+}
+
+#[test]
+fn route_prefers_first_matching_view() {
+    let mut router = ZoneRouter::new();
+    let mut first = SimpleDnsDatabase::empty();
+    first.add_record("example.com".to_string(), "10.0.0.1".parse().unwrap());
+    let mut second = SimpleDnsDatabase::empty();
+    second.add_record("example.com".to_string(), "10.0.0.2".parse().unwrap());
+    router.add_route("example.com", IpAcl::new("a".to_string(), vec![Cidr::parse("10.0.0.0/8").unwrap()]), first);
+    router.add_route("example.com", IpAcl::new("any".to_string(), vec![Cidr::parse("0.0.0.0/0").unwrap()]), second);
+
+    assert_eq!(router.route("example.com", ip("10.1.1.1")).unwrap().lookup("example.com"), Some("10.0.0.1".parse().unwrap()));
+    assert_eq!(router.route("example.com", ip("8.8.8.8")).unwrap().lookup("example.com"), Some("10.0.0.2".parse().unwrap()));
+}
+
+#[test]
+fn reports_errors_with_line_numbers() {
+    let dir = TempDir::new("errors");
+    let cases = [
+        ("acl internal { 192.168.0.0/16 }", "';' attendu"),
+        ("acl internal { 192.168.0.0/16; };\nzone \"example.com\" { match-clients { interne; }; file \"x\"; };", "ligne 2: ACL inconnue"),
+        ("zone \"example.com\" { file \"x.zone\"; };", "sans match-clients"),
+        ("view \"x\" { };", "instruction inconnue 'view'"),
+        ("zone \"example.com\" { match-clients { any; }; file \"absent.zone\"; };", "absent.zone"),
+    ];
+    for (text, expected) in cases {
+        let error = ZoneRouter::parse(text, &dir.0).unwrap_err().to_string();
+        assert!(error.contains(expected), "{} -> {}", text, error);
+    }
+}
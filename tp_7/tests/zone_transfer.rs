@@ -0,0 +1,79 @@
+// Tests du transfert de zone : enregistrements d'une zone et messages AXFR
+use std::net::Ipv4Addr;
+
+use dns_client_server::{DnsMessage, DnsOpcode, DnsRecord, DnsRecordType, SimpleDnsDatabase};
+
+const ZONE: &str = "$ORIGIN example.com.
+@       IN SOA ns1 hostmaster 2024010101 3600 600 86400 300
+@       IN NS  ns1
+ns1     IN A   10.0.0.53
+www     IN A   10.0.0.80
+other.org. IN A 192.0.2.1
+";
+
+#[test]
+fn zone_records_cover_the_zone_only() {
+    let database = SimpleDnsDatabase::import_zone_file(ZONE).unwrap();
+
+    let records = database.zone_records("Example.COM.", 300);
+    let summary: Vec<(&str, DnsRecordType)> = records.iter().map(|record| (record.name.as_str(), record.rtype)).collect();
+    assert_eq!(summary, [
+        ("example.com", DnsRecordType::NS),
+        ("ns1.example.com", DnsRecordType::A),
+        ("www.example.com", DnsRecordType::A),
+    ]);
+    assert_eq!(records[2].get_ip(), Some(Ipv4Addr::new(10, 0, 0, 80)));
+    assert!(records.iter().all(|record| record.ttl <= 300));
+
+    assert!(database.zone_records("example.net", 300).is_empty());
+}
+
+#[test]
+fn axfr_response_is_framed_by_soa() {
+    let database = SimpleDnsDatabase::import_zone_file(ZONE).unwrap();
+    let soa = DnsRecord::new_soa_record("example.com".to_string(), database.lookup_soa("example.com").unwrap(), 300);
+    let records = database.zone_records("example.com", 300);
+
+    let messages = DnsMessage::new_axfr_response(&records, &soa);
+
+    assert_eq!(messages.len(), records.len() + 2);
+    assert_eq!(messages.first().unwrap().answers, std::slice::from_ref(&soa));
+    assert_eq!(messages.last().unwrap().answers, [soa]);
+    for (message, record) in messages[1..].iter().zip(&records) {
+        assert_eq!(message.answers, std::slice::from_ref(record));
+    }
+    for message in &messages {
+        assert!(message.header.qr && message.header.aa);
+        assert_eq!(message.header.ancount, 1);
+        assert_eq!(message.header.validate(), Ok(()));
+    }
+    assert_eq!(DnsRecordType::from_name("axfr"), Some(DnsRecordType::AXFR));
+}
+
+#[test]
+fn import_axfr_rebuilds_the_zone() {
+    let primary = SimpleDnsDatabase::import_zone_file(ZONE).unwrap();
+    let soa = DnsRecord::new_soa_record("example.com".to_string(), primary.lookup_soa("example.com").unwrap(), 300);
+    let records = primary.zone_records("example.com", 300);
+    let mut messages: Vec<Vec<u8>> = DnsMessage::new_axfr_response(&records, &soa).iter().map(|message| message.to_bytes()).collect();
+    // Un enregistrement hors zone glissé par le primaire est ignoré
+    messages.insert(1, DnsMessage::new_axfr_response(&[], &DnsRecord::new_a_record("other.org".to_string(), Ipv4Addr::new(192, 0, 2, 1), 300))[0].to_bytes());
+
+    let secondary = SimpleDnsDatabase::import_axfr("example.com", &messages).unwrap();
+
+    assert_eq!(secondary.zone_records("example.com", 300), records);
+    assert_eq!(secondary.lookup_soa("example.com"), primary.lookup_soa("example.com"));
+    assert!(secondary.lookup("other.org").is_none());
+
+    // Sans SOA, le transfert est incomplet
+    assert!(SimpleDnsDatabase::import_axfr("example.com", &messages[1..2]).is_err());
+}
+
+#[test]
+fn notify_may_carry_authoritative_bit() {
+    let mut notify = DnsMessage::new_query("example.com".to_string(), DnsRecordType::SOA);
+    notify.header.opcode = DnsOpcode::NOTIFY as u8;
+    notify.header.aa = true;
+    assert_eq!(notify.header.validate(), Ok(()));
+    assert_eq!(DnsOpcode::from_u8(notify.header.opcode), Some(DnsOpcode::NOTIFY));
+}